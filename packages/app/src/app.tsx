@@ -57,7 +57,18 @@ function UiI18nBridge(props: ParentProps) {
 
 declare global {
   interface Window {
-    __OPENCODE__?: { updaterEnabled?: boolean; serverPassword?: string; deepLinks?: string[]; wsl?: boolean }
+    __OPENCODE__?: {
+      updaterEnabled?: boolean
+      serverPassword?: string
+      deepLinks?: string[]
+      wsl?: boolean
+      runBenchmarks?: () => Promise<unknown>
+      projectPath?: string
+      serverUrl?: string | null
+      quickPanel?: boolean
+      pip?: boolean
+      csd?: boolean
+    }
   }
 }
 
@@ -46,6 +46,8 @@ export function Titlebar() {
 
   const mac = createMemo(() => platform.platform === "desktop" && platform.os === "macos")
   const windows = createMemo(() => platform.platform === "desktop" && platform.os === "windows")
+  // Window controls are drawn into the titlebar on Windows, and on Linux without a native titlebar
+  const controls = createMemo(() => windows() || (platform.platform === "desktop" && !!window.__OPENCODE__?.csd))
   const web = createMemo(() => platform.platform === "web")
   const zoom = () => platform.webviewZoom?.() ?? 1
   const minHeight = () => (mac() ? `${40 / zoom()}px` : undefined)
@@ -119,6 +121,29 @@ export function Titlebar() {
     void win.setTheme(value).catch(() => undefined)
   })
 
+  let header: HTMLElement | undefined
+
+  createEffect(() => {
+    if (platform.platform !== "desktop" || !platform.setWindowBackground) return
+
+    theme.themeId()
+    theme.mode()
+    // Read once the theme's styles are in, so the native background matches what's painted
+    requestAnimationFrame(() => {
+      const color = header && getComputedStyle(header).backgroundColor
+      if (!color) return
+      void platform.setWindowBackground?.(color).catch(() => undefined)
+    })
+  })
+
+  createEffect(() => {
+    if (!mac()) return
+
+    // The header grows with the zoom level, and the window buttons stay centered in it
+    const height = Math.max(40, 40 * zoom())
+    void platform.setTrafficLightInset?.(12, 18 + (height - 40) / 2).catch(() => undefined)
+  })
+
   const interactive = (target: EventTarget | null) => {
     if (!(target instanceof Element)) return false
 
@@ -145,6 +170,12 @@ export function Titlebar() {
     if (interactive(e.target)) return
     if (e.target instanceof Element && e.target.closest("[data-tauri-decorum-tb]")) return
 
+    if (platform.titlebarDoubleClick) {
+      e.preventDefault()
+      void platform.titlebarDoubleClick().catch(() => undefined)
+      return
+    }
+
     const win = getWin()
     if (!win?.toggleMaximize) return
 
@@ -154,6 +185,7 @@ export function Titlebar() {
 
   return (
     <header
+      ref={header}
       class="h-10 shrink-0 bg-background-base relative grid grid-cols-[auto_minmax(0,1fr)_auto] items-center"
       style={{ "min-height": minHeight() }}
       onMouseDown={drag}
@@ -166,7 +198,7 @@ export function Titlebar() {
         }}
       >
         <Show when={mac()}>
-          <div class="h-full shrink-0" style={{ width: `${72 / zoom()}px` }} />
+          <div data-traffic-light-spacer="left" class="h-full shrink-0" style={{ width: `${72 / zoom()}px` }} />
           <div class="xl:hidden w-10 shrink-0 flex items-center justify-center">
             <IconButton
               icon="menu"
@@ -250,15 +282,18 @@ export function Titlebar() {
       <div
         classList={{
           "flex items-center min-w-0 justify-end": true,
-          "pr-6": !windows(),
+          "pr-6": !controls(),
         }}
         onMouseDown={drag}
       >
         <div id="opencode-titlebar-right" class="flex items-center gap-3 shrink-0 justify-end" />
-        <Show when={windows()}>
+        <Show when={controls()}>
           <div class="w-6 shrink-0" />
           <div data-tauri-decorum-tb class="flex flex-row" />
         </Show>
+        <Show when={mac()}>
+          <div data-traffic-light-spacer="right" class="h-full shrink-0" style={{ width: `${72 / zoom()}px` }} />
+        </Show>
       </div>
     </header>
   )
@@ -12,7 +12,7 @@ import { base64Encode } from "@opencode-ai/util/encode"
 import { decode64 } from "@/utils/base64"
 import { EventSessionError } from "@opencode-ai/sdk/v2"
 import { Persist, persisted } from "@/utils/persist"
-import { playSound, soundSrc } from "@/utils/sound"
+import { playAlertSound, soundSrc } from "@/utils/sound"
 
 type NotificationBase = {
   directory?: string
@@ -193,6 +193,12 @@ export const { use: useNotification, provider: NotificationProvider } = createSi
       })
     })
 
+    createEffect(() => {
+      if (!ready()) return
+      const unread = store.list.filter((notification) => !notification.viewed).length
+      void platform.setBadgeCount?.(unread).catch(() => undefined)
+    })
+
     const append = (notification: Notification) => {
       const list = pruneNotifications([...store.list, notification])
       const keep = new Set(list)
@@ -234,7 +240,7 @@ export const { use: useNotification, provider: NotificationProvider } = createSi
         if (session.parentID) return
 
         if (settings.sounds.agentEnabled()) {
-          playSound(soundSrc(settings.sounds.agent()))
+          playAlertSound(soundSrc(settings.sounds.agent()), platform.isQuietHours)
         }
 
         append({
@@ -247,7 +253,12 @@ export const { use: useNotification, provider: NotificationProvider } = createSi
 
         const href = `/${base64Encode(directory)}/session/${sessionID}`
         if (settings.notifications.agent()) {
-          void platform.notify(language.t("notification.session.responseReady.title"), session.title ?? sessionID, href)
+          void platform.notify(
+            language.t("notification.session.responseReady.title"),
+            session.title ?? sessionID,
+            href,
+            { kind: "response_ready", directory },
+          )
         }
       })
     }
@@ -263,7 +274,7 @@ export const { use: useNotification, provider: NotificationProvider } = createSi
         if (session?.parentID) return
 
         if (settings.sounds.errorsEnabled()) {
-          playSound(soundSrc(settings.sounds.errors()))
+          playAlertSound(soundSrc(settings.sounds.errors()), platform.isQuietHours)
         }
 
         const error = "error" in event.properties ? event.properties.error : undefined
@@ -280,7 +291,10 @@ export const { use: useNotification, provider: NotificationProvider } = createSi
           (typeof error === "string" ? error : language.t("notification.session.error.fallbackDescription"))
         const href = sessionID ? `/${base64Encode(directory)}/session/${sessionID}` : `/${base64Encode(directory)}`
         if (settings.notifications.errors()) {
-          void platform.notify(language.t("notification.session.error.title"), description, href)
+          void platform.notify(language.t("notification.session.error.title"), description, href, {
+            kind: "error",
+            directory,
+          })
         }
       })
     }
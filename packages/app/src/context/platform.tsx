@@ -33,8 +33,8 @@ export type Platform = {
   /** Navigate forward in history */
   forward(): void
 
-  /** Send a system notification (optional deep link) */
-  notify(title: string, description?: string, href?: string): Promise<void>
+  /** Send a system notification (optional deep link, and what it's about for routing rules) */
+  notify(title: string, description?: string, href?: string, meta?: NotificationMeta): Promise<void>
 
   /** Open directory picker dialog (native on Tauri, server-backed on web) */
   openDirectoryPickerDialog?(opts?: OpenDirectoryPickerOptions): Promise<PickerPaths>
@@ -86,10 +86,47 @@ export type Platform = {
 
   /** Read image from clipboard (desktop only) */
   readClipboardImage?(): Promise<File | null>
+
+  /** Mark this window as running an agent, so quitting asks first (desktop only) */
+  setBusyState?(busy: boolean, description?: string): Promise<void>
+
+  /** Whether notifications and alert sounds are currently silenced (desktop only) */
+  isQuietHours?(): Promise<boolean>
+
+  /** Act on a titlebar double-click as configured, e.g. maximize or minimize (desktop only) */
+  titlebarDoubleClick?(): Promise<void>
+
+  /** Place the window buttons relative to the titlebar, in logical pixels (macOS only) */
+  setTrafficLightInset?(x: number, y: number): Promise<void>
+
+  /** Show a count on the app icon, e.g. unread results; 0 clears it (desktop only) */
+  setBadgeCount?(count: number): Promise<void>
+
+  /** Show progress on this window's taskbar button or the dock icon (desktop only) */
+  setTaskbarProgress?(state: TaskbarProgress, value?: number): Promise<void>
+
+  /** Fill this window behind the page with a CSS color, and open new windows with it (desktop only) */
+  setWindowBackground?(color: string): Promise<void>
+
+  /** Open the system print dialog for this window's page (desktop only) */
+  printPage?(): Promise<void>
+
+  /** Save this window's page to an absolute path as a PDF, without a dialog (desktop only) */
+  exportPagePdf?(path: string, landscape?: boolean): Promise<void>
+
+  /** Ask the user before applying a deep link that changes anything, resolving to whether it may be applied (desktop only) */
+  confirmDeepLink?(url: string): Promise<boolean>
 }
 
 export type DisplayBackend = "auto" | "wayland"
 
+export type TaskbarProgress = "none" | "normal" | "indeterminate" | "paused" | "error"
+
+export type NotificationMeta = {
+  kind: "response_ready" | "permission" | "question" | "error"
+  directory?: string
+}
+
 export const { use: usePlatform, provider: PlatformProvider } = createSimpleContext({
   name: "Platform",
   init: (props: { value: Platform }) => {
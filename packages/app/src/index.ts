@@ -1,4 +1,5 @@
-export { PlatformProvider, type Platform, type DisplayBackend } from "./context/platform"
+export { PlatformProvider, type Platform, type DisplayBackend, type NotificationMeta } from "./context/platform"
 export { AppBaseProviders, AppInterface } from "./app"
 export { useCommand } from "./context/command"
+export { useServer, normalizeServerUrl } from "./context/server"
 export { handleNotificationClick } from "./utils/notification-click"
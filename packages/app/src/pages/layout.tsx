@@ -40,7 +40,7 @@ import { useNotification } from "@/context/notification"
 import { usePermission } from "@/context/permission"
 import { Binary } from "@opencode-ai/util/binary"
 import { retry } from "@opencode-ai/util/retry"
-import { playSound, soundSrc } from "@/utils/sound"
+import { playAlertSound, soundSrc } from "@/utils/sound"
 import { createAim } from "@/utils/aim"
 import { Worktree as WorktreeState } from "@/utils/worktree"
 
@@ -389,16 +389,16 @@ export default function Layout(props: ParentProps) {
 
         if (e.details.type === "permission.asked") {
           if (settings.sounds.permissionsEnabled()) {
-            playSound(soundSrc(settings.sounds.permissions()))
+            playAlertSound(soundSrc(settings.sounds.permissions()), platform.isQuietHours)
           }
           if (settings.notifications.permissions()) {
-            void platform.notify(title, description, href)
+            void platform.notify(title, description, href, { kind: "permission", directory })
           }
         }
 
         if (e.details.type === "question.asked") {
           if (settings.notifications.agent()) {
-            void platform.notify(title, description, href)
+            void platform.notify(title, description, href, { kind: "question", directory })
           }
         }
 
@@ -1110,10 +1110,14 @@ export default function Layout(props: ParentProps) {
     if (navigate) navigateToProject(directory)
   }
 
-  const handleDeepLinks = (urls: string[]) => {
+  const handleDeepLinks = async (urls: string[]) => {
     if (!server.isLocal()) return
-    for (const directory of collectOpenProjectDeepLinks(urls)) {
-      openProject(directory)
+    for (const url of urls) {
+      const [directory] = collectOpenProjectDeepLinks([url])
+      if (!directory) continue
+      // Any page or app can open a deep link, so the user confirms what it changes first
+      const allowed = platform.confirmDeepLink ? await platform.confirmDeepLink(url).catch(() => false) : true
+      if (allowed) openProject(directory)
     }
   }
 
@@ -1122,10 +1126,10 @@ export default function Layout(props: ParentProps) {
       const detail = (event as CustomEvent<{ urls: string[] }>).detail
       const urls = detail?.urls ?? []
       if (urls.length === 0) return
-      handleDeepLinks(urls)
+      void handleDeepLinks(urls)
     }
 
-    handleDeepLinks(drainPendingDeepLinks(window))
+    void handleDeepLinks(drainPendingDeepLinks(window))
     window.addEventListener(deepLinkEvent, handler as EventListener)
     onCleanup(() => window.removeEventListener(deepLinkEvent, handler as EventListener))
   })
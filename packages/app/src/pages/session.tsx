@@ -20,6 +20,7 @@ import { Mark } from "@opencode-ai/ui/logo"
 import { DragDropProvider, DragDropSensors, DragOverlay, SortableProvider, closestCenter } from "@thisbeyond/solid-dnd"
 import type { DragEvent } from "@thisbeyond/solid-dnd"
 import { useSync } from "@/context/sync"
+import { usePlatform } from "@/context/platform"
 import { useTerminal, type LocalPTY } from "@/context/terminal"
 import { useLayout } from "@/context/layout"
 import { checksum, base64Encode } from "@opencode-ai/util/encode"
@@ -89,6 +90,7 @@ export default function Page() {
   const local = useLocal()
   const file = useFile()
   const sync = useSync()
+  const platform = usePlatform()
   const terminal = useTerminal()
   const dialog = useDialog()
   const codeComponent = useCodeComponent()
@@ -727,6 +729,16 @@ export default function Page() {
 
   const status = createMemo(() => sync.data.session_status[params.id ?? ""] ?? idle)
 
+  createEffect(() => {
+    const busy = status().type !== "idle"
+    void platform.setBusyState?.(busy, busy ? info()?.title : undefined).catch(() => undefined)
+    void platform.setTaskbarProgress?.(busy ? "indeterminate" : "none").catch(() => undefined)
+  })
+  onCleanup(() => {
+    void platform.setBusyState?.(false).catch(() => undefined)
+    void platform.setTaskbarProgress?.("none").catch(() => undefined)
+  })
+
   createEffect(
     on(
       sessionKey,
@@ -115,3 +115,13 @@ export function playSound(src: string | undefined) {
     audio.currentTime = 0
   }
 }
+
+// Alerts stay silent while the platform reports quiet hours, like its notifications.
+export function playAlertSound(src: string | undefined, isQuietHours?: () => Promise<boolean>) {
+  if (!isQuietHours) return void playSound(src)
+  void isQuietHours()
+    .catch(() => false)
+    .then((quiet) => {
+      if (!quiet) playSound(src)
+    })
+}
@@ -1,3 +1,29 @@
+use std::path::PathBuf;
+
+use sha2::{Digest, Sha256};
+
 fn main() {
+    embed_sidecar_manifest();
     tauri_build::build()
 }
+
+// Records the size & hash of the bundled sidecar so the app can tell a corrupt or
+// partially-written binary apart from a working one at runtime. Signing while bundling changes
+// the binary afterwards, so signed sidecars are checked through their signature instead.
+fn embed_sidecar_manifest() {
+    println!("cargo:rerun-if-changed=sidecars");
+
+    let target = std::env::var("TARGET").unwrap_or_default();
+    let extension = if target.contains("windows") { ".exe" } else { "" };
+    let path = PathBuf::from("sidecars").join(format!("opencode-cli-{target}{extension}"));
+
+    let Ok(bytes) = std::fs::read(&path) else {
+        return;
+    };
+
+    println!("cargo:rustc-env=OPENCODE_SIDECAR_SIZE={}", bytes.len());
+    println!(
+        "cargo:rustc-env=OPENCODE_SIDECAR_SHA256={:x}",
+        Sha256::digest(&bytes)
+    );
+}
@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::{
+    ServerState,
+    constants::{ADOPTED_SERVER_KEY, SETTINGS_STORE},
+    server::url_is_localhost,
+};
+
+const CLIENT_ID_HEADER: &str = "x-opencode-client-id";
+
+/// A server the app connected to but didn't start, so it must never be killed by the app.
+#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type, Debug)]
+pub struct AdoptedServer {
+    pub url: String,
+    pub version: Option<String>,
+    pub requires_auth: bool,
+    pub client_id: String,
+}
+
+#[derive(serde::Deserialize)]
+struct HealthResponse {
+    healthy: bool,
+    version: Option<String>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_adopted_server(app: AppHandle) -> Result<Option<AdoptedServer>, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(ADOPTED_SERVER_KEY)
+        .and_then(|v| serde_json::from_value(v).ok()))
+}
+
+/// Checks that `url` is an opencode server this app can talk to, then records it as adopted.
+/// The client id from an earlier adoption of the same server is reused.
+pub async fn adopt(
+    app: &AppHandle,
+    url: &str,
+    password: Option<&str>,
+) -> Result<AdoptedServer, String> {
+    let client_id = get_adopted_server(app.clone())
+        .ok()
+        .flatten()
+        .filter(|adopted| adopted.url == url)
+        .map(|adopted| adopted.client_id)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    let adopted = handshake(url, password, client_id).await?;
+    tracing::info!(
+        %url,
+        version = ?adopted.version,
+        requires_auth = adopted.requires_auth,
+        "Adopted existing server"
+    );
+
+    set(app, Some(&adopted))?;
+    Ok(adopted)
+}
+
+/// The version the server at `url` reports, without adopting it.
+pub async fn server_version(app: &AppHandle, url: &str, password: Option<&str>) -> Option<String> {
+    let client_id = get_adopted_server(app.clone())
+        .ok()
+        .flatten()
+        .map(|adopted| adopted.client_id)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    handshake(url, password, client_id).await.ok()?.version
+}
+
+/// Forgets any adopted server, e.g. once the app spawns its own sidecar.
+pub fn clear(app: &AppHandle) -> Result<(), String> {
+    set(app, None)
+}
+
+fn set(app: &AppHandle, adopted: Option<&AdoptedServer>) -> Result<(), String> {
+    if let Some(state) = app.try_state::<ServerState>() {
+        state.set_adopted(adopted.cloned());
+    }
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    match adopted {
+        Some(adopted) => store.set(ADOPTED_SERVER_KEY, serde_json::json!(adopted)),
+        None => {
+            store.delete(ADOPTED_SERVER_KEY);
+        }
+    }
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+async fn handshake(
+    url: &str,
+    password: Option<&str>,
+    client_id: String,
+) -> Result<AdoptedServer, String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid server URL: {}", e))?;
+    let health_url = parsed
+        .join("/global/health")
+        .map_err(|e| format!("Invalid server URL: {}", e))?;
+
+    let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(3));
+    if url_is_localhost(&parsed) {
+        builder = builder.no_proxy();
+    }
+    let client = builder
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let request = || {
+        client
+            .get(health_url.clone())
+            .header(CLIENT_ID_HEADER, &client_id)
+    };
+
+    // Probe without credentials first to learn whether the server wants them at all
+    let mut response = request()
+        .send()
+        .await
+        .map_err(|e| format!("Server at {} isn't reachable: {}", url, e))?;
+
+    let requires_auth = response.status() == StatusCode::UNAUTHORIZED;
+    if requires_auth {
+        let password = password.ok_or_else(|| format!("Server at {} requires a password", url))?;
+        response = request()
+            .basic_auth("opencode", Some(password))
+            .send()
+            .await
+            .map_err(|e| format!("Server at {} isn't reachable: {}", url, e))?;
+
+        if response.status() == StatusCode::UNAUTHORIZED {
+            return Err(format!("Server at {} rejected the password", url));
+        }
+    }
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Server at {} failed its health check ({})",
+            url,
+            response.status()
+        ));
+    }
+
+    // The server may assign its own id, otherwise ours is kept
+    let client_id = response
+        .headers()
+        .get(CLIENT_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from)
+        .unwrap_or(client_id);
+
+    let health = response
+        .bytes()
+        .await
+        .ok()
+        .and_then(|body| serde_json::from_slice::<HealthResponse>(&body).ok())
+        .ok_or_else(|| format!("Server at {} isn't an opencode server", url))?;
+    if !health.healthy {
+        return Err(format!("Server at {} reports it isn't healthy", url));
+    }
+
+    Ok(AdoptedServer {
+        url: url.to_string(),
+        version: health.version,
+        requires_auth,
+        client_id,
+    })
+}
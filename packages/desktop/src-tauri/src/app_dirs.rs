@@ -0,0 +1,228 @@
+//! Every directory the app keeps its own files in, resolved the same way as Tauri's path
+//! resolver so it can be used before the app is built, e.g. to read display settings in `main`.
+
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+// Matches `identifier` in `tauri.conf.json` and `tauri.prod.conf.json`
+const IDENTIFIER: &str = if cfg!(debug_assertions) {
+    "ai.opencode.desktop.dev"
+} else {
+    "ai.opencode.desktop"
+};
+// The other build's identifier, whose settings a first launch carries over
+const OTHER_IDENTIFIER: &str = if cfg!(debug_assertions) {
+    "ai.opencode.desktop"
+} else {
+    "ai.opencode.desktop.dev"
+};
+
+// Settings stores live in `config` and `data`, which the webview also resolves on its own, so
+// only the directories nothing else looks up can be moved
+const LOG_DIR_ENV: &str = "OPENCODE_DESKTOP_LOG_DIR";
+const CACHE_DIR_ENV: &str = "OPENCODE_DESKTOP_CACHE_DIR";
+const STATE_DIR_ENV: &str = "OPENCODE_DESKTOP_STATE_DIR";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AppDirs {
+    pub config: PathBuf,
+    /// Holds the settings stores.
+    pub data: PathBuf,
+    pub logs: PathBuf,
+    pub cache: PathBuf,
+    pub state: PathBuf,
+}
+
+// The platform's base directories, from `dirs`
+struct BaseDirs {
+    home: PathBuf,
+    config: PathBuf,
+    data: PathBuf,
+    data_local: PathBuf,
+    cache: PathBuf,
+    state: Option<PathBuf>,
+}
+
+impl BaseDirs {
+    fn capture() -> Option<Self> {
+        Some(Self {
+            home: dirs::home_dir()?,
+            config: dirs::config_dir()?,
+            data: dirs::data_dir()?,
+            data_local: dirs::data_local_dir()?,
+            cache: dirs::cache_dir()?,
+            state: dirs::state_dir(),
+        })
+    }
+}
+
+impl AppDirs {
+    /// The directories for this build, with overrides from the environment applied. `None` when
+    /// the home directory can't be determined.
+    pub fn get() -> Option<&'static AppDirs> {
+        static DIRS: OnceLock<Option<AppDirs>> = OnceLock::new();
+        DIRS.get_or_init(|| {
+            let base = BaseDirs::capture()?;
+            Some(Self::resolve(IDENTIFIER, &base, |key| {
+                std::env::var_os(key)
+            }))
+        })
+        .as_ref()
+    }
+
+    fn resolve(identifier: &str, base: &BaseDirs, env: impl Fn(&str) -> Option<OsString>) -> Self {
+        let dir = |key: &str, default: PathBuf| {
+            env(key)
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+                .unwrap_or(default)
+        };
+
+        let logs = if cfg!(target_os = "macos") {
+            base.home.join("Library").join("Logs").join(identifier)
+        } else {
+            base.data_local.join(identifier).join("logs")
+        };
+        let state = base
+            .state
+            .as_ref()
+            .unwrap_or(&base.data_local)
+            .join(identifier);
+
+        Self {
+            config: base.config.join(identifier),
+            data: base.data.join(identifier),
+            logs: dir(LOG_DIR_ENV, logs),
+            cache: dir(CACHE_DIR_ENV, base.cache.join(identifier)),
+            state: dir(STATE_DIR_ENV, state),
+        }
+    }
+}
+
+/// Carries settings over from the other build on its first launch, e.g. when a dev build is run
+/// after using a release. The other build's files are copied, so both keep working.
+pub fn migrate() {
+    let Some(base) = BaseDirs::capture() else {
+        return;
+    };
+    let current = AppDirs::resolve(IDENTIFIER, &base, |_| None);
+    let previous = AppDirs::resolve(OTHER_IDENTIFIER, &base, |_| None);
+
+    for (to, from) in [
+        (&current.config, &previous.config),
+        (&current.data, &previous.data),
+    ] {
+        if to.exists() || !from.is_dir() {
+            continue;
+        }
+
+        match copy_dir(from, to) {
+            Ok(()) => eprintln!("Migrated {} to {}", from.display(), to.display()),
+            Err(e) => {
+                eprintln!("Failed to migrate {}: {e}", from.display());
+                // A partial copy would stop the migration from being retried
+                let _ = std::fs::remove_dir_all(to);
+            }
+        }
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// The database of the `opencode` CLI, which the sidecar shares.
+pub fn opencode_db() -> Result<PathBuf, &'static str> {
+    let xdg_data_home = crate::dev_profile::data_home()
+        .map(PathBuf::into_os_string)
+        .or_else(|| std::env::var_os("XDG_DATA_HOME").filter(|v| !v.is_empty()));
+
+    let data_home = match xdg_data_home {
+        Some(v) => PathBuf::from(v),
+        None => {
+            let home = dirs::home_dir().ok_or("cannot determine home directory")?;
+            home.join(".local").join("share")
+        }
+    };
+
+    Ok(data_home.join("opencode").join("opencode.db"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> BaseDirs {
+        BaseDirs {
+            home: PathBuf::from("/home/me"),
+            config: PathBuf::from("/home/me/.config"),
+            data: PathBuf::from("/home/me/.local/share"),
+            data_local: PathBuf::from("/home/me/.local/share"),
+            cache: PathBuf::from("/home/me/.cache"),
+            state: Some(PathBuf::from("/home/me/.local/state")),
+        }
+    }
+
+    #[test]
+    fn resolves_under_identifier() {
+        let dirs = AppDirs::resolve("ai.opencode.desktop", &base(), |_| None);
+        assert_eq!(
+            dirs.config,
+            Path::new("/home/me/.config/ai.opencode.desktop")
+        );
+        assert_eq!(
+            dirs.data,
+            Path::new("/home/me/.local/share/ai.opencode.desktop")
+        );
+        assert_eq!(dirs.cache, Path::new("/home/me/.cache/ai.opencode.desktop"));
+        assert_eq!(
+            dirs.state,
+            Path::new("/home/me/.local/state/ai.opencode.desktop")
+        );
+    }
+
+    #[test]
+    fn applies_overrides() {
+        let dirs = AppDirs::resolve("ai.opencode.desktop", &base(), |key| match key {
+            LOG_DIR_ENV => Some("/tmp/logs".into()),
+            CACHE_DIR_ENV => Some("".into()),
+            _ => None,
+        });
+        assert_eq!(dirs.logs, Path::new("/tmp/logs"));
+        assert_eq!(dirs.cache, Path::new("/home/me/.cache/ai.opencode.desktop"));
+    }
+
+    #[test]
+    fn copies_nested_dirs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let from = tmp.path().join("from");
+        std::fs::create_dir_all(from.join("nested")).unwrap();
+        std::fs::write(from.join("opencode.settings.dat"), "{}").unwrap();
+        std::fs::write(from.join("nested").join("file"), "x").unwrap();
+
+        let to = tmp.path().join("to");
+        copy_dir(&from, &to).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(to.join("opencode.settings.dat")).unwrap(),
+            "{}"
+        );
+        assert_eq!(
+            std::fs::read_to_string(to.join("nested").join("file")).unwrap(),
+            "x"
+        );
+        assert!(from.join("opencode.settings.dat").exists());
+    }
+}
@@ -0,0 +1,41 @@
+use tauri::{AppHandle, Manager, UserAttentionType};
+
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AttentionLevel {
+    /// Bounces the dock icon once, or flashes the taskbar button until the window is focused.
+    Informational,
+    /// Keeps bouncing the dock icon until the app is activated.
+    Critical,
+}
+
+impl From<AttentionLevel> for UserAttentionType {
+    fn from(level: AttentionLevel) -> Self {
+        match level {
+            AttentionLevel::Informational => Self::Informational,
+            AttentionLevel::Critical => Self::Critical,
+        }
+    }
+}
+
+/// Asks the OS to draw attention to window `label`, e.g. when an agent finishes or needs
+/// approval. Does nothing while the window is focused; `None` cancels an earlier request.
+#[tauri::command]
+#[specta::specta]
+pub fn request_attention(
+    app: AppHandle,
+    label: String,
+    level: Option<AttentionLevel>,
+) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("No window with label '{}'", label))?;
+
+    if level.is_some() && window.is_focused().unwrap_or(false) {
+        return Ok(());
+    }
+
+    window
+        .request_user_attention(level.map(Into::into))
+        .map_err(|e| format!("Failed to request attention: {}", e))
+}
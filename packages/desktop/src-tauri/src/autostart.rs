@@ -0,0 +1,284 @@
+//! Launching the app at login: a launch agent on macOS, a `Run` registry value on Windows and an
+//! XDG autostart entry on Linux. A hidden start is a warm-up launch, which stays in the tray
+//! without a window until one is summoned.
+
+use std::path::Path;
+
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::{
+    constants::{AUTOSTART_KEY, SETTINGS_STORE, WARM_UP_KEY},
+    warmup::WARM_UP_ARG,
+};
+
+#[cfg(any(target_os = "macos", test))]
+const LAUNCHD_LABEL: &str = "ai.opencode.desktop.autostart";
+#[cfg(target_os = "macos")]
+const LEGACY_LAUNCHD_LABEL: &str = "ai.opencode.desktop.warm-up";
+#[cfg(target_os = "linux")]
+const AUTOSTART_FILE: &str = "opencode-autostart.desktop";
+#[cfg(target_os = "linux")]
+const LEGACY_AUTOSTART_FILE: &str = "opencode-warm-up.desktop";
+#[cfg(windows)]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+#[cfg(windows)]
+const RUN_VALUE: &str = "OpenCode";
+#[cfg(windows)]
+const LEGACY_RUN_VALUE: &str = "OpenCode Warm-up";
+
+#[derive(
+    Clone, Copy, Default, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq,
+)]
+#[serde(default)]
+pub struct AutostartState {
+    pub enabled: bool,
+    /// Start in the tray with the server running, and open no window until one is asked for.
+    pub hidden: bool,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_autostart_state(app: AppHandle) -> Result<AutostartState, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    if let Some(state) = store
+        .get(AUTOSTART_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+    {
+        return Ok(state);
+    }
+
+    // Warm-up was the only way to start at login before, and always hidden
+    let warm_up = store
+        .get(WARM_UP_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or(false);
+    Ok(AutostartState {
+        enabled: warm_up,
+        hidden: warm_up,
+    })
+}
+
+/// Starts the app when the user logs in, in the tray without a window when `hidden`.
+#[tauri::command]
+#[specta::specta]
+pub fn enable_autostart(app: AppHandle, hidden: bool) -> Result<(), String> {
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to determine app executable: {}", e))?;
+    unregister_legacy()?;
+    register(&exe, args(hidden))?;
+
+    save(
+        &app,
+        AutostartState {
+            enabled: true,
+            hidden,
+        },
+    )
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn disable_autostart(app: AppHandle) -> Result<(), String> {
+    unregister_legacy()?;
+    unregister()?;
+
+    save(&app, AutostartState::default())
+}
+
+fn save(app: &AppHandle, state: AutostartState) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(AUTOSTART_KEY, serde_json::json!(state));
+    store.delete(WARM_UP_KEY);
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+fn args(hidden: bool) -> &'static [&'static str] {
+    if hidden { &[WARM_UP_ARG] } else { &[] }
+}
+
+#[cfg(target_os = "macos")]
+fn launch_agent_path(label: &str) -> Result<std::path::PathBuf, String> {
+    let home = dirs::home_dir().ok_or_else(|| "Could not determine home directory".to_string())?;
+    Ok(home
+        .join("Library")
+        .join("LaunchAgents")
+        .join(format!("{label}.plist")))
+}
+
+#[cfg(target_os = "macos")]
+fn register(exe: &Path, args: &[&str]) -> Result<(), String> {
+    let path = launch_agent_path(LAUNCHD_LABEL)?;
+    write(&path, &launch_agent(&exe.to_string_lossy(), args))
+}
+
+#[cfg(target_os = "macos")]
+fn unregister() -> Result<(), String> {
+    remove(&launch_agent_path(LAUNCHD_LABEL)?)
+}
+
+#[cfg(target_os = "macos")]
+fn unregister_legacy() -> Result<(), String> {
+    remove(&launch_agent_path(LEGACY_LAUNCHD_LABEL)?)
+}
+
+#[cfg(target_os = "linux")]
+fn autostart_path(file: &str) -> Result<std::path::PathBuf, String> {
+    let config_dir =
+        dirs::config_dir().ok_or_else(|| "Could not determine config directory".to_string())?;
+    Ok(config_dir.join("autostart").join(file))
+}
+
+#[cfg(target_os = "linux")]
+fn register(exe: &Path, args: &[&str]) -> Result<(), String> {
+    write(
+        &autostart_path(AUTOSTART_FILE)?,
+        &autostart_entry(&exe.to_string_lossy(), args),
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn unregister() -> Result<(), String> {
+    remove(&autostart_path(AUTOSTART_FILE)?)
+}
+
+#[cfg(target_os = "linux")]
+fn unregister_legacy() -> Result<(), String> {
+    remove(&autostart_path(LEGACY_AUTOSTART_FILE)?)
+}
+
+#[cfg(windows)]
+fn register(exe: &Path, args: &[&str]) -> Result<(), String> {
+    let mut command = format!("\"{}\"", exe.display());
+    for arg in args {
+        command.push(' ');
+        command.push_str(arg);
+    }
+    crate::service::run(
+        "reg",
+        &[
+            "add", RUN_KEY, "/v", RUN_VALUE, "/t", "REG_SZ", "/d", &command, "/f",
+        ],
+    )
+}
+
+#[cfg(windows)]
+fn unregister() -> Result<(), String> {
+    // Deleting a value that doesn't exist fails, which is fine here
+    let _ = crate::service::run("reg", &["delete", RUN_KEY, "/v", RUN_VALUE, "/f"]);
+    Ok(())
+}
+
+#[cfg(windows)]
+fn unregister_legacy() -> Result<(), String> {
+    let _ = crate::service::run("reg", &["delete", RUN_KEY, "/v", LEGACY_RUN_VALUE, "/f"]);
+    Ok(())
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn write(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    std::fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn remove(path: &Path) -> Result<(), String> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Failed to remove {}: {}", path.display(), e))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(any(target_os = "macos", test))]
+fn launch_agent(exe: &str, args: &[&str]) -> String {
+    let arguments = std::iter::once(exe)
+        .chain(args.iter().copied())
+        .map(|arg| format!("\t\t<string>{}</string>\n", crate::service::xml_escape(arg)))
+        .collect::<String>();
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>Label</key>
+	<string>{LAUNCHD_LABEL}</string>
+	<key>ProgramArguments</key>
+	<array>
+{arguments}	</array>
+	<key>RunAtLoad</key>
+	<true/>
+</dict>
+</plist>
+"#
+    )
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn autostart_entry(exe: &str, args: &[&str]) -> String {
+    let mut exec = desktop_exec_quote(exe);
+    for arg in args {
+        exec.push(' ');
+        exec.push_str(arg);
+    }
+    format!(
+        "[Desktop Entry]\nType=Application\nName=OpenCode\nExec={exec}\nNoDisplay=true\nX-GNOME-Autostart-enabled=true\n"
+    )
+}
+
+// Quoting rules of the Exec key in the desktop entry spec
+#[cfg(any(target_os = "linux", test))]
+fn desktop_exec_quote(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    // The whole value is unescaped once more as a string, doubling backslashes again
+    quoted.replace('\\', "\\\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn launch_agent_escapes_path() {
+        let plist = launch_agent(
+            "/Applications/R&D/OpenCode.app/Contents/MacOS/OpenCode",
+            args(true),
+        );
+        assert!(plist.contains(
+            "<string>/Applications/R&amp;D/OpenCode.app/Contents/MacOS/OpenCode</string>"
+        ));
+        assert!(plist.contains("<string>--warm-up</string>"));
+        assert!(!launch_agent("/opencode", args(false)).contains("--warm-up"));
+    }
+
+    #[test]
+    fn autostart_entry_quotes_exec() {
+        assert!(
+            autostart_entry("/opt/Open Code/opencode", args(true))
+                .contains("Exec=\"/opt/Open Code/opencode\" --warm-up\n")
+        );
+        assert!(autostart_entry("/opt/opencode", args(false)).contains("Exec=\"/opt/opencode\"\n"));
+        assert_eq!(desktop_exec_quote("/a/$b\"c"), r#""/a/\\$b\\"c""#);
+    }
+}
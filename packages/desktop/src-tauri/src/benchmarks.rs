@@ -0,0 +1,162 @@
+use std::time::Instant;
+
+use tauri::{AppHandle, Emitter};
+
+use crate::markdown::parse_markdown;
+
+const EVENT_COUNT: u32 = 2_000;
+const MARKDOWN_ITERATIONS: u32 = 20;
+// Emitted to every webview, which ignore it, so only the emit path is measured
+const BENCHMARK_EVENT: &str = "benchmark-ping";
+
+const MARKDOWN_SAMPLE: &str = r#"## Changes
+
+Updated the **parser** to handle `nested` lists, see [the docs](https://opencode.ai/docs).
+
+- [x] First item with *emphasis*
+- [ ] Second item
+  - Nested item with ~~strikethrough~~
+
+| File | Lines |
+| ---- | ----: |
+| src/lib.rs | 120 |
+| src/cli.rs | 2048 |
+
+```rust
+fn main() {
+    println!("hello");
+}
+```
+
+> A quote that spans
+> multiple lines.
+
+"#;
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug, PartialEq)]
+pub struct LatencyStats {
+    pub samples: u32,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Performance of the shell on this machine, attached to performance bug reports.
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+pub struct BenchmarkReport {
+    pub os: String,
+    pub arch: String,
+    pub cpus: u32,
+    /// Round trips of `benchmark_echo` as timed by the caller.
+    pub invoke: Option<LatencyStats>,
+    pub events_per_sec: f64,
+    pub markdown_mb_per_sec: f64,
+}
+
+/// Returns `payload` unchanged, so the frontend can time invoke round trips.
+#[tauri::command]
+#[specta::specta]
+pub fn benchmark_echo(payload: String) -> String {
+    payload
+}
+
+/// Measures event emission and markdown rendering throughput. The webview can't be timed from
+/// here, so invoke latency is computed from `invoke_samples_ms` the caller collected.
+#[tauri::command]
+#[specta::specta]
+pub async fn run_benchmarks(
+    app: AppHandle,
+    invoke_samples_ms: Vec<f64>,
+) -> Result<BenchmarkReport, String> {
+    let start = Instant::now();
+    for i in 0..EVENT_COUNT {
+        app.emit(BENCHMARK_EVENT, i)
+            .map_err(|e| format!("Failed to emit event: {}", e))?;
+    }
+    let events_per_sec = f64::from(EVENT_COUNT) / start.elapsed().as_secs_f64();
+
+    let markdown_mb_per_sec = tokio::task::spawn_blocking(markdown_throughput)
+        .await
+        .map_err(|e| format!("Markdown benchmark failed: {}", e))?;
+
+    let report = BenchmarkReport {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        cpus: std::thread::available_parallelism().map_or(1, |n| n.get() as u32),
+        invoke: latency_stats(&invoke_samples_ms),
+        events_per_sec,
+        markdown_mb_per_sec,
+    };
+
+    tracing::info!(?report, "Ran benchmarks");
+    Ok(report)
+}
+
+fn markdown_throughput() -> f64 {
+    // Roughly the size of a long assistant message
+    let input = MARKDOWN_SAMPLE.repeat(100);
+
+    let start = Instant::now();
+    for _ in 0..MARKDOWN_ITERATIONS {
+        std::hint::black_box(parse_markdown(std::hint::black_box(&input)));
+    }
+
+    let mb = (input.len() as f64 * f64::from(MARKDOWN_ITERATIONS)) / 1_000_000.0;
+    mb / start.elapsed().as_secs_f64()
+}
+
+fn latency_stats(samples: &[f64]) -> Option<LatencyStats> {
+    let mut sorted = samples
+        .iter()
+        .copied()
+        .filter(|sample| sample.is_finite() && *sample >= 0.0)
+        .collect::<Vec<_>>();
+    if sorted.is_empty() {
+        return None;
+    }
+    sorted.sort_by(f64::total_cmp);
+
+    // Nearest-rank percentiles
+    let percentile =
+        |p: f64| sorted[((p * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len()) - 1];
+
+    Some(LatencyStats {
+        samples: sorted.len() as u32,
+        min_ms: sorted[0],
+        median_ms: percentile(0.5),
+        p95_ms: percentile(0.95),
+        max_ms: sorted[sorted.len() - 1],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_latency_percentiles() {
+        let samples = (1..=100).rev().map(f64::from).collect::<Vec<_>>();
+
+        assert_eq!(
+            latency_stats(&samples),
+            Some(LatencyStats {
+                samples: 100,
+                min_ms: 1.0,
+                median_ms: 50.0,
+                p95_ms: 95.0,
+                max_ms: 100.0,
+            })
+        );
+    }
+
+    #[test]
+    fn ignores_invalid_samples() {
+        assert_eq!(latency_stats(&[]), None);
+        assert_eq!(latency_stats(&[f64::NAN, -1.0]), None);
+        assert_eq!(
+            latency_stats(&[2.0, f64::INFINITY]).map(|stats| stats.samples),
+            Some(1)
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! Teardown for resources that must not outlive the app, such as the sidecar, CLI processes or
+//! port forwarding rules. Each is registered by the code owning the resource and run from one
+//! place: all of them when the app exits, and the ones tied to the server connection when
+//! `reinitialize` resets it. Hooks run in the order they were registered, each with a time
+//! limit, so one that hangs can't keep the others from running or the app from exiting.
+
+use std::{future::Future, sync::Mutex, time::Duration};
+
+use futures::future::BoxFuture;
+use tauri::{AppHandle, Manager};
+use tokio::time::timeout;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scope {
+    /// Runs when the server connection is reset, and when the app exits.
+    Session,
+    /// Runs when the app exits.
+    App,
+}
+
+type Hook = Box<dyn Fn(AppHandle) -> BoxFuture<'static, ()> + Send + Sync>;
+
+struct Entry {
+    name: &'static str,
+    scope: Scope,
+    limit: Duration,
+    hook: Hook,
+}
+
+#[derive(Default)]
+pub struct CleanupHooks(Mutex<Vec<Entry>>);
+
+/// Runs `hook` when `scope` ends, giving up on it after `limit`.
+pub fn register<F, Fut>(app: &AppHandle, name: &'static str, scope: Scope, limit: Duration, hook: F)
+where
+    F: Fn(AppHandle) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let Some(hooks) = app.try_state::<CleanupHooks>() else {
+        tracing::warn!(name, "Cleanup hook registered before the registry exists");
+        return;
+    };
+    hooks.0.lock().unwrap().push(Entry {
+        name,
+        scope,
+        limit,
+        hook: Box::new(move |app| Box::pin(hook(app))),
+    });
+}
+
+/// Runs the hooks for the end of `scope`, in the order they were registered.
+pub async fn run(app: &AppHandle, scope: Scope) {
+    let Some(hooks) = app.try_state::<CleanupHooks>() else {
+        return;
+    };
+    // The lock isn't held while hooks run, as one may register another
+    let pending = hooks
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|entry| runs_in(entry.scope, scope))
+        .map(|entry| (entry.name, entry.limit, (entry.hook)(app.clone())))
+        .collect::<Vec<_>>();
+
+    for (name, limit, hook) in pending {
+        tracing::debug!(name, ?scope, "Running cleanup hook");
+        if timeout(limit, hook).await.is_err() {
+            tracing::warn!(name, ?limit, "Cleanup hook timed out");
+        }
+    }
+}
+
+fn runs_in(hook: Scope, ending: Scope) -> bool {
+    ending == Scope::App || hook == Scope::Session
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exiting_runs_every_hook() {
+        assert!(runs_in(Scope::Session, Scope::App));
+        assert!(runs_in(Scope::App, Scope::App));
+        assert!(runs_in(Scope::Session, Scope::Session));
+        assert!(!runs_in(Scope::App, Scope::Session));
+    }
+}
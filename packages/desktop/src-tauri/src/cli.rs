@@ -6,179 +6,1637 @@ use process_wrap::tokio::ProcessGroup;
 use process_wrap::tokio::{JobObject, KillOnDrop};
 #[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
-use std::{process::Stdio, time::Duration};
-use tauri::{AppHandle, Manager, path::BaseDirectory};
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+use tauri::{AppHandle, Manager, ipc::Channel, path::BaseDirectory};
 use tauri_plugin_store::StoreExt;
 use tauri_specta::Event;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::Command;
 use tokio::sync::{mpsc, oneshot};
-use tokio_stream::wrappers::ReceiverStream;
 use tracing::Instrument;
 
-use crate::constants::{SETTINGS_STORE, WSL_ENABLED_KEY};
+use crate::{
+    app_dirs::AppDirs,
+    constants::{
+        CLI_CHANNEL_KEY, CLI_INSTALL_DIR_KEY, CLI_PINNED_VERSION_KEY, CLI_TAKE_OVER_KEY,
+        SETTINGS_STORE, WSL_ENABLED_KEY,
+    },
+    dev_profile, env_policy, fs_boundary,
+    init_progress::{self, InitProgress},
+    install_lock, integrity, limits,
+    output::CommandOutput,
+    package_manager::PackageManager,
+    power,
+    process_registry::ProcessRegistry,
+    project,
+    recorder::{self, ShellEventKind},
+    releases::{self, CliChannel},
+    shell, sidecar,
+    slow_startup::{self, Stage},
+    wsl_script::WslScript,
+    wsl_setup,
+};
 
 const CLI_INSTALL_DIR: &str = ".opencode/bin";
 const CLI_BINARY_NAME: &str = "opencode";
+const CLI_VERSION_TIMEOUT: Duration = Duration::from_secs(10);
+const CONFIG_CACHE_FILE: &str = "cli-config-cache.json";
+const EXIT_OUTPUT_LINES: usize = 50;
+const OUTPUT_BUFFER_BYTES: usize = 256 * 1024;
+// Writes queued for a command's stdin before `write_stdin` starts failing
+const STDIN_BUFFER: usize = 32;
+/// Exit code of the install script when `--verify-checksum` finds the downloaded archive doesn't
+/// match the release's published sha256.
+pub const INSTALL_VERIFY_EXIT_CODE: i32 = 97;
+/// Exit code used by the WSL launch script when the CLI isn't installed in the distro and the
+/// user hasn't agreed to installing it.
+pub const WSL_CLI_MISSING_EXIT_CODE: i32 = 98;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct ServerConfig {
+    pub hostname: Option<String>,
+    pub port: Option<u32>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct Config {
+    pub server: Option<ServerConfig>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ConfigCache {
+    key: String,
+    config: Config,
+}
+
+#[derive(Clone, Debug)]
+pub enum CommandEvent {
+    Stdout(Vec<u8>),
+    Stderr(Vec<u8>),
+    Error(String),
+    Terminated(TerminatedPayload),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TerminatedPayload {
+    pub code: Option<i32>,
+    pub signal: Option<i32>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SidecarExit {
+    pub payload: TerminatedPayload,
+    /// The last lines the sidecar printed before exiting.
+    pub output: Vec<String>,
+}
+
+/// Why the sidecar exited before becoming healthy, so the frontend can suggest a fix.
+#[derive(Clone, serde::Serialize, specta::Type, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SidecarFailure {
+    PortInUse {
+        port: Option<u32>,
+    },
+    MissingSharedLibrary {
+        library: Option<String>,
+    },
+    ConfigParseError {
+        detail: String,
+    },
+    CrashedWithSignal {
+        signal: i32,
+    },
+    AuthMisconfigured,
+    /// WSL mode is on but the CLI still has to be installed with `install_cli_wsl`.
+    WslCliMissing,
+    /// The CLI release downloaded into WSL didn't match its published sha256.
+    ChecksumMismatch,
+    /// The sidecar couldn't be started at all. `issues` lists what's wrong with the bundled
+    /// binary, which `repair_sidecar` can fix.
+    SpawnFailed {
+        error: String,
+        issues: Vec<sidecar::SidecarIssue>,
+    },
+    Unknown {
+        code: Option<i32>,
+    },
+}
+
+// STATUS_DLL_NOT_FOUND, reported when Windows can't load a DLL the binary links against
+const WINDOWS_DLL_NOT_FOUND: i32 = 0xC000_0135_u32 as i32;
+
+impl SidecarFailure {
+    pub fn classify(exit: &SidecarExit) -> Self {
+        let lines = || exit.output.iter().map(|line| line.trim());
+
+        if exit.payload.code == Some(WSL_CLI_MISSING_EXIT_CODE) {
+            return Self::WslCliMissing;
+        }
+
+        if exit.payload.code == Some(INSTALL_VERIFY_EXIT_CODE) {
+            return Self::ChecksumMismatch;
+        }
+
+        if let Some(line) = lines().find(|line| {
+            line.contains("EADDRINUSE")
+                || line.contains("address already in use")
+                || (line.contains("port") && line.contains("in use"))
+        }) {
+            return Self::PortInUse {
+                port: number_after(line, "port "),
+            };
+        }
+
+        if let Some(line) = lines().find(|line| {
+            line.contains("error while loading shared libraries")
+                || line.contains("Library not loaded")
+        }) {
+            let library = ["shared libraries: ", "Library not loaded: "]
+                .into_iter()
+                .find_map(|marker| line.split_once(marker))
+                .and_then(|(_, rest)| rest.split(':').next())
+                .map(|library| library.trim().to_string());
+            return Self::MissingSharedLibrary { library };
+        }
+
+        if exit.payload.code == Some(WINDOWS_DLL_NOT_FOUND) {
+            return Self::MissingSharedLibrary { library: None };
+        }
+
+        if let Some(line) = lines().find(|line| {
+            line.contains("ConfigJsonError")
+                || line.contains("ConfigInvalidError")
+                || line.contains("JSON Parse error")
+        }) {
+            return Self::ConfigParseError {
+                detail: line.to_string(),
+            };
+        }
+
+        if lines().any(|line| {
+            line.contains("OPENCODE_SERVER_PASSWORD")
+                || line.contains("OPENCODE_SERVER_USERNAME")
+                || line.contains("Unauthorized")
+        }) {
+            return Self::AuthMisconfigured;
+        }
+
+        if let Some(signal) = exit.payload.signal {
+            return Self::CrashedWithSignal { signal };
+        }
+
+        Self::Unknown {
+            code: exit.payload.code,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Self::PortInUse { port: Some(port) } => format!("Port {port} is already in use"),
+            Self::PortInUse { port: None } => "The server port is already in use".to_string(),
+            Self::MissingSharedLibrary {
+                library: Some(library),
+            } => format!("A required shared library is missing: {library}"),
+            Self::MissingSharedLibrary { library: None } => {
+                "A required shared library is missing".to_string()
+            }
+            Self::ConfigParseError { detail } => format!("Failed to parse config: {detail}"),
+            Self::CrashedWithSignal { signal } => format!("Sidecar crashed with signal {signal}"),
+            Self::AuthMisconfigured => "Server authentication is misconfigured".to_string(),
+            Self::WslCliMissing => "opencode isn't installed in WSL yet".to_string(),
+            Self::ChecksumMismatch => {
+                "The downloaded opencode release failed checksum verification".to_string()
+            }
+            Self::SpawnFailed { error, issues } if issues.is_empty() => {
+                format!("Failed to start the sidecar: {error}")
+            }
+            Self::SpawnFailed { error, issues } => format!(
+                "Failed to start the sidecar: {error} ({})",
+                sidecar::describe(issues)
+            ),
+            Self::Unknown { code } => format!("Sidecar exited with code {code:?}"),
+        }
+    }
+}
+
+fn number_after(line: &str, marker: &str) -> Option<u32> {
+    let (_, rest) = line.split_once(marker)?;
+    let digits = rest
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>();
+    digits.parse().ok()
+}
+
+#[derive(Clone, Debug)]
+pub struct CommandChild {
+    id: Option<u32>,
+    kill: mpsc::Sender<()>,
+    stdin: Option<mpsc::Sender<Vec<u8>>>,
+    pid: Option<u32>,
+    output: CommandOutput,
+}
+
+impl CommandChild {
+    /// Id in the `ProcessRegistry`, for `kill_child` and `write_stdin`.
+    pub fn id(&self) -> Option<u32> {
+        self.id
+    }
+
+    /// Process id of the spawned shell, which also leads its process group on unix.
+    pub fn pid(&self) -> Option<u32> {
+        self.pid
+    }
+
+    /// Independent view of the command's output, starting with whatever is still buffered.
+    pub fn subscribe(&self) -> impl Stream<Item = CommandEvent> + 'static {
+        self.output.subscribe()
+    }
+
+    pub fn kill(&self) -> std::io::Result<()> {
+        self.kill
+            .try_send(())
+            .map_err(|e| std::io::Error::other(e.to_string()))
+    }
+
+    /// Queues `data` for the command's stdin. Fails if it was spawned without one or has
+    /// stopped reading.
+    pub fn write_stdin(&self, data: Vec<u8>) -> std::io::Result<()> {
+        self.stdin
+            .as_ref()
+            .ok_or_else(|| std::io::Error::other("Command was spawned without stdin"))?
+            .try_send(data)
+            .map_err(|e| std::io::Error::other(e.to_string()))
+    }
+}
+
+pub async fn get_config(app: &AppHandle) -> Option<Config> {
+    let key = config_cache_key(app);
+
+    if let Some(config) = read_config_cache(&key) {
+        tracing::debug!("Using cached CLI config");
+        return Some(config);
+    }
+
+    let cache = ConfigCache {
+        key,
+        config: read_config(app).await?,
+    };
+    write_config_cache(&cache);
+
+    Some(cache.config)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn refresh_cli_config(app: AppHandle) -> Result<(), String> {
+    if let Some(path) = config_cache_path() {
+        let _ = std::fs::remove_file(path);
+    }
+
+    get_config(&app)
+        .await
+        .map(|_| ())
+        .ok_or_else(|| "Failed to read CLI config".to_string())
+}
+
+// Read in the active project, like the sidecar, so its project config is included
+async fn read_config(app: &AppHandle) -> Option<Config> {
+    let project = project::active_project(app);
+    let (events, _) = spawn_command(app, "debug config", &[], project.as_deref(), false).ok()?;
+
+    events
+        .fold(String::new(), async |mut config_str, event| {
+            if let CommandEvent::Stdout(stdout) = event
+                && let Ok(s) = str::from_utf8(&stdout)
+            {
+                config_str += s
+            }
+
+            config_str
+        })
+        .map(|v| serde_json::from_str::<Config>(&v))
+        .await
+        .ok()
+}
+
+fn config_cache_path() -> Option<PathBuf> {
+    Some(AppDirs::get()?.data.join(CONFIG_CACHE_FILE))
+}
+
+fn config_files(project: Option<&Path>) -> Vec<PathBuf> {
+    let mut files = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config")))
+        .map(|dir| {
+            ["config.json", "opencode.json", "opencode.jsonc"]
+                .map(|name| dir.join("opencode").join(name))
+                .to_vec()
+        })
+        .unwrap_or_default();
+
+    if let Some(path) = std::env::var_os("OPENCODE_CONFIG") {
+        files.push(PathBuf::from(path));
+    }
+    if let Some(project) = project {
+        files.extend(project_config_files(project));
+    }
+
+    files
+}
+
+// The CLI looks for project config from the project up to its git worktree root, or up to the
+// filesystem root outside a repository.
+fn project_config_files(project: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for dir in project.ancestors() {
+        for name in ["opencode.json", "opencode.jsonc"] {
+            files.push(dir.join(name));
+            files.push(dir.join(".opencode").join(name));
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    files
+}
+
+// The cache is keyed by the size and mtime of every config file the CLI would read,
+// plus anything else that changes which CLI answers `debug config`. Hashed with sha256 since
+// the key is kept across app updates, which may change the standard library's hasher.
+fn config_cache_key(app: &AppHandle) -> String {
+    let project = project::active_project(app);
+    let mut parts = vec![
+        app.package_info().version.to_string(),
+        project::uses_wsl(app, project.as_deref()).to_string(),
+        wsl_setup::distro_args(app).join(" "),
+        format!("{:?}", project),
+    ];
+
+    for path in config_files(project.as_deref()) {
+        let meta = std::fs::metadata(&path).ok();
+        parts.push(format!(
+            "{}:{:?}:{:?}",
+            path.display(),
+            meta.as_ref().map(|meta| meta.len()),
+            meta.and_then(|meta| meta.modified().ok())
+        ));
+    }
+
+    integrity::sha256_hex(parts.join("\n").as_bytes())
+}
+
+fn read_config_cache(key: &str) -> Option<Config> {
+    let raw = std::fs::read_to_string(config_cache_path()?).ok()?;
+    let cache = serde_json::from_str::<ConfigCache>(&raw).ok()?;
+
+    (cache.key == key).then_some(cache.config)
+}
+
+fn write_config_cache(cache: &ConfigCache) {
+    if let Some(path) = config_cache_path()
+        && let Ok(raw) = serde_json::to_string(cache)
+    {
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        if let Err(e) = std::fs::write(&path, raw) {
+            tracing::warn!("Failed to write CLI config cache: {e}");
+        }
+    }
+}
+
+pub(crate) fn get_cli_install_path(app: &AppHandle) -> Option<std::path::PathBuf> {
+    let binary = if cfg!(windows) {
+        format!("{CLI_BINARY_NAME}.exe")
+    } else {
+        CLI_BINARY_NAME.to_string()
+    };
+
+    if let Some(dir) = get_custom_cli_install_dir(app) {
+        return Some(dir.join(binary));
+    }
+
+    if cfg!(windows) {
+        return dirs::data_local_dir().map(|dir| dir.join("opencode").join("bin").join(binary));
+    }
+
+    std::env::var("HOME").ok().map(|home| {
+        std::path::PathBuf::from(home)
+            .join(CLI_INSTALL_DIR)
+            .join(binary)
+    })
+}
+
+fn get_custom_cli_install_dir(app: &AppHandle) -> Option<PathBuf> {
+    app.store(SETTINGS_STORE)
+        .ok()?
+        .get(CLI_INSTALL_DIR_KEY)
+        .and_then(|v| v.as_str().map(PathBuf::from))
+        .filter(|dir| !dir.as_os_str().is_empty())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_cli_install_dir(app: AppHandle) -> Result<String, String> {
+    get_cli_install_path(&app)
+        .and_then(|path| path.parent().map(|dir| dir.to_string_lossy().to_string()))
+        .ok_or_else(|| "Could not determine install path".to_string())
+}
+
+/// Changes where the CLI is installed, moving an existing install to the new location.
+/// Passing `None` restores the default location.
+#[tauri::command]
+#[specta::specta]
+pub fn set_cli_install_dir(app: AppHandle, dir: Option<String>) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    let previous = store.get(CLI_INSTALL_DIR_KEY);
+    let old_path = get_cli_install_path(&app);
+
+    match &dir {
+        Some(dir) if !dir.trim().is_empty() => {
+            let dir = PathBuf::from(dir.trim());
+            if !dir.is_absolute() {
+                return Err("Install directory must be an absolute path".to_string());
+            }
+            store.set(
+                CLI_INSTALL_DIR_KEY,
+                serde_json::Value::String(dir.to_string_lossy().to_string()),
+            );
+        }
+        _ => {
+            store.delete(CLI_INSTALL_DIR_KEY);
+        }
+    }
+
+    let new_path = get_cli_install_path(&app);
+
+    let migrated = match (old_path, new_path) {
+        (Some(old), Some(new)) if old != new && old.exists() => migrate_cli_install(&old, &new),
+        _ => Ok(()),
+    };
+
+    if let Err(e) = migrated {
+        match previous {
+            Some(previous) => store.set(CLI_INSTALL_DIR_KEY, previous),
+            None => {
+                store.delete(CLI_INSTALL_DIR_KEY);
+            }
+        }
+        return Err(e);
+    }
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+fn migrate_cli_install(old: &std::path::Path, new: &std::path::Path) -> Result<(), String> {
+    tracing::info!(from = %old.display(), to = %new.display(), "Moving CLI install");
+
+    let dir = new
+        .parent()
+        .ok_or_else(|| "Could not determine install directory".to_string())?;
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create install directory: {}", e))?;
+
+    // Fall back to copying when the new location is on another filesystem.
+    if std::fs::rename(old, new).is_err() {
+        std::fs::copy(old, new).map_err(|e| format!("Failed to move CLI binary: {}", e))?;
+        let _ = std::fs::remove_file(old);
+    }
+
+    #[cfg(windows)]
+    add_to_user_path(dir)?;
+
+    Ok(())
+}
+
+pub fn get_sidecar_path(app: &tauri::AppHandle) -> std::path::PathBuf {
+    // Get binary with symlinks support
+    tauri::process::current_binary(&app.env())
+        .expect("Failed to get current binary")
+        .parent()
+        .expect("Failed to get parent dir")
+        .join("opencode-cli")
+}
+
+pub(crate) fn is_cli_installed(app: &AppHandle) -> bool {
+    get_cli_install_path(app)
+        .map(|path| path.exists())
+        .unwrap_or(false)
+}
+
+pub(crate) const INSTALL_SCRIPT: &str = include_str!("../../../../install");
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InstallProgress {
+    Started {
+        path: String,
+    },
+    /// A line printed by the install script, without terminal colors.
+    Output {
+        line: String,
+    },
+}
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+pub struct CliInstallResult {
+    pub path: String,
+    /// Shell config files the installer added the CLI's directory to `PATH` in.
+    pub modified_rc_files: Vec<String>,
+}
+
+// Lets `cancel_install_cli` stop the install that's currently running
+static INSTALL_CANCEL: std::sync::Mutex<Option<oneshot::Sender<()>>> = std::sync::Mutex::new(None);
+
+/// Installs the bundled sidecar as the `opencode` CLI, streaming the installer's output.
+#[tauri::command]
+#[specta::specta]
+pub async fn install_cli(
+    app: tauri::AppHandle,
+    events: Channel<InstallProgress>,
+) -> Result<CliInstallResult, String> {
+    let (cancel_tx, cancel_rx) = oneshot::channel();
+    {
+        let mut cancel = INSTALL_CANCEL.lock().unwrap();
+        if cancel.is_some() {
+            return Err("A CLI install is already running".to_string());
+        }
+        *cancel = Some(cancel_tx);
+    }
+
+    let on_progress = |progress| {
+        let _ = events.send(progress);
+    };
+    let result = tokio::select! {
+        result = install_bundled_cli(&app, &on_progress) => result,
+        Ok(()) = cancel_rx => Err("CLI install was cancelled".to_string()),
+    };
+
+    INSTALL_CANCEL.lock().unwrap().take();
+    result
+}
+
+/// Stops a running `install_cli`. The install script is killed and anything it staged is
+/// removed, so the previous CLI stays in place. On Windows the copy is quick and is left to
+/// finish.
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_install_cli() -> Result<(), String> {
+    let cancel = INSTALL_CANCEL
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| "No CLI install is running".to_string())?;
+
+    tracing::info!("Cancelling CLI install");
+    let _ = cancel.send(());
+    Ok(())
+}
+
+// Installs the bundled sidecar as the CLI
+async fn install_bundled_cli(
+    app: &AppHandle,
+    on_progress: &(dyn Fn(InstallProgress) + Sync),
+) -> Result<CliInstallResult, String> {
+    let sidecar = get_sidecar_path(app);
+    if !sidecar.exists() {
+        return Err("Sidecar binary not found".to_string());
+    }
+
+    let install_path =
+        get_cli_install_path(app).ok_or_else(|| "Could not determine install path".to_string())?;
+    let path = install_path.to_string_lossy().to_string();
+    on_progress(InstallProgress::Started { path: path.clone() });
+
+    #[cfg(windows)]
+    let output: Vec<String> = {
+        tauri::async_runtime::spawn_blocking({
+            let app = app.clone();
+            move || install_cli_windows(&app, &sidecar)
+        })
+        .await
+        .map_err(|e| format!("CLI install task failed: {}", e))??;
+        vec![]
+    };
+
+    #[cfg(not(windows))]
+    let output = run_install_script(
+        app,
+        &[std::ffi::OsStr::new("--binary"), sidecar.as_os_str()],
+        &|line| on_progress(InstallProgress::Output { line }),
+    )
+    .await?;
+
+    Ok(CliInstallResult {
+        path,
+        modified_rc_files: output
+            .iter()
+            .filter_map(|line| added_to_path_in(line))
+            .map(String::from)
+            .collect(),
+    })
+}
+
+// The install script reports each shell config file it adds the install directory to
+fn added_to_path_in(line: &str) -> Option<&str> {
+    line.strip_prefix("Successfully added opencode to $PATH in ")
+        .map(str::trim)
+        .filter(|file| !file.is_empty())
+}
+
+// Keeps only what a terminal would end up showing: the text after the last carriage return
+// (progress bars redraw themselves with `\r`), without color escapes.
+pub(crate) fn strip_ansi(line: &str) -> String {
+    let line = line
+        .rsplit('\r')
+        .find(|part| !part.is_empty())
+        .unwrap_or("");
+
+    let mut stripped = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+        if chars.next() == Some('[') {
+            // Skip parameters up to the final byte of the sequence
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    stripped
+}
+
+// Holds the install lock for the whole run, so concurrent app instances never interleave
+// installs. A binary that already exists may be running, so it's replaced by staging the new
+// one next to it and renaming it over. The first install goes straight to the install
+// directory, since the script also has to put that directory on PATH.
+#[cfg(not(windows))]
+async fn run_install_script(
+    app: &AppHandle,
+    args: &[&std::ffi::OsStr],
+    on_output: &(dyn Fn(String) + Sync),
+) -> Result<Vec<String>, String> {
+    let install_path =
+        get_cli_install_path(app).ok_or_else(|| "Could not determine install path".to_string())?;
+    let dir = install_path
+        .parent()
+        .ok_or_else(|| "Could not determine install directory".to_string())?;
+
+    let _lock = tauri::async_runtime::spawn_blocking({
+        let dir = dir.to_path_buf();
+        move || install_lock::acquire(&dir)
+    })
+    .await
+    .map_err(|e| format!("CLI install task failed: {}", e))??;
+
+    if !install_path.exists() {
+        return run_install_script_into(app, args, None, on_output).await;
+    }
+
+    let staging = tempfile::Builder::new()
+        .prefix(".install-")
+        .tempdir_in(dir)
+        .map_err(|e| format!("Failed to create staging directory: {}", e))?;
+
+    let mut staged_args = args.to_vec();
+    staged_args.push(std::ffi::OsStr::new("--no-modify-path"));
+    let output =
+        run_install_script_into(app, &staged_args, Some(staging.path()), on_output).await?;
+
+    // The script exits early without installing anything when the version is already there
+    let staged = staging.path().join(CLI_BINARY_NAME);
+    if staged.exists() {
+        install_lock::promote(&staged, &install_path)?;
+    }
+
+    Ok(output)
+}
+
+#[cfg(not(windows))]
+async fn run_install_script_into(
+    app: &AppHandle,
+    args: &[&std::ffi::OsStr],
+    install_dir: Option<&Path>,
+    on_output: &(dyn Fn(String) + Sync),
+) -> Result<Vec<String>, String> {
+    let temp_dir = integrity::secure_temp_dir("opencode-install-")
+        .map_err(|e| format!("Failed to create install directory: {}", e))?;
+    let temp_script = temp_dir.path().join("install.sh");
+    std::fs::write(&temp_script, INSTALL_SCRIPT)
+        .map_err(|e| format!("Failed to write install script: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&temp_script, std::fs::Permissions::from_mode(0o700))
+            .map_err(|e| format!("Failed to set script permissions: {}", e))?;
+    }
+
+    let mut cmd = Command::new(&temp_script);
+    cmd.args(args);
+
+    if let Some(dir) = install_dir
+        .map(Path::to_path_buf)
+        .or_else(|| get_custom_cli_install_dir(app))
+    {
+        cmd.env("OPENCODE_INSTALL_DIR", dir);
+    }
+
+    // Killed when the install is cancelled and this future dropped
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to run install script: {}", e))?;
+
+    let mut stdout = BufReader::new(child.stdout.take().unwrap()).lines();
+    let mut stderr = BufReader::new(child.stderr.take().unwrap()).lines();
+    let (mut stdout_open, mut stderr_open) = (true, true);
+    let mut output = vec![];
+    let mut errors = vec![];
+
+    while stdout_open || stderr_open {
+        tokio::select! {
+            line = stdout.next_line(), if stdout_open => match line {
+                Ok(Some(line)) => {
+                    let line = strip_ansi(&line);
+                    on_output(line.clone());
+                    output.push(line);
+                }
+                _ => stdout_open = false,
+            },
+            line = stderr.next_line(), if stderr_open => match line {
+                Ok(Some(line)) => {
+                    let line = strip_ansi(&line);
+                    on_output(line.clone());
+                    errors.push(line.clone());
+                    output.push(line);
+                }
+                _ => stderr_open = false,
+            },
+        }
+    }
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| format!("Failed to run install script: {}", e))?;
+
+    if status.code() == Some(INSTALL_VERIFY_EXIT_CODE) {
+        return Err(format!(
+            "The downloaded opencode release failed checksum verification: {}",
+            errors.join("\n")
+        ));
+    }
+
+    if !status.success() {
+        return Err(format!("Install script failed: {}", errors.join("\n")));
+    }
+
+    Ok(output)
+}
+
+// Copies the sidecar to %LOCALAPPDATA%\opencode\bin and puts that directory on the
+// user PATH. Any previous binary is kept aside until every step has succeeded.
+#[cfg(windows)]
+fn install_cli_windows(app: &AppHandle, sidecar: &std::path::Path) -> Result<(), String> {
+    let install_path =
+        get_cli_install_path(app).ok_or_else(|| "Could not determine install path".to_string())?;
+    let dir = install_path
+        .parent()
+        .ok_or_else(|| "Could not determine install directory".to_string())?;
+
+    let created_dir = !dir.exists();
+    let _lock = install_lock::acquire(dir)?;
+
+    let backup = install_path.with_extension("exe.bak");
+    let has_backup = install_path.exists();
+    if has_backup {
+        std::fs::rename(&install_path, &backup)
+            .map_err(|e| format!("Failed to back up existing CLI: {}", e))?;
+    }
+
+    let rollback = || {
+        let _ = std::fs::remove_file(&install_path);
+        if has_backup {
+            let _ = std::fs::rename(&backup, &install_path);
+        }
+        if created_dir {
+            let _ = std::fs::remove_dir(dir);
+        }
+    };
+
+    if let Err(e) = install_lock::atomic_copy(sidecar, &install_path) {
+        rollback();
+        return Err(e);
+    }
+
+    if let Err(e) = add_to_user_path(dir) {
+        rollback();
+        return Err(e);
+    }
+
+    if has_backup {
+        let _ = std::fs::remove_file(&backup);
+    }
+
+    Ok(())
+}
+
+#[cfg(windows)]
+fn add_to_user_path(dir: &std::path::Path) -> Result<(), String> {
+    use std::os::windows::process::CommandExt;
+
+    const ENVIRONMENT_KEY: &str = r"HKCU\Environment";
+
+    // Read the raw registry value so unexpanded entries like %USERPROFILE% survive.
+    let output = std::process::Command::new("reg")
+        .args(["query", ENVIRONMENT_KEY, "/v", "Path"])
+        .creation_flags(0x0800_0000)
+        .output()
+        .map_err(|e| format!("Failed to read user PATH: {}", e))?;
+
+    let current = output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+        .and_then(|stdout| {
+            stdout.lines().find_map(|line| {
+                let (_, rest) = line.trim().split_once("REG_")?;
+                let (_, value) = rest.split_once(char::is_whitespace)?;
+                Some(value.trim().to_string())
+            })
+        })
+        .unwrap_or_default();
+
+    let dir = dir.to_string_lossy();
+    if current.split(';').any(|v| {
+        v.trim_end_matches('\\')
+            .eq_ignore_ascii_case(dir.trim_end_matches('\\'))
+    }) {
+        return Ok(());
+    }
+
+    let next = if current.is_empty() {
+        dir.to_string()
+    } else {
+        format!("{};{}", current.trim_end_matches(';'), dir)
+    };
+
+    let output = std::process::Command::new("reg")
+        .args([
+            "add",
+            ENVIRONMENT_KEY,
+            "/v",
+            "Path",
+            "/t",
+            "REG_EXPAND_SZ",
+            "/d",
+        ])
+        .arg(&next)
+        .arg("/f")
+        .creation_flags(0x0800_0000)
+        .output()
+        .map_err(|e| format!("Failed to update user PATH: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to update user PATH: {}", stderr.trim()));
+    }
+
+    broadcast_environment_change();
+    Ok(())
+}
+
+// Tells Explorer and other running apps to reload the environment, so terminals opened from them
+// from now on see the new PATH without signing out
+#[cfg(windows)]
+fn broadcast_environment_change() {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        HWND_BROADCAST, SMTO_ABORTIFHUNG, SendMessageTimeoutW, WM_SETTINGCHANGE,
+    };
+
+    let area = "Environment\0".encode_utf16().collect::<Vec<_>>();
+    // Safety: `area` is a null-terminated UTF-16 string that outlives the call
+    let sent = unsafe {
+        SendMessageTimeoutW(
+            HWND_BROADCAST,
+            WM_SETTINGCHANGE,
+            0,
+            area.as_ptr() as isize,
+            SMTO_ABORTIFHUNG,
+            5000,
+            std::ptr::null_mut(),
+        )
+    };
+    if sent == 0 {
+        tracing::warn!(
+            "Failed to announce the PATH change: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn pin_cli_version(app: AppHandle, version: String) -> Result<(), String> {
+    if cfg!(windows) {
+        return Err("CLI version pinning is only supported on macOS & Linux".to_string());
+    }
+
+    let version = semver::Version::parse(version.trim_start_matches('v'))
+        .map_err(|e| format!("Invalid version '{}': {}", version, e))?;
+
+    tracing::info!(%version, "Pinning CLI version");
+
+    install_cli_version(&app, &version).await?;
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(
+        CLI_PINNED_VERSION_KEY,
+        serde_json::Value::String(version.to_string()),
+    );
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn unpin_cli_version(app: AppHandle) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.delete(CLI_PINNED_VERSION_KEY);
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    tracing::info!("Unpinned CLI version");
+
+    spawn_sync_cli(app);
+
+    Ok(())
+}
+
+// Installs a released CLI version through the install script, leaving PATH untouched.
+#[cfg(not(windows))]
+async fn install_cli_version(app: &AppHandle, version: &semver::Version) -> Result<(), String> {
+    let version = version.to_string();
+    run_install_script(
+        app,
+        &[
+            std::ffi::OsStr::new("--version"),
+            std::ffi::OsStr::new(&version),
+            std::ffi::OsStr::new("--no-modify-path"),
+            std::ffi::OsStr::new("--verify-checksum"),
+        ],
+        &|_| {},
+    )
+    .await?;
+
+    let installed = get_installed_cli_version(app).await?;
+    if installed.to_string() != version {
+        return Err(format!(
+            "Installed CLI reports version {installed}, expected {version}"
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(windows)]
+async fn install_cli_version(_app: &AppHandle, _version: &semver::Version) -> Result<(), String> {
+    Err("Installing a specific CLI version is only supported on macOS & Linux".to_string())
+}
+
+fn get_pinned_cli_version(app: &AppHandle) -> Option<String> {
+    app.store(SETTINGS_STORE)
+        .ok()?
+        .get(CLI_PINNED_VERSION_KEY)
+        .and_then(|v| v.as_str().map(String::from))
+}
+
+pub(crate) async fn get_installed_cli_version(app: &AppHandle) -> Result<semver::Version, String> {
+    let cli_path = get_cli_install_path(app)
+        .ok_or_else(|| "Could not determine CLI install path".to_string())?;
+
+    let mut cmd = Command::new(&cli_path);
+    cmd.arg("--version").kill_on_drop(true);
+
+    #[cfg(windows)]
+    cmd.creation_flags(0x0800_0000);
+
+    let output = tokio::time::timeout(CLI_VERSION_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| "Timed out getting CLI version".to_string())?
+        .map_err(|e| format!("Failed to get CLI version: {}", e))?;
+
+    if !output.status.success() {
+        return Err("Failed to get CLI version".to_string());
+    }
+
+    let cli_version_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    semver::Version::parse(&cli_version_str)
+        .map_err(|e| format!("Failed to parse CLI version '{}': {}", cli_version_str, e))
+}
+
+fn get_cli_channel_setting(app: &AppHandle) -> CliChannel {
+    app.store(SETTINGS_STORE)
+        .ok()
+        .and_then(|store| store.get(CLI_CHANNEL_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_cli_channel(app: AppHandle) -> CliChannel {
+    get_cli_channel_setting(&app)
+}
+
+/// Sets the release channel the installed CLI follows and syncs to it.
+#[tauri::command]
+#[specta::specta]
+pub fn set_cli_channel(app: AppHandle, channel: CliChannel) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(CLI_CHANNEL_KEY, serde_json::json!(channel));
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    tracing::info!(?channel, "Changed CLI release channel");
+
+    spawn_sync_cli(app);
+
+    Ok(())
+}
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+pub struct CliChannelVersion {
+    pub channel: CliChannel,
+    pub latest: Option<String>,
+    pub update_available: bool,
+}
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+pub struct CliUpdateStatus {
+    pub installed: Option<String>,
+    pub pinned: Option<String>,
+    pub channel: CliChannel,
+    pub channels: Vec<CliChannelVersion>,
+}
+
+/// Reports the installed CLI version against the latest release on each channel,
+/// without installing anything.
+#[tauri::command]
+#[specta::specta]
+pub async fn check_cli_updates(app: AppHandle) -> Result<CliUpdateStatus, String> {
+    let installed = if is_cli_installed(&app) {
+        get_installed_cli_version(&app).await.ok()
+    } else {
+        None
+    };
+
+    let releases = releases::fetch_releases().await?;
+
+    let channels = CliChannel::ALL
+        .into_iter()
+        .map(|channel| {
+            let latest = releases::latest_for_channel(&releases, channel);
+            CliChannelVersion {
+                channel,
+                update_available: match (&installed, &latest) {
+                    (Some(installed), Some(latest)) => latest > installed,
+                    (None, latest) => latest.is_some(),
+                    (Some(_), None) => false,
+                },
+                latest: latest.map(|v| v.to_string()),
+            }
+        })
+        .collect();
+
+    Ok(CliUpdateStatus {
+        installed: installed.map(|v| v.to_string()),
+        pinned: get_pinned_cli_version(&app),
+        channel: get_cli_channel_setting(&app),
+        channels,
+    })
+}
+
+/// Something wrong with the installed CLI.
+#[derive(Clone, serde::Serialize, specta::Type, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CliInstallIssue {
+    NotInstalled,
+    /// The binary is there, but `opencode --version` fails.
+    DoesNotRun {
+        error: String,
+    },
+    /// The binary claims the bundled version but its contents differ from the bundled sidecar.
+    ChecksumMismatch,
+    /// The user's shell can't find `opencode` at all.
+    NotOnPath,
+    /// The user's shell finds another `opencode` first, e.g. one installed with Homebrew.
+    Shadowed {
+        path: String,
+    },
+}
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+pub struct CliInstallHealth {
+    pub path: String,
+    pub version: Option<String>,
+    pub issues: Vec<CliInstallIssue>,
+}
+
+/// Checks that the installed CLI runs, matches the bundled sidecar when it claims the same
+/// version, and is what the user's shell runs for `opencode`.
+#[tauri::command]
+#[specta::specta]
+pub async fn verify_cli_install(app: AppHandle) -> Result<CliInstallHealth, String> {
+    let install_path =
+        get_cli_install_path(&app).ok_or_else(|| "Could not determine install path".to_string())?;
+
+    let mut health = CliInstallHealth {
+        path: install_path.to_string_lossy().to_string(),
+        version: None,
+        issues: vec![],
+    };
+
+    if !install_path.exists() {
+        health.issues.push(CliInstallIssue::NotInstalled);
+        return Ok(health);
+    }
+
+    match get_installed_cli_version(&app).await {
+        Ok(version) => {
+            if version == app.package_info().version && !matches_sidecar(&app, &install_path).await
+            {
+                health.issues.push(CliInstallIssue::ChecksumMismatch);
+            }
+            health.version = Some(version.to_string());
+        }
+        Err(error) => health.issues.push(CliInstallIssue::DoesNotRun { error }),
+    }
+
+    match shell::which(CLI_BINARY_NAME).await {
+        None => health.issues.push(CliInstallIssue::NotOnPath),
+        Some(found) if !is_same_file(&found, &install_path) => {
+            health.issues.push(CliInstallIssue::Shadowed {
+                path: found.to_string_lossy().to_string(),
+            });
+        }
+        Some(_) => {}
+    }
+
+    tracing::info!(issues = ?health.issues, "Verified CLI install");
+    Ok(health)
+}
+
+/// Reinstalls a missing or broken CLI from the bundled sidecar and puts its directory first
+/// on PATH in the user's shell config, then verifies again. Another `opencode` that comes
+/// earlier in PATH is never touched.
+#[tauri::command]
+#[specta::specta]
+pub async fn repair_cli_install(app: AppHandle) -> Result<CliInstallHealth, String> {
+    let health = verify_cli_install(app.clone()).await?;
+
+    let broken = health.issues.iter().any(|issue| {
+        matches!(
+            issue,
+            CliInstallIssue::NotInstalled
+                | CliInstallIssue::DoesNotRun { .. }
+                | CliInstallIssue::ChecksumMismatch
+        )
+    });
+    if broken {
+        tracing::warn!(issues = ?health.issues, "Reinstalling broken CLI");
+        install_bundled_cli(&app, &|_| {}).await?;
+    }
+
+    let unlinked = health.issues.iter().any(|issue| {
+        matches!(
+            issue,
+            CliInstallIssue::NotOnPath | CliInstallIssue::Shadowed { .. }
+        )
+    });
+    if unlinked {
+        tracing::warn!(issues = ?health.issues, "Relinking CLI");
+        link_cli(&app)?;
+    }
+
+    verify_cli_install(app).await
+}
+
+async fn matches_sidecar(app: &AppHandle, install_path: &Path) -> bool {
+    let sidecar = get_sidecar_path(app);
+    let install_path = install_path.to_path_buf();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let Ok(bytes) = std::fs::read(&sidecar) else {
+            // Nothing to compare against
+            return true;
+        };
+        // A differing CLI is expected, e.g. one the user manages, so this isn't a mismatch
+        // worth logging like `integrity::verify_file` does
+        std::fs::read(&install_path).is_ok_and(|installed| {
+            integrity::sha256_hex(&installed) == integrity::sha256_hex(&bytes)
+        })
+    })
+    .await
+    .unwrap_or(true)
+}
+
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+// Puts the install directory on PATH. On macOS & Linux the line goes at the very end of the
+// shell's config, so it wins over anything earlier that also prepends to PATH.
+fn link_cli(app: &AppHandle) -> Result<(), String> {
+    let install_path =
+        get_cli_install_path(app).ok_or_else(|| "Could not determine install path".to_string())?;
+    let dir = install_path
+        .parent()
+        .ok_or_else(|| "Could not determine install directory".to_string())?;
+
+    #[cfg(windows)]
+    return add_to_user_path(dir);
+
+    #[cfg(not(windows))]
+    {
+        let home =
+            dirs::home_dir().ok_or_else(|| "Could not determine home directory".to_string())?;
+        let shell = get_user_shell();
+        let name = Path::new(&shell)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+
+        // Same lines the install script writes, so they're recognised as duplicates
+        let (rc, line) = match name {
+            "zsh" => (
+                std::env::var_os("ZDOTDIR")
+                    .map(PathBuf::from)
+                    .unwrap_or(home)
+                    .join(".zshrc"),
+                format!("export PATH={}:$PATH", dir.display()),
+            ),
+            "bash" => (
+                home.join(".bashrc"),
+                format!("export PATH={}:$PATH", dir.display()),
+            ),
+            "fish" => (
+                std::env::var_os("XDG_CONFIG_HOME")
+                    .filter(|v| !v.is_empty())
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| home.join(".config"))
+                    .join("fish")
+                    .join("config.fish"),
+                format!("fish_add_path {}", dir.display()),
+            ),
+            "sh" | "dash" | "ash" => (
+                home.join(".profile"),
+                format!("export PATH={}:$PATH", dir.display()),
+            ),
+            _ => {
+                return Err(format!(
+                    "Add {} to PATH in your {} config",
+                    dir.display(),
+                    name
+                ));
+            }
+        };
+
+        let existing = std::fs::read_to_string(&rc).unwrap_or_default();
+        std::fs::write(&rc, move_to_end(&existing, &line))
+            .map_err(|e| format!("Failed to update {}: {}", rc.display(), e))?;
+
+        tracing::info!(rc = %rc.display(), "Moved CLI directory to the front of PATH");
+        Ok(())
+    }
+}
+
+// Drops earlier copies of `line`, along with the comment the install script puts above
+// them, and appends it again
+#[cfg_attr(windows, allow(dead_code))]
+fn move_to_end(existing: &str, line: &str) -> String {
+    let mut kept: Vec<&str> = vec![];
+    for current in existing.lines() {
+        if current.trim() == line {
+            if kept.last().is_some_and(|last| last.trim() == "# opencode") {
+                kept.pop();
+            }
+            continue;
+        }
+        kept.push(current);
+    }
+    while kept.last().is_some_and(|last| last.trim().is_empty()) {
+        kept.pop();
+    }
 
-#[derive(serde::Deserialize, Debug)]
-pub struct ServerConfig {
-    pub hostname: Option<String>,
-    pub port: Option<u32>,
+    let mut updated = kept.join("\n");
+    if !updated.is_empty() {
+        updated.push_str("\n\n");
+    }
+    updated.push_str("# opencode\n");
+    updated.push_str(line);
+    updated.push('\n');
+    updated
 }
 
-#[derive(serde::Deserialize, Debug)]
-pub struct Config {
-    pub server: Option<ServerConfig>,
+/// Why a CLI sync didn't install anything.
+#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type, Debug)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum CliSyncSkip {
+    DebugBuild,
+    /// The `opencode` the user runs belongs to a package manager, which should update it.
+    ManagedExternally {
+        manager: PackageManager,
+    },
+    NotInstalled,
+    Pinned {
+        version: String,
+    },
+    UpToDate,
 }
 
-#[derive(Clone, Debug)]
-pub enum CommandEvent {
-    Stdout(Vec<u8>),
-    Stderr(Vec<u8>),
-    Error(String),
-    Terminated(TerminatedPayload),
+#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type, Debug)]
+pub struct CliSyncResult {
+    /// Installed version before the sync.
+    pub before: Option<String>,
+    /// Installed version after the sync, differing from `before` when the CLI was updated.
+    pub after: Option<String>,
+    pub skipped: Option<CliSyncSkip>,
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct TerminatedPayload {
-    pub code: Option<i32>,
-    pub signal: Option<i32>,
+impl CliSyncResult {
+    fn skipped(before: Option<String>, reason: CliSyncSkip) -> Self {
+        Self {
+            after: before.clone(),
+            before,
+            skipped: Some(reason),
+        }
+    }
+
+    async fn synced(app: &AppHandle, before: Option<String>) -> Self {
+        Self {
+            before,
+            after: get_installed_cli_version(app)
+                .await
+                .ok()
+                .map(|v| v.to_string()),
+            skipped: None,
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
-pub struct CommandChild {
-    kill: mpsc::Sender<()>,
+#[derive(tauri_specta::Event, serde::Serialize, serde::Deserialize, Clone, Debug, specta::Type)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum CliSyncProgress {
+    /// Comparing the installed CLI against the app and the release channel.
+    Checking,
+    /// Downloading & installing a newer release through the install script.
+    Downloading {
+        from: String,
+        to: String,
+    },
+    /// Installing the sidecar bundled with the app.
+    Installing {
+        from: String,
+        to: String,
+    },
+    Done {
+        result: CliSyncResult,
+    },
+    Failed {
+        message: String,
+    },
 }
 
-impl CommandChild {
-    pub fn kill(&self) -> std::io::Result<()> {
-        self.kill
-            .try_send(())
-            .map_err(|e| std::io::Error::other(e.to_string()))
-    }
+/// Sent when sync leaves the CLI alone because a package manager owns the `opencode` the user
+/// runs. `take_over_cli` makes the app manage it instead.
+#[derive(tauri_specta::Event, serde::Serialize, serde::Deserialize, Clone, Debug, specta::Type)]
+pub struct CliManagedExternally {
+    pub manager: PackageManager,
+    pub path: String,
+    pub upgrade_command: String,
 }
 
-pub async fn get_config(app: &AppHandle) -> Option<Config> {
-    let (events, _) = spawn_command(app, "debug config", &[]).ok()?;
+/// Installs the app's own CLI ahead of a package manager's on PATH and keeps syncing it from
+/// then on.
+#[tauri::command]
+#[specta::specta]
+pub async fn take_over_cli(app: AppHandle) -> Result<CliInstallHealth, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
 
-    events
-        .fold(String::new(), async |mut config_str, event| {
-            if let CommandEvent::Stdout(stdout) = event
-                && let Ok(s) = str::from_utf8(&stdout)
-            {
-                config_str += s
-            }
+    store.set(CLI_TAKE_OVER_KEY, serde_json::Value::Bool(true));
 
-            config_str
-        })
-        .map(|v| serde_json::from_str::<Config>(&v))
-        .await
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    tracing::info!("Taking over CLI management");
+
+    repair_cli_install(app).await
+}
+
+fn get_cli_take_over(app: &AppHandle) -> bool {
+    app.store(SETTINGS_STORE)
         .ok()
+        .and_then(|store| store.get(CLI_TAKE_OVER_KEY))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
 }
 
-fn get_cli_install_path() -> Option<std::path::PathBuf> {
-    std::env::var("HOME").ok().map(|home| {
-        std::path::PathBuf::from(home)
-            .join(CLI_INSTALL_DIR)
-            .join(CLI_BINARY_NAME)
+// The `opencode` the user's shell runs, if a package manager owns it. A custom install
+// directory may itself belong to one, so the app's own install isn't exempt.
+async fn external_cli(app: &AppHandle) -> Option<CliManagedExternally> {
+    let found = shell::which(CLI_BINARY_NAME).await?;
+    let resolved = found.canonicalize().unwrap_or_else(|_| found.clone());
+    let manager = PackageManager::detect(&resolved)?;
+
+    Some(CliManagedExternally {
+        manager,
+        path: found.to_string_lossy().to_string(),
+        upgrade_command: manager.upgrade_command().to_string(),
     })
 }
 
-pub fn get_sidecar_path(app: &tauri::AppHandle) -> std::path::PathBuf {
-    // Get binary with symlinks support
-    tauri::process::current_binary(&app.env())
-        .expect("Failed to get current binary")
-        .parent()
-        .expect("Failed to get parent dir")
-        .join("opencode-cli")
-}
+// Background and manual syncs would otherwise race on the same install
+static SYNC_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
 
-fn is_cli_installed() -> bool {
-    get_cli_install_path()
-        .map(|path| path.exists())
-        .unwrap_or(false)
+pub fn spawn_sync_cli(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let _guard = SYNC_LOCK.lock().await;
+        init_progress::report(&app, InitProgress::CliSync);
+        slow_startup::delay(Stage::CliSync).await;
+        if let Err(e) = run_sync(&app).await {
+            tracing::error!("Failed to sync CLI: {e}");
+        }
+        if let Err(e) = wsl_setup::sync_cli(&app).await {
+            tracing::error!("Failed to sync CLI in WSL: {e}");
+        }
+    });
 }
 
-const INSTALL_SCRIPT: &str = include_str!("../../../../install");
-
+/// Brings the installed CLI up to date right away, instead of waiting for the next launch.
 #[tauri::command]
 #[specta::specta]
-pub fn install_cli(app: tauri::AppHandle) -> Result<String, String> {
-    if cfg!(not(unix)) {
-        return Err("CLI installation is only supported on macOS & Linux".to_string());
-    }
-
-    let sidecar = get_sidecar_path(&app);
-    if !sidecar.exists() {
-        return Err("Sidecar binary not found".to_string());
-    }
-
-    let temp_script = std::env::temp_dir().join("opencode-install.sh");
-    std::fs::write(&temp_script, INSTALL_SCRIPT)
-        .map_err(|e| format!("Failed to write install script: {}", e))?;
-
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        std::fs::set_permissions(&temp_script, std::fs::Permissions::from_mode(0o755))
-            .map_err(|e| format!("Failed to set script permissions: {}", e))?;
-    }
-
-    let output = std::process::Command::new(&temp_script)
-        .arg("--binary")
-        .arg(&sidecar)
-        .output()
-        .map_err(|e| format!("Failed to run install script: {}", e))?;
+pub async fn sync_cli_now(app: AppHandle) -> Result<CliSyncResult, String> {
+    let _guard = SYNC_LOCK
+        .try_lock()
+        .map_err(|_| "A CLI sync is already running".to_string())?;
 
-    let _ = std::fs::remove_file(&temp_script);
+    run_sync(&app).await
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Install script failed: {}", stderr));
-    }
+async fn run_sync(app: &AppHandle) -> Result<CliSyncResult, String> {
+    let _ = CliSyncProgress::Checking.emit(app);
 
-    let install_path =
-        get_cli_install_path().ok_or_else(|| "Could not determine install path".to_string())?;
+    let result = sync_cli(app.clone()).await;
+    let _ = match &result {
+        Ok(result) => CliSyncProgress::Done {
+            result: result.clone(),
+        }
+        .emit(app),
+        Err(message) => CliSyncProgress::Failed {
+            message: message.clone(),
+        }
+        .emit(app),
+    };
 
-    Ok(install_path.to_string_lossy().to_string())
+    result
 }
 
-pub fn sync_cli(app: tauri::AppHandle) -> Result<(), String> {
+async fn sync_cli(app: tauri::AppHandle) -> Result<CliSyncResult, String> {
     if cfg!(debug_assertions) {
         tracing::debug!("Skipping CLI sync for debug build");
-        return Ok(());
+        return Ok(CliSyncResult::skipped(None, CliSyncSkip::DebugBuild));
     }
 
-    if !is_cli_installed() {
-        tracing::info!("No CLI installation found, skipping sync");
-        return Ok(());
+    if !get_cli_take_over(&app)
+        && let Some(external) = external_cli(&app).await
+    {
+        tracing::info!(
+            manager = ?external.manager,
+            path = %external.path,
+            "CLI is managed by a package manager, skipping sync"
+        );
+        let manager = external.manager;
+        let _ = external.emit(&app);
+
+        let installed = get_installed_cli_version(&app)
+            .await
+            .ok()
+            .map(|v| v.to_string());
+        return Ok(CliSyncResult::skipped(
+            installed,
+            CliSyncSkip::ManagedExternally { manager },
+        ));
     }
 
-    let cli_path =
-        get_cli_install_path().ok_or_else(|| "Could not determine CLI install path".to_string())?;
-
-    let output = std::process::Command::new(&cli_path)
-        .arg("--version")
-        .output()
-        .map_err(|e| format!("Failed to get CLI version: {}", e))?;
+    if !is_cli_installed(&app) {
+        tracing::info!("No CLI installation found, skipping sync");
+        return Ok(CliSyncResult::skipped(None, CliSyncSkip::NotInstalled));
+    }
 
-    if !output.status.success() {
-        return Err("Failed to get CLI version".to_string());
+    if let Some(version) = get_pinned_cli_version(&app) {
+        tracing::info!(%version, "CLI version is pinned, skipping sync");
+        let installed = get_installed_cli_version(&app)
+            .await
+            .ok()
+            .map(|v| v.to_string());
+        return Ok(CliSyncResult::skipped(
+            installed,
+            CliSyncSkip::Pinned { version },
+        ));
     }
 
-    let cli_version_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    let cli_version = semver::Version::parse(&cli_version_str)
-        .map_err(|e| format!("Failed to parse CLI version '{}': {}", cli_version_str, e))?;
+    let cli_version = get_installed_cli_version(&app).await?;
+    let before = Some(cli_version.to_string());
 
     let app_version = app.package_info().version.clone();
 
+    let channel = get_cli_channel_setting(&app);
+    let latest = match releases::fetch_releases().await {
+        Ok(releases) => releases::latest_for_channel(&releases, channel),
+        Err(e) => {
+            tracing::warn!(?channel, "Failed to resolve latest CLI release: {e}");
+            None
+        }
+    };
+
+    // Releases newer than the bundled sidecar can only be installed through the install script
+    if !cfg!(windows)
+        && let Some(latest) = latest.filter(|latest| *latest > app_version)
+    {
+        if cli_version >= latest {
+            tracing::info!(
+                %cli_version, %latest, ?channel,
+                "CLI is up to date with release channel, skipping sync"
+            );
+            return Ok(CliSyncResult::skipped(before, CliSyncSkip::UpToDate));
+        }
+
+        tracing::info!(
+            %cli_version, %latest, ?channel,
+            "CLI is older than latest release on channel, syncing"
+        );
+
+        let _ = CliSyncProgress::Downloading {
+            from: cli_version.to_string(),
+            to: latest.to_string(),
+        }
+        .emit(&app);
+
+        install_cli_version(&app, &latest).await?;
+
+        tracing::info!("Synced installed CLI");
+
+        return Ok(CliSyncResult::synced(&app, before).await);
+    }
+
     if cli_version >= app_version {
         tracing::info!(
             %cli_version, %app_version,
             "CLI is up to date, skipping sync"
         );
-        return Ok(());
+        return Ok(CliSyncResult::skipped(before, CliSyncSkip::UpToDate));
     }
 
     tracing::info!(
@@ -186,18 +1644,25 @@ pub fn sync_cli(app: tauri::AppHandle) -> Result<(), String> {
         "CLI is older than app version, syncing"
     );
 
-    install_cli(app)?;
+    let _ = CliSyncProgress::Installing {
+        from: cli_version.to_string(),
+        to: app_version.to_string(),
+    }
+    .emit(&app);
+
+    install_bundled_cli(&app, &|_| {}).await?;
 
     tracing::info!("Synced installed CLI");
 
-    Ok(())
+    Ok(CliSyncResult::synced(&app, before).await)
 }
 
-fn get_user_shell() -> String {
+pub(crate) fn get_user_shell() -> String {
     std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string())
 }
 
-fn is_wsl_enabled(app: &tauri::AppHandle) -> bool {
+/// The global WSL setting. Projects may override it, see `project::uses_wsl`.
+pub(crate) fn is_wsl_enabled(app: &tauri::AppHandle) -> bool {
     let Ok(store) = app.store(SETTINGS_STORE) else {
         return false;
     };
@@ -209,22 +1674,76 @@ fn is_wsl_enabled(app: &tauri::AppHandle) -> bool {
         .unwrap_or(false)
 }
 
-fn shell_escape(input: &str) -> String {
-    if input.is_empty() {
-        return "''".to_string();
+/// Host settings that `wsl -e` doesn't carry over, so the sidecar would otherwise run
+/// in UTC with the C locale and disagree with the host about timestamps.
+#[derive(Debug, Default)]
+struct HostEnv {
+    tz: Option<String>,
+    lang: Option<String>,
+    user: Option<String>,
+}
+
+impl HostEnv {
+    fn detect() -> Self {
+        let var = |key: &str| std::env::var(key).ok().filter(|v| !v.is_empty());
+
+        Self {
+            tz: var("TZ").or_else(|| iana_time_zone::get_timezone().ok()),
+            lang: var("LANG").or_else(|| sys_locale::get_locale().map(|v| posix_locale(&v))),
+            user: var("USERNAME").or_else(|| var("USER")),
+        }
     }
 
-    let mut escaped = String::from("'");
-    escaped.push_str(&input.replace("'", "'\"'\"'"));
-    escaped.push('\'');
-    escaped
+    fn vars(&self) -> Vec<(&'static str, String)> {
+        [
+            ("TZ", &self.tz),
+            ("LANG", &self.lang),
+            ("OPENCODE_HOST_USER", &self.user),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, value.clone()?)))
+        .collect()
+    }
 }
 
-pub fn spawn_command(
-    app: &tauri::AppHandle,
+// Windows reports BCP 47 tags like `en-US`, POSIX expects `en_US.UTF-8`.
+fn posix_locale(locale: &str) -> String {
+    if locale.contains('.') {
+        return locale.to_string();
+    }
+
+    format!("{}.UTF-8", locale.replace('-', "_"))
+}
+
+// Without `install_version` the script fails when the CLI is missing instead of installing it
+fn wsl_script(
+    install_version: Option<&str>,
     args: &str,
-    extra_env: &[(&str, String)],
-) -> Result<(impl Stream<Item = CommandEvent> + 'static, CommandChild), std::io::Error> {
+    envs: &[(String, String)],
+    host: &HostEnv,
+) -> WslScript {
+    let script = match install_version {
+        Some(version) => WslScript::new().install_if_missing(version),
+        None => WslScript::new().fail_if_missing(),
+    };
+
+    script
+        .env("OPENCODE_EXPERIMENTAL_ICON_DISCOVERY", "true")
+        .env("OPENCODE_EXPERIMENTAL_FILEWATCHER", "true")
+        .env("OPENCODE_CLIENT", "desktop")
+        .env_expr("XDG_STATE_HOME", "$HOME/.local/state")
+        .envs(host.vars())
+        // The host's XDG directories don't exist inside WSL
+        .envs(
+            envs.iter()
+                .filter(|(key, _)| !key.starts_with("XDG_"))
+                .map(|(key, value)| (key.as_str(), value.as_str())),
+        )
+        .args(args.split_whitespace())
+}
+
+/// The variables the app itself sets for the sidecar, on top of whatever it inherits.
+pub fn sidecar_envs(app: &tauri::AppHandle, extra_env: &[(&str, String)]) -> Vec<(String, String)> {
     let state_dir = app
         .path()
         .resolve("", BaseDirectory::AppLocalData)
@@ -245,51 +1764,67 @@ pub fn spawn_command(
             state_dir.to_string_lossy().to_string(),
         ),
     ];
+    if let (Some(data_home), Some(cache_home)) =
+        (dev_profile::data_home(), dev_profile::cache_home())
+    {
+        tracing::info!(data_home = %data_home.display(), "Isolating dev sidecar data");
+        envs.push((
+            "XDG_DATA_HOME".to_string(),
+            data_home.to_string_lossy().to_string(),
+        ));
+        envs.push((
+            "XDG_CACHE_HOME".to_string(),
+            cache_home.to_string_lossy().to_string(),
+        ));
+    }
+    if power::cached_power_state().is_low_power() {
+        tracing::info!("Low power mode detected, hinting sidecar to reduce background work");
+        envs.push(("OPENCODE_POWER_SAVER".to_string(), "1".to_string()));
+    }
     envs.extend(
         extra_env
             .iter()
             .map(|(key, value)| (key.to_string(), value.clone())),
     );
 
+    envs
+}
+
+pub fn spawn_command(
+    app: &tauri::AppHandle,
+    args: &str,
+    extra_env: &[(&str, String)],
+    cwd: Option<&Path>,
+    stdin: bool,
+) -> Result<(impl Stream<Item = CommandEvent> + 'static, CommandChild), std::io::Error> {
+    let envs = sidecar_envs(app, extra_env);
+    let policy = env_policy::get(app);
+    let limits = limits::get(app);
+    let wsl = project::uses_wsl(app, cwd);
+    // Written to stdin ahead of anything the caller sends
+    let mut stdin_prelude = None;
+
     let mut cmd = if cfg!(windows) {
-        if is_wsl_enabled(app) {
-            tracing::info!("WSL is enabled, spawning CLI server in WSL");
+        if wsl {
+            tracing::info!(?cwd, "Project uses WSL, spawning CLI server in WSL");
             let version = app.package_info().version.to_string();
-            let mut script = vec![
-                "set -e".to_string(),
-                "BIN=\"$HOME/.opencode/bin/opencode\"".to_string(),
-                "if [ ! -x \"$BIN\" ]; then".to_string(),
-                format!(
-                    "  curl -fsSL https://opencode.ai/install | bash -s -- --version {} --no-modify-path",
-                    shell_escape(&version)
-                ),
-                "fi".to_string(),
-            ];
-
-            let mut env_prefix = vec![
-                "OPENCODE_EXPERIMENTAL_ICON_DISCOVERY=true".to_string(),
-                "OPENCODE_EXPERIMENTAL_FILEWATCHER=true".to_string(),
-                "OPENCODE_CLIENT=desktop".to_string(),
-                "XDG_STATE_HOME=\"$HOME/.local/state\"".to_string(),
-            ];
-            env_prefix.extend(
-                envs.iter()
-                    .filter(|(key, _)| key != "OPENCODE_EXPERIMENTAL_ICON_DISCOVERY")
-                    .filter(|(key, _)| key != "OPENCODE_EXPERIMENTAL_FILEWATCHER")
-                    .filter(|(key, _)| key != "OPENCODE_CLIENT")
-                    .filter(|(key, _)| key != "XDG_STATE_HOME")
-                    .map(|(key, value)| format!("{}={}", key, shell_escape(value))),
-            );
-
-            script.push(format!("{} exec \"$BIN\" {}", env_prefix.join(" "), args));
+            let install_version = wsl_setup::cli_install_allowed(app).then_some(version.as_str());
+            let script = wsl_script(install_version, args, &envs, &HostEnv::detect());
+            stdin_prelude = script.stdin();
 
             let mut cmd = Command::new("wsl");
-            cmd.args(["-e", "bash", "-lc", &script.join("\n")]);
+            cmd.args(wsl_setup::distro_args(app));
+            // wsl translates Windows paths given to --cd itself
+            if let Some(cwd) = cwd {
+                cmd.arg("--cd").arg(cwd);
+            }
+            cmd.args(["-e", "bash", "-lc", &script.build()]);
             cmd
         } else {
             let sidecar = get_sidecar_path(app);
             let mut cmd = Command::new(sidecar);
             cmd.args(args.split_whitespace());
+            env_policy::apply(&mut cmd, &policy);
 
             for (key, value) in envs {
                 cmd.env(key, value);
@@ -299,16 +1834,8 @@ pub fn spawn_command(
         }
     } else {
         let sidecar = get_sidecar_path(app);
-        let shell = get_user_shell();
-
-        let line = if shell.ends_with("/nu") {
-            format!("^\"{}\" {}", sidecar.display(), args)
-        } else {
-            format!("\"{}\" {}", sidecar.display(), args)
-        };
-
-        let mut cmd = Command::new(shell);
-        cmd.args(["-il", "-c", &line]);
+        let mut cmd = shell::command(&shell::get(app), &sidecar, args, &limits);
+        env_policy::apply(&mut cmd, &policy);
 
         for (key, value) in envs {
             cmd.env(key, value);
@@ -317,12 +1844,22 @@ pub fn spawn_command(
         cmd
     };
 
-    cmd.stdin(Stdio::null());
+    if let Some(cwd) = cwd
+        && !wsl
+    {
+        cmd.current_dir(cwd);
+    }
+
+    cmd.stdin(if stdin || stdin_prelude.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    });
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
 
     #[cfg(windows)]
-    cmd.creation_flags(0x0800_0000);
+    cmd.creation_flags(0x0800_0000 | limits::priority_class(&limits));
 
     let mut wrap = CommandWrap::from(cmd);
 
@@ -337,65 +1874,115 @@ pub fn spawn_command(
     }
 
     let mut child = wrap.spawn()?;
+    let pid = child.id();
+
+    recorder::record(ShellEventKind::Command {
+        args: args.to_string(),
+    });
     let stdout = child.stdout().take();
     let stderr = child.stderr().take();
-    let (tx, rx) = mpsc::channel(256);
+    let output = CommandOutput::new(OUTPUT_BUFFER_BYTES);
     let (kill_tx, mut kill_rx) = mpsc::channel(1);
 
+    // Writes are forwarded until the command stops reading or every handle to it is dropped.
+    // Without `stdin` the pipe is closed right after the prelude.
+    let stdin_tx = child.stdin().take().and_then(|mut pipe| {
+        let (tx, mut rx) = mpsc::channel::<Vec<u8>>(STDIN_BUFFER);
+        tokio::spawn(async move {
+            if let Some(prelude) = stdin_prelude
+                && (pipe.write_all(prelude.as_bytes()).await.is_err()
+                    || pipe.flush().await.is_err())
+            {
+                return;
+            }
+
+            while let Some(data) = rx.recv().await {
+                if pipe.write_all(&data).await.is_err() || pipe.flush().await.is_err() {
+                    break;
+                }
+            }
+        });
+        stdin.then_some(tx)
+    });
+
     if let Some(stdout) = stdout {
-        let tx = tx.clone();
+        let output = output.clone();
         tokio::spawn(async move {
             let mut lines = BufReader::new(stdout).lines();
             while let Ok(Some(line)) = lines.next_line().await {
-                let _ = tx.send(CommandEvent::Stdout(line.into_bytes())).await;
+                output.push(CommandEvent::Stdout(line.into_bytes()));
             }
         });
     }
 
     if let Some(stderr) = stderr {
-        let tx = tx.clone();
+        let output = output.clone();
         tokio::spawn(async move {
             let mut lines = BufReader::new(stderr).lines();
             while let Ok(Some(line)) = lines.next_line().await {
-                let _ = tx.send(CommandEvent::Stderr(line.into_bytes())).await;
+                output.push(CommandEvent::Stderr(line.into_bytes()));
             }
         });
     }
 
-    tokio::spawn(async move {
-        let status = loop {
-            match child.try_wait() {
-                Ok(Some(status)) => break Ok(status),
-                Ok(None) => {}
-                Err(err) => break Err(err),
-            }
+    let registry = app.try_state::<ProcessRegistry>();
+    let id = registry.as_ref().map(|registry| registry.next_id());
+    let command_child = CommandChild {
+        id,
+        kill: kill_tx,
+        stdin: stdin_tx,
+        pid,
+        output: output.clone(),
+    };
+    if let (Some(registry), Some(id)) = (&registry, id) {
+        registry.insert(id, command_child.clone(), args, stdin);
+    }
 
-            tokio::select! {
-                _ = kill_rx.recv() => {
-                    let _ = child.start_kill();
+    tokio::spawn({
+        let output = output.clone();
+        let app = app.clone();
+        async move {
+            let status = loop {
+                match child.try_wait() {
+                    Ok(Some(status)) => break Ok(status),
+                    Ok(None) => {}
+                    Err(err) => break Err(err),
                 }
-                _ = tokio::time::sleep(Duration::from_millis(100)) => {}
-            }
-        };
 
-        match status {
-            Ok(status) => {
-                let payload = TerminatedPayload {
-                    code: status.code(),
-                    signal: signal_from_status(status),
-                };
-                let _ = tx.send(CommandEvent::Terminated(payload)).await;
+                tokio::select! {
+                    _ = kill_rx.recv() => {
+                        let _ = child.start_kill();
+                    }
+                    _ = tokio::time::sleep(Duration::from_millis(100)) => {}
+                }
+            };
+
+            if let Some(id) = id {
+                app.state::<ProcessRegistry>().remove(id);
             }
-            Err(err) => {
-                let _ = tx.send(CommandEvent::Error(err.to_string())).await;
+
+            match status {
+                Ok(status) => {
+                    let payload = TerminatedPayload {
+                        code: status.code(),
+                        signal: signal_from_status(status),
+                    };
+                    recorder::record(ShellEventKind::CommandTerminated {
+                        code: payload.code,
+                        signal: payload.signal,
+                    });
+                    output.push(CommandEvent::Terminated(payload));
+                }
+                Err(err) => {
+                    output.push(CommandEvent::Error(err.to_string()));
+                }
             }
         }
     });
 
-    let event_stream = ReceiverStream::new(rx);
-    let event_stream = sqlite_migration::logs_middleware(app.clone(), event_stream);
+    let event_stream = sqlite_migration::logs_middleware(app.clone(), output.subscribe());
 
-    Ok((event_stream, CommandChild { kill: kill_tx }))
+    Ok((event_stream, command_child))
 }
 
 fn signal_from_status(status: std::process::ExitStatus) -> Option<i32> {
@@ -416,24 +2003,48 @@ pub fn serve(
     hostname: &str,
     port: u32,
     password: &str,
-) -> (CommandChild, oneshot::Receiver<TerminatedPayload>) {
-    let (exit_tx, exit_rx) = oneshot::channel::<TerminatedPayload>();
+    cwd: Option<&Path>,
+) -> Result<(CommandChild, oneshot::Receiver<SidecarExit>), SidecarFailure> {
+    let (exit_tx, exit_rx) = oneshot::channel::<SidecarExit>();
 
-    tracing::info!(port, "Spawning sidecar");
+    tracing::info!(port, ?cwd, "Spawning sidecar");
+    fs_boundary::check(app, cwd);
 
     let envs = [
         ("OPENCODE_SERVER_USERNAME", "opencode".to_string()),
         ("OPENCODE_SERVER_PASSWORD", password.to_string()),
     ];
 
+    let sidecar_path = get_sidecar_path(app);
+    if !project::uses_wsl(app, cwd)
+        && let Err(e) = sidecar::fix_permissions(&sidecar_path)
+    {
+        tracing::error!("{e}");
+    }
+
     let (events, child) = spawn_command(
         app,
         format!("--print-logs --log-level WARN serve --hostname {hostname} --port {port}").as_str(),
         &envs,
+        cwd,
+        false,
     )
-    .expect("Failed to spawn opencode");
+    .map_err(|e| {
+        let failure = SidecarFailure::SpawnFailed {
+            error: e.to_string(),
+            issues: sidecar::check(&sidecar_path),
+        };
+        tracing::error!(?failure, "Failed to spawn sidecar");
+        failure
+    })?;
+
+    // Other CLI commands are short-lived, so only the server is reniced
+    #[cfg(unix)]
+    limits::apply_priority(child.pid(), &limits::get(app));
 
     let mut exit_tx = Some(exit_tx);
+    // Recent output is kept so an early exit can be classified from what the sidecar printed
+    let mut recent = VecDeque::with_capacity(EXIT_OUTPUT_LINES);
     tokio::spawn(
         events
             .for_each(move |event| {
@@ -441,10 +2052,12 @@ pub fn serve(
                     CommandEvent::Stdout(line_bytes) => {
                         let line = String::from_utf8_lossy(&line_bytes);
                         tracing::info!("{line}");
+                        push_recent(&mut recent, &line);
                     }
                     CommandEvent::Stderr(line_bytes) => {
                         let line = String::from_utf8_lossy(&line_bytes);
                         tracing::info!("{line}");
+                        push_recent(&mut recent, &line);
                     }
                     CommandEvent::Error(err) => {
                         tracing::error!("{err}");
@@ -457,7 +2070,10 @@ pub fn serve(
                         );
 
                         if let Some(tx) = exit_tx.take() {
-                            let _ = tx.send(payload);
+                            let _ = tx.send(SidecarExit {
+                                payload,
+                                output: recent.drain(..).collect(),
+                            });
                         }
                     }
                 }
@@ -467,7 +2083,14 @@ pub fn serve(
             .instrument(tracing::info_span!("sidecar")),
     );
 
-    (child, exit_rx)
+    Ok((child, exit_rx))
+}
+
+fn push_recent(recent: &mut VecDeque<String>, line: &str) {
+    if recent.len() == EXIT_OUTPUT_LINES {
+        recent.pop_front();
+    }
+    recent.push_back(line.to_string());
 }
 
 pub mod sqlite_migration {
@@ -518,3 +2141,178 @@ pub mod sqlite_migration {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exit(code: Option<i32>, signal: Option<i32>, output: &[&str]) -> SidecarExit {
+        SidecarExit {
+            payload: TerminatedPayload { code, signal },
+            output: output.iter().map(|line| line.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn moves_path_line_to_end() {
+        let line = "export PATH=/home/me/.opencode/bin:$PATH";
+        let existing = format!(
+            "export FOO=1\n\n# opencode\n{line}\neval \"$(/opt/homebrew/bin/brew shellenv)\"\n"
+        );
+
+        assert_eq!(
+            move_to_end(&existing, line),
+            format!(
+                "export FOO=1\n\neval \"$(/opt/homebrew/bin/brew shellenv)\"\n\n# opencode\n{line}\n"
+            )
+        );
+        assert_eq!(move_to_end("", line), format!("# opencode\n{line}\n"));
+    }
+
+    #[test]
+    fn strips_terminal_escapes() {
+        assert_eq!(
+            strip_ansi("\x1b[0;2mInstalling \x1b[0mopencode"),
+            "Installing opencode"
+        );
+        assert_eq!(strip_ansi("###   10.0%\r######  50.0%\r"), "######  50.0%");
+    }
+
+    #[test]
+    fn finds_modified_rc_files() {
+        let line = strip_ansi(
+            "\x1b[0;2mSuccessfully added \x1b[0mopencode \x1b[0;2mto $PATH in \x1b[0m/home/me/.zshrc\x1b[0m",
+        );
+        assert_eq!(added_to_path_in(&line), Some("/home/me/.zshrc"));
+        assert_eq!(
+            added_to_path_in("Command already exists in /home/me/.zshrc"),
+            None
+        );
+    }
+
+    #[test]
+    fn classifies_port_in_use() {
+        let exit = exit(
+            Some(1),
+            None,
+            &["error: Failed to start server. Is port 4096 in use?"],
+        );
+        assert_eq!(
+            SidecarFailure::classify(&exit),
+            SidecarFailure::PortInUse { port: Some(4096) }
+        );
+    }
+
+    #[test]
+    fn classifies_missing_shared_library() {
+        let exit = exit(
+            Some(127),
+            None,
+            &[
+                "opencode: error while loading shared libraries: libstdc++.so.6: cannot open shared object file",
+            ],
+        );
+        assert_eq!(
+            SidecarFailure::classify(&exit),
+            SidecarFailure::MissingSharedLibrary {
+                library: Some("libstdc++.so.6".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn classifies_config_parse_error() {
+        let line =
+            "ConfigJsonError: Config file at ~/.config/opencode/opencode.json is not valid JSON";
+        assert_eq!(
+            SidecarFailure::classify(&exit(Some(1), None, &["INFO starting", line])),
+            SidecarFailure::ConfigParseError {
+                detail: line.to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn falls_back_to_signal_and_code() {
+        assert_eq!(
+            SidecarFailure::classify(&exit(None, Some(11), &["INFO starting"])),
+            SidecarFailure::CrashedWithSignal { signal: 11 }
+        );
+        assert_eq!(
+            SidecarFailure::classify(&exit(Some(2), None, &[])),
+            SidecarFailure::Unknown { code: Some(2) }
+        );
+        assert_eq!(
+            SidecarFailure::classify(&exit(Some(WSL_CLI_MISSING_EXIT_CODE), None, &[])),
+            SidecarFailure::WslCliMissing
+        );
+        assert_eq!(
+            SidecarFailure::classify(&exit(Some(INSTALL_VERIFY_EXIT_CODE), None, &[])),
+            SidecarFailure::ChecksumMismatch
+        );
+    }
+
+    #[test]
+    fn spawn_failure_names_sidecar_issues() {
+        let failure = SidecarFailure::SpawnFailed {
+            error: "Permission denied (os error 13)".to_string(),
+            issues: vec![
+                sidecar::SidecarIssue::NotExecutable,
+                sidecar::SidecarIssue::Quarantined,
+            ],
+        };
+        assert_eq!(
+            failure.message(),
+            "Failed to start the sidecar: Permission denied (os error 13) (it isn't executable, macOS has quarantined it)"
+        );
+    }
+
+    #[test]
+    fn converts_bcp47_to_posix_locale() {
+        assert_eq!(posix_locale("en-US"), "en_US.UTF-8");
+        assert_eq!(posix_locale("de_DE.ISO-8859-1"), "de_DE.ISO-8859-1");
+    }
+
+    #[test]
+    fn wsl_script_propagates_host_env() {
+        let host = HostEnv {
+            tz: Some("Europe/Berlin".to_string()),
+            lang: Some("de_DE.UTF-8".to_string()),
+            user: Some("O'Brien".to_string()),
+        };
+        let envs = vec![("OPENCODE_SERVER_PASSWORD".to_string(), "secret".to_string())];
+
+        let script = wsl_script(Some("1.2.3"), "serve --port 4096", &envs, &host).build();
+        let exec = script.lines().last().unwrap();
+
+        assert!(exec.contains(" TZ='Europe/Berlin' "));
+        assert!(exec.contains(" LANG='de_DE.UTF-8' "));
+        assert!(exec.contains(" OPENCODE_HOST_USER='O'\"'\"'Brien' "));
+        assert!(exec.contains(" OPENCODE_SERVER_PASSWORD='secret' "));
+        assert!(exec.ends_with("exec \"$BIN\" 'serve' '--port' '4096'"));
+    }
+
+    #[test]
+    fn wsl_script_skips_unknown_host_env() {
+        let script = wsl_script(Some("1.2.3"), "serve", &[], &HostEnv::default()).build();
+
+        assert!(!script.contains("TZ="));
+        assert!(!script.contains("LANG="));
+        assert!(!script.contains("OPENCODE_HOST_USER="));
+    }
+
+    #[test]
+    fn project_config_stops_at_the_worktree_root() {
+        let root = tempfile::tempdir().unwrap();
+        let repo = root.path().join("repo");
+        let project = repo.join("packages/app");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::create_dir(repo.join(".git")).unwrap();
+
+        let files = project_config_files(&project);
+        assert!(files.contains(&project.join("opencode.json")));
+        assert!(files.contains(&project.join(".opencode/opencode.jsonc")));
+        assert!(files.contains(&repo.join("opencode.jsonc")));
+        assert!(!files.contains(&root.path().join("opencode.json")));
+    }
+}
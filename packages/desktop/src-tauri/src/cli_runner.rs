@@ -0,0 +1,156 @@
+use std::path::PathBuf;
+
+use futures::StreamExt;
+use tauri::{AppHandle, Manager, ipc::Channel};
+
+use crate::{
+    cli::{self, CommandEvent},
+    process_registry::ProcessRegistry,
+};
+
+/// Subcommands the UI may run. Anything that starts the server or touches sessions is left
+/// to the sidecar itself.
+const ALLOWED_SUBCOMMANDS: &[&str] = &["auth", "mcp", "models", "stats"];
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CliCommandEvent {
+    /// Always sent first, with the id to pass to `write_stdin` or `kill_child`.
+    Started {
+        child_id: Option<u32>,
+    },
+    Stdout {
+        line: String,
+    },
+    Stderr {
+        line: String,
+    },
+    Error {
+        message: String,
+    },
+    Terminated {
+        code: Option<i32>,
+        signal: Option<i32>,
+    },
+}
+
+impl From<CommandEvent> for CliCommandEvent {
+    fn from(event: CommandEvent) -> Self {
+        match event {
+            CommandEvent::Stdout(bytes) => Self::Stdout {
+                line: String::from_utf8_lossy(&bytes).to_string(),
+            },
+            CommandEvent::Stderr(bytes) => Self::Stderr {
+                line: String::from_utf8_lossy(&bytes).to_string(),
+            },
+            CommandEvent::Error(message) => Self::Error { message },
+            CommandEvent::Terminated(payload) => Self::Terminated {
+                code: payload.code,
+                signal: payload.signal,
+            },
+        }
+    }
+}
+
+/// Runs an allowlisted CLI subcommand, streaming its output until it exits. With
+/// `interactive`, its stdin stays open for `write_stdin` so prompts can be answered.
+#[tauri::command]
+#[specta::specta]
+pub async fn run_cli_command(
+    app: AppHandle,
+    args: Vec<String>,
+    cwd: Option<String>,
+    interactive: bool,
+    events: Channel<CliCommandEvent>,
+) -> Result<(), String> {
+    validate_args(&args)?;
+
+    let cwd = cwd.map(PathBuf::from);
+    if let Some(cwd) = &cwd
+        && !cwd.is_dir()
+    {
+        return Err(format!("Directory not found: {}", cwd.display()));
+    }
+
+    tracing::info!(?args, ?cwd, interactive, "Running CLI command");
+
+    let (stream, child) =
+        cli::spawn_command(&app, &args.join(" "), &[], cwd.as_deref(), interactive)
+            .map_err(|e| format!("Failed to run CLI command: {}", e))?;
+
+    let _ = events.send(CliCommandEvent::Started {
+        child_id: child.id(),
+    });
+
+    stream
+        .for_each(|event| {
+            let _ = events.send(event.into());
+            futures::future::ready(())
+        })
+        .await;
+
+    Ok(())
+}
+
+/// Sends `data` to the stdin of an interactive `run_cli_command`. Include the trailing newline
+/// to submit a prompt.
+#[tauri::command]
+#[specta::specta]
+pub fn write_stdin(app: AppHandle, child_id: u32, data: String) -> Result<(), String> {
+    app.try_state::<ProcessRegistry>()
+        .and_then(|registry| registry.get(child_id))
+        .ok_or_else(|| format!("No running CLI command with id {}", child_id))?
+        .write_stdin(data.into_bytes())
+        .map_err(|e| format!("Failed to write to CLI command: {}", e))
+}
+
+// Args are joined into a shell line by `spawn_command`, so only plain tokens are accepted
+// rather than trying to quote for every shell the user might have.
+fn validate_args(args: &[String]) -> Result<(), String> {
+    let subcommand = args
+        .first()
+        .ok_or_else(|| "No CLI command given".to_string())?;
+
+    if !ALLOWED_SUBCOMMANDS.contains(&subcommand.as_str()) {
+        return Err(format!("CLI command '{}' is not allowed", subcommand));
+    }
+
+    if let Some(arg) = args.iter().find(|arg| {
+        arg.is_empty()
+            || !arg
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_.:/@=".contains(c))
+    }) {
+        return Err(format!("Unsupported CLI argument '{}'", arg));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn allows_listed_subcommands() {
+        assert!(validate_args(&args(&["auth", "login"])).is_ok());
+        assert!(validate_args(&args(&["models", "--refresh", "anthropic"])).is_ok());
+    }
+
+    #[test]
+    fn rejects_other_subcommands() {
+        assert!(validate_args(&args(&["serve"])).is_err());
+        assert!(validate_args(&[]).is_err());
+    }
+
+    #[test]
+    fn rejects_shell_syntax() {
+        assert!(validate_args(&args(&["auth", "login; rm -rf ~"])).is_err());
+        assert!(validate_args(&args(&["auth", "$(whoami)"])).is_err());
+        assert!(validate_args(&args(&["auth", ""])).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use tauri::AppHandle;
+use tokio::process::Command;
+
+use crate::cli::{get_cli_install_path, get_user_shell};
+
+const COMPLETION_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    Nushell,
+}
+
+impl CompletionShell {
+    fn detect(shell: &str) -> Option<Self> {
+        match Path::new(shell).file_name()?.to_str()? {
+            "bash" => Some(Self::Bash),
+            "zsh" => Some(Self::Zsh),
+            "fish" => Some(Self::Fish),
+            "nu" => Some(Self::Nushell),
+            _ => None,
+        }
+    }
+}
+
+const FISH_COMPLETIONS: &str = r#"# opencode completions, installed by OpenCode Desktop
+complete -c opencode -f -a '(opencode --get-yargs-completions (commandline -opc)[2..-1] (commandline -ct))'
+"#;
+
+const NUSHELL_COMPLETIONS: &str = r#"# opencode completions, installed by OpenCode Desktop
+def "nu-complete opencode" [context: string] {
+    let args = ($context | str trim --left | split row -r '\s+' | skip 1)
+    ^opencode --get-yargs-completions ...$args | lines
+}
+
+export extern "opencode" [...args: string@"nu-complete opencode"]
+"#;
+
+/// Writes completion scripts for the installed CLI and returns the path written.
+/// Uses the user's login shell when `shell` isn't given.
+#[tauri::command]
+#[specta::specta]
+pub async fn install_shell_completions(
+    app: AppHandle,
+    shell: Option<CompletionShell>,
+) -> Result<String, String> {
+    if cfg!(windows) {
+        return Err("Shell completions are only supported on macOS & Linux".to_string());
+    }
+
+    let shell = match shell {
+        Some(shell) => shell,
+        None => {
+            let user_shell = get_user_shell();
+            CompletionShell::detect(&user_shell)
+                .ok_or_else(|| format!("Unsupported shell '{}'", user_shell))?
+        }
+    };
+
+    let cli = get_cli_install_path(&app)
+        .filter(|path| path.exists())
+        .ok_or_else(|| "Install the opencode CLI before adding shell completions".to_string())?;
+
+    let home = dirs::home_dir().ok_or_else(|| "Could not determine home directory".to_string())?;
+    let data_dir = std::env::var_os("XDG_DATA_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".local").join("share"));
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".config"));
+
+    let (path, script) = match shell {
+        CompletionShell::Bash => (
+            data_dir
+                .join("bash-completion")
+                .join("completions")
+                .join("opencode"),
+            yargs_completions(&cli, "/bin/bash").await?,
+        ),
+        CompletionShell::Zsh => (
+            home.join(".opencode")
+                .join("completions")
+                .join("opencode.zsh"),
+            yargs_completions(&cli, "/bin/zsh").await?,
+        ),
+        CompletionShell::Fish => (
+            config_dir
+                .join("fish")
+                .join("completions")
+                .join("opencode.fish"),
+            FISH_COMPLETIONS.to_string(),
+        ),
+        CompletionShell::Nushell => (
+            config_dir
+                .join("nushell")
+                .join("autoload")
+                .join("opencode.nu"),
+            NUSHELL_COMPLETIONS.to_string(),
+        ),
+    };
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create completions directory: {}", e))?;
+    }
+    std::fs::write(&path, script).map_err(|e| format!("Failed to write completions: {}", e))?;
+
+    // zsh has no per-user completions directory on the default fpath, so source the script.
+    if shell == CompletionShell::Zsh {
+        let zshrc = std::env::var_os("ZDOTDIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.clone())
+            .join(".zshrc");
+        let line = format!("[ -f \"{0}\" ] && source \"{0}\"", path.display());
+
+        let existing = std::fs::read_to_string(&zshrc).unwrap_or_default();
+        if !existing.lines().any(|v| v.trim() == line) {
+            let separator = if existing.is_empty() || existing.ends_with('\n') {
+                ""
+            } else {
+                "\n"
+            };
+            std::fs::write(
+                &zshrc,
+                format!("{existing}{separator}\n# opencode\n{line}\n"),
+            )
+            .map_err(|e| format!("Failed to update {}: {}", zshrc.display(), e))?;
+        }
+    }
+
+    tracing::info!(?shell, path = %path.display(), "Installed shell completions");
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+// The CLI uses yargs, which picks the bash or zsh flavour of its script from $SHELL.
+async fn yargs_completions(cli: &Path, shell: &str) -> Result<String, String> {
+    let mut cmd = Command::new(cli);
+    cmd.arg("completion").env("SHELL", shell).kill_on_drop(true);
+
+    let output = tokio::time::timeout(COMPLETION_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| "Timed out generating completions".to_string())?
+        .map_err(|e| format!("Failed to generate completions: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to generate completions: {}", stderr.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
@@ -1,8 +1,41 @@
 use tauri_plugin_window_state::StateFlags;
 
 pub const SETTINGS_STORE: &str = "opencode.settings.dat";
+pub const WINDOW_SESSION_STORE: &str = "opencode.window-session.dat";
 pub const DEFAULT_SERVER_URL_KEY: &str = "defaultServerUrl";
 pub const WSL_ENABLED_KEY: &str = "wslEnabled";
+pub const WSL_DISTRO_KEY: &str = "wslDistro";
+pub const WSL_CLI_INSTALL_KEY: &str = "wslCliInstall";
+pub const WSL_LAN_ACCESS_KEY: &str = "wslLanAccess";
+pub const SERVER_FALLBACK_URLS_KEY: &str = "serverFallbackUrls";
+pub const CLI_PINNED_VERSION_KEY: &str = "cliPinnedVersion";
+pub const CLI_TAKE_OVER_KEY: &str = "cliTakeOver";
+pub const CLI_INSTALL_DIR_KEY: &str = "cliInstallDir";
+pub const CLI_CHANNEL_KEY: &str = "cliChannel";
+pub const WAKE_ON_LAN_KEY: &str = "wakeOnLan";
+pub const QUIET_HOURS_KEY: &str = "quietHours";
+pub const NOTIFICATION_RULES_KEY: &str = "notificationRules";
+pub const SIDECAR_LIMITS_KEY: &str = "sidecarLimits";
+pub const SERVER_SERVICE_KEY: &str = "serverService";
+pub const ACTIVE_PROJECT_KEY: &str = "activeProject";
+pub const BACKEND_RULES_KEY: &str = "backendRules";
+pub const SHELL_MODE_KEY: &str = "shellMode";
+pub const ADOPTED_SERVER_KEY: &str = "adoptedServer";
+pub const ENV_POLICY_KEY: &str = "envPolicy";
+pub const OS_SEARCH_KEY: &str = "osSearch";
+pub const USAGE_DIGEST_KEY: &str = "usageDigest";
+pub const USAGE_DIGEST_SENT_KEY: &str = "usageDigestLastSent";
+pub const WARM_UP_KEY: &str = "warmUp";
+pub const AUTOSTART_KEY: &str = "autostart";
+pub const CLOSE_TO_TRAY_KEY: &str = "closeToTray";
+pub const GLOBAL_SHORTCUT_KEY: &str = "globalShortcut";
+pub const QUICK_PANEL_SHORTCUT_KEY: &str = "quickPanelShortcut";
+pub const WINDOW_PREFERENCES_KEY: &str = "windowPreferences";
+pub const WINDOW_EFFECT_KEY: &str = "windowEffect";
+pub const WINDOW_STATE_KEY: &str = "windowState";
+pub const TITLEBAR_KEY: &str = "titlebar";
+pub const WINDOW_BACKGROUND_KEY: &str = "windowBackground";
+pub const DEVELOPER_MODE_KEY: &str = "developerMode";
 pub const UPDATER_ENABLED: bool = option_env!("TAURI_SIGNING_PRIVATE_KEY").is_some();
 
 pub fn window_state_flags() -> StateFlags {
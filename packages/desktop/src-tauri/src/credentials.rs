@@ -0,0 +1,126 @@
+//! Passwords for servers the app adopts but didn't start, kept in the OS keychain by server
+//! URL. A password the app generates for its own sidecar is never stored.
+
+use tokio::process::Command;
+
+use crate::server;
+
+const KEYCHAIN_SERVICE: &str = "ai.opencode.desktop.server";
+const PROMPT_ATTEMPTS: usize = 3;
+
+/// Finds the password for a server at `url` that rejected an unauthenticated health check,
+/// trying the keychain before asking for it. Returns `None` when the user gives up.
+pub async fn password_for(url: &str) -> Option<String> {
+    if let Some(password) = stored(url)
+        && server::check_health(url, Some(&password)).await
+    {
+        tracing::info!(%url, "Using stored server password");
+        return Some(password);
+    }
+
+    let mut message = format!("The OpenCode server at {url} requires a password.");
+    for _ in 0..PROMPT_ATTEMPTS {
+        let password = match prompt(&message).await {
+            Ok(Some(password)) => password,
+            Ok(None) => break,
+            Err(e) => {
+                tracing::warn!("Failed to ask for the server password: {e}");
+                break;
+            }
+        };
+
+        if server::check_health(url, Some(&password)).await {
+            if let Err(e) = store(url, &password) {
+                tracing::warn!(%url, "Failed to store server password: {e}");
+            }
+            return Some(password);
+        }
+        message = format!("The password for the OpenCode server at {url} was rejected. Try again.");
+    }
+
+    tracing::info!(%url, "No password for server");
+    None
+}
+
+fn entry(url: &str) -> keyring::Result<keyring::Entry> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, url)
+}
+
+/// The password stored for the server at `url`, without checking or asking for it.
+pub fn stored(url: &str) -> Option<String> {
+    entry(url).and_then(|entry| entry.get_password()).ok()
+}
+
+fn store(url: &str, password: &str) -> Result<(), String> {
+    entry(url)
+        .and_then(|entry| entry.set_password(password))
+        .map_err(|e| e.to_string())
+}
+
+// Each platform's own password prompt, so this works before any window is open. `None` when
+// it was cancelled.
+async fn prompt(message: &str) -> Result<Option<String>, String> {
+    let output = prompt_command(message)?
+        .output()
+        .await
+        .map_err(|e| format!("Failed to show password prompt: {}", e))?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let password = String::from_utf8_lossy(&output.stdout)
+        .trim_end_matches(['\r', '\n'])
+        .to_string();
+    Ok((!password.is_empty()).then_some(password))
+}
+
+#[cfg(target_os = "macos")]
+fn prompt_command(message: &str) -> Result<Command, String> {
+    let mut cmd = Command::new("osascript");
+    cmd.args([
+        "-e",
+        "on run argv",
+        "-e",
+        "text returned of (display dialog (item 1 of argv) default answer \"\" with hidden \
+         answer with title \"OpenCode\" buttons {\"Cancel\", \"Connect\"} default button \
+         \"Connect\")",
+        "-e",
+        "end run",
+        message,
+    ]);
+    Ok(cmd)
+}
+
+#[cfg(target_os = "linux")]
+fn prompt_command(message: &str) -> Result<Command, String> {
+    let found = |name: &str| {
+        std::env::var_os("PATH")
+            .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(name).is_file()))
+    };
+
+    if found("zenity") {
+        let mut cmd = Command::new("zenity");
+        cmd.args(["--password", "--title", message]);
+        Ok(cmd)
+    } else if found("kdialog") {
+        let mut cmd = Command::new("kdialog");
+        cmd.args(["--title", "OpenCode", "--password", message]);
+        Ok(cmd)
+    } else {
+        Err("neither zenity nor kdialog is installed".to_string())
+    }
+}
+
+#[cfg(windows)]
+fn prompt_command(message: &str) -> Result<Command, String> {
+    // The message is passed through the environment so it never has to be quoted for PowerShell
+    let mut cmd = Command::new("powershell");
+    cmd.env("OPENCODE_PROMPT", message).args([
+        "-NoProfile",
+        "-Command",
+        "$c = Get-Credential -UserName opencode -Message $env:OPENCODE_PROMPT; \
+         if (-not $c) { exit 1 }; $c.GetNetworkCredential().Password",
+    ]);
+    cmd.creation_flags(0x0800_0000);
+    Ok(cmd)
+}
@@ -0,0 +1,67 @@
+// Window controls for Linux sessions without a native titlebar, drawn into the same
+// `[data-tauri-decorum-tb]` slots decorum fills on Windows. See `csd.rs`.
+(() => {
+  const invoke = (cmd, args) => window.__TAURI_INTERNALS__.invoke(cmd, args)
+  const icon = (path, cls = "") =>
+    `<svg class="${cls}" width="10" height="10" viewBox="0 0 10 10" fill="none" stroke="currentColor" stroke-width="1">${path}</svg>`
+  const buttons = {
+    minimize: icon('<path d="M0 5.5h10"/>'),
+    maximize:
+      icon('<rect x="0.5" y="0.5" width="9" height="9"/>', "csd-maximize") +
+      icon('<path d="M2.5 2.5V0.5h7v7h-2M0.5 2.5h7v7h-7z"/>', "csd-restore"),
+    close: icon('<path d="M0 0l10 10M10 0L0 10"/>'),
+  }
+
+  const style = `
+    .opencode-csd-button {
+      display: inline-flex; align-items: center; justify-content: center;
+      width: 46px; height: 100%; min-height: 32px; color: inherit; background: transparent;
+      border: 0; cursor: default;
+    }
+    .opencode-csd-button:hover { background: rgba(128, 128, 128, 0.2); }
+    .opencode-csd-button[data-button="close"]:hover { background: #c42b1c; color: #fff; }
+    .opencode-csd-button .csd-restore, :root[data-maximized] .opencode-csd-button .csd-maximize { display: none; }
+    :root[data-maximized] .opencode-csd-button .csd-restore { display: block; }
+  `
+
+  const fill = (slot) => {
+    if (slot.dataset.opencodeCsd) return
+    slot.dataset.opencodeCsd = "true"
+
+    for (const [button, svg] of Object.entries(buttons)) {
+      const el = document.createElement("button")
+      el.className = "opencode-csd-button"
+      el.dataset.button = button
+      el.setAttribute("aria-label", button)
+      el.innerHTML = svg
+      el.addEventListener("click", () => invoke("window_button", { button }))
+      slot.append(el)
+    }
+
+    // The gaps between the buttons move the window like the rest of the titlebar
+    slot.addEventListener("mousedown", (e) => {
+      if (e.buttons !== 1 || e.target.closest("button")) return
+      e.preventDefault()
+      e.stopPropagation()
+      if (e.detail === 2) invoke("titlebar_double_click")
+      else invoke("start_dragging")
+    })
+  }
+
+  const scan = () => document.querySelectorAll("[data-tauri-decorum-tb]").forEach(fill)
+  new MutationObserver(scan).observe(document, { childList: true, subtree: true })
+
+  document.addEventListener("DOMContentLoaded", () => {
+    const el = document.createElement("style")
+    el.textContent = style
+    document.head.append(el)
+    scan()
+
+    // Later changes are pushed from Rust as the window is resized
+    invoke("plugin:window|is_maximized", { label: window.__TAURI_INTERNALS__.metadata.currentWindow.label })
+      .then((maximized) => {
+        if (maximized) document.documentElement.dataset.maximized = ""
+      })
+      .catch(() => undefined)
+  })
+})()
@@ -0,0 +1,68 @@
+//! Client-side decorations for Linux sessions where windows get no titlebar, e.g. on tiling
+//! window managers. Like decorum's overlay on Windows, the window controls are drawn into the
+//! frontend's `[data-tauri-decorum-tb]` slots, by a script injected into each window.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{WebviewWindow, WindowEvent};
+
+/// Draws the window controls and moves the window from the gaps between them.
+pub const SCRIPT: &str = include_str!("csd.js");
+
+#[derive(Clone, Copy, serde::Deserialize, specta::Type, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowButton {
+    Minimize,
+    /// Maximizes the window, or restores it when it's maximized.
+    Maximize,
+    Close,
+}
+
+/// Starts moving the calling window with the mouse, for a mouse button that's held down.
+#[tauri::command]
+#[specta::specta]
+pub fn start_dragging(window: WebviewWindow) -> Result<(), String> {
+    window
+        .start_dragging()
+        .map_err(|e| format!("Failed to start dragging: {}", e))
+}
+
+/// Acts on the calling window like the button of a native titlebar.
+#[tauri::command]
+#[specta::specta]
+pub fn window_button(window: WebviewWindow, button: WindowButton) -> Result<(), String> {
+    let result = match button {
+        WindowButton::Minimize => window.minimize(),
+        WindowButton::Maximize if window.is_maximized().unwrap_or(false) => window.unmaximize(),
+        WindowButton::Maximize => window.maximize(),
+        WindowButton::Close => window.close(),
+    };
+
+    result.map_err(|e| format!("Failed to handle {:?} button: {}", button, e))
+}
+
+/// Keeps the maximize button of `window` in sync, e.g. when the window manager maximizes it
+/// from a keyboard shortcut.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn attach(window: &WebviewWindow) {
+    let maximized = AtomicBool::new(window.is_maximized().unwrap_or(false));
+    let handle = window.clone();
+
+    window.on_window_event(move |event| {
+        if !matches!(event, WindowEvent::Resized(_)) {
+            return;
+        }
+
+        let now = handle.is_maximized().unwrap_or(false);
+        if maximized.swap(now, Ordering::Relaxed) == now {
+            return;
+        }
+
+        let script = if now {
+            "document.documentElement.dataset.maximized = ''"
+        } else {
+            "delete document.documentElement.dataset.maximized"
+        };
+        let _ = handle.eval(script);
+    });
+}
@@ -0,0 +1,102 @@
+//! Keeps the app's own reads of the opencode database out of the way while the server migrates
+//! it. A read holds a lock on the sqlite file, which can stall a long migration until the app's
+//! startup times out.
+
+use std::{fmt, future::Future, sync::LazyLock};
+
+use tauri::AppHandle;
+use tokio::sync::watch;
+
+use crate::cli::sqlite_migration::SqliteMigrationProgress;
+
+static MIGRATING: LazyLock<watch::Sender<bool>> = LazyLock::new(|| watch::channel(false).0);
+
+/// Why a read from the local database failed.
+#[derive(Clone, serde::Serialize, specta::Type, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DbQueryError {
+    /// The server is migrating the database. Reads work again once `SqliteMigrationProgress`
+    /// reports it's done.
+    MigrationInProgress,
+    Failed {
+        message: String,
+    },
+}
+
+impl fmt::Display for DbQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MigrationInProgress => {
+                write!(
+                    f,
+                    "The database is being migrated, try again once it's done"
+                )
+            }
+            Self::Failed { message } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl From<String> for DbQueryError {
+    fn from(message: String) -> Self {
+        Self::Failed { message }
+    }
+}
+
+impl From<DbQueryError> for String {
+    fn from(error: DbQueryError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Follows migrations reported by any run of the CLI.
+pub fn watch(app: &AppHandle) {
+    SqliteMigrationProgress::listen_any(app, |e| {
+        let migrating = matches!(e.payload, SqliteMigrationProgress::InProgress(_));
+        if MIGRATING.send_replace(migrating) != migrating {
+            tracing::info!(migrating, "Database migration state changed");
+        }
+    });
+}
+
+/// Fails with `MigrationInProgress` while the database is being migrated.
+pub fn check() -> Result<(), DbQueryError> {
+    if *MIGRATING.borrow() {
+        return Err(DbQueryError::MigrationInProgress);
+    }
+    Ok(())
+}
+
+/// Runs `query`, and again once the migration is done when it was turned away by one. For reads
+/// in the background, which have no caller to retry them.
+pub async fn retry<T, F, Fut>(mut query: F) -> Result<T, DbQueryError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, DbQueryError>>,
+{
+    loop {
+        match query().await {
+            Err(DbQueryError::MigrationInProgress) => {
+                tracing::info!("Database is being migrated, reading it once that's done");
+                let _ = MIGRATING.subscribe().wait_for(|migrating| !migrating).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_errors() {
+        assert_eq!(
+            String::from(DbQueryError::from(
+                "Timed out querying the database".to_string()
+            )),
+            "Timed out querying the database"
+        );
+        assert!(String::from(DbQueryError::MigrationInProgress).contains("migrated"));
+    }
+}
@@ -0,0 +1,171 @@
+//! Confirmation before a deep link changes anything, since any web page or app can open one.
+//! The dialog says exactly what the link asks for and where it claims to come from. Nothing
+//! verifies that claim, so every link is confirmed and none is remembered as trusted.
+
+use tauri::{AppHandle, WebviewWindow};
+use tauri_plugin_dialog::{
+    DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult,
+};
+use tokio::sync::oneshot;
+
+const DEEP_LINK_SCHEME: &str = "opencode";
+const ALLOW: &str = "Allow";
+
+/// What a deep link asks the app to change.
+#[derive(Debug, PartialEq)]
+enum DeepLinkChange {
+    /// Adds the project to the sidebar and opens it, at `session` if given.
+    OpenProject {
+        directory: String,
+        session: Option<String>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+struct DeepLinkRequest {
+    change: DeepLinkChange,
+    /// The `origin` the link gives, e.g. the site that built it, reduced to scheme, host and port.
+    /// Nothing vouches for it.
+    origin: Option<String>,
+}
+
+/// Asks whether to apply the deep link `url`, over the calling window. Resolves to whether the
+/// link may be applied. Links this app doesn't know are refused.
+#[tauri::command]
+#[specta::specta]
+pub async fn confirm_deep_link(
+    app: AppHandle,
+    window: WebviewWindow,
+    url: String,
+) -> Result<bool, String> {
+    let Some(request) = parse(&url) else {
+        tracing::warn!(%url, "Refusing unknown deep link");
+        return Ok(false);
+    };
+
+    let (tx, rx) = oneshot::channel();
+    app.dialog()
+        .message(summary(&request))
+        .title("Open this link?")
+        .kind(MessageDialogKind::Warning)
+        .parent(&window)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            ALLOW.to_string(),
+            "Cancel".to_string(),
+        ))
+        .show_with_result(move |result| {
+            let _ = tx.send(result);
+        });
+
+    let result = rx
+        .await
+        .map_err(|_| "The confirmation dialog closed unexpectedly".to_string())?;
+    let confirmed = matches!(result, MessageDialogResult::Custom(name) if name == ALLOW);
+
+    tracing::info!(origin = ?request.origin, confirmed, "Deep link confirmation");
+    Ok(confirmed)
+}
+
+fn parse(url: &str) -> Option<DeepLinkRequest> {
+    let url = tauri::Url::parse(url).ok()?;
+    if url.scheme() != DEEP_LINK_SCHEME {
+        return None;
+    }
+
+    let param = |key: &str| {
+        url.query_pairs()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.into_owned())
+            .filter(|value| !value.is_empty())
+    };
+    let change = match url.host_str()? {
+        "open-project" => DeepLinkChange::OpenProject {
+            directory: param("directory")?,
+            session: param("session"),
+        },
+        _ => return None,
+    };
+
+    // Only web origins, which can be shown unambiguously
+    let origin = param("origin")
+        .and_then(|origin| tauri::Url::parse(&origin).ok())
+        .filter(|origin| matches!(origin.scheme(), "http" | "https"))
+        .map(|origin| origin.origin().ascii_serialization());
+
+    Some(DeepLinkRequest { change, origin })
+}
+
+fn summary(request: &DeepLinkRequest) -> String {
+    let mut message = match &request.change {
+        DeepLinkChange::OpenProject { directory, session } => {
+            let mut message = format!("A link wants to open the project\n\n{directory}");
+            if let Some(session) = session {
+                message.push_str(&format!("\n\nat session {session}"));
+            }
+            message
+        }
+    };
+
+    match &request.origin {
+        Some(origin) => message.push_str(&format!(
+            "\n\nThe link claims to come from {origin}, which can't be verified. Only allow it \
+             if you opened it yourself."
+        )),
+        None => message.push_str(
+            "\n\nThe link doesn't say where it comes from. Only allow it if you opened it yourself.",
+        ),
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_project_links_with_their_origin() {
+        assert_eq!(
+            parse(
+                "opencode://open-project?directory=%2FUsers%2Fme%2Fmy+app&session=ses_123&origin=https%3A%2F%2Fexample.com%2Fshare%2Fabc"
+            ),
+            Some(DeepLinkRequest {
+                change: DeepLinkChange::OpenProject {
+                    directory: "/Users/me/my app".to_string(),
+                    session: Some("ses_123".to_string()),
+                },
+                origin: Some("https://example.com".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn ignores_origins_that_arent_web_pages() {
+        let request = parse("opencode://open-project?directory=/a&origin=javascript:alert(1)");
+        assert_eq!(request.and_then(|request| request.origin), None);
+    }
+
+    #[test]
+    fn refuses_unknown_links() {
+        assert_eq!(parse("opencode://open-project"), None);
+        assert_eq!(parse("opencode://reset-settings"), None);
+        assert_eq!(parse("https://example.com/open-project?directory=/a"), None);
+    }
+
+    #[test]
+    fn marks_the_claimed_origin_as_unverified() {
+        let request =
+            parse("opencode://open-project?directory=/a&origin=https://example.com").unwrap();
+        assert!(summary(&request).contains("claims to come from https://example.com"));
+        assert!(summary(&request).contains("can't be verified"));
+    }
+
+    #[test]
+    fn summarizes_what_changes() {
+        let request = parse("opencode://open-project?directory=/a").unwrap();
+        assert_eq!(
+            summary(&request),
+            "A link wants to open the project\n\n/a\n\nThe link doesn't say where it comes from. \
+             Only allow it if you opened it yourself."
+        );
+    }
+}
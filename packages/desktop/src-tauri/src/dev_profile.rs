@@ -0,0 +1,35 @@
+//! Keeps debug builds from touching a release install's data. The app's own settings are already
+//! separate, through the `.dev` identifier, but the sidecar would otherwise share the CLI's
+//! database and the app would adopt the release's server service.
+//!
+//! Set `OPENCODE_DESKTOP_SHARED_PROFILE=1` to run a debug build against the real data.
+
+use std::{net::TcpListener, ops::RangeInclusive, path::PathBuf};
+
+use crate::app_dirs::AppDirs;
+
+const SHARED_PROFILE_ENV: &str = "OPENCODE_DESKTOP_SHARED_PROFILE";
+// Away from the CLI's default 4096 and the ephemeral ports release builds get
+const PORT_RANGE: RangeInclusive<u16> = 47100..=47199;
+
+/// Whether this is a debug build running with its own data.
+pub fn isolated() -> bool {
+    cfg!(debug_assertions) && std::env::var(SHARED_PROFILE_ENV).is_none_or(|v| v != "1")
+}
+
+/// `XDG_DATA_HOME` for the sidecar, where the CLI keeps its database and auth.
+pub fn data_home() -> Option<PathBuf> {
+    isolated().then(|| AppDirs::get().map(|dirs| dirs.data.join("sidecar")))?
+}
+
+/// `XDG_CACHE_HOME` for the sidecar.
+pub fn cache_home() -> Option<PathBuf> {
+    isolated().then(|| AppDirs::get().map(|dirs| dirs.cache.join("sidecar")))?
+}
+
+/// A free port from the dev range, so a dev sidecar is easy to tell apart from a release one.
+pub fn free_port() -> Option<u16> {
+    PORT_RANGE
+        .into_iter()
+        .find(|port| TcpListener::bind(("127.0.0.1", *port)).is_ok())
+}
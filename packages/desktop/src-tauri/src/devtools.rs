@@ -0,0 +1,70 @@
+//! The webview's inspector, which debug builds always have and release builds only offer with
+//! developer mode on, e.g. to look at errors in the console when reporting a bug.
+
+use tauri::{AppHandle, Manager, WebviewWindow};
+use tauri_plugin_store::StoreExt;
+
+use crate::constants::{DEVELOPER_MODE_KEY, SETTINGS_STORE};
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_developer_mode(app: AppHandle) -> bool {
+    app.store(SETTINGS_STORE)
+        .ok()
+        .and_then(|store| store.get(DEVELOPER_MODE_KEY))
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false)
+}
+
+/// Turning it off closes any open inspector. The menu item to toggle the inspector shows after a
+/// restart.
+#[tauri::command]
+#[specta::specta]
+pub fn set_developer_mode(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    store.set(DEVELOPER_MODE_KEY, serde_json::json!(enabled));
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    if !allowed(&app) {
+        for window in app.webview_windows().into_values() {
+            window.close_devtools();
+        }
+    }
+    Ok(())
+}
+
+/// Opens the inspector for the calling window.
+#[tauri::command]
+#[specta::specta]
+pub fn open_devtools(app: AppHandle, window: WebviewWindow) -> Result<(), String> {
+    if !allowed(&app) {
+        return Err("Developer tools need developer mode to be turned on".to_string());
+    }
+
+    window.open_devtools();
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn close_devtools(window: WebviewWindow) {
+    window.close_devtools();
+}
+
+/// Whether the inspector can be opened in this build with the current settings.
+pub fn allowed(app: &AppHandle) -> bool {
+    cfg!(debug_assertions) || get_developer_mode(app.clone())
+}
+
+/// Opens the inspector of `window`, or closes it when it's open.
+pub fn toggle(app: &AppHandle, window: &WebviewWindow) {
+    if window.is_devtools_open() {
+        window.close_devtools();
+    } else if allowed(app) {
+        window.open_devtools();
+    }
+}
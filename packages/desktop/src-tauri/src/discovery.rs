@@ -0,0 +1,128 @@
+use std::{collections::HashMap, net::Ipv4Addr, time::Duration};
+
+use futures::future;
+use tokio::{process::Command, time::timeout};
+
+const DEFAULT_SERVER_PORT: u32 = 4096;
+const TAILSCALE_TIMEOUT: Duration = Duration::from_secs(5);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, serde::Serialize, specta::Type, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscoverySource {
+    Tailscale,
+}
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+pub struct DiscoveredServer {
+    name: String,
+    url: String,
+    source: DiscoverySource,
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct TailscaleStatus {
+    #[serde(rename = "Peer", default)]
+    peer: HashMap<String, TailscalePeer>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct TailscalePeer {
+    #[serde(rename = "HostName", default)]
+    host_name: String,
+    #[serde(rename = "DNSName", default)]
+    dns_name: String,
+    #[serde(rename = "TailscaleIPs", default)]
+    tailscale_ips: Vec<String>,
+    #[serde(rename = "Online", default)]
+    online: bool,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn discover_servers(port: Option<u32>) -> Result<Vec<DiscoveredServer>, String> {
+    let port = port.unwrap_or(DEFAULT_SERVER_PORT);
+
+    let probes = tailscale_peers()
+        .await
+        .into_iter()
+        .filter(|peer| peer.online)
+        .filter_map(|peer| {
+            let ip = peer
+                .tailscale_ips
+                .iter()
+                .find(|ip| ip.parse::<Ipv4Addr>().is_ok())?;
+            let name = Some(peer.dns_name.trim_end_matches('.'))
+                .filter(|v| !v.is_empty())
+                .unwrap_or(&peer.host_name)
+                .to_string();
+
+            Some((name, format!("http://{ip}:{port}")))
+        })
+        .map(|(name, url)| async move {
+            probe(&url).await.then_some(DiscoveredServer {
+                name,
+                url,
+                source: DiscoverySource::Tailscale,
+            })
+        });
+
+    Ok(future::join_all(probes)
+        .await
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+async fn tailscale_peers() -> Vec<TailscalePeer> {
+    let binary = if cfg!(target_os = "macos")
+        && std::path::Path::new("/Applications/Tailscale.app/Contents/MacOS/Tailscale").exists()
+    {
+        "/Applications/Tailscale.app/Contents/MacOS/Tailscale"
+    } else {
+        "tailscale"
+    };
+
+    let mut cmd = Command::new(binary);
+    cmd.args(["status", "--json"]).kill_on_drop(true);
+
+    #[cfg(windows)]
+    cmd.creation_flags(0x0800_0000);
+
+    let output = match timeout(TAILSCALE_TIMEOUT, cmd.output()).await {
+        Ok(Ok(output)) if output.status.success() => output,
+        Ok(Ok(_)) => {
+            tracing::debug!("Tailscale is not running");
+            return Vec::new();
+        }
+        Ok(Err(e)) => {
+            tracing::debug!("Tailscale not available: {e}");
+            return Vec::new();
+        }
+        Err(_) => {
+            tracing::warn!("Timed out querying Tailscale status");
+            return Vec::new();
+        }
+    };
+
+    serde_json::from_slice::<TailscaleStatus>(&output.stdout)
+        .map(|status| status.peer.into_values().collect())
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to parse Tailscale status: {e}");
+            Vec::new()
+        })
+}
+
+// Password-protected servers answer the health endpoint with 401, which still means
+// an opencode server is listening there.
+async fn probe(url: &str) -> bool {
+    let Ok(client) = reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() else {
+        return false;
+    };
+
+    client
+        .get(format!("{url}/global/health"))
+        .send()
+        .await
+        .is_ok_and(|r| r.status().is_success() || r.status() == reqwest::StatusCode::UNAUTHORIZED)
+}
@@ -0,0 +1,235 @@
+//! Everything that decides how the app behaves, in one list with where each value comes from.
+//! Settings can be overridden from several places, so what's saved is often not what's used.
+
+use serde_json::Value;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::{
+    cli,
+    constants::{DEFAULT_SERVER_URL_KEY, ENV_POLICY_KEY, SETTINGS_STORE, UPDATER_ENABLED},
+    launch_args::LaunchArgs,
+};
+
+/// Environment variables the app reads itself. Those meant for the CLI are listed by
+/// `preview_sidecar_env` instead.
+const ENV_VARS: &[&str] = &[
+    "OPENCODE_PORT",
+    "OPENCODE_DESKTOP_LOG_DIR",
+    "OPENCODE_DESKTOP_CACHE_DIR",
+    "OPENCODE_DESKTOP_STATE_DIR",
+    "OPENCODE_DESKTOP_SHARED_PROFILE",
+    "OPENCODE_DESKTOP_SLOW_STARTUP",
+    "OPENCODE_RECORD_EVENTS",
+    "OPENCODE_REPLAY_EVENTS",
+    "OC_FORCE_X11",
+    "OC_FORCE_WAYLAND",
+    "OC_ALLOW_WAYLAND",
+    "OC_LINUX_DECORATIONS",
+    "OC_NO_DECORATIONS",
+    "OC_FORCE_DECORATIONS",
+];
+
+// Values under keys containing any of these are hidden
+const SECRET_KEYS: &[&str] = &["password", "secret", "token"];
+const REDACTED: &str = "<redacted>";
+
+#[derive(Clone, Copy, serde::Serialize, specta::Type, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    /// Fixed when the app was built.
+    Build,
+    /// A flag the app was launched with.
+    LaunchArgument,
+    /// An environment variable the app was started with.
+    Environment,
+    /// A policy in the settings that limits what else applies, e.g. the sidecar's environment.
+    Policy,
+    /// Saved in the app's settings.
+    Settings,
+    /// The `server` block of the CLI's config.
+    CliConfig,
+}
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug, PartialEq)]
+pub struct ConfigEntry {
+    pub key: String,
+    /// The value as JSON, with secrets redacted.
+    pub value: String,
+    pub source: ConfigSource,
+    /// Set when the value is ignored because another source takes precedence.
+    pub overridden_by: Option<ConfigSource>,
+}
+
+impl ConfigEntry {
+    fn new(key: impl Into<String>, mut value: Value, source: ConfigSource) -> Self {
+        let key = key.into();
+        if is_secret(&key) {
+            value = Value::String(REDACTED.to_string());
+        }
+        redact(&mut value);
+
+        Self {
+            key,
+            value: value.to_string(),
+            source,
+            overridden_by: None,
+        }
+    }
+
+    fn overridden_by(mut self, source: Option<ConfigSource>) -> Self {
+        self.overridden_by = source;
+        self
+    }
+}
+
+/// The configuration the app is running with, for support to see what a user's app is actually
+/// doing.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_effective_config(app: AppHandle) -> Result<Vec<ConfigEntry>, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    let env = ENV_VARS
+        .iter()
+        .filter_map(|key| Some((*key, std::env::var(key).ok()?)))
+        .collect::<Vec<_>>();
+
+    let mut entries = vec![
+        ConfigEntry::new(
+            "updaterEnabled",
+            UPDATER_ENABLED.into(),
+            ConfigSource::Build,
+        ),
+        ConfigEntry::new(
+            "debugBuild",
+            cfg!(debug_assertions).into(),
+            ConfigSource::Build,
+        ),
+    ];
+
+    let build_port = option_env!("OPENCODE_PORT");
+    if let Some(port) = build_port {
+        entries.push(ConfigEntry::new(
+            "OPENCODE_PORT",
+            port.into(),
+            ConfigSource::Build,
+        ));
+    }
+
+    if LaunchArgs::get().kiosk {
+        entries.push(ConfigEntry::new(
+            "kiosk",
+            true.into(),
+            ConfigSource::LaunchArgument,
+        ));
+    }
+
+    for (key, value) in &env {
+        let overridden_by =
+            (*key == "OPENCODE_PORT" && build_port.is_some()).then_some(ConfigSource::Build);
+        entries.push(
+            ConfigEntry::new(*key, value.as_str().into(), ConfigSource::Environment)
+                .overridden_by(overridden_by),
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    if let Some(wayland) = crate::linux_display::read_wayland() {
+        let forced = env
+            .iter()
+            .any(|(key, _)| matches!(*key, "OC_FORCE_X11" | "OC_FORCE_WAYLAND"));
+        entries.push(
+            ConfigEntry::new(
+                "linuxDisplay.wayland",
+                wayland.into(),
+                ConfigSource::Settings,
+            )
+            .overridden_by(forced.then_some(ConfigSource::Environment)),
+        );
+    }
+
+    let mut settings = store.entries();
+    settings.sort_by(|a, b| a.0.cmp(&b.0));
+    for (key, value) in settings {
+        let source = if key == ENV_POLICY_KEY {
+            ConfigSource::Policy
+        } else {
+            ConfigSource::Settings
+        };
+        entries.push(ConfigEntry::new(key, value, source));
+    }
+
+    // The desktop's default server is used over the one the CLI is configured with
+    let server_url_saved = store
+        .get(DEFAULT_SERVER_URL_KEY)
+        .is_some_and(|value| !value.is_null());
+    if let Some(server) = cli::get_config(&app).await.and_then(|config| config.server) {
+        let overridden_by = server_url_saved.then_some(ConfigSource::Settings);
+        for (key, value) in [
+            ("server.hostname", server.hostname.map(Value::from)),
+            ("server.port", server.port.map(Value::from)),
+        ] {
+            if let Some(value) = value {
+                entries.push(
+                    ConfigEntry::new(key, value, ConfigSource::CliConfig)
+                        .overridden_by(overridden_by),
+                );
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+fn is_secret(key: &str) -> bool {
+    let key = key.to_lowercase();
+    SECRET_KEYS.iter().any(|secret| key.contains(secret))
+}
+
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_secret(key) {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_nested_secrets() {
+        let entry = ConfigEntry::new(
+            "adoptedServer",
+            serde_json::json!({
+                "url": "http://localhost:4096",
+                "auth": [{ "Password": "hunter2", "username": "opencode" }],
+            }),
+            ConfigSource::Settings,
+        );
+
+        assert!(!entry.value.contains("hunter2"));
+        assert!(entry.value.contains("opencode"));
+        assert!(entry.value.contains("localhost:4096"));
+        assert_eq!(
+            ConfigEntry::new(
+                "OPENCODE_SERVER_PASSWORD",
+                "x".into(),
+                ConfigSource::Environment
+            )
+            .value,
+            format!("\"{REDACTED}\"")
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+use tokio::process::Command;
+
+use crate::{
+    cli,
+    constants::{ENV_POLICY_KEY, SETTINGS_STORE},
+};
+
+/// Variables the sidecar can't start without, kept under every allowlist.
+const ESSENTIAL_VARS: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USER",
+    "LOGNAME",
+    "SHELL",
+    "TERM",
+    "TMPDIR",
+    "LANG",
+    "LC_*",
+    "TZ",
+    // systemd-run needs these to reach the user manager when limits are applied
+    "XDG_RUNTIME_DIR",
+    "DBUS_SESSION_BUS_ADDRESS",
+    "SYSTEMROOT",
+    "WINDIR",
+    "COMSPEC",
+    "PATHEXT",
+    "TEMP",
+    "TMP",
+    "USERPROFILE",
+    "APPDATA",
+    "LOCALAPPDATA",
+];
+
+/// Which of the app's environment variables are passed on to the sidecar. Variables the app
+/// sets itself, like `OPENCODE_CLIENT`, are always passed. Patterns may end in `*` to match a
+/// prefix, e.g. `AWS_*`.
+#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type, Debug, Default, PartialEq)]
+#[serde(tag = "policy", rename_all = "snake_case")]
+pub enum EnvPolicy {
+    #[default]
+    InheritAll,
+    /// Only pass variables matching `vars`, plus the few the sidecar needs to run.
+    Allowlist { vars: Vec<String> },
+    /// Pass everything except variables matching `vars`.
+    Denylist { vars: Vec<String> },
+}
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug, PartialEq)]
+pub struct SidecarEnvVar {
+    pub key: String,
+    pub value: String,
+}
+
+impl EnvPolicy {
+    pub fn allows(&self, key: &str) -> bool {
+        match self {
+            Self::InheritAll => true,
+            Self::Allowlist { vars } => ESSENTIAL_VARS
+                .iter()
+                .copied()
+                .chain(vars.iter().map(String::as_str))
+                .any(|pattern| matches(pattern, key)),
+            Self::Denylist { vars } => !vars.iter().any(|pattern| matches(pattern, key)),
+        }
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_env_policy(app: AppHandle) -> Result<EnvPolicy, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(ENV_POLICY_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_env_policy(app: AppHandle, policy: EnvPolicy) -> Result<(), String> {
+    if let EnvPolicy::Allowlist { vars } | EnvPolicy::Denylist { vars } = &policy
+        && let Some(var) = vars.iter().find(|var| !is_pattern(var))
+    {
+        return Err(format!("Invalid environment variable pattern '{}'", var));
+    }
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(ENV_POLICY_KEY, serde_json::json!(policy));
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+/// Lists exactly which variables the sidecar would be started with under `policy`, or the
+/// saved policy when none is given. Login shells may still add variables from profiles.
+#[tauri::command]
+#[specta::specta]
+pub fn preview_sidecar_env(
+    app: AppHandle,
+    policy: Option<EnvPolicy>,
+) -> Result<Vec<SidecarEnvVar>, String> {
+    let policy = match policy {
+        Some(policy) => policy,
+        None => get_env_policy(app.clone())?,
+    };
+
+    let mut vars = std::env::vars_os()
+        .filter_map(|(key, value)| Some((key.into_string().ok()?, value)))
+        .filter(|(key, _)| policy.allows(key))
+        .map(|(key, value)| (key, value.to_string_lossy().to_string()))
+        .collect::<Vec<_>>();
+
+    for (key, value) in cli::sidecar_envs(&app, &[]) {
+        vars.retain(|(existing, _)| *existing != key);
+        vars.push((key, value));
+    }
+    vars.sort();
+
+    Ok(vars
+        .into_iter()
+        .map(|(key, value)| SidecarEnvVar { key, value })
+        .collect())
+}
+
+pub fn get(app: &AppHandle) -> EnvPolicy {
+    get_env_policy(app.clone()).unwrap_or_default()
+}
+
+/// Replaces the inherited environment of `cmd` with the variables `policy` allows. Must run
+/// before the app's own variables are set on `cmd`.
+pub fn apply(cmd: &mut Command, policy: &EnvPolicy) {
+    if *policy == EnvPolicy::InheritAll {
+        return;
+    }
+
+    cmd.env_clear();
+    cmd.envs(
+        std::env::vars_os().filter(|(key, _)| key.to_str().is_some_and(|key| policy.allows(key))),
+    );
+}
+
+fn matches(pattern: &str, key: &str) -> bool {
+    // Windows variable names aren't case sensitive
+    let (pattern, key) = if cfg!(windows) {
+        (pattern.to_ascii_uppercase(), key.to_ascii_uppercase())
+    } else {
+        (pattern.to_string(), key.to_string())
+    };
+
+    match pattern.strip_suffix('*') {
+        Some(prefix) => key.starts_with(prefix),
+        None => key == pattern,
+    }
+}
+
+fn is_pattern(value: &str) -> bool {
+    let name = value.strip_suffix('*').unwrap_or(value);
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(vars: &[&str]) -> Vec<String> {
+        vars.iter().map(|var| var.to_string()).collect()
+    }
+
+    #[test]
+    fn allowlist_keeps_essentials_and_listed() {
+        let policy = EnvPolicy::Allowlist {
+            vars: vars(&["ANTHROPIC_API_KEY", "AWS_*"]),
+        };
+
+        assert!(policy.allows("PATH"));
+        assert!(policy.allows("LC_ALL"));
+        assert!(policy.allows("ANTHROPIC_API_KEY"));
+        assert!(policy.allows("AWS_PROFILE"));
+        assert!(!policy.allows("HTTPS_PROXY"));
+        assert!(!policy.allows("GITHUB_TOKEN"));
+    }
+
+    #[test]
+    fn denylist_drops_matches() {
+        let policy = EnvPolicy::Denylist {
+            vars: vars(&["HTTP_PROXY", "HTTPS_PROXY", "GITHUB_*"]),
+        };
+
+        assert!(!policy.allows("HTTPS_PROXY"));
+        assert!(!policy.allows("GITHUB_TOKEN"));
+        assert!(policy.allows("PATH"));
+        assert!(policy.allows("NO_PROXY"));
+    }
+
+    #[test]
+    fn validates_patterns() {
+        assert!(is_pattern("AWS_*"));
+        assert!(is_pattern("GITHUB_TOKEN"));
+        assert!(!is_pattern("*"));
+        assert!(!is_pattern("A B"));
+        assert!(!is_pattern("AWS*_KEY"));
+    }
+}
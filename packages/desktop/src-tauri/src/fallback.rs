@@ -0,0 +1,118 @@
+use tauri::AppHandle;
+use tauri_plugin_dialog::{
+    DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult,
+};
+
+use crate::{
+    sidecar::{self, SidecarIssue},
+    tray,
+};
+
+#[cfg(target_os = "linux")]
+const RETRY: &str = "Retry with X11";
+#[cfg(not(target_os = "linux"))]
+const RETRY: &str = "Retry";
+const OPEN_LOGS: &str = "Open Logs";
+const REPAIR: &str = "Repair";
+
+/// Shown instead of panicking when a window can't be built, e.g. when the webview fails to
+/// initialize on the current display. On Linux, retrying restarts in safe mode with X11 and
+/// software rendering.
+pub fn window_failed(app: &AppHandle, error: tauri::Error) {
+    tracing::error!("Failed to create window: {error}");
+
+    let mut message = format!("OpenCode couldn't open its window.\n\n{error}");
+    if let Some(note) = backend_note() {
+        message.push_str(&format!("\n\nDisplay backend: {note}"));
+    }
+
+    loop {
+        let res = app
+            .dialog()
+            .message(&message)
+            .title("OpenCode failed to start")
+            .kind(MessageDialogKind::Error)
+            .buttons(MessageDialogButtons::YesNoCancelCustom(
+                RETRY.to_string(),
+                OPEN_LOGS.to_string(),
+                "Quit".to_string(),
+            ))
+            .blocking_show_with_result();
+
+        match res {
+            MessageDialogResult::Custom(name) if name == RETRY => retry(app),
+            MessageDialogResult::Custom(name) if name == OPEN_LOGS => tray::open_logs(app),
+            _ => {
+                app.exit(1);
+                return;
+            }
+        }
+    }
+}
+
+/// Shown when the bundled sidecar couldn't be spawned and `issues` says why. Repairing restores
+/// it from the backup and restarts the app; closing leaves the error in the main window.
+pub fn sidecar_failed(app: &AppHandle, error: &str, issues: &[SidecarIssue]) {
+    let mut message = format!(
+        "OpenCode couldn't start its server.\n\n{error}\n\nThe bundled server binary can't run: {}",
+        sidecar::describe(issues)
+    );
+
+    loop {
+        let res = app
+            .dialog()
+            .message(&message)
+            .title("OpenCode failed to start")
+            .kind(MessageDialogKind::Error)
+            .buttons(MessageDialogButtons::YesNoCancelCustom(
+                REPAIR.to_string(),
+                OPEN_LOGS.to_string(),
+                "Close".to_string(),
+            ))
+            .blocking_show_with_result();
+
+        match res {
+            MessageDialogResult::Custom(name) if name == REPAIR => {
+                match tauri::async_runtime::block_on(sidecar::repair_sidecar(app.clone())) {
+                    Ok(remaining) if remaining.is_empty() => {
+                        tracing::info!("Restarting after sidecar repair");
+                        app.restart()
+                    }
+                    Ok(remaining) => {
+                        message = format!(
+                            "The bundled server binary still can't run: {}\n\nPlease reinstall the app.",
+                            sidecar::describe(&remaining)
+                        )
+                    }
+                    Err(e) => message = format!("Repairing the server binary failed.\n\n{e}"),
+                }
+            }
+            MessageDialogResult::Custom(name) if name == OPEN_LOGS => tray::open_logs(app),
+            _ => return,
+        }
+    }
+}
+
+fn retry(app: &AppHandle) -> ! {
+    #[cfg(target_os = "linux")]
+    if let Err(e) = crate::linux_display::write_safe_mode(app) {
+        tracing::error!("Failed to enable display safe mode: {e}");
+    }
+
+    tracing::info!("Restarting after window failure");
+    app.restart()
+}
+
+#[cfg(target_os = "linux")]
+pub fn backend_note() -> Option<String> {
+    use crate::{linux_display, linux_windowing};
+
+    let session = linux_windowing::SessionEnv::capture();
+    let prefer_wayland = linux_display::read_wayland().unwrap_or(false);
+    linux_windowing::select_backend(&session, prefer_wayland).map(|decision| decision.note)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn backend_note() -> Option<String> {
+    None
+}
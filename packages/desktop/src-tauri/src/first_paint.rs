@@ -0,0 +1,55 @@
+//! Windows are created hidden and shown once their frontend has painted, so they open on the
+//! app's background instead of flashing white while the page loads.
+
+use std::{future::Future, sync::Mutex, time::Duration};
+
+use tauri::{AppHandle, Listener, WebviewWindow};
+use tauri_specta::Event;
+use tokio::{sync::oneshot, time::timeout};
+
+// A window that hasn't painted by then, e.g. on a slow machine at login, is shown anyway
+const READY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Sent by a window's frontend once it has painted for the first time.
+#[derive(tauri_specta::Event, serde::Serialize, serde::Deserialize, Clone, Debug, specta::Type)]
+pub struct FrontendReady {
+    pub label: String,
+}
+
+/// Resolves once the frontend of `window` has painted, or after `READY_TIMEOUT`. Call it right
+/// after building the window so the signal isn't missed.
+pub fn ready(app: &AppHandle, window: &WebviewWindow) -> impl Future<Output = ()> + use<> {
+    let (tx, rx) = oneshot::channel();
+    let tx = Mutex::new(Some(tx));
+    let label = window.label().to_string();
+    let id = FrontendReady::listen(app, {
+        let label = label.clone();
+        move |event| {
+            if event.payload.label != label {
+                return;
+            }
+            if let Some(tx) = tx.lock().unwrap().take() {
+                let _ = tx.send(());
+            }
+        }
+    });
+
+    let app = app.clone();
+    async move {
+        if timeout(READY_TIMEOUT, rx).await.is_err() {
+            tracing::warn!(%label, "Window didn't paint in time, showing it anyway");
+        }
+        app.unlisten(id);
+    }
+}
+
+/// Shows and focuses `window` once it's ready, see `ready`.
+pub fn show_when_ready(app: &AppHandle, window: &WebviewWindow) {
+    let ready = ready(app, window);
+    let window = window.clone();
+    tauri::async_runtime::spawn(async move {
+        ready.await;
+        let _ = window.show();
+        let _ = window.set_focus();
+    });
+}
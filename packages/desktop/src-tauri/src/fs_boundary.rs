@@ -0,0 +1,147 @@
+use std::path::Path;
+
+use tauri::AppHandle;
+use tauri_specta::Event;
+
+use crate::project::{self, ProjectBackend};
+
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BoundaryCrossing {
+    /// A sidecar inside WSL working on the Windows drive, e.g. `/mnt/c/...`.
+    WslToWindows,
+    /// A Windows sidecar working inside WSL through `\\wsl$\...`.
+    WindowsToWsl,
+}
+
+/// Sent when the sidecar starts in a project on the other side of the WSL filesystem boundary.
+/// Every file access then goes over the 9p protocol, which makes watching and indexing slow.
+#[derive(tauri_specta::Event, serde::Serialize, serde::Deserialize, Clone, Debug, specta::Type)]
+pub struct FilesystemBoundaryWarning {
+    pub path: String,
+    pub crossing: BoundaryCrossing,
+    pub impact: String,
+    pub suggestion: String,
+}
+
+/// Whether the project at `path`, or the active project when unset, crosses the boundary.
+#[tauri::command]
+#[specta::specta]
+pub fn check_filesystem_boundary(
+    app: AppHandle,
+    path: Option<String>,
+) -> Option<FilesystemBoundaryWarning> {
+    let path = path
+        .map(Into::into)
+        .or_else(|| project::active_project(&app))?;
+
+    warning(&app, &path)
+}
+
+/// Emits a warning when the sidecar is about to start in `cwd` across the boundary.
+pub fn check(app: &AppHandle, cwd: Option<&Path>) {
+    let Some(warning) = cwd.and_then(|cwd| warning(app, cwd)) else {
+        return;
+    };
+
+    tracing::warn!(path = %warning.path, crossing = ?warning.crossing, "Project crosses the WSL filesystem boundary");
+    let _ = warning.emit(app);
+}
+
+fn warning(app: &AppHandle, path: &Path) -> Option<FilesystemBoundaryWarning> {
+    if !cfg!(windows) {
+        return None;
+    }
+
+    let backend = if project::uses_wsl(app, Some(path)) {
+        ProjectBackend::Wsl
+    } else {
+        ProjectBackend::Native
+    };
+    let path = path.to_string_lossy().to_string();
+    let crossing = crossing(backend, &path)?;
+
+    let (impact, suggestion) = match crossing {
+        BoundaryCrossing::WslToWindows => (
+            "The sidecar runs in WSL but the project is on the Windows drive. File watching \
+             may miss changes, and searching and indexing can be 10-20x slower.",
+            "Move the project into the WSL filesystem, e.g. ~/code, or add a backend rule to \
+             run this project natively.",
+        ),
+        BoundaryCrossing::WindowsToWsl => (
+            "The sidecar runs on Windows but the project is inside WSL. File watching doesn't \
+             work over \\\\wsl$, and searching and indexing can be 10-20x slower.",
+            "Add a backend rule to run this project in WSL.",
+        ),
+    };
+
+    Some(FilesystemBoundaryWarning {
+        path,
+        crossing,
+        impact: impact.to_string(),
+        suggestion: suggestion.to_string(),
+    })
+}
+
+fn crossing(backend: ProjectBackend, path: &str) -> Option<BoundaryCrossing> {
+    let path = path.replace('\\', "/").to_lowercase();
+
+    match backend {
+        ProjectBackend::Wsl if is_windows_drive(&path) => Some(BoundaryCrossing::WslToWindows),
+        ProjectBackend::Native
+            if path.starts_with("//wsl$/") || path.starts_with("//wsl.localhost/") =>
+        {
+            Some(BoundaryCrossing::WindowsToWsl)
+        }
+        _ => None,
+    }
+}
+
+// `C:/...`, or the drive as WSL mounts it at `/mnt/c/...`
+fn is_windows_drive(path: &str) -> bool {
+    match path.as_bytes() {
+        [drive, b':'] | [drive, b':', b'/', ..] => drive.is_ascii_alphabetic(),
+        [b'/', b'm', b'n', b't', b'/', drive] | [b'/', b'm', b'n', b't', b'/', drive, b'/', ..] => {
+            drive.is_ascii_alphabetic()
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_wsl_on_windows_drive() {
+        for path in [r"C:\code\app", "c:/code", "/mnt/c/code/app", "/mnt/d"] {
+            assert_eq!(
+                crossing(ProjectBackend::Wsl, path),
+                Some(BoundaryCrossing::WslToWindows),
+                "{path}"
+            );
+        }
+        assert_eq!(crossing(ProjectBackend::Wsl, "/home/me/app"), None);
+        assert_eq!(crossing(ProjectBackend::Wsl, "/mnt/wsl/shared"), None);
+        assert_eq!(crossing(ProjectBackend::Native, r"C:\code\app"), None);
+    }
+
+    #[test]
+    fn detects_windows_on_wsl_share() {
+        for path in [
+            r"\\wsl$\Ubuntu\home\me\app",
+            r"\\wsl.localhost\Ubuntu\home\me",
+        ] {
+            assert_eq!(
+                crossing(ProjectBackend::Native, path),
+                Some(BoundaryCrossing::WindowsToWsl),
+                "{path}"
+            );
+        }
+        assert_eq!(crossing(ProjectBackend::Native, r"\\server\share"), None);
+        assert_eq!(
+            crossing(ProjectBackend::Wsl, r"\\wsl$\Ubuntu\home\me"),
+            None
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use tauri::{AppHandle, Manager};
+use tauri_specta::Event;
+use tokio::time::sleep;
+
+use crate::{
+    ServerState, adoption, limits,
+    server::{self, ServerSpawn},
+    sidecar_lock,
+};
+
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+static LAST_ACTIVITY: Mutex<Option<Instant>> = Mutex::new(None);
+/// What the sidecar stopped for being idle was started with, so it comes back on the same
+/// port and password.
+static STOPPED: Mutex<Option<ServerSpawn>> = Mutex::new(None);
+// A focus event and the frontend asking to wake the sidecar often arrive together
+static RESUME_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Sent when the sidecar is stopped for being idle, and again once it's running after a wake.
+#[derive(tauri_specta::Event, serde::Serialize, serde::Deserialize, Clone, Debug, specta::Type)]
+pub struct SidecarIdleChanged {
+    pub stopped: bool,
+}
+
+/// Starts the sidecar again if it was stopped for being idle, resolving once it's healthy.
+/// The frontend calls this before talking to the server after a long time in the background.
+#[tauri::command]
+#[specta::specta]
+pub async fn wake_sidecar(app: AppHandle) -> Result<(), String> {
+    touch();
+
+    let _guard = RESUME_LOCK.lock().await;
+    let Some(spawn) = STOPPED.lock().unwrap().clone() else {
+        return Ok(());
+    };
+
+    // The lock was released when the sidecar stopped, so another instance using it may have
+    // taken it over on the same port and password in the meantime
+    if let Some(owner) = sidecar_lock::claim().await
+        && owner.port == spawn.port
+        && owner.password == spawn.password
+    {
+        tracing::info!(
+            pid = owner.pid,
+            "Idle sidecar was taken over by another instance"
+        );
+        let url = owner.url();
+        if let Err(e) = adoption::adopt(&app, &url, Some(&owner.password)).await {
+            tracing::warn!(%url, "Handshake with another instance's sidecar failed: {e}");
+        }
+        *app.state::<ServerState>().spawned_by_us.lock().unwrap() = false;
+        sidecar_lock::watch_owner(app.clone(), owner);
+        forget(&app);
+        return Ok(());
+    }
+
+    tracing::info!(port = spawn.port, "Waking idle sidecar");
+    sidecar_lock::record(spawn.port, &spawn.password);
+    // Stays stopped on failure so the next focus tries again
+    server::restart_local_server(app, spawn).await
+}
+
+/// Marks the user as active, e.g. when a window is focused, waking the sidecar if needed.
+pub fn record_activity(app: &AppHandle) {
+    touch();
+
+    if STOPPED.lock().unwrap().is_none() {
+        return;
+    }
+
+    // Called from the event loop, outside the async runtime
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = wake_sidecar(app).await {
+            tracing::error!("Failed to wake idle sidecar: {e}");
+        }
+    });
+}
+
+/// Called whenever a sidecar is started, which ends any idle stop.
+pub fn forget(app: &AppHandle) {
+    touch();
+
+    if STOPPED.lock().unwrap().take().is_some() {
+        let _ = SidecarIdleChanged { stopped: false }.emit(app);
+    }
+}
+
+/// Stops the sidecar once no window has been focused and no session has been busy for the
+/// configured time. Only a sidecar the app spawned itself is stopped.
+pub fn spawn_monitor(app: AppHandle) {
+    tokio::spawn(async move {
+        loop {
+            sleep(IDLE_CHECK_INTERVAL).await;
+
+            let Some(minutes) = limits::get(&app).idle_shutdown_minutes else {
+                continue;
+            };
+            if STOPPED.lock().unwrap().is_some() {
+                continue;
+            }
+            let Some(spawn) = owned_spawn(&app) else {
+                continue;
+            };
+
+            let focused = app
+                .webview_windows()
+                .values()
+                .any(|window| window.is_focused().unwrap_or(false));
+            if focused || sessions_busy(&spawn).await {
+                touch();
+                continue;
+            }
+
+            let idle_for = LAST_ACTIVITY
+                .lock()
+                .unwrap()
+                .get_or_insert_with(Instant::now)
+                .elapsed();
+            if idle_for >= Duration::from_secs(u64::from(minutes) * 60) {
+                stop(&app, spawn);
+            }
+        }
+    });
+}
+
+fn touch() {
+    *LAST_ACTIVITY.lock().unwrap() = Some(Instant::now());
+}
+
+fn owned_spawn(app: &AppHandle) -> Option<ServerSpawn> {
+    let state = app.try_state::<ServerState>()?;
+    if !*state.spawned_by_us.lock().unwrap() || state.child.lock().unwrap().is_none() {
+        return None;
+    }
+    state.spawn()
+}
+
+// The memory watchdog sees the process group disappear and stops on its own, and the spawn
+// stays in `ServerState` so a project switch while stopped still starts the right sidecar
+fn stop(app: &AppHandle, spawn: ServerSpawn) {
+    let Some(child) = app
+        .state::<ServerState>()
+        .child
+        .lock()
+        .expect("Failed to acquire mutex lock")
+        .take()
+    else {
+        return;
+    };
+
+    tracing::info!(port = spawn.port, "Stopping idle sidecar");
+    let _ = child.kill();
+    // Other instances sharing the sidecar see the lock free and start it again for themselves
+    sidecar_lock::release();
+    *STOPPED.lock().unwrap() = Some(spawn);
+    let _ = SidecarIdleChanged { stopped: true }.emit(app);
+}
+
+async fn sessions_busy(spawn: &ServerSpawn) -> bool {
+    let url = format!("http://{}:{}/session/status", spawn.hostname, spawn.port);
+    // The sidecar is local, so proxy variables must not get in the way
+    let Ok(client) = reqwest::Client::builder()
+        .timeout(Duration::from_secs(3))
+        .no_proxy()
+        .build()
+    else {
+        return false;
+    };
+
+    let body = match client
+        .get(url)
+        .basic_auth("opencode", Some(&spawn.password))
+        .send()
+        .await
+    {
+        Ok(res) if res.status().is_success() => res.text().await.unwrap_or_default(),
+        _ => return false,
+    };
+
+    any_busy(&body)
+}
+
+// Statuses are keyed by session ID, e.g. `{"ses_1": {"type": "busy"}}`
+fn any_busy(body: &str) -> bool {
+    serde_json::from_str::<HashMap<String, serde_json::Value>>(body).is_ok_and(|statuses| {
+        statuses
+            .values()
+            .any(|status| status.get("type").and_then(|kind| kind.as_str()) != Some("idle"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_busy_sessions() {
+        assert!(any_busy(
+            r#"{"ses_1": {"type": "idle"}, "ses_2": {"type": "busy"}}"#
+        ));
+        assert!(any_busy(
+            r#"{"ses_1": {"type": "retry", "attempt": 2, "message": "", "next": 0}}"#
+        ));
+        assert!(!any_busy(r#"{"ses_1": {"type": "idle"}}"#));
+        assert!(!any_busy("{}"));
+        assert!(!any_busy("Unauthorized"));
+    }
+}
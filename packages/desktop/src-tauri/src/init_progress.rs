@@ -0,0 +1,93 @@
+//! Finer-grained startup progress than `InitStep`, for the loading window to show what it's
+//! waiting on instead of a spinner.
+
+use std::{future::Future, sync::Mutex, time::Duration};
+
+use tauri::AppHandle;
+use tauri_specta::Event;
+use tokio::time::{Instant, error::Elapsed, interval_at, timeout};
+
+static LAST: Mutex<Option<InitProgress>> = Mutex::new(None);
+
+// How often the health check's progress is reported
+const TICK: Duration = Duration::from_millis(500);
+
+/// What startup is doing. Stages can arrive out of order, e.g. the CLI syncs alongside the
+/// server starting.
+#[derive(
+    tauri_specta::Event, serde::Serialize, serde::Deserialize, Clone, Copy, Debug, specta::Type,
+)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum InitProgress {
+    /// Bringing the installed CLI up to date.
+    CliSync,
+    /// Reading the server settings and the CLI's config.
+    ConfigRead,
+    /// Starting the local server.
+    ServerSpawn,
+    /// Waiting for the server to respond, with how much of the time it's given has passed.
+    HealthCheck { percent: u8 },
+    /// Migrating the database.
+    Migration { percent: u8 },
+}
+
+/// The stage startup last reported, for a loading window opened after it started.
+#[tauri::command]
+#[specta::specta]
+pub fn get_init_progress() -> Option<InitProgress> {
+    *LAST.lock().unwrap()
+}
+
+pub fn report(app: &AppHandle, progress: InitProgress) {
+    *LAST.lock().unwrap() = Some(progress);
+    let _ = progress.emit(app);
+}
+
+/// Forgets the last stage, e.g. when reinitializing.
+pub fn clear() {
+    *LAST.lock().unwrap() = None;
+}
+
+/// Runs `check` for at most `limit`, reporting how much of it has been used along the way.
+pub async fn health_check<T>(
+    app: &AppHandle,
+    limit: Duration,
+    check: impl Future<Output = T>,
+) -> Result<T, Elapsed> {
+    let started = Instant::now();
+    let check = timeout(limit, check);
+    tokio::pin!(check);
+    let mut ticks = interval_at(started + TICK, TICK);
+
+    loop {
+        tokio::select! {
+            res = &mut check => return res,
+            _ = ticks.tick() => {
+                report(app, InitProgress::HealthCheck {
+                    percent: percent(started.elapsed(), limit),
+                });
+            }
+        }
+    }
+}
+
+fn percent(elapsed: Duration, limit: Duration) -> u8 {
+    if limit.is_zero() {
+        return 100;
+    }
+    (elapsed.as_secs_f64() / limit.as_secs_f64() * 100.0).clamp(0.0, 100.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_share_of_limit() {
+        let limit = Duration::from_secs(30);
+        assert_eq!(percent(Duration::ZERO, limit), 0);
+        assert_eq!(percent(Duration::from_secs(15), limit), 50);
+        assert_eq!(percent(Duration::from_secs(45), limit), 100);
+        assert_eq!(percent(Duration::from_secs(1), Duration::ZERO), 100);
+    }
+}
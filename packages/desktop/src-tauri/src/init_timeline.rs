@@ -0,0 +1,108 @@
+use std::{sync::Mutex, time::Instant};
+
+use crate::InitStep;
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug, PartialEq)]
+pub struct InitTimelineEntry {
+    pub step: InitStep,
+    /// Milliseconds since initialization started.
+    pub started_ms: u32,
+    /// How long the step lasted, or has lasted so far if it's still the current one.
+    pub duration_ms: u32,
+}
+
+/// Every step initialization went through, so slow startups can be pinned on a phase.
+pub struct InitTimeline {
+    start: Instant,
+    steps: Mutex<Vec<(InitStep, Instant)>>,
+}
+
+impl InitTimeline {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            steps: Mutex::new(vec![]),
+        }
+    }
+
+    /// Records a transition to `step`. Repeats of the current step are ignored, since e.g.
+    /// every migration progress event reports `SqliteWaiting` again.
+    pub fn record(&self, step: InitStep) {
+        let mut steps = self.steps.lock().unwrap();
+        if steps.last().is_some_and(|(last, _)| *last == step) {
+            return;
+        }
+
+        tracing::debug!(?step, elapsed = ?self.start.elapsed(), "Init step");
+        steps.push((step, Instant::now()));
+    }
+
+    pub fn entries(&self) -> Vec<InitTimelineEntry> {
+        entries(self.start, &self.steps.lock().unwrap(), Instant::now())
+    }
+}
+
+fn entries(start: Instant, steps: &[(InitStep, Instant)], now: Instant) -> Vec<InitTimelineEntry> {
+    steps
+        .iter()
+        .enumerate()
+        .map(|(i, (step, at))| {
+            let end = match steps.get(i + 1) {
+                Some((_, next)) => *next,
+                None if matches!(step, InitStep::Done) => *at,
+                None => now,
+            };
+
+            InitTimelineEntry {
+                step: *step,
+                started_ms: at.duration_since(start).as_millis() as u32,
+                duration_ms: end.duration_since(*at).as_millis() as u32,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn measures_each_step_until_the_next() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let steps = [
+            (InitStep::ServerWaiting, at(0)),
+            (InitStep::SqliteWaiting, at(200)),
+            (InitStep::Done, at(20_200)),
+        ];
+
+        let entries = entries(start, &steps, at(30_000));
+
+        assert_eq!(entries[0].duration_ms, 200);
+        assert_eq!(entries[1].started_ms, 200);
+        assert_eq!(entries[1].duration_ms, 20_000);
+        assert_eq!(entries[2].duration_ms, 0);
+    }
+
+    #[test]
+    fn current_step_runs_until_now() {
+        let start = Instant::now();
+        let steps = [(InitStep::ServerWaiting, start)];
+
+        let entries = entries(start, &steps, start + Duration::from_secs(5));
+
+        assert_eq!(entries[0].duration_ms, 5_000);
+    }
+
+    #[test]
+    fn ignores_repeated_steps() {
+        let timeline = InitTimeline::new();
+        timeline.record(InitStep::SqliteWaiting);
+        timeline.record(InitStep::SqliteWaiting);
+        timeline.record(InitStep::Done);
+
+        assert_eq!(timeline.entries().len(), 2);
+    }
+}
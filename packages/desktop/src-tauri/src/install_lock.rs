@@ -0,0 +1,79 @@
+use std::{
+    fs::File,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+const LOCK_FILE: &str = ".install.lock";
+const LOCK_TIMEOUT: Duration = Duration::from_secs(120);
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Exclusive lock on a CLI install directory, shared by every app instance. Released when
+/// dropped.
+pub struct InstallLock {
+    _file: File,
+}
+
+/// Waits until no other process is installing into `dir`.
+pub fn acquire(dir: &Path) -> Result<InstallLock, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create install directory: {}", e))?;
+
+    let path = dir.join(LOCK_FILE);
+    let file = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+
+    let started = Instant::now();
+    loop {
+        match file.try_lock() {
+            Ok(()) => return Ok(InstallLock { _file: file }),
+            Err(std::fs::TryLockError::WouldBlock) => {
+                if started.elapsed() > LOCK_TIMEOUT {
+                    return Err("Timed out waiting for another CLI install to finish".to_string());
+                }
+                if started.elapsed() < LOCK_POLL_INTERVAL {
+                    tracing::info!(path = %path.display(), "Waiting for another CLI install");
+                }
+                std::thread::sleep(LOCK_POLL_INTERVAL);
+            }
+            Err(std::fs::TryLockError::Error(e)) => {
+                return Err(format!("Failed to lock {}: {}", path.display(), e));
+            }
+        }
+    }
+}
+
+/// Moves a fully written binary over `dest` in one step, so a running CLI keeps its old file
+/// and nothing ever sees a partial one. Both paths must be on the same filesystem.
+pub fn promote(staged: &Path, dest: &Path) -> Result<(), String> {
+    File::open(staged)
+        .and_then(|file| file.sync_all())
+        .map_err(|e| format!("Failed to flush {}: {}", staged.display(), e))?;
+
+    std::fs::rename(staged, dest).map_err(|e| format!("Failed to move CLI into place: {}", e))?;
+
+    // Persist the rename itself
+    #[cfg(unix)]
+    if let Some(dir) = dest.parent()
+        && let Ok(dir) = File::open(dir)
+    {
+        let _ = dir.sync_all();
+    }
+
+    Ok(())
+}
+
+/// Copies `src` next to `dest` and promotes it, leaving `dest` untouched on failure.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn atomic_copy(src: &Path, dest: &Path) -> Result<(), String> {
+    let staged = dest.with_extension("partial");
+    std::fs::copy(src, &staged).map_err(|e| format!("Failed to copy CLI binary: {}", e))?;
+
+    promote(&staged, dest).inspect_err(|_| {
+        let _ = std::fs::remove_file(&staged);
+    })
+}
@@ -0,0 +1,46 @@
+use std::{fmt, path::Path};
+
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+
+#[derive(Debug)]
+pub enum IntegrityError {
+    Io(std::io::Error),
+    ChecksumMismatch { expected: String, actual: String },
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read file for verification: {e}"),
+            Self::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "Checksum verification failed (expected sha256 {expected}, got {actual})"
+            ),
+        }
+    }
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+pub fn verify_file(path: &Path, expected: &str) -> Result<(), IntegrityError> {
+    let actual = sha256_hex(&std::fs::read(path).map_err(IntegrityError::Io)?);
+
+    if !actual.eq_ignore_ascii_case(expected) {
+        tracing::error!(path = %path.display(), %expected, %actual, "Checksum mismatch");
+        return Err(IntegrityError::ChecksumMismatch {
+            expected: expected.to_string(),
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
+/// Creates a randomly named directory only the current user can access (0700 on unix).
+/// It is removed when the returned handle is dropped.
+pub fn secure_temp_dir(prefix: &str) -> std::io::Result<TempDir> {
+    tempfile::Builder::new().prefix(prefix).tempdir()
+}
@@ -0,0 +1,54 @@
+//! Flags the app is launched with, parsed once in `main`. Anything not recognized is left alone,
+//! since deep links and the warm-up login item also come in as arguments.
+
+use std::sync::OnceLock;
+
+const KIOSK_ARG: &str = "--kiosk";
+
+static ARGS: OnceLock<LaunchArgs> = OnceLock::new();
+
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct LaunchArgs {
+    /// Shows the main window frameless and fullscreen, and doesn't bring back the size, position
+    /// or other windows from earlier launches. For demos and presentations.
+    pub kiosk: bool,
+}
+
+impl LaunchArgs {
+    fn parse(args: impl IntoIterator<Item = String>) -> Self {
+        let mut parsed = Self::default();
+        for arg in args {
+            if arg == KIOSK_ARG {
+                parsed.kiosk = true;
+            }
+        }
+        parsed
+    }
+
+    /// The parsed flags, or the defaults when `init` wasn't called.
+    pub fn get() -> Self {
+        ARGS.get().copied().unwrap_or_default()
+    }
+}
+
+/// Parses the process arguments, without the program name.
+pub fn init(args: impl IntoIterator<Item = String>) {
+    let _ = ARGS.set(LaunchArgs::parse(args));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> LaunchArgs {
+        LaunchArgs::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_kiosk() {
+        assert!(parse(&["--kiosk"]).kiosk);
+        assert!(parse(&["opencode://open", "--kiosk"]).kiosk);
+        assert!(!parse(&[]).kiosk);
+        assert!(!parse(&["--kiosk=false", "kiosk"]).kiosk);
+    }
+}
@@ -1,24 +1,96 @@
+mod adoption;
+pub mod app_dirs;
+mod attention;
+mod autostart;
+mod benchmarks;
+mod cleanup;
 mod cli;
+mod cli_runner;
+mod completions;
 mod constants;
+mod credentials;
+mod csd;
+mod db_guard;
+mod deep_link_guard;
+mod dev_profile;
+mod devtools;
+mod discovery;
+mod doctor;
+mod effective_config;
+mod env_policy;
+mod fallback;
+mod first_paint;
+mod fs_boundary;
+mod idle;
+mod init_progress;
+mod init_timeline;
+mod install_lock;
+mod integrity;
+pub mod launch_args;
+mod limits;
 #[cfg(target_os = "linux")]
 pub mod linux_display;
 #[cfg(target_os = "linux")]
 pub mod linux_windowing;
 mod logging;
 mod markdown;
+mod menu;
+mod migration_recovery;
+mod monitors;
+mod notification_rules;
+mod os_search;
+mod output;
+mod package_manager;
+mod platform;
+mod port_proxy;
+mod power;
+mod print;
+mod process_registry;
+mod project;
+mod quiet_hours;
+mod quit_guard;
+mod recorder;
+mod releases;
+mod second_instance;
 mod server;
+mod service;
+mod share;
+mod shell;
+mod shortcut;
+mod sidecar;
+mod sidecar_lock;
+mod slow_startup;
+mod support_info;
+mod taskbar;
+mod titlebar;
+mod traffic_lights;
+mod tray;
+mod usage;
+mod validate;
+mod warmup;
+mod watchdog;
+#[cfg(windows)]
+pub mod webview2;
+mod window_background;
 mod window_customizer;
+mod window_effects;
+mod window_overlay;
+mod window_prefs;
+mod window_session;
+mod window_state;
 mod windows;
+mod wol;
+mod wsl_path;
+mod wsl_script;
+mod wsl_setup;
 
 use crate::cli::CommandChild;
 use futures::{
-    FutureExt, TryFutureExt,
+    FutureExt,
     future::{self, Shared},
 };
 use std::{
-    env,
     net::TcpListener,
-    path::PathBuf,
     process::Command,
     sync::{Arc, Mutex},
     time::Duration,
@@ -32,10 +104,17 @@ use tokio::{
     time::{sleep, timeout},
 };
 
-use crate::cli::{sqlite_migration::SqliteMigrationProgress, sync_cli};
+use crate::app_dirs::AppDirs;
+use crate::cli::sqlite_migration::SqliteMigrationProgress;
 use crate::constants::*;
+use crate::init_progress::InitProgress;
+use crate::init_timeline::InitTimeline;
+use crate::launch_args::LaunchArgs;
 use crate::server::get_saved_server_url;
-use crate::windows::{LoadingWindow, MainWindow};
+use crate::slow_startup::Stage;
+use crate::windows::{LoadingWindow, MainWindow, QuickPanel};
+
+const INIT_CANCELLED: &str = "Startup was cancelled";
 
 #[derive(Clone, serde::Serialize, specta::Type, Debug)]
 struct ServerReadyData {
@@ -43,7 +122,7 @@ struct ServerReadyData {
     password: Option<String>,
 }
 
-#[derive(Clone, Copy, serde::Serialize, specta::Type, Debug)]
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq)]
 #[serde(tag = "phase", rename_all = "snake_case")]
 enum InitStep {
     ServerWaiting,
@@ -51,47 +130,162 @@ enum InitStep {
     Done,
 }
 
-#[derive(serde::Deserialize, specta::Type)]
-#[serde(rename_all = "snake_case")]
-enum WslPathMode {
-    Windows,
-    Linux,
+struct InitState {
+    current: Mutex<watch::Receiver<InitStep>>,
+    timeline: Mutex<Arc<InitTimeline>>,
+    cancel: Mutex<watch::Sender<bool>>,
 }
 
-struct InitState {
-    current: watch::Receiver<InitStep>,
+impl InitState {
+    fn new(current: watch::Receiver<InitStep>, timeline: Arc<InitTimeline>) -> Self {
+        Self {
+            current: Mutex::new(current),
+            timeline: Mutex::new(timeline),
+            cancel: Mutex::new(watch::channel(false).0),
+        }
+    }
+
+    fn current(&self) -> watch::Receiver<InitStep> {
+        self.current.lock().unwrap().clone()
+    }
+
+    fn timeline(&self) -> Arc<InitTimeline> {
+        self.timeline.lock().unwrap().clone()
+    }
+
+    /// Becomes true once `cancel_initialization` gives up on the current run.
+    fn cancelled(&self) -> watch::Receiver<bool> {
+        self.cancel.lock().unwrap().subscribe()
+    }
+
+    /// Starts a fresh run of init steps, e.g. when reinitializing.
+    fn reset(&self) -> (watch::Sender<InitStep>, Arc<InitTimeline>) {
+        let (init_tx, init_rx) = watch::channel(InitStep::ServerWaiting);
+        let timeline = Arc::new(InitTimeline::new());
+        timeline.record(InitStep::ServerWaiting);
+
+        recorder::watch_init_steps(init_rx.clone());
+        init_progress::clear();
+        *self.current.lock().unwrap() = init_rx;
+        *self.timeline.lock().unwrap() = timeline.clone();
+        *self.cancel.lock().unwrap() = watch::channel(false).0;
+
+        (init_tx, timeline)
+    }
 }
 
 #[derive(Clone)]
 struct ServerState {
     child: Arc<Mutex<Option<CommandChild>>>,
-    status: future::Shared<oneshot::Receiver<Result<ServerReadyData, String>>>,
+    spawn: Arc<Mutex<Option<server::ServerSpawn>>>,
+    failure: Arc<Mutex<Option<cli::SidecarFailure>>>,
+    adopted: Arc<Mutex<Option<adoption::AdoptedServer>>>,
+    /// Whether the running server was started by this app, and so may be killed by it.
+    spawned_by_us: Arc<Mutex<bool>>,
+    endpoint_monitor: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    status: Arc<Mutex<ServerStatus>>,
 }
 
+type ServerStatus = Shared<oneshot::Receiver<Result<ServerReadyData, String>>>;
+
 impl ServerState {
-    pub fn new(
-        child: Option<CommandChild>,
-        status: Shared<oneshot::Receiver<Result<ServerReadyData, String>>>,
-    ) -> Self {
+    pub fn new(child: Option<CommandChild>, status: ServerStatus) -> Self {
         Self {
             child: Arc::new(Mutex::new(child)),
-            status,
+            spawn: Arc::new(Mutex::new(None)),
+            failure: Arc::new(Mutex::new(None)),
+            adopted: Arc::new(Mutex::new(None)),
+            spawned_by_us: Arc::new(Mutex::new(false)),
+            endpoint_monitor: Arc::new(Mutex::new(None)),
+            status: Arc::new(Mutex::new(status)),
+        }
+    }
+
+    pub fn status(&self) -> ServerStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    pub fn set_status(&self, status: ServerStatus) {
+        *self.status.lock().unwrap() = status;
+    }
+
+    /// Points the app at another endpoint of the server it's connected to, e.g. after failing
+    /// over, so windows opened later connect to it.
+    pub fn set_ready(&self, ready: ServerReadyData) {
+        let (tx, rx) = oneshot::channel();
+        let _ = tx.send(Ok(ready));
+        self.set_status(rx.shared());
+    }
+
+    /// The server the app is connected to, once it's ready.
+    pub fn ready(&self) -> Option<ServerReadyData> {
+        match self.status().peek() {
+            Some(Ok(Ok(ready))) => Some(ready.clone()),
+            _ => None,
+        }
+    }
+
+    /// Replaces the task failing over between server endpoints, stopping the previous one.
+    pub fn set_endpoint_monitor(&self, monitor: Option<tokio::task::JoinHandle<()>>) {
+        if let Some(previous) =
+            std::mem::replace(&mut *self.endpoint_monitor.lock().unwrap(), monitor)
+        {
+            previous.abort();
         }
     }
 
     pub fn set_child(&self, child: Option<CommandChild>) {
+        *self.spawned_by_us.lock().unwrap() = child.is_some();
         *self.child.lock().unwrap() = child;
     }
+
+    pub fn spawn(&self) -> Option<server::ServerSpawn> {
+        self.spawn.lock().unwrap().clone()
+    }
+
+    pub fn set_spawn(&self, spawn: Option<server::ServerSpawn>) {
+        *self.spawn.lock().unwrap() = spawn;
+    }
+
+    pub fn set_failure(&self, failure: Option<cli::SidecarFailure>) {
+        *self.failure.lock().unwrap() = failure;
+    }
+
+    pub fn set_adopted(&self, adopted: Option<adoption::AdoptedServer>) {
+        if adopted.is_some() {
+            *self.spawned_by_us.lock().unwrap() = false;
+        }
+        *self.adopted.lock().unwrap() = adopted;
+    }
 }
 
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum KillSidecarError {
+    /// The server wasn't started by this app. Pass `force` to kill it anyway.
+    NotOwned { url: Option<String> },
+}
+
+/// Kills the sidecar, refusing to touch servers the app didn't spawn unless `force` is set.
 #[tauri::command]
 #[specta::specta]
-fn kill_sidecar(app: AppHandle) {
+fn kill_sidecar(app: AppHandle, force: bool) -> Result<(), KillSidecarError> {
     let Some(server_state) = app.try_state::<ServerState>() else {
         tracing::info!("Server not running");
-        return;
+        return Ok(());
     };
 
+    if !force && !*server_state.spawned_by_us.lock().unwrap() {
+        let url = server_state
+            .adopted
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|adopted| adopted.url.clone());
+        tracing::info!(?url, "Not killing server the app didn't spawn");
+        return Err(KillSidecarError::NotOwned { url });
+    }
+
     let Some(server_state) = server_state
         .child
         .lock()
@@ -99,12 +293,64 @@ fn kill_sidecar(app: AppHandle) {
         .take()
     else {
         tracing::info!("Server state missing");
-        return;
+        return Ok(());
     };
 
     let _ = server_state.kill();
 
     tracing::info!("Killed server");
+    Ok(())
+}
+
+/// Registers teardown for what the app starts itself, see `cleanup`.
+fn register_cleanup(app: &AppHandle) {
+    // Only a sidecar the app spawned is stopped, adopted servers keep running
+    cleanup::register(
+        app,
+        "sidecar",
+        cleanup::Scope::Session,
+        Duration::from_secs(5),
+        |app| async move {
+            let _ = kill_sidecar(app, false);
+        },
+    );
+    cleanup::register(
+        app,
+        "sidecar takeover",
+        cleanup::Scope::Session,
+        Duration::from_secs(1),
+        |_| async { sidecar_lock::stop_watching_owner() },
+    );
+    cleanup::register(
+        app,
+        "CLI processes",
+        cleanup::Scope::App,
+        Duration::from_secs(5),
+        |app| async move {
+            if let Some(registry) = app.try_state::<process_registry::ProcessRegistry>() {
+                registry.kill_all();
+            }
+        },
+    );
+    // Removing the rules may wait on an elevation prompt
+    cleanup::register(
+        app,
+        "port forwarding",
+        cleanup::Scope::App,
+        Duration::from_secs(60),
+        |_| port_proxy::cleanup(),
+    );
+}
+
+/// Why the last sidecar exited before becoming healthy, if it did.
+#[tauri::command]
+#[specta::specta]
+fn get_sidecar_failure(app: AppHandle) -> Option<cli::SidecarFailure> {
+    app.try_state::<ServerState>()?
+        .failure
+        .lock()
+        .expect("Failed to acquire mutex lock")
+        .clone()
 }
 
 fn get_logs() -> String {
@@ -118,7 +364,7 @@ async fn await_initialization(
     init_state: State<'_, InitState>,
     events: Channel<InitStep>,
 ) -> Result<ServerReadyData, String> {
-    let mut rx = init_state.current.clone();
+    let mut rx = init_state.current();
 
     let events = async {
         let e = *rx.borrow();
@@ -135,12 +381,34 @@ async fn await_initialization(
         }
     };
 
-    future::join(state.status.clone(), events)
+    future::join(state.status(), events)
         .await
         .0
         .map_err(|_| "Failed to get server status".to_string())?
 }
 
+/// Every init step so far with when it started and how long it took, to explain slow
+/// startups.
+#[tauri::command]
+#[specta::specta]
+fn get_init_timeline(init_state: State<'_, InitState>) -> Vec<init_timeline::InitTimelineEntry> {
+    init_state.timeline().entries()
+}
+
+/// Gives up on starting the server, e.g. when a migration takes too long. Startup fails with an
+/// error that can be retried from, like any other startup failure.
+#[tauri::command]
+#[specta::specta]
+fn cancel_initialization(init_state: State<'_, InitState>) -> Result<(), String> {
+    if matches!(*init_state.current().borrow(), InitStep::Done) {
+        return Err("Startup has already finished".to_string());
+    }
+
+    tracing::info!("Cancelling initialization");
+    init_state.cancel.lock().unwrap().send_replace(true);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 fn check_app_exists(app_name: &str) -> bool {
@@ -306,17 +574,19 @@ fn resolve_windows_app_path(app_name: &str) -> Option<String> {
 
 #[tauri::command]
 #[specta::specta]
-fn resolve_app_path(app_name: &str) -> Option<String> {
+fn resolve_app_path(app_name: &str) -> Result<Option<String>, validate::InvalidInput> {
+    validate::name("app name", app_name)?;
+
     #[cfg(target_os = "windows")]
     {
-        resolve_windows_app_path(app_name)
+        Ok(resolve_windows_app_path(app_name))
     }
 
     #[cfg(not(target_os = "windows"))]
     {
         // On macOS/Linux, just return the app_name as-is since
         // the opener plugin handles them correctly
-        Some(app_name.to_string())
+        Ok(Some(app_name.to_string()))
     }
 }
 
@@ -388,44 +658,6 @@ fn check_linux_app(app_name: &str) -> bool {
     return true;
 }
 
-#[tauri::command]
-#[specta::specta]
-fn wsl_path(path: String, mode: Option<WslPathMode>) -> Result<String, String> {
-    if !cfg!(windows) {
-        return Ok(path);
-    }
-
-    let flag = match mode.unwrap_or(WslPathMode::Linux) {
-        WslPathMode::Windows => "-w",
-        WslPathMode::Linux => "-u",
-    };
-
-    let output = if path.starts_with('~') {
-        let suffix = path.strip_prefix('~').unwrap_or("");
-        let escaped = suffix.replace('"', "\\\"");
-        let cmd = format!("wslpath {flag} \"$HOME{escaped}\"");
-        Command::new("wsl")
-            .args(["-e", "sh", "-lc", &cmd])
-            .output()
-            .map_err(|e| format!("Failed to run wslpath: {e}"))?
-    } else {
-        Command::new("wsl")
-            .args(["-e", "wslpath", flag, &path])
-            .output()
-            .map_err(|e| format!("Failed to run wslpath: {e}"))?
-    };
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-        if stderr.is_empty() {
-            return Err("wslpath failed".to_string());
-        }
-        return Err(stderr);
-    }
-
-    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
-}
-
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let builder = make_specta_builder();
@@ -438,22 +670,20 @@ pub fn run() {
         .arg("opencode-cli")
         .output();
 
+    let persist_window_state = window_state::enabled_at_launch();
+
     let mut builder = tauri::Builder::default()
-        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
-            // Focus existing window when another instance is launched
-            if let Some(window) = app.get_webview_window(MainWindow::LABEL) {
-                let _ = window.set_focus();
-                let _ = window.unminimize();
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            // A warm-up launch has no window yet, so the user opening the app creates it
+            if warmup::is_warm_up_launch() {
+                tray::show_main_window(app);
+                return;
             }
+
+            second_instance::handle(app, args, cwd);
         }))
         .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_os::init())
-        .plugin(
-            tauri_plugin_window_state::Builder::new()
-                .with_state_flags(window_state_flags())
-                .with_denylist(&[LoadingWindow::LABEL])
-                .build(),
-        )
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
@@ -462,21 +692,40 @@ pub fn run() {
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_notification::init())
+        .plugin(shortcut::plugin())
         .plugin(crate::window_customizer::PinchZoomDisablePlugin)
         .plugin(tauri_plugin_decorum::init())
         .invoke_handler(builder.invoke_handler())
         .setup(move |app| {
             let handle = app.handle().clone();
 
-            let log_dir = app
-                .path()
-                .app_log_dir()
-                .expect("failed to resolve app log dir");
+            let log_dir = &AppDirs::get().expect("failed to resolve app log dir").logs;
             // Hold the guard in managed state so it lives for the app's lifetime,
             // ensuring all buffered logs are flushed on shutdown.
-            handle.manage(logging::init(&log_dir));
+            handle.manage(logging::init(log_dir));
+            if LaunchArgs::get().kiosk {
+                tracing::info!("Starting in kiosk mode");
+            }
+            handle.manage(process_registry::ProcessRegistry::default());
+            handle.manage(cleanup::CleanupHooks::default());
+            register_cleanup(&handle);
+            handle.manage(windows::ProjectWindows::default());
+            handle.manage(windows::QuickPanelState::default());
+            handle.manage(quit_guard::BusyWindows::default());
+            recorder::init(&handle);
+
+            if let Err(e) = tray::create(&handle) {
+                tracing::warn!("Failed to show tray icon: {e}");
+            }
+            #[cfg(target_os = "macos")]
+            if let Err(e) = menu::create(&handle) {
+                tracing::warn!("Failed to create menu bar: {e}");
+            }
+            window_state::spawn(&handle, persist_window_state);
+            shortcut::register_saved(&handle);
 
             builder.mount_events(&handle);
+            db_guard::watch(&handle);
             tauri::async_runtime::spawn(initialize(handle));
 
             Ok(())
@@ -486,15 +735,61 @@ pub fn run() {
         builder = builder.plugin(tauri_plugin_updater::Builder::new().build());
     }
 
+    if persist_window_state {
+        builder = builder.plugin(
+            tauri_plugin_window_state::Builder::new()
+                .with_state_flags(window_state_flags())
+                .with_denylist(if LaunchArgs::get().kiosk {
+                    &[LoadingWindow::LABEL, QuickPanel::LABEL, MainWindow::LABEL]
+                } else {
+                    &[LoadingWindow::LABEL, QuickPanel::LABEL]
+                })
+                .build(),
+        );
+    }
+
     builder
         .build(tauri::generate_context!())
         .expect("error while running tauri application")
-        .run(|app, event| {
-            if let RunEvent::Exit = event {
+        .run(|app, event| match event {
+            RunEvent::ExitRequested { api, code, .. } if quit_guard::hold_exit(app, code) => {
+                api.prevent_exit();
+            }
+            RunEvent::ExitRequested { .. } => {
+                window_session::save(app);
+                window_state::save_all(app);
+            }
+            RunEvent::Exit => {
                 tracing::info!("Received Exit");
 
-                kill_sidecar(app.clone());
+                tauri::async_runtime::block_on(cleanup::run(app, cleanup::Scope::App));
             }
+            RunEvent::WindowEvent {
+                event: tauri::WindowEvent::Focused(true),
+                ..
+            } => idle::record_activity(app),
+            RunEvent::WindowEvent {
+                label,
+                event: tauri::WindowEvent::CloseRequested { api, .. },
+                ..
+            } if tray::closes_to_tray(app, &label) => {
+                api.prevent_close();
+                if let Some(window) = app.get_webview_window(&label) {
+                    let _ = window.hide();
+                }
+            }
+            RunEvent::WindowEvent {
+                label,
+                event: tauri::WindowEvent::CloseRequested { api, .. },
+                ..
+            } if quit_guard::hold_close(app, &label) => api.prevent_close(),
+            // Once the last window is gone there's nothing left to save on exit
+            RunEvent::WindowEvent {
+                label,
+                event: tauri::WindowEvent::CloseRequested { .. },
+                ..
+            } if window_session::is_last_window(app, &label) => window_session::save(app),
+            _ => {}
         });
 }
 
@@ -503,22 +798,164 @@ fn make_specta_builder() -> tauri_specta::Builder<tauri::Wry> {
         // Then register them (separated by a comma)
         .commands(tauri_specta::collect_commands![
             kill_sidecar,
+            get_sidecar_failure,
             cli::install_cli,
+            cli::pin_cli_version,
+            cli::unpin_cli_version,
+            cli::get_cli_install_dir,
+            cli::set_cli_install_dir,
             await_initialization,
             server::get_default_server_url,
             server::set_default_server_url,
             server::get_wsl_config,
             server::set_wsl_config,
+            wsl_setup::get_wsl_status,
+            wsl_setup::run_wsl_setup,
+            wsl_setup::list_wsl_distros,
+            wsl_setup::get_wsl_distro,
+            wsl_setup::set_wsl_distro,
+            wsl_setup::check_wsl_environment,
+            wsl_setup::install_cli_wsl,
+            server::get_server_fallback_urls,
+            server::set_server_fallback_urls,
             get_display_backend,
             set_display_backend,
             markdown::parse_markdown_command,
             check_app_exists,
-            wsl_path,
-            resolve_app_path
+            wsl_path::wsl_path,
+            wsl_path::wsl_paths,
+            resolve_app_path,
+            share::get_share_info,
+            cli::refresh_cli_config,
+            cli::get_cli_channel,
+            cli::set_cli_channel,
+            cli::check_cli_updates,
+            cli_runner::run_cli_command,
+            discovery::discover_servers,
+            wol::wake_host,
+            wol::get_wake_on_lan_config,
+            wol::set_wake_on_lan_config,
+            quiet_hours::get_quiet_hours,
+            quiet_hours::set_quiet_hours,
+            quiet_hours::is_quiet_hours,
+            notification_rules::get_notification_rules,
+            notification_rules::set_notification_rules,
+            notification_rules::route_notification,
+            power::get_power_state,
+            completions::install_shell_completions,
+            limits::get_sidecar_limits,
+            limits::set_sidecar_limits,
+            service::get_server_service_status,
+            service::install_server_service,
+            service::uninstall_server_service,
+            project::get_active_project,
+            project::set_active_project,
+            project::get_backend_rules,
+            project::set_backend_rules,
+            project::get_effective_backend,
+            shell::get_shell_mode,
+            shell::set_shell_mode,
+            adoption::get_adopted_server,
+            env_policy::get_env_policy,
+            env_policy::set_env_policy,
+            env_policy::preview_sidecar_env,
+            sidecar::check_sidecar,
+            sidecar::repair_sidecar,
+            get_init_timeline,
+            cancel_initialization,
+            init_progress::get_init_progress,
+            cli::sync_cli_now,
+            reinitialize,
+            cli::cancel_install_cli,
+            doctor::run_doctor,
+            cli::verify_cli_install,
+            cli::repair_cli_install,
+            cli_runner::write_stdin,
+            cli::take_over_cli,
+            process_registry::list_children,
+            process_registry::kill_child,
+            os_search::get_os_search_enabled,
+            os_search::set_os_search_enabled,
+            os_search::index_sessions_for_search,
+            os_search::clear_search_index,
+            window_overlay::set_window_icon_overlay,
+            attention::request_attention,
+            taskbar::set_badge_count,
+            taskbar::set_taskbar_progress,
+            port_proxy::list_port_proxies,
+            port_proxy::add_sidecar_port_proxy,
+            port_proxy::remove_port_proxy,
+            usage::get_usage_summary,
+            usage::get_usage_digest,
+            usage::set_usage_digest,
+            idle::wake_sidecar,
+            fs_boundary::check_filesystem_boundary,
+            warmup::get_warm_up_enabled,
+            warmup::set_warm_up_enabled,
+            autostart::get_autostart_state,
+            autostart::enable_autostart,
+            autostart::disable_autostart,
+            benchmarks::benchmark_echo,
+            benchmarks::run_benchmarks,
+            tray::get_close_to_tray,
+            tray::set_close_to_tray,
+            shortcut::get_global_shortcut,
+            shortcut::set_global_shortcut,
+            platform::get_platform_capabilities,
+            windows::create_project_window,
+            windows::create_pip_window,
+            windows::close_pip_window,
+            windows::toggle_fullscreen,
+            csd::start_dragging,
+            csd::window_button,
+            migration_recovery::get_migration_failure,
+            migration_recovery::recover_migration,
+            monitors::list_monitors,
+            monitors::move_window_to_monitor,
+            effective_config::get_effective_config,
+            support_info::copy_support_info,
+            window_effects::get_window_effect,
+            window_effects::set_window_effect,
+            window_background::set_window_background,
+            devtools::get_developer_mode,
+            devtools::set_developer_mode,
+            devtools::open_devtools,
+            devtools::close_devtools,
+            print::print_window,
+            print::export_page_pdf,
+            deep_link_guard::confirm_deep_link,
+            window_state::get_window_state_settings,
+            window_state::set_window_state_settings,
+            quit_guard::set_busy_state,
+            titlebar::get_titlebar_settings,
+            titlebar::set_titlebar_settings,
+            titlebar::titlebar_double_click,
+            traffic_lights::set_traffic_light_inset,
+            shortcut::get_quick_panel_shortcut,
+            shortcut::set_quick_panel_shortcut,
+            window_prefs::set_window_always_on_top,
+            window_prefs::set_window_opacity,
+            window_prefs::set_compact_mode,
+            window_prefs::get_zoom_level,
+            window_prefs::set_zoom_level,
+            menu::set_menu_labels
         ])
         .events(tauri_specta::collect_events![
             LoadingWindowComplete,
-            SqliteMigrationProgress
+            SqliteMigrationProgress,
+            server::ServerEndpointChanged,
+            watchdog::SidecarMemoryLimitHit,
+            shell::SlowLoginShell,
+            cli::CliSyncProgress,
+            cli::CliManagedExternally,
+            usage::UsageDigestReady,
+            idle::SidecarIdleChanged,
+            fs_boundary::FilesystemBoundaryWarning,
+            menu::MenuCommand,
+            migration_recovery::MigrationFailed,
+            init_progress::InitProgress,
+            second_instance::SecondInstance,
+            first_paint::FrontendReady
         ])
         .error_handling(tauri_specta::ErrorHandlingMode::Throw)
 }
@@ -546,28 +983,168 @@ async fn initialize(app: AppHandle) {
     tracing::info!("Initializing app");
 
     let (init_tx, init_rx) = watch::channel(InitStep::ServerWaiting);
+    let timeline = Arc::new(InitTimeline::new());
+    timeline.record(InitStep::ServerWaiting);
+
+    recorder::watch_init_steps(init_rx.clone());
+    recorder::spawn_replay(&app);
+    menu::watch_init_steps(&app, init_rx.clone());
 
-    setup_app(&app, init_rx);
-    spawn_cli_sync_task(app.clone());
+    setup_app(&app, init_rx, timeline.clone());
+    cli::spawn_sync_cli(app.clone());
+    shell::spawn_startup_check(app.clone());
+    sidecar::spawn_backup(app.clone());
+    os_search::spawn_index(app.clone());
+    usage::spawn_digest(app.clone());
+    idle::spawn_monitor(app.clone());
 
     let (server_ready_tx, server_ready_rx) = oneshot::channel();
     let server_ready_rx = server_ready_rx.shared();
     app.manage(ServerState::new(None, server_ready_rx.clone()));
 
+    let warm_up = warmup::is_warm_up_launch();
+    if warm_up && let Err(e) = warmup::hide_from_dock(&app) {
+        tracing::warn!("Failed to hide warm-up launch from the dock: {e}");
+    }
+
     let loading_window_complete = event_once_fut::<LoadingWindowComplete>(&app);
 
     tracing::info!("Main and loading windows created");
 
+    let needs_sqlite_migration = !sqlite_file_exists() || slow_startup::simulates(Stage::Migration);
+    let loading_task = connect(
+        &app,
+        server_ready_tx,
+        server_ready_rx,
+        &init_tx,
+        &timeline,
+        needs_sqlite_migration,
+    );
+
+    if warm_up {
+        let _ = loading_task.await;
+        timeline.record(InitStep::Done);
+        let _ = init_tx.send(InitStep::Done);
+        return;
+    }
+
+    if !platform::check_webview(&app) {
+        app.exit(0);
+        return;
+    }
+
+    let loading_window = if needs_sqlite_migration
+        && timeout(Duration::from_secs(1), loading_task.clone())
+            .await
+            .is_err()
+    {
+        tracing::debug!("Loading task timed out, showing loading window");
+        let loading_window = match LoadingWindow::create(&app) {
+            Ok(window) => window,
+            Err(e) => return fallback::window_failed(&app, e),
+        };
+        sleep(Duration::from_secs(1)).await;
+        Some(loading_window)
+    } else {
+        tracing::debug!("Showing main window without loading window");
+        if let Err(e) = MainWindow::create(&app) {
+            return fallback::window_failed(&app, e);
+        }
+
+        None
+    };
+
+    let _ = loading_task.await;
+
+    tracing::info!("Loading done, completing initialisation");
+    timeline.record(InitStep::Done);
+    let _ = init_tx.send(InitStep::Done);
+
+    if loading_window.is_some() {
+        loading_window_complete.await;
+
+        tracing::info!("Loading window completed");
+    }
+
+    if let Err(e) = MainWindow::create(&app) {
+        return fallback::window_failed(&app, e);
+    }
+    window_session::restore(&app);
+
+    if let Some(loading_window) = loading_window {
+        let _ = loading_window.close();
+    }
+}
+
+/// Tears down the current server connection and runs the startup flow again without touching
+/// windows, e.g. after switching servers or toggling WSL. Streams init steps like
+/// `await_initialization`.
+#[tauri::command]
+#[specta::specta]
+async fn reinitialize(
+    app: AppHandle,
+    events: Channel<InitStep>,
+) -> Result<ServerReadyData, String> {
+    tracing::info!("Reinitializing app");
+
+    let Some(server_state) = app.try_state::<ServerState>() else {
+        return Err("Server state isn't initialized yet".to_string());
+    };
+
+    cleanup::run(&app, cleanup::Scope::Session).await;
+
+    server_state.set_child(None);
+    server_state.set_endpoint_monitor(None);
+    server_state.set_spawn(None);
+    server_state.set_failure(None);
+    server_state.set_adopted(None);
+    idle::forget(&app);
+
+    let (server_ready_tx, server_ready_rx) = oneshot::channel();
+    let server_ready_rx = server_ready_rx.shared();
+    server_state.set_status(server_ready_rx.clone());
+
+    let (init_tx, timeline) = app.state::<InitState>().reset();
+    menu::watch_init_steps(&app, app.state::<InitState>().current());
+    let loading_task = connect(
+        &app,
+        server_ready_tx,
+        server_ready_rx,
+        &init_tx,
+        &timeline,
+        !sqlite_file_exists() || slow_startup::simulates(Stage::Migration),
+    );
+
+    tokio::spawn(async move {
+        let _ = loading_task.await;
+
+        tracing::info!("Reinitialization done");
+        timeline.record(InitStep::Done);
+        let _ = init_tx.send(InitStep::Done);
+    });
+
+    await_initialization(app.state(), app.state(), events).await
+}
+
+/// Sets up the server connection, resolving once the server is ready or has failed. The
+/// result is sent through `server_ready_tx`.
+fn connect(
+    app: &AppHandle,
+    server_ready_tx: oneshot::Sender<Result<ServerReadyData, String>>,
+    server_ready_rx: ServerStatus,
+    init_tx: &watch::Sender<InitStep>,
+    timeline: &Arc<InitTimeline>,
+    needs_sqlite_migration: bool,
+) -> Shared<future::BoxFuture<'static, ()>> {
     // SQLite migration handling:
     // We only do this if the sqlite db doesn't exist, and we're expecting the sidecar to create it
     // First, we spawn a task that listens for SqliteMigrationProgress events that can
     // come from any invocation of the sidecar CLI. The progress is captured by a stdout stream interceptor.
     // Then in the loading task, we wait for sqlite migration to complete before
     // starting our health check against the server, otherwise long migrations could result in a timeout.
-    let needs_sqlite_migration = !sqlite_file_exists();
     let sqlite_done = needs_sqlite_migration.then(|| {
         tracing::info!(
-            path = %opencode_db_path().expect("failed to get db path").display(),
+            path = %app_dirs::opencode_db().expect("failed to get db path").display(),
             "Sqlite file not found, waiting for it to be generated"
         );
 
@@ -575,9 +1152,18 @@ async fn initialize(app: AppHandle) {
         let done_tx = Arc::new(Mutex::new(Some(done_tx)));
 
         let init_tx = init_tx.clone();
-        let id = SqliteMigrationProgress::listen(&app, move |e| {
+        let timeline = timeline.clone();
+        let progress_app = app.clone();
+        let id = SqliteMigrationProgress::listen(app, move |e| {
+            timeline.record(InitStep::SqliteWaiting);
             let _ = init_tx.send(InitStep::SqliteWaiting);
 
+            let percent = match e.payload {
+                SqliteMigrationProgress::InProgress(percent) => percent.min(100),
+                SqliteMigrationProgress::Done => 100,
+            };
+            init_progress::report(&progress_app, InitProgress::Migration { percent });
+
             if matches!(e.payload, SqliteMigrationProgress::Done)
                 && let Some(done_tx) = done_tx.lock().unwrap().take()
             {
@@ -585,18 +1171,29 @@ async fn initialize(app: AppHandle) {
             }
         });
 
+        slow_startup::spawn_migration(app.clone());
+
         let app = app.clone();
         tokio::spawn(done_rx.map(async move |_| {
             app.unlisten(id);
         }))
     });
 
-    let loading_task = tokio::spawn({
+    tokio::spawn({
         let app = app.clone();
 
         async move {
+            let mut cancelled = app.state::<InitState>().cancelled();
+
             tracing::info!("Setting up server connection");
-            let server_connection = setup_server_connection(app.clone()).await;
+            let server_connection = tokio::select! {
+                connection = setup_server_connection(app.clone()) => connection,
+                _ = cancelled.wait_for(|cancelled| *cancelled) => {
+                    tracing::info!("Initialization cancelled while connecting");
+                    let _ = server_ready_tx.send(Err(INIT_CANCELLED.to_string()));
+                    return;
+                }
+            };
             tracing::info!("Server connection setup");
 
             // we delay spawning this future so that the timeout is created lazily
@@ -606,11 +1203,56 @@ async fn initialize(app: AppHandle) {
                     health_check,
                     url,
                     password,
+                    spawn,
                 } => {
                     let app = app.clone();
+                    let migrating = sqlite_done.is_some();
                     Some(
                         async move {
-                            let res = timeout(Duration::from_secs(30), health_check.0).await;
+                            let mut health_check = health_check.0;
+                            let wait = async {
+                                // A sidecar that dies while migrating never reports the
+                                // migration done, so its exit ends the wait too
+                                let mut finished = None;
+                                if let Some(sqlite_done) = sqlite_done {
+                                    tokio::select! {
+                                        _ = sqlite_done => {}
+                                        res = &mut health_check => finished = Some(res),
+                                    }
+                                }
+
+                                match finished {
+                                    Some(res) => Ok(res),
+                                    None => {
+                                        init_progress::health_check(
+                                            &app,
+                                            Duration::from_secs(30),
+                                            async {
+                                                slow_startup::delay(Stage::HealthCheck).await;
+                                                (&mut health_check).await
+                                            },
+                                        )
+                                        .await
+                                    }
+                                }
+                            };
+
+                            let res = tokio::select! {
+                                res = wait => res,
+                                _ = cancelled.wait_for(|cancelled| *cancelled) => {
+                                    tracing::info!("Initialization cancelled, stopping sidecar");
+                                    let _ = child.kill();
+                                    // What's been migrated so far would pass for a finished
+                                    // migration on the next launch
+                                    if db_guard::check().is_err() {
+                                        migration_recovery::record(
+                                            &app,
+                                            "The migration was cancelled before it finished",
+                                        );
+                                    }
+                                    return Err(INIT_CANCELLED.to_string());
+                                }
+                            };
                             let err = match res {
                                 Ok(Ok(Ok(()))) => None,
                                 Ok(Ok(Err(e))) => Some(e),
@@ -621,6 +1263,14 @@ async fn initialize(app: AppHandle) {
                             if let Some(err) = err {
                                 let _ = child.kill();
 
+                                if migrating {
+                                    migration_recovery::record(&app, &err);
+                                    return Err(format!(
+                                        "The database migration failed ({err}). Logs:\n{}",
+                                        get_logs()
+                                    ));
+                                }
+
                                 return Err(format!(
                                     "Failed to spawn OpenCode Server ({err}). Logs:\n{}",
                                     get_logs()
@@ -629,7 +1279,10 @@ async fn initialize(app: AppHandle) {
 
                             tracing::info!("CLI health check OK");
 
-                            app.state::<ServerState>().set_child(Some(child));
+                            let state = app.state::<ServerState>();
+                            state.set_child(Some(child.clone()));
+                            state.set_spawn(Some(spawn.clone()));
+                            watchdog::spawn(app.clone(), child, spawn);
 
                             Ok(ServerReadyData { url, password })
                         }
@@ -638,21 +1291,37 @@ async fn initialize(app: AppHandle) {
                         }),
                     )
                 }
-                ServerConnection::Existing { url } => {
+                ServerConnection::Existing { url, password } => {
+                    slow_startup::delay(Stage::HealthCheck).await;
                     let _ = server_ready_tx.send(Ok(ServerReadyData {
                         url: url.to_string(),
-                        password: None,
+                        password,
                     }));
                     None
                 }
+                ServerConnection::Failed(err) => {
+                    if let Some(cli::SidecarFailure::SpawnFailed { error, issues }) =
+                        get_sidecar_failure(app.clone())
+                        && !issues.is_empty()
+                    {
+                        // The dialog blocks until it's answered
+                        let app = app.clone();
+                        tauri::async_runtime::spawn_blocking(move || {
+                            fallback::sidecar_failed(&app, &error, &issues)
+                        });
+                    }
+
+                    let _ = server_ready_tx.send(Err(format!(
+                        "Failed to spawn OpenCode Server ({err}). Logs:\n{}",
+                        get_logs()
+                    )));
+                    None
+                }
             };
 
             tracing::info!("server connection started");
 
             if let Some(cli_health_check) = cli_health_check {
-                if let Some(sqlite_done_rx) = sqlite_done {
-                    let _ = sqlite_done_rx.await;
-                }
                 tokio::spawn(cli_health_check);
             }
 
@@ -661,103 +1330,160 @@ async fn initialize(app: AppHandle) {
             tracing::info!("Loading task finished");
         }
     })
-    .map_err(|_| ())
-    .shared();
-
-    let loading_window = if needs_sqlite_migration
-        && timeout(Duration::from_secs(1), loading_task.clone())
-            .await
-            .is_err()
-    {
-        tracing::debug!("Loading task timed out, showing loading window");
-        let loading_window = LoadingWindow::create(&app).expect("Failed to create loading window");
-        sleep(Duration::from_secs(1)).await;
-        Some(loading_window)
-    } else {
-        tracing::debug!("Showing main window without loading window");
-        MainWindow::create(&app).expect("Failed to create main window");
-
-        None
-    };
-
-    let _ = loading_task.await;
-
-    tracing::info!("Loading done, completing initialisation");
-    let _ = init_tx.send(InitStep::Done);
-
-    if loading_window.is_some() {
-        loading_window_complete.await;
-
-        tracing::info!("Loading window completed");
-    }
-
-    MainWindow::create(&app).expect("Failed to create main window");
-
-    if let Some(loading_window) = loading_window {
-        let _ = loading_window.close();
-    }
+    .map(|_| ())
+    .boxed()
+    .shared()
 }
 
-fn setup_app(app: &tauri::AppHandle, init_rx: watch::Receiver<InitStep>) {
+fn setup_app(
+    app: &tauri::AppHandle,
+    init_rx: watch::Receiver<InitStep>,
+    timeline: Arc<InitTimeline>,
+) {
     #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
     app.deep_link().register_all().ok();
 
-    app.manage(InitState { current: init_rx });
-}
-
-fn spawn_cli_sync_task(app: AppHandle) {
-    tokio::spawn(async move {
-        if let Err(e) = sync_cli(app) {
-            tracing::error!("Failed to sync CLI: {e}");
-        }
-    });
+    app.manage(InitState::new(init_rx, timeline));
 }
 
 enum ServerConnection {
     Existing {
         url: String,
+        password: Option<String>,
     },
     CLI {
         url: String,
         password: Option<String>,
         child: CommandChild,
         health_check: server::HealthCheck,
+        spawn: server::ServerSpawn,
     },
+    /// The sidecar couldn't be spawned, so there's nothing to health check.
+    Failed(String),
 }
 
 async fn setup_server_connection(app: AppHandle) -> ServerConnection {
+    init_progress::report(&app, InitProgress::ConfigRead);
     let custom_url = get_saved_server_url(&app).await;
 
     tracing::info!(?custom_url, "Attempting server connection");
 
     if let Some(url) = custom_url
-        && server::check_health_or_ask_retry(&app, &url).await
+        && let Some(active) = server::check_health_or_ask_retry(&app, &url).await
     {
-        tracing::info!(%url, "Connected to custom server");
-        return ServerConnection::Existing { url: url.clone() };
+        tracing::info!(%url, active = %active.url, "Connected to custom server");
+        server::spawn_endpoint_monitor(app.clone(), url, active.clone());
+        if let Err(e) = adoption::adopt(&app, &active.url, active.password.as_deref()).await {
+            tracing::warn!(url = %active.url, "Handshake with custom server failed: {e}");
+        }
+        return ServerConnection::Existing {
+            url: active.url,
+            password: active.password,
+        };
+    }
+
+    if let Some(service) = service::get(&app) {
+        let url = service.url();
+        if server::check_health(&url, Some(&service.password)).await {
+            tracing::info!(%url, "Using server service");
+            if let Err(e) = adoption::adopt(&app, &url, Some(&service.password)).await {
+                tracing::warn!(%url, "Handshake with server service failed: {e}");
+            }
+            return ServerConnection::Existing {
+                url,
+                password: Some(service.password),
+            };
+        }
+        tracing::warn!(%url, "Server service isn't responding, spawning sidecar instead");
     }
 
-    let local_port = get_sidecar_port();
+    if let Some(owner) = sidecar_lock::claim().await {
+        let url = owner.url();
+        tracing::info!(%url, pid = owner.pid, "Using sidecar of another app instance");
+        if let Err(e) = adoption::adopt(&app, &url, Some(&owner.password)).await {
+            tracing::warn!(%url, "Handshake with another instance's sidecar failed: {e}");
+        }
+        sidecar_lock::watch_owner(app.clone(), owner.clone());
+        return ServerConnection::Existing {
+            url,
+            password: Some(owner.password),
+        };
+    }
+
+    let mut local_port = get_sidecar_port();
     let hostname = "127.0.0.1";
-    let local_url = format!("http://{hostname}:{local_port}");
+    let mut local_url = format!("http://{hostname}:{local_port}");
 
     tracing::debug!(url = %local_url, "Checking health of local server");
-    if server::check_health(&local_url, None).await {
-        tracing::info!(url = %local_url, "Health check OK, using existing server");
-        return ServerConnection::Existing { url: local_url };
+    let port_taken = if server::check_health(&local_url, None).await {
+        match adoption::adopt(&app, &local_url, None).await {
+            Ok(_) => {
+                tracing::info!(url = %local_url, "Health check OK, using existing server");
+                return ServerConnection::Existing {
+                    url: local_url,
+                    password: None,
+                };
+            }
+            Err(e) => tracing::warn!(url = %local_url, "Not adopting local server: {e}"),
+        }
+        true
+    } else if server::requires_password(&local_url).await {
+        tracing::info!(url = %local_url, "Local server requires a password");
+        if let Some(password) = credentials::password_for(&local_url).await {
+            match adoption::adopt(&app, &local_url, Some(&password)).await {
+                Ok(_) => {
+                    tracing::info!(url = %local_url, "Using existing server with password");
+                    return ServerConnection::Existing {
+                        url: local_url,
+                        password: Some(password),
+                    };
+                }
+                Err(e) => tracing::warn!(url = %local_url, "Not adopting local server: {e}"),
+            }
+        }
+        true
+    } else {
+        false
+    };
+
+    // A server that wasn't adopted keeps its port, so the sidecar needs another one
+    if port_taken {
+        local_port = free_port();
+        local_url = format!("http://{hostname}:{local_port}");
     }
 
-    let password = uuid::Uuid::new_v4().to_string();
+    if let Err(e) = adoption::clear(&app) {
+        tracing::warn!("Failed to clear adopted server: {e}");
+    }
 
-    tracing::info!("Spawning new local server");
-    let (child, health_check) =
-        server::spawn_local_server(app, hostname.to_string(), local_port, password.clone());
+    let password = uuid::Uuid::new_v4().to_string();
+    let cwd = project::active_project(&app);
+    sidecar_lock::record(local_port, &password);
+
+    tracing::info!(?cwd, "Spawning new local server");
+    init_progress::report(&app, InitProgress::ServerSpawn);
+    let (child, health_check) = match server::spawn_local_server(
+        app,
+        hostname.to_string(),
+        local_port,
+        password.clone(),
+        cwd.clone(),
+    ) {
+        Ok(spawned) => spawned,
+        Err(e) => return ServerConnection::Failed(e),
+    };
 
     ServerConnection::CLI {
         url: local_url,
-        password: Some(password),
+        password: Some(password.clone()),
         child,
         health_check,
+        spawn: server::ServerSpawn {
+            hostname: hostname.to_string(),
+            port: local_port,
+            password,
+            cwd,
+        },
     }
 }
 
@@ -766,37 +1492,31 @@ fn get_sidecar_port() -> u32 {
         .map(|s| s.to_string())
         .or_else(|| std::env::var("OPENCODE_PORT").ok())
         .and_then(|port_str| port_str.parse().ok())
-        .unwrap_or_else(|| {
-            TcpListener::bind("127.0.0.1:0")
-                .expect("Failed to bind to find free port")
-                .local_addr()
-                .expect("Failed to get local address")
-                .port()
-        }) as u32
+        .or_else(|| {
+            dev_profile::isolated()
+                .then(dev_profile::free_port)
+                .flatten()
+        })
+        .map(u32::from)
+        .unwrap_or_else(free_port)
+}
+
+fn free_port() -> u32 {
+    TcpListener::bind("127.0.0.1:0")
+        .expect("Failed to bind to find free port")
+        .local_addr()
+        .expect("Failed to get local address")
+        .port() as u32
 }
 
 fn sqlite_file_exists() -> bool {
-    let Ok(path) = opencode_db_path() else {
+    let Ok(path) = app_dirs::opencode_db() else {
         return true;
     };
 
     path.exists()
 }
 
-fn opencode_db_path() -> Result<PathBuf, &'static str> {
-    let xdg_data_home = env::var_os("XDG_DATA_HOME").filter(|v| !v.is_empty());
-
-    let data_home = match xdg_data_home {
-        Some(v) => PathBuf::from(v),
-        None => {
-            let home = dirs::home_dir().ok_or("cannot determine home directory")?;
-            home.join(".local").join("share")
-        }
-    };
-
-    Ok(data_home.join("opencode").join("opencode.db"))
-}
-
 // Creates a `once` listener for the specified event and returns a future that resolves
 // when the listener is fired.
 // Since the future creation and awaiting can be done separately, it's possible to create the listener
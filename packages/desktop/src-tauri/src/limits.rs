@@ -0,0 +1,159 @@
+use std::ffi::OsStr;
+
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+use tokio::process::Command;
+
+use crate::constants::{SETTINGS_STORE, SIDECAR_LIMITS_KEY};
+
+const MIN_MEMORY_LIMIT_MB: u32 = 256;
+
+#[derive(
+    Clone, Copy, serde::Serialize, serde::Deserialize, specta::Type, Debug, Default, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SidecarPriority {
+    #[default]
+    Normal,
+    BelowNormal,
+    Idle,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type, Debug, Default)]
+#[serde(default)]
+pub struct SidecarLimits {
+    pub priority: SidecarPriority,
+    /// CPU cap as a percentage of one core. Linux only, applied through a systemd user scope.
+    pub cpu_limit_percent: Option<u32>,
+    /// Resident memory ceiling for the sidecar and its children, in MB.
+    pub memory_limit_mb: Option<u32>,
+    /// Start a fresh sidecar after it's killed for exceeding `memory_limit_mb`.
+    pub restart_on_memory_limit: bool,
+    /// Stop the sidecar after this many minutes without a focused window or a busy session.
+    /// It starts again on the same port and password when a window is focused.
+    pub idle_shutdown_minutes: Option<u32>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_sidecar_limits(app: AppHandle) -> Result<SidecarLimits, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(SIDECAR_LIMITS_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_sidecar_limits(app: AppHandle, limits: SidecarLimits) -> Result<(), String> {
+    if limits.cpu_limit_percent == Some(0) {
+        return Err("CPU limit must be greater than 0%".to_string());
+    }
+    if limits.idle_shutdown_minutes == Some(0) {
+        return Err("Idle shutdown must be at least 1 minute".to_string());
+    }
+    if limits
+        .memory_limit_mb
+        .is_some_and(|mb| mb < MIN_MEMORY_LIMIT_MB)
+    {
+        return Err(format!(
+            "Memory limit must be at least {} MB",
+            MIN_MEMORY_LIMIT_MB
+        ));
+    }
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(SIDECAR_LIMITS_KEY, serde_json::json!(limits));
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+pub fn get(app: &AppHandle) -> SidecarLimits {
+    get_sidecar_limits(app.clone()).unwrap_or_default()
+}
+
+/// Builds the command for `program`, placing it in a limited systemd scope when a CPU or
+/// memory cap is configured and `systemd-run` is available.
+pub fn command(program: impl AsRef<OsStr>, limits: &SidecarLimits) -> Command {
+    if cfg!(target_os = "linux")
+        && (limits.cpu_limit_percent.is_some() || limits.memory_limit_mb.is_some())
+        && find_in_path("systemd-run")
+    {
+        let mut cmd = Command::new("systemd-run");
+        cmd.args(["--user", "--scope", "--quiet"]);
+
+        if let Some(percent) = limits.cpu_limit_percent {
+            tracing::info!(percent, "Limiting sidecar CPU usage");
+            cmd.arg("-p").arg(format!("CPUQuota={percent}%"));
+        }
+
+        // The watchdog kills the sidecar at the configured limit so it can report and restart;
+        // the cgroup ceiling sits above it as a backstop for sudden spikes between samples.
+        if let Some(mb) = limits.memory_limit_mb {
+            tracing::info!(mb, "Limiting sidecar memory usage");
+            cmd.arg("-p")
+                .arg(format!("MemoryMax={}M", memory_ceiling_mb(mb)));
+        }
+
+        cmd.arg("--").arg(program);
+        return cmd;
+    }
+
+    Command::new(program)
+}
+
+/// Hard memory cap for the sidecar, set by the OS above the watchdog's `limit_mb` as a backstop
+/// for sudden spikes between its samples.
+pub fn memory_ceiling_mb(limit_mb: u32) -> u32 {
+    limit_mb.saturating_add(limit_mb / 4)
+}
+
+/// Lowers the priority of the process group led by `pid`, which covers everything the
+/// sidecar spawns. `renice` runs in the background so spawning doesn't wait on it.
+#[cfg(unix)]
+pub fn apply_priority(pid: Option<u32>, limits: &SidecarLimits) {
+    let nice = match limits.priority {
+        SidecarPriority::Normal => return,
+        SidecarPriority::BelowNormal => 10,
+        SidecarPriority::Idle => 19,
+    };
+    let Some(pid) = pid else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        let res = Command::new("renice")
+            .args(["-n", &nice.to_string(), "-g", &pid.to_string()])
+            .kill_on_drop(true)
+            .output()
+            .await;
+
+        if !res.is_ok_and(|output| output.status.success()) {
+            tracing::warn!(pid, nice, "Failed to lower sidecar priority");
+        }
+    });
+}
+
+/// Windows priority class creation flag. Child processes inherit below-normal and idle classes.
+#[cfg(windows)]
+pub fn priority_class(limits: &SidecarLimits) -> u32 {
+    match limits.priority {
+        SidecarPriority::Normal => 0,
+        SidecarPriority::BelowNormal => 0x0000_4000,
+        SidecarPriority::Idle => 0x0000_0040,
+    }
+}
+
+fn find_in_path(binary: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(binary).is_file()))
+}
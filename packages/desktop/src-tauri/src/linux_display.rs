@@ -4,36 +4,39 @@ use std::path::PathBuf;
 use tauri::AppHandle;
 use tauri_plugin_store::StoreExt;
 
-use crate::constants::SETTINGS_STORE;
+use crate::{app_dirs::AppDirs, constants::SETTINGS_STORE};
 
 pub const LINUX_DISPLAY_CONFIG_KEY: &str = "linuxDisplayConfig";
 
 #[derive(Default, Serialize, Deserialize)]
 struct DisplayConfig {
     wayland: Option<bool>,
-}
-
-fn dir() -> Option<PathBuf> {
-    Some(dirs::data_dir()?.join(if cfg!(debug_assertions) {
-        "ai.opencode.desktop.dev"
-    } else {
-        "ai.opencode.desktop"
-    }))
+    /// Forces X11 and software rendering after the main window failed to open.
+    safe_mode: Option<bool>,
 }
 
 fn path() -> Option<PathBuf> {
-    dir().map(|dir| dir.join(SETTINGS_STORE))
+    AppDirs::get().map(|dirs| dirs.data.join(SETTINGS_STORE))
 }
 
-pub fn read_wayland() -> Option<bool> {
+fn read() -> Option<DisplayConfig> {
     let raw = std::fs::read_to_string(path()?).ok()?;
     let root = serde_json::from_str::<serde_json::Value>(&raw)
         .ok()?
         .get(LINUX_DISPLAY_CONFIG_KEY)
         .cloned()?;
-    serde_json::from_value::<DisplayConfig>(root).ok()?.wayland
+    serde_json::from_value::<DisplayConfig>(root).ok()
+}
+
+pub fn read_wayland() -> Option<bool> {
+    read()?.wayland
 }
 
+pub fn read_safe_mode() -> bool {
+    read().and_then(|config| config.safe_mode).unwrap_or(false)
+}
+
+// Picking a backend explicitly also leaves safe mode
 pub fn write_wayland(app: &AppHandle, value: bool) -> Result<(), String> {
     let store = app
         .store(SETTINGS_STORE)
@@ -43,6 +46,26 @@ pub fn write_wayland(app: &AppHandle, value: bool) -> Result<(), String> {
         LINUX_DISPLAY_CONFIG_KEY,
         json!(DisplayConfig {
             wayland: Some(value),
+            safe_mode: None,
+        }),
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings store: {}", e))?;
+
+    Ok(())
+}
+
+pub fn write_safe_mode(app: &AppHandle) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(
+        LINUX_DISPLAY_CONFIG_KEY,
+        json!(DisplayConfig {
+            wayland: None,
+            safe_mode: Some(true),
         }),
     );
     store
@@ -15,6 +15,11 @@ fn configure_display_backend() -> Option<String> {
         }
     };
 
+    if opencode_lib::linux_display::read_safe_mode() {
+        set_env_if_absent("OC_FORCE_X11", "1");
+        set_env_if_absent("WEBKIT_DISABLE_COMPOSITING_MODE", "1");
+    }
+
     let session = SessionEnv::capture();
     let prefer_wayland = opencode_lib::linux_display::read_wayland().unwrap_or(false);
     let decision = select_backend(&session, prefer_wayland)?;
@@ -67,6 +72,9 @@ fn main() {
     upsert("NO_PROXY");
     upsert("no_proxy");
 
+    opencode_lib::launch_args::init(std::env::args().skip(1));
+    opencode_lib::app_dirs::migrate();
+
     #[cfg(target_os = "linux")]
     {
         if let Some(backend_note) = configure_display_backend() {
@@ -74,5 +82,11 @@ fn main() {
         }
     }
 
+    // Without the runtime the app fails before any window exists, leaving nothing on screen
+    #[cfg(windows)]
+    if !opencode_lib::webview2::ensure_runtime() {
+        return;
+    }
+
     opencode_lib::run()
 }
@@ -0,0 +1,299 @@
+//! The macOS menu bar. Items that act on the app's UI are sent to the frontend as
+//! `MenuCommand`s carrying the id of a frontend command, e.g. `session.new`. Other platforms
+//! don't get a menu bar.
+#![cfg_attr(not(target_os = "macos"), allow(dead_code))]
+
+use tauri::{
+    AppHandle, Manager, Wry,
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
+};
+use tauri_plugin_opener::OpenerExt;
+use tauri_specta::Event;
+use tokio::sync::watch;
+
+use crate::{InitStep, constants::UPDATER_ENABLED, devtools, support_info};
+
+const DOCS_URL: &str = "https://opencode.ai/docs";
+const SUPPORT_URL: &str = "https://discord.com/invite/opencode";
+const FEEDBACK_URL: &str =
+    "https://github.com/anomalyco/opencode/issues/new?template=feature_request.yml";
+const BUG_REPORT_URL: &str =
+    "https://github.com/anomalyco/opencode/issues/new?template=bug_report.yml";
+
+/// Sent to every window when a menu item is picked, for the focused one to run the command with
+/// this id.
+#[derive(tauri_specta::Event, serde::Serialize, serde::Deserialize, Clone, Debug, specta::Type)]
+pub struct MenuCommand {
+    pub id: String,
+}
+
+/// Labels for the items the frontend translates, applied once its locale is known.
+#[derive(serde::Deserialize, specta::Type, Debug)]
+pub struct MenuLabels {
+    pub check_for_updates: String,
+    pub install_cli: String,
+    pub reload_webview: String,
+    pub restart: String,
+}
+
+// Items changed after the menu is built
+struct AppMenu {
+    check_for_updates: MenuItem<Wry>,
+    install_cli: MenuItem<Wry>,
+    reload_webview: MenuItem<Wry>,
+    restart: MenuItem<Wry>,
+    /// Items that need the app to have loaded, disabled until it has.
+    needs_init: Vec<MenuItem<Wry>>,
+}
+
+/// Sets the menu bar and routes its items.
+pub fn create(app: &AppHandle) -> Result<(), tauri::Error> {
+    let item = |id: &str, text: &str, accelerator: Option<&str>| {
+        MenuItem::with_id(app, id, text, true, accelerator)
+    };
+    let separator = || PredefinedMenuItem::separator(app);
+
+    let check_for_updates = MenuItem::with_id(
+        app,
+        "desktop.checkForUpdates",
+        "Check for Updates...",
+        UPDATER_ENABLED,
+        None::<&str>,
+    )?;
+    let install_cli = item("desktop.installCli", "Install CLI...", None)?;
+    let reload_webview = item("desktop.reloadWebview", "Reload Webview", None)?;
+    let restart = item("desktop.restart", "Restart", None)?;
+    let settings = item("settings.open", "Settings...", Some("Cmd+,"))?;
+
+    let new_session = item("session.new", "New Session", Some("Shift+Cmd+S"))?;
+    let open_project = item("project.open", "Open Project...", Some("Cmd+O"))?;
+
+    let toggle_sidebar = item("sidebar.toggle", "Toggle Sidebar", Some("Cmd+B"))?;
+    let toggle_terminal = item("terminal.toggle", "Toggle Terminal", Some("Ctrl+`"))?;
+    let toggle_file_tree = item("fileTree.toggle", "Toggle File Tree", None)?;
+    let back = item("common.goBack", "Back", None)?;
+    let forward = item("common.goForward", "Forward", None)?;
+    let previous_session = item(
+        "session.previous",
+        "Previous Session",
+        Some("Option+ArrowUp"),
+    )?;
+    let next_session = item("session.next", "Next Session", Some("Option+ArrowDown"))?;
+
+    let zoom_in = item("view.zoomIn", "Zoom In", Some("Cmd+="))?;
+    let zoom_out = item("view.zoomOut", "Zoom Out", Some("Cmd+-"))?;
+    let zoom_reset = item("view.zoomReset", "Actual Size", Some("Cmd+0"))?;
+
+    let app_submenu = Submenu::with_items(
+        app,
+        "OpenCode",
+        true,
+        &[
+            &PredefinedMenuItem::about(app, None, None)?,
+            &check_for_updates,
+            &install_cli,
+            &reload_webview,
+            &restart,
+            &separator()?,
+            &settings,
+            &separator()?,
+            &PredefinedMenuItem::services(app, None)?,
+            &separator()?,
+            &PredefinedMenuItem::hide(app, None)?,
+            &PredefinedMenuItem::hide_others(app, None)?,
+            &PredefinedMenuItem::show_all(app, None)?,
+            &separator()?,
+            &PredefinedMenuItem::quit(app, None)?,
+        ],
+    )?;
+    let file_submenu = Submenu::with_items(
+        app,
+        "File",
+        true,
+        &[
+            &new_session,
+            &open_project,
+            &separator()?,
+            &PredefinedMenuItem::close_window(app, None)?,
+        ],
+    )?;
+    let edit_submenu = Submenu::with_items(
+        app,
+        "Edit",
+        true,
+        &[
+            &PredefinedMenuItem::undo(app, None)?,
+            &PredefinedMenuItem::redo(app, None)?,
+            &separator()?,
+            &PredefinedMenuItem::cut(app, None)?,
+            &PredefinedMenuItem::copy(app, None)?,
+            &PredefinedMenuItem::paste(app, None)?,
+            &PredefinedMenuItem::select_all(app, None)?,
+        ],
+    )?;
+    let view_submenu = Submenu::with_items(
+        app,
+        "View",
+        true,
+        &[
+            &toggle_sidebar,
+            &toggle_terminal,
+            &toggle_file_tree,
+            &separator()?,
+            &back,
+            &forward,
+            &separator()?,
+            &previous_session,
+            &next_session,
+            &separator()?,
+            &zoom_in,
+            &zoom_out,
+            &zoom_reset,
+            &separator()?,
+            &PredefinedMenuItem::fullscreen(app, None)?,
+        ],
+    )?;
+    // Release builds only offer the webview's inspector in developer mode
+    if devtools::allowed(app) {
+        view_submenu.append(&item(
+            "desktop.toggleDevtools",
+            "Toggle Developer Tools",
+            Some("Alt+Cmd+I"),
+        )?)?;
+    }
+    let window_submenu = Submenu::with_items(
+        app,
+        "Window",
+        true,
+        &[
+            &PredefinedMenuItem::minimize(app, None)?,
+            &PredefinedMenuItem::maximize(app, None)?,
+        ],
+    )?;
+    let help_submenu = Submenu::with_items(
+        app,
+        "Help",
+        true,
+        &[
+            &item("help.docs", "OpenCode Documentation", None)?,
+            &item("help.support", "Support Forum", None)?,
+            &separator()?,
+            &item("help.feedback", "Share Feedback", None)?,
+            &item("help.bugReport", "Report a Bug", None)?,
+            &item("help.copySupportInfo", "Copy Support Info", None)?,
+        ],
+    )?;
+
+    let menu = Menu::with_items(
+        app,
+        &[
+            &app_submenu,
+            &file_submenu,
+            &edit_submenu,
+            &view_submenu,
+            &window_submenu,
+            &help_submenu,
+        ],
+    )?;
+    #[cfg(target_os = "macos")]
+    {
+        window_submenu.set_as_windows_menu_for_nsapp()?;
+        help_submenu.set_as_help_menu_for_nsapp()?;
+    }
+    app.set_menu(menu)?;
+    app.on_menu_event(|app, event| on_menu_event(app, event.id().as_ref()));
+
+    let needs_init = vec![
+        settings,
+        new_session,
+        open_project,
+        toggle_sidebar,
+        toggle_terminal,
+        toggle_file_tree,
+        back,
+        forward,
+        previous_session,
+        next_session,
+    ];
+    for item in &needs_init {
+        item.set_enabled(false)?;
+    }
+    app.manage(AppMenu {
+        check_for_updates,
+        install_cli,
+        reload_webview,
+        restart,
+        needs_init,
+    });
+
+    Ok(())
+}
+
+/// Enables the items that need the app to have loaded once `steps` is done, and disables them
+/// again while it reinitializes.
+pub fn watch_init_steps(app: &AppHandle, mut steps: watch::Receiver<InitStep>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let done = matches!(*steps.borrow_and_update(), InitStep::Done);
+            if let Some(menu) = app.try_state::<AppMenu>() {
+                for item in &menu.needs_init {
+                    let _ = item.set_enabled(done);
+                }
+            }
+
+            if steps.changed().await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_menu_labels(app: AppHandle, labels: MenuLabels) -> Result<(), String> {
+    let Some(menu) = app.try_state::<AppMenu>() else {
+        return Ok(());
+    };
+
+    [
+        (&menu.check_for_updates, labels.check_for_updates),
+        (&menu.install_cli, labels.install_cli),
+        (&menu.reload_webview, labels.reload_webview),
+        (&menu.restart, labels.restart),
+    ]
+    .into_iter()
+    .try_for_each(|(item, text)| item.set_text(text))
+    .map_err(|e| format!("Failed to set menu labels: {}", e))
+}
+
+fn on_menu_event(app: &AppHandle, id: &str) {
+    let url = match id {
+        "help.docs" => DOCS_URL,
+        "help.support" => SUPPORT_URL,
+        "help.feedback" => FEEDBACK_URL,
+        "help.bugReport" => BUG_REPORT_URL,
+        "desktop.toggleDevtools" => return toggle_devtools(app),
+        "help.copySupportInfo" => return support_info::spawn_copy(app),
+        // Tray items come through here too
+        id if id.starts_with("tray-") => return,
+        id => {
+            let _ = MenuCommand { id: id.to_string() }.emit(app);
+            return;
+        }
+    };
+
+    if let Err(e) = app.opener().open_url(url, None::<&str>) {
+        tracing::error!(%url, "Failed to open link: {e}");
+    }
+}
+
+fn toggle_devtools(app: &AppHandle) {
+    if let Some(window) = app
+        .webview_windows()
+        .into_values()
+        .find(|window| window.is_focused().unwrap_or(false))
+    {
+        devtools::toggle(app, &window);
+    }
+}
@@ -0,0 +1,170 @@
+//! Recovery from a sidecar that dies while migrating the JSON storage to sqlite. Left alone, the
+//! partial database it leaves behind looks like a finished migration to the next launch, and
+//! the sessions it didn't get to go missing.
+//!
+//! The migration only reads the JSON storage, so moving the partial database aside puts the data
+//! back the way it was before. Nothing is deleted, everything moved aside is kept under
+//! `failed-migration-<time>` next to the database.
+
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use tauri::AppHandle;
+use tauri_specta::Event;
+
+use crate::app_dirs::{self, AppDirs};
+
+const FAILURE_FILE: &str = "migration-failure.json";
+const STORAGE_DIR: &str = "storage";
+// sqlite keeps uncommitted pages next to the database
+const DB_SUFFIXES: [&str; 3] = ["", "-wal", "-shm"];
+
+/// A migration the sidecar didn't finish.
+#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq)]
+pub struct MigrationFailure {
+    /// Why the sidecar stopped, including its exit code or signal.
+    pub message: String,
+    /// When the migration failed, in RFC 3339.
+    pub time: String,
+}
+
+/// Sent when the sidecar exits mid-migration, before startup fails.
+#[derive(tauri_specta::Event, serde::Serialize, serde::Deserialize, Clone, Debug, specta::Type)]
+pub struct MigrationFailed {
+    pub failure: MigrationFailure,
+}
+
+#[derive(Clone, Copy, serde::Deserialize, specta::Type, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationRecovery {
+    /// Puts the data back the way it was before the migration and quits, e.g. to go back to an
+    /// earlier version of the app.
+    Restore,
+    /// Puts the data back and migrates it again.
+    Retry,
+    /// Sets the data aside and starts with an empty database.
+    StartFresh,
+}
+
+/// The last migration that failed, until it's recovered from.
+#[tauri::command]
+#[specta::specta]
+pub fn get_migration_failure() -> Option<MigrationFailure> {
+    let raw = std::fs::read_to_string(failure_path()?).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+/// Recovers from the failed migration. Call `reinitialize` afterwards to start the server again,
+/// except after `Restore`, which quits the app.
+#[tauri::command]
+#[specta::specta]
+pub fn recover_migration(app: AppHandle, recovery: MigrationRecovery) -> Result<(), String> {
+    if get_migration_failure().is_none() {
+        return Err("No failed migration to recover from".to_string());
+    }
+
+    let db = app_dirs::opencode_db().map_err(|e| format!("Failed to find database: {}", e))?;
+    let data_dir = db
+        .parent()
+        .ok_or_else(|| "Database has no parent directory".to_string())?;
+    let aside = data_dir.join(format!(
+        "failed-migration-{}",
+        Local::now().format("%Y%m%d-%H%M%S")
+    ));
+
+    set_aside(&db_files(&db), &aside)?;
+    if recovery == MigrationRecovery::StartFresh {
+        set_aside(&[data_dir.join(STORAGE_DIR)], &aside)?;
+    }
+
+    if let Some(path) = failure_path() {
+        let _ = std::fs::remove_file(path);
+    }
+    tracing::info!(?recovery, aside = %aside.display(), "Recovered from failed migration");
+
+    if recovery == MigrationRecovery::Restore {
+        app.exit(0);
+    }
+    Ok(())
+}
+
+/// Keeps the failure for the doctor and the next launch, and tells the frontend.
+pub fn record(app: &AppHandle, message: &str) {
+    let failure = MigrationFailure {
+        message: message.to_string(),
+        time: Local::now().to_rfc3339(),
+    };
+    tracing::error!(?failure, "Sidecar exited while migrating the database");
+
+    if let Some(path) = failure_path() {
+        let result = serde_json::to_string(&failure)
+            .map_err(|e| e.to_string())
+            .and_then(|raw| {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                }
+                std::fs::write(&path, raw).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            tracing::warn!("Failed to save migration failure: {e}");
+        }
+    }
+
+    let _ = MigrationFailed { failure }.emit(app);
+}
+
+fn failure_path() -> Option<PathBuf> {
+    AppDirs::get().map(|dirs| dirs.state.join(FAILURE_FILE))
+}
+
+fn db_files(db: &Path) -> Vec<PathBuf> {
+    DB_SUFFIXES
+        .iter()
+        .map(|suffix| {
+            let mut name = db.as_os_str().to_os_string();
+            name.push(suffix);
+            PathBuf::from(name)
+        })
+        .collect()
+}
+
+fn set_aside(paths: &[PathBuf], aside: &Path) -> Result<(), String> {
+    for path in paths.iter().filter(|path| path.exists()) {
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+
+        std::fs::create_dir_all(aside)
+            .map_err(|e| format!("Failed to create {}: {}", aside.display(), e))?;
+        std::fs::rename(path, aside.join(name))
+            .map_err(|e| format!("Failed to move {} aside: {}", path.display(), e))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sets_aside_database_and_leftovers() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("opencode.db");
+        std::fs::write(&db, "partial").unwrap();
+        std::fs::write(dir.path().join("opencode.db-wal"), "pages").unwrap();
+        std::fs::write(dir.path().join("opencode.dbx"), "unrelated").unwrap();
+
+        let aside = dir.path().join("failed-migration");
+        set_aside(&db_files(&db), &aside).unwrap();
+
+        assert!(!db.exists());
+        assert_eq!(
+            std::fs::read_to_string(aside.join("opencode.db")).unwrap(),
+            "partial"
+        );
+        assert!(aside.join("opencode.db-wal").exists());
+        assert!(!aside.join("opencode.db-shm").exists());
+        assert!(dir.path().join("opencode.dbx").exists());
+    }
+}
@@ -0,0 +1,250 @@
+//! Keeps windows on a monitor that's still connected. The window-state plugin restores positions
+//! as they were saved, so a window last used on an external display comes back off-screen once
+//! that display is unplugged.
+
+use tauri::{AppHandle, Monitor, PhysicalPosition, PhysicalSize, WebviewWindow};
+
+use crate::window_prefs;
+
+// How much of a window has to be on a monitor to be found and dragged back, in physical pixels
+const MIN_VISIBLE_WIDTH: u32 = 120;
+const MIN_VISIBLE_HEIGHT: u32 = 40;
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+pub struct MonitorInfo {
+    /// Pass to `move_window_to_monitor`. Not every platform names its monitors.
+    pub name: Option<String>,
+    /// Position and size in physical pixels, in the desktop's coordinates.
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    pub primary: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Rect {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl Rect {
+    fn work_area(monitor: &Monitor) -> Self {
+        let area = monitor.work_area();
+        Self {
+            x: area.position.x,
+            y: area.position.y,
+            width: area.size.width,
+            height: area.size.height,
+        }
+    }
+
+    fn of(window: &WebviewWindow) -> Option<Self> {
+        let position = window.outer_position().ok()?;
+        let size = window.outer_size().ok()?;
+        Some(Self {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        })
+    }
+}
+
+/// Every connected monitor.
+#[tauri::command]
+#[specta::specta]
+pub fn list_monitors(app: AppHandle) -> Result<Vec<MonitorInfo>, String> {
+    let primary = app
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .and_then(|monitor| monitor.name().cloned());
+
+    Ok(available(&app)?
+        .iter()
+        .map(|monitor| MonitorInfo {
+            name: monitor.name().cloned(),
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+            scale_factor: monitor.scale_factor(),
+            primary: monitor.name().is_some() && monitor.name() == primary.as_ref(),
+        })
+        .collect())
+}
+
+/// Moves the calling window to the middle of the monitor named `name`, keeping it maximized
+/// when it was.
+#[tauri::command]
+#[specta::specta]
+pub fn move_window_to_monitor(
+    app: AppHandle,
+    window: WebviewWindow,
+    name: String,
+) -> Result<(), String> {
+    let monitor = available(&app)?
+        .into_iter()
+        .find(|monitor| monitor.name() == Some(&name))
+        .ok_or_else(|| format!("No monitor named '{}'", name))?;
+
+    place(&window, &monitor).map_err(|e| format!("Failed to move window: {}", e))?;
+    window_prefs::remember_monitor(&app, &window);
+    Ok(())
+}
+
+/// Moves `window` back onto a connected monitor when it was restored where none is, preferring
+/// the monitor it was last on.
+pub fn ensure_visible(app: &AppHandle, window: &WebviewWindow) {
+    let (Some(bounds), Ok(monitors)) = (Rect::of(window), available(app)) else {
+        return;
+    };
+    let areas = monitors.iter().map(Rect::work_area).collect::<Vec<_>>();
+    if areas.is_empty() || reachable(bounds, &areas) {
+        return;
+    }
+
+    let saved = window_prefs::saved_monitor(app, window.label());
+    let monitor = monitors
+        .iter()
+        .find(|monitor| saved.is_some() && monitor.name() == saved.as_ref())
+        .cloned()
+        .or_else(|| app.primary_monitor().ok().flatten())
+        .unwrap_or_else(|| monitors[0].clone());
+
+    tracing::info!(
+        label = window.label(),
+        ?bounds,
+        monitor = ?monitor.name(),
+        "Window was restored off-screen, moving it"
+    );
+    if let Err(e) = place(window, &monitor) {
+        tracing::warn!(
+            label = window.label(),
+            "Failed to move window on-screen: {e}"
+        );
+    }
+}
+
+fn available(app: &AppHandle) -> Result<Vec<Monitor>, String> {
+    app.available_monitors()
+        .map_err(|e| format!("Failed to list monitors: {}", e))
+}
+
+fn place(window: &WebviewWindow, monitor: &Monitor) -> Result<(), tauri::Error> {
+    let maximized = window.is_maximized().unwrap_or(false);
+    if maximized {
+        window.unmaximize()?;
+    }
+
+    let size = window.outer_size()?;
+    let target = centered(size.width, size.height, Rect::work_area(monitor));
+    window.set_size(PhysicalSize::new(target.width, target.height))?;
+    window.set_position(PhysicalPosition::new(target.x, target.y))?;
+
+    if maximized {
+        window.maximize()?;
+    }
+    Ok(())
+}
+
+// Whether enough of the window's top edge is on some monitor to grab it by the titlebar
+fn reachable(window: Rect, areas: &[Rect]) -> bool {
+    let strip = Rect {
+        height: window.height.min(MIN_VISIBLE_HEIGHT),
+        ..window
+    };
+
+    areas.iter().any(|area| {
+        let (width, height) = overlap(strip, *area);
+        width >= MIN_VISIBLE_WIDTH.min(strip.width) as i64 && height >= strip.height as i64
+    })
+}
+
+fn overlap(a: Rect, b: Rect) -> (i64, i64) {
+    let span = |a_start: i32, a_len: u32, b_start: i32, b_len: u32| {
+        let start = a_start.max(b_start) as i64;
+        let end = (a_start as i64 + a_len as i64).min(b_start as i64 + b_len as i64);
+        (end - start).max(0)
+    };
+
+    (
+        span(a.x, a.width, b.x, b.width),
+        span(a.y, a.height, b.y, b.height),
+    )
+}
+
+// Shrinks the window to fit when it's larger than the area
+fn centered(width: u32, height: u32, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+
+    Rect {
+        x: area.x + ((area.width - width) / 2) as i32,
+        y: area.y + ((area.height - height) / 2) as i32,
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAPTOP: Rect = Rect {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+    };
+    const EXTERNAL: Rect = Rect {
+        x: 1920,
+        y: 0,
+        width: 2560,
+        height: 1440,
+    };
+
+    fn window(x: i32, y: i32) -> Rect {
+        Rect {
+            x,
+            y,
+            width: 1280,
+            height: 800,
+        }
+    }
+
+    #[test]
+    fn finds_windows_left_on_unplugged_monitor() {
+        assert!(reachable(window(2200, 100), &[LAPTOP, EXTERNAL]));
+        assert!(!reachable(window(2200, 100), &[LAPTOP]));
+        assert!(reachable(window(100, 100), &[LAPTOP]));
+    }
+
+    #[test]
+    fn needs_titlebar_on_screen() {
+        // Only a sliver on the left edge
+        assert!(!reachable(window(1900, 100), &[LAPTOP]));
+        // Titlebar above the top of the screen
+        assert!(!reachable(window(100, -500), &[LAPTOP]));
+        // Hanging off the bottom is fine, the titlebar can still be grabbed
+        assert!(reachable(window(100, 900), &[LAPTOP]));
+    }
+
+    #[test]
+    fn centers_and_fits_window() {
+        assert_eq!(
+            centered(1280, 800, EXTERNAL),
+            Rect {
+                x: 1920 + 640,
+                y: 320,
+                width: 1280,
+                height: 800
+            }
+        );
+        assert_eq!(centered(3000, 2000, LAPTOP), LAPTOP);
+    }
+}
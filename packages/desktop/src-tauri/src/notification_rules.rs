@@ -0,0 +1,213 @@
+use std::path::Path;
+
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::constants::{NOTIFICATION_RULES_KEY, SETTINGS_STORE};
+
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    ResponseReady,
+    Permission,
+    Question,
+    Error,
+}
+
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl NotificationKind {
+    fn severity(self) -> NotificationSeverity {
+        match self {
+            Self::ResponseReady => NotificationSeverity::Info,
+            Self::Permission | Self::Question => NotificationSeverity::Warning,
+            Self::Error => NotificationSeverity::Error,
+        }
+    }
+}
+
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationAction {
+    /// Show a system notification.
+    Toast,
+    /// Only play the in-app sound, without a system notification.
+    Sound,
+    /// Only mark the window's icon.
+    BadgeOnly,
+    Suppress,
+}
+
+/// Unset fields match anything.
+#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq)]
+pub struct NotificationRule {
+    pub kind: Option<NotificationKind>,
+    /// A project directory, or just its folder name.
+    pub project: Option<String>,
+    pub severity: Option<NotificationSeverity>,
+    pub action: NotificationAction,
+}
+
+impl NotificationRule {
+    fn matches(&self, kind: NotificationKind, directory: Option<&str>) -> bool {
+        self.kind.is_none_or(|rule| rule == kind)
+            && self.severity.is_none_or(|rule| rule == kind.severity())
+            && self
+                .project
+                .as_deref()
+                .is_none_or(|project| directory.is_some_and(|dir| same_project(project, dir)))
+    }
+}
+
+fn same_project(project: &str, directory: &str) -> bool {
+    let trim = |path: &str| path.trim_end_matches(['/', '\\']).to_string();
+    let (project, directory) = (trim(project), trim(directory));
+
+    project == directory
+        || Path::new(&directory.replace('\\', "/"))
+            .file_name()
+            .is_some_and(|name| name == project.as_str())
+}
+
+/// The action of the first rule matching, or `Toast` when none does.
+fn route(
+    rules: &[NotificationRule],
+    kind: NotificationKind,
+    directory: Option<&str>,
+) -> NotificationAction {
+    rules
+        .iter()
+        .find(|rule| rule.matches(kind, directory))
+        .map_or(NotificationAction::Toast, |rule| rule.action)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_notification_rules(app: AppHandle) -> Result<Vec<NotificationRule>, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(NOTIFICATION_RULES_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+/// Rules are checked in order, so more specific ones should come first.
+#[tauri::command]
+#[specta::specta]
+pub fn set_notification_rules(app: AppHandle, rules: Vec<NotificationRule>) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(NOTIFICATION_RULES_KEY, serde_json::json!(rules));
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+/// Decides how a notification about `kind` in project `directory` should be delivered.
+#[tauri::command]
+#[specta::specta]
+pub fn route_notification(
+    app: AppHandle,
+    kind: NotificationKind,
+    directory: Option<String>,
+) -> NotificationAction {
+    let rules = get_notification_rules(app).unwrap_or_default();
+    route(&rules, kind, directory.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        kind: Option<NotificationKind>,
+        project: Option<&str>,
+        severity: Option<NotificationSeverity>,
+        action: NotificationAction,
+    ) -> NotificationRule {
+        NotificationRule {
+            kind,
+            project: project.map(String::from),
+            severity,
+            action,
+        }
+    }
+
+    #[test]
+    fn defaults_to_toast() {
+        assert_eq!(
+            route(&[], NotificationKind::ResponseReady, None),
+            NotificationAction::Toast
+        );
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let rules = [
+            rule(
+                None,
+                Some("scratch"),
+                Some(NotificationSeverity::Info),
+                NotificationAction::Suppress,
+            ),
+            rule(
+                Some(NotificationKind::ResponseReady),
+                None,
+                None,
+                NotificationAction::BadgeOnly,
+            ),
+        ];
+
+        assert_eq!(
+            route(
+                &rules,
+                NotificationKind::ResponseReady,
+                Some("/home/me/scratch/")
+            ),
+            NotificationAction::Suppress
+        );
+        assert_eq!(
+            route(
+                &rules,
+                NotificationKind::ResponseReady,
+                Some("/home/me/app")
+            ),
+            NotificationAction::BadgeOnly
+        );
+        assert_eq!(
+            route(&rules, NotificationKind::Error, Some("/home/me/scratch")),
+            NotificationAction::Toast
+        );
+    }
+
+    #[test]
+    fn project_rules_need_a_directory() {
+        let rules = [rule(
+            None,
+            Some(r"C:\code\app"),
+            None,
+            NotificationAction::Sound,
+        )];
+
+        assert_eq!(
+            route(&rules, NotificationKind::Question, Some(r"C:\code\app\")),
+            NotificationAction::Sound
+        );
+        assert_eq!(
+            route(&rules, NotificationKind::Question, None),
+            NotificationAction::Toast
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::{
+    constants::{OS_SEARCH_KEY, SETTINGS_STORE},
+    db_guard::{self, DbQueryError},
+    sidecar,
+};
+
+const MAX_SESSIONS: u32 = 50;
+const MAX_TITLE_LEN: usize = 80;
+
+#[cfg(target_os = "macos")]
+const EXTENSION: &str = "webloc";
+#[cfg(not(target_os = "macos"))]
+const EXTENSION: &str = "url";
+
+#[derive(serde::Deserialize, Debug)]
+struct SessionRow {
+    id: String,
+    title: String,
+    directory: String,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_os_search_enabled(app: AppHandle) -> Result<bool, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(OS_SEARCH_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+/// Turning OS search off removes every entry the app added.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_os_search_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(OS_SEARCH_KEY, serde_json::json!(enabled));
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    if enabled {
+        index_sessions_for_search(app).await.map(|_| ())
+    } else {
+        clear_search_index(app)
+    }
+}
+
+/// Replaces the OS search entries with the most recently updated sessions, returning how many
+/// were indexed. Spotlight picks up `.webloc` files on macOS, and Windows Search indexes `.url`
+/// shortcuts in the Start menu; both open the session through the `opencode://` deep link.
+#[tauri::command]
+#[specta::specta]
+pub async fn index_sessions_for_search(app: AppHandle) -> Result<u32, String> {
+    let dir = index_dir(&app)?;
+    let sessions = recent_sessions(&app).await?;
+
+    write_index(&dir, &sessions)
+}
+
+fn write_index(dir: &Path, sessions: &[SessionRow]) -> Result<u32, String> {
+    clear_dir(dir)?;
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let mut taken = HashSet::new();
+    for session in sessions {
+        let name = unique_name(&file_stem(&session.title), &mut taken);
+        let path = dir.join(format!("{name}.{EXTENSION}"));
+        let url = deep_link(&session.directory, &session.id);
+
+        std::fs::write(&path, shortcut(&url))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+
+    tracing::info!(count = sessions.len(), dir = %dir.display(), "Indexed sessions for OS search");
+    Ok(sessions.len() as u32)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn clear_search_index(app: AppHandle) -> Result<(), String> {
+    clear_dir(&index_dir(&app)?)
+}
+
+/// Refreshes the index in the background on startup when OS search is enabled.
+pub fn spawn_index(app: AppHandle) {
+    if !get_os_search_enabled(app.clone()).unwrap_or_default() {
+        return;
+    }
+
+    // Runs at startup, when the server may still be migrating the database
+    tokio::spawn(async move {
+        let result = match index_dir(&app) {
+            Ok(dir) => db_guard::retry(|| recent_sessions(&app))
+                .await
+                .map_err(String::from)
+                .and_then(|sessions| write_index(&dir, &sessions)),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to index sessions for OS search: {e}");
+        }
+    });
+}
+
+#[cfg(target_os = "macos")]
+fn index_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    // Spotlight indexes metadata files apps leave here, e.g. Safari's history
+    dirs::cache_dir()
+        .map(|dir| dir.join("Metadata").join(&app.config().identifier))
+        .ok_or_else(|| "Cannot determine cache directory".to_string())
+}
+
+#[cfg(windows)]
+fn index_dir(_app: &AppHandle) -> Result<PathBuf, String> {
+    dirs::data_dir()
+        .map(|dir| dir.join(r"Microsoft\Windows\Start Menu\Programs\OpenCode Sessions"))
+        .ok_or_else(|| "Cannot determine AppData directory".to_string())
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn index_dir(_app: &AppHandle) -> Result<PathBuf, String> {
+    Err("OS search isn't supported on this platform".to_string())
+}
+
+async fn recent_sessions(app: &AppHandle) -> Result<Vec<SessionRow>, DbQueryError> {
+    sidecar::query_db(
+        app,
+        &format!(
+            "SELECT id, title, directory FROM session \
+             WHERE parent_id IS NULL AND time_archived IS NULL \
+             ORDER BY time_updated DESC LIMIT {MAX_SESSIONS}"
+        ),
+    )
+    .await
+}
+
+// Only removes shortcut files so anything else placed in the directory survives
+fn clear_dir(dir: &Path) -> Result<(), String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(());
+    };
+
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.extension().is_some_and(|ext| ext == EXTENSION) {
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn deep_link(directory: &str, session: &str) -> String {
+    reqwest::Url::parse_with_params(
+        "opencode://open-project",
+        &[("directory", directory), ("session", session)],
+    )
+    .map(String::from)
+    .unwrap_or_default()
+}
+
+/// The title as a file name that's valid on every platform.
+fn file_stem(title: &str) -> String {
+    let stem = title
+        .chars()
+        .map(|c| {
+            if c.is_control() || r#"/\:*?"<>|"#.contains(c) {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    // Windows ignores trailing dots, and a leading one hides the file elsewhere
+    let stem = stem
+        .trim_matches('.')
+        .chars()
+        .take(MAX_TITLE_LEN)
+        .collect::<String>();
+
+    if stem.trim().is_empty() {
+        "Untitled session".to_string()
+    } else {
+        stem.trim().to_string()
+    }
+}
+
+fn unique_name(stem: &str, taken: &mut HashSet<String>) -> String {
+    let mut name = stem.to_string();
+    let mut n = 2;
+    while !taken.insert(name.to_lowercase()) {
+        name = format!("{stem} ({n})");
+        n += 1;
+    }
+    name
+}
+
+#[cfg(target_os = "macos")]
+fn shortcut(url: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\">\n<dict>\n\t<key>URL</key>\n\t<string>{}</string>\n</dict>\n</plist>\n",
+        url.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    )
+}
+
+#[cfg(not(target_os = "macos"))]
+fn shortcut(url: &str) -> String {
+    format!("[InternetShortcut]\r\nURL={url}\r\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deep_link_encodes_params() {
+        assert_eq!(
+            deep_link("/Users/me/my app", "ses_123"),
+            "opencode://open-project?directory=%2FUsers%2Fme%2Fmy+app&session=ses_123"
+        );
+    }
+
+    #[test]
+    fn file_stem_strips_invalid_characters() {
+        assert_eq!(file_stem("Fix: a/b <test>?"), "Fix a b test");
+        assert_eq!(file_stem("  ...  "), "Untitled session");
+        assert_eq!(file_stem("line\nbreak."), "line break");
+        assert_eq!(file_stem(&"a".repeat(200)).len(), MAX_TITLE_LEN);
+    }
+
+    #[test]
+    fn unique_name_numbers_duplicates() {
+        let mut taken = HashSet::new();
+        assert_eq!(unique_name("Refactor", &mut taken), "Refactor");
+        assert_eq!(unique_name("refactor", &mut taken), "refactor (2)");
+        assert_eq!(unique_name("Refactor", &mut taken), "Refactor (3)");
+    }
+}
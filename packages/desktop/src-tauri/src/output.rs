@@ -0,0 +1,172 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use futures::{Stream, StreamExt, stream};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::{BroadcastStream, errors::BroadcastStreamRecvError};
+
+use crate::cli::CommandEvent;
+
+const LIVE_EVENTS: usize = 1024;
+
+/// Output of a spawned command, shared between any number of subscribers.
+///
+/// The most recent `capacity` bytes of stdout/stderr are retained and replayed to each new
+/// subscriber before it receives live events, so consumers that attach late still see early
+/// lines. Pushing never blocks; a subscriber that falls too far behind skips ahead instead of
+/// stalling the child.
+#[derive(Clone, Debug)]
+pub struct CommandOutput {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    history: VecDeque<CommandEvent>,
+    bytes: usize,
+    capacity: usize,
+    live: broadcast::Sender<CommandEvent>,
+}
+
+impl CommandOutput {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                history: VecDeque::new(),
+                bytes: 0,
+                capacity,
+                live: broadcast::channel(LIVE_EVENTS).0,
+            })),
+        }
+    }
+
+    pub fn push(&self, event: CommandEvent) {
+        let mut inner = self.inner.lock().expect("Failed to acquire mutex lock");
+
+        inner.bytes += event_len(&event);
+        inner.history.push_back(event.clone());
+        while inner.bytes > inner.capacity {
+            let Some(evicted) = inner.history.pop_front() else {
+                break;
+            };
+            inner.bytes -= event_len(&evicted);
+        }
+
+        // Only fails when nobody is subscribed, the history covers them
+        let _ = inner.live.send(event);
+    }
+
+    /// Replays retained output, then follows live events until the command exits.
+    pub fn subscribe(&self) -> impl Stream<Item = CommandEvent> + 'static {
+        let (history, live) = {
+            let inner = self.inner.lock().expect("Failed to acquire mutex lock");
+            (
+                inner.history.iter().cloned().collect::<Vec<_>>(),
+                inner.live.subscribe(),
+            )
+        };
+
+        let live = BroadcastStream::new(live).filter_map(|event| {
+            futures::future::ready(match event {
+                Ok(event) => Some(event),
+                Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Command output subscriber lagged behind");
+                    None
+                }
+            })
+        });
+
+        stream::iter(history)
+            .chain(live)
+            .scan(false, |done, event| {
+                if *done {
+                    return futures::future::ready(None);
+                }
+                *done = matches!(event, CommandEvent::Terminated(_) | CommandEvent::Error(_));
+                futures::future::ready(Some(event))
+            })
+    }
+}
+
+fn event_len(event: &CommandEvent) -> usize {
+    match event {
+        CommandEvent::Stdout(bytes) | CommandEvent::Stderr(bytes) => bytes.len(),
+        CommandEvent::Error(err) => err.len(),
+        CommandEvent::Terminated(_) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::cli::TerminatedPayload;
+
+    fn stdout(line: &str) -> CommandEvent {
+        CommandEvent::Stdout(line.as_bytes().to_vec())
+    }
+
+    fn lines(events: Vec<CommandEvent>) -> Vec<String> {
+        events
+            .into_iter()
+            .map(|event| match event {
+                CommandEvent::Stdout(bytes) => String::from_utf8(bytes).unwrap(),
+                CommandEvent::Terminated(_) => "<exit>".to_string(),
+                other => format!("{other:?}"),
+            })
+            .collect()
+    }
+
+    fn exit() -> CommandEvent {
+        CommandEvent::Terminated(TerminatedPayload {
+            code: Some(0),
+            signal: None,
+        })
+    }
+
+    #[test]
+    fn late_subscriber_sees_history() {
+        let output = CommandOutput::new(1024);
+        output.push(stdout("one"));
+        output.push(stdout("two"));
+        output.push(exit());
+
+        let events = block_on(output.subscribe().collect::<Vec<_>>());
+        assert_eq!(lines(events), ["one", "two", "<exit>"]);
+    }
+
+    #[test]
+    fn evicts_oldest_output_past_capacity() {
+        let output = CommandOutput::new(6);
+        output.push(stdout("one"));
+        output.push(stdout("two"));
+        output.push(stdout("six"));
+        output.push(exit());
+
+        let events = block_on(output.subscribe().collect::<Vec<_>>());
+        assert_eq!(lines(events), ["two", "six", "<exit>"]);
+    }
+
+    #[test]
+    fn subscribers_are_independent() {
+        let output = CommandOutput::new(1024);
+        output.push(stdout("early"));
+
+        let first = output.subscribe();
+        output.push(stdout("live"));
+        let second = output.subscribe();
+        output.push(exit());
+
+        assert_eq!(
+            lines(block_on(first.collect::<Vec<_>>())),
+            ["early", "live", "<exit>"]
+        );
+        assert_eq!(
+            lines(block_on(second.collect::<Vec<_>>())),
+            ["early", "live", "<exit>"]
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use std::path::Path;
+
+/// Where an `opencode` binary outside the app's own install directory came from.
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageManager {
+    Homebrew,
+    /// apt, or another distribution package manager installing into `/usr`.
+    Apt,
+    Scoop,
+    Npm,
+    Nix,
+}
+
+impl PackageManager {
+    /// Guesses the package manager owning `path` from where it lives. Symlinks should be
+    /// resolved first, since e.g. Homebrew links into its `Cellar`.
+    pub fn detect(path: &Path) -> Option<Self> {
+        let path = path
+            .to_string_lossy()
+            .replace('\\', "/")
+            .to_ascii_lowercase();
+
+        if path.contains("/cellar/")
+            || path.contains("/.linuxbrew/")
+            || path.starts_with("/opt/homebrew/")
+        {
+            Some(Self::Homebrew)
+        } else if path.contains("/scoop/") {
+            Some(Self::Scoop)
+        } else if path.contains("/nix/store/") || path.contains("/.nix-profile/") {
+            Some(Self::Nix)
+        } else if path.contains("/node_modules/") {
+            Some(Self::Npm)
+        } else if ["/usr/bin/", "/usr/lib/", "/usr/share/", "/bin/"]
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+        {
+            Some(Self::Apt)
+        } else {
+            None
+        }
+    }
+
+    pub fn upgrade_command(self) -> &'static str {
+        match self {
+            Self::Homebrew => "brew upgrade opencode",
+            Self::Apt => "sudo apt install --only-upgrade opencode",
+            Self::Scoop => "scoop update opencode",
+            Self::Npm => "npm install -g opencode-ai@latest",
+            Self::Nix => "nix profile upgrade opencode",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(path: &str) -> Option<PackageManager> {
+        PackageManager::detect(Path::new(path))
+    }
+
+    #[test]
+    fn detects_package_managers() {
+        assert_eq!(
+            detect("/opt/homebrew/Cellar/opencode/1.0.0/bin/opencode"),
+            Some(PackageManager::Homebrew)
+        );
+        assert_eq!(
+            detect("/home/linuxbrew/.linuxbrew/bin/opencode"),
+            Some(PackageManager::Homebrew)
+        );
+        assert_eq!(detect("/usr/bin/opencode"), Some(PackageManager::Apt));
+        assert_eq!(
+            detect(r"C:\Users\me\scoop\apps\opencode\current\opencode.exe"),
+            Some(PackageManager::Scoop)
+        );
+        assert_eq!(
+            detect("/usr/local/lib/node_modules/opencode-ai/bin/opencode"),
+            Some(PackageManager::Npm)
+        );
+        assert_eq!(
+            detect("/nix/store/abc-opencode-1.0.0/bin/opencode"),
+            Some(PackageManager::Nix)
+        );
+    }
+
+    #[test]
+    fn ignores_own_install() {
+        assert_eq!(detect("/home/me/.opencode/bin/opencode"), None);
+        assert_eq!(detect("/usr/local/bin/opencode"), None);
+    }
+}
@@ -0,0 +1,166 @@
+use tauri::AppHandle;
+#[cfg(target_os = "linux")]
+use tauri_plugin_dialog::{
+    DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult,
+};
+
+// First release with every web platform feature the frontend relies on, e.g. CSS `:has()`
+#[cfg(any(target_os = "linux", test))]
+const MIN_WEBKITGTK: (u32, u32) = (2, 40);
+
+/// What the webview the app runs in supports, for features that depend on the platform.
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+pub struct PlatformCapabilities {
+    /// WebKitGTK version the app is running with, on Linux.
+    pub webkitgtk_version: Option<String>,
+    /// Whether the webview is recent enough for the frontend to render correctly.
+    pub webview_supported: bool,
+    /// How to update the webview when it's unsupported, specific to the distro when known.
+    pub webview_guidance: Option<String>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_platform_capabilities() -> PlatformCapabilities {
+    #[cfg(target_os = "linux")]
+    {
+        let version = webkitgtk_version();
+        let supported = is_supported(version);
+        PlatformCapabilities {
+            webkitgtk_version: Some(format!("{}.{}.{}", version.0, version.1, version.2)),
+            webview_supported: supported,
+            webview_guidance: (!supported)
+                .then(|| guidance(&std::fs::read_to_string("/etc/os-release").unwrap_or_default())),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    PlatformCapabilities {
+        webkitgtk_version: None,
+        webview_supported: true,
+        webview_guidance: None,
+    }
+}
+
+/// Warns before any window opens when the webview is too old to render the app. Returns `false`
+/// when the user chose to quit.
+#[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+pub fn check_webview(app: &AppHandle) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        let capabilities = get_platform_capabilities();
+        if capabilities.webview_supported {
+            return true;
+        }
+
+        let version = capabilities.webkitgtk_version.unwrap_or_default();
+        let guidance = capabilities.webview_guidance.unwrap_or_default();
+        tracing::warn!(%version, "WebKitGTK is older than the supported minimum");
+
+        const CONTINUE: &str = "Continue Anyway";
+
+        let res = app
+            .dialog()
+            .message(format!(
+                "OpenCode needs WebKitGTK {}.{} or newer, but this system has {}. Parts of the \
+                 app may not display or work correctly.\n\n{}",
+                MIN_WEBKITGTK.0, MIN_WEBKITGTK.1, version, guidance
+            ))
+            .title("Unsupported WebKitGTK")
+            .kind(MessageDialogKind::Warning)
+            .buttons(MessageDialogButtons::OkCancelCustom(
+                CONTINUE.to_string(),
+                "Quit".to_string(),
+            ))
+            .blocking_show_with_result();
+
+        matches!(res, MessageDialogResult::Custom(name) if name == CONTINUE)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    true
+}
+
+// The version of the library loaded at runtime, which can differ from the one built against
+#[cfg(target_os = "linux")]
+fn webkitgtk_version() -> (u32, u32, u32) {
+    unsafe extern "C" {
+        fn webkit_get_major_version() -> u32;
+        fn webkit_get_minor_version() -> u32;
+        fn webkit_get_micro_version() -> u32;
+    }
+
+    // Safety: these only read constants from the linked libwebkit2gtk
+    unsafe {
+        (
+            webkit_get_major_version(),
+            webkit_get_minor_version(),
+            webkit_get_micro_version(),
+        )
+    }
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn is_supported((major, minor, _): (u32, u32, u32)) -> bool {
+    (major, minor) >= MIN_WEBKITGTK
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn guidance(os_release: &str) -> String {
+    let field = |key: &str| {
+        os_release.lines().find_map(|line| {
+            let value = line.strip_prefix(key)?.strip_prefix('=')?;
+            Some(value.trim_matches('"').to_lowercase())
+        })
+    };
+    let ids = [field("ID"), field("ID_LIKE")]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let is = |id: &str| ids.split_whitespace().any(|candidate| candidate == id);
+
+    if is("ubuntu") || is("debian") {
+        "Install the latest updates with `sudo apt update && sudo apt upgrade libwebkit2gtk-4.1-0`. \
+         Older releases don't get new WebKitGTK versions, so upgrading the distribution may be \
+         needed."
+            .to_string()
+    } else if is("fedora") || is("rhel") {
+        "Install the latest updates with `sudo dnf upgrade webkit2gtk4.1`.".to_string()
+    } else if is("arch") {
+        "Install the latest updates with `sudo pacman -Syu webkit2gtk-4.1`.".to_string()
+    } else if is("opensuse") || is("suse") {
+        "Install the latest updates with `sudo zypper update libwebkit2gtk-4_1-0`.".to_string()
+    } else {
+        "Update WebKitGTK through your distribution's package manager.".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_against_minimum() {
+        assert!(is_supported((2, 40, 0)));
+        assert!(is_supported((2, 44, 3)));
+        assert!(is_supported((3, 0, 0)));
+        assert!(!is_supported((2, 38, 6)));
+    }
+
+    #[test]
+    fn guidance_follows_distro() {
+        let mint = "NAME=\"Linux Mint\"\nID=linuxmint\nID_LIKE=\"ubuntu debian\"\n";
+        assert!(guidance(mint).contains("apt"));
+
+        let fedora = "NAME=\"Fedora Linux\"\nID=fedora\n";
+        assert!(guidance(fedora).contains("dnf"));
+
+        let tumbleweed = "ID=\"opensuse-tumbleweed\"\nID_LIKE=\"opensuse suse\"\n";
+        assert!(guidance(tumbleweed).contains("zypper"));
+
+        assert!(guidance("ID=nixos\n").contains("package manager"));
+        // `ID_LIKE` must not be mistaken for `ID`
+        assert!(guidance("ID_LIKE=arch\n").contains("pacman"));
+    }
+}
@@ -0,0 +1,285 @@
+use std::{path::Path, sync::Mutex};
+
+use tauri::{AppHandle, Manager};
+use tokio::process::Command;
+
+use crate::{ServerState, project, server, wsl_setup};
+
+const ALL_INTERFACES: &str = "0.0.0.0";
+
+/// Rules added by this app, removed again when it exits.
+static CREATED: Mutex<Vec<PortProxyRule>> = Mutex::new(Vec::new());
+
+/// A `netsh interface portproxy` rule forwarding a Windows port, e.g. to a server inside WSL.
+#[derive(Clone, serde::Serialize, specta::Type, Debug, PartialEq)]
+pub struct PortProxyRule {
+    pub listen_address: String,
+    pub listen_port: u32,
+    pub connect_address: String,
+    pub connect_port: u32,
+}
+
+impl PortProxyRule {
+    fn firewall_rule_name(&self) -> String {
+        format!("opencode {}", self.listen_port)
+    }
+
+    fn add_commands(&self) -> [String; 2] {
+        [
+            format!(
+                "netsh interface portproxy add v4tov4 listenaddress={} listenport={} connectaddress={} connectport={}",
+                self.listen_address, self.listen_port, self.connect_address, self.connect_port
+            ),
+            format!(
+                "netsh advfirewall firewall add rule name=\"{}\" dir=in action=allow protocol=TCP localport={}",
+                self.firewall_rule_name(),
+                self.listen_port
+            ),
+        ]
+    }
+
+    fn remove_commands(&self) -> [String; 2] {
+        [
+            format!(
+                "netsh interface portproxy delete v4tov4 listenaddress={} listenport={}",
+                self.listen_address, self.listen_port
+            ),
+            format!(
+                "netsh advfirewall firewall delete rule name=\"{}\"",
+                self.firewall_rule_name()
+            ),
+        ]
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_port_proxies() -> Result<Vec<PortProxyRule>, String> {
+    if !cfg!(windows) {
+        return Err("Port forwarding is only needed on Windows".to_string());
+    }
+
+    let mut cmd = Command::new("netsh");
+    cmd.args(["interface", "portproxy", "show", "v4tov4"]);
+    #[cfg(windows)]
+    cmd.creation_flags(0x0800_0000);
+
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to list port forwarding rules: {}", e))?;
+
+    Ok(parse_rules(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Forwards the sidecar's port on every Windows interface to the server inside WSL and opens
+/// it in the firewall, so other devices on the LAN can reach it. Asks for elevation.
+#[tauri::command]
+#[specta::specta]
+pub async fn add_sidecar_port_proxy(app: AppHandle) -> Result<PortProxyRule, String> {
+    if !cfg!(windows) {
+        return Err("Port forwarding is only needed on Windows".to_string());
+    }
+
+    let spawn = app
+        .try_state::<ServerState>()
+        .and_then(|state| state.spawn())
+        .ok_or_else(|| "The local server isn't running".to_string())?;
+
+    if !project::uses_wsl(&app, spawn.cwd.as_deref()) {
+        return Err("The local server isn't running inside WSL".to_string());
+    }
+
+    if bind_hostname(&app, &spawn.hostname, spawn.cwd.as_deref()) != ALL_INTERFACES {
+        return Err(
+            "The server inside WSL only listens on localhost. Turn on LAN access in the WSL \
+             settings (wslLanAccess), then restart the server"
+                .to_string(),
+        );
+    }
+
+    let port = spawn.port;
+    let rule = PortProxyRule {
+        listen_address: ALL_INTERFACES.to_string(),
+        listen_port: port,
+        connect_address: wsl_address(&app).await?,
+        connect_port: port,
+    };
+
+    // Replace a rule left over from an earlier run, whose WSL address may be stale
+    let mut commands = rule.remove_commands().to_vec();
+    commands.extend(rule.add_commands());
+    run_elevated(&commands).await?;
+
+    tracing::info!(?rule, "Added port forwarding rule");
+    let mut created = CREATED.lock().unwrap();
+    created.retain(|existing| existing.listen_port != rule.listen_port);
+    created.push(rule.clone());
+
+    Ok(rule)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_port_proxy(listen_port: u32) -> Result<(), String> {
+    let rule = list_port_proxies()
+        .await?
+        .into_iter()
+        .find(|rule| rule.listen_port == listen_port)
+        .ok_or_else(|| format!("No port forwarding rule for port {}", listen_port))?;
+
+    run_elevated(&rule.remove_commands()).await?;
+
+    tracing::info!(?rule, "Removed port forwarding rule");
+    CREATED
+        .lock()
+        .unwrap()
+        .retain(|existing| existing.listen_port != listen_port);
+
+    Ok(())
+}
+
+/// Removes the rules added during this run, e.g. when the app exits.
+pub async fn cleanup() {
+    let rules = std::mem::take(&mut *CREATED.lock().unwrap());
+    if rules.is_empty() {
+        return;
+    }
+
+    let commands = rules
+        .iter()
+        .flat_map(PortProxyRule::remove_commands)
+        .collect::<Vec<_>>();
+    if let Err(e) = run_elevated(&commands).await {
+        tracing::warn!("Failed to remove port forwarding rules: {e}");
+    }
+}
+
+/// The address the sidecar is told to listen on. A sidecar inside WSL listens on every
+/// interface when LAN access is turned on, while the app keeps connecting through `hostname`.
+pub fn bind_hostname(app: &AppHandle, hostname: &str, cwd: Option<&Path>) -> String {
+    let lan_access = server::get_wsl_config(app.clone()).is_ok_and(|config| config.lan_access);
+    bind_address(hostname, project::uses_wsl(app, cwd), lan_access).to_string()
+}
+
+fn bind_address(hostname: &str, wsl: bool, lan_access: bool) -> &str {
+    if wsl && lan_access {
+        ALL_INTERFACES
+    } else {
+        hostname
+    }
+}
+
+async fn wsl_address(app: &AppHandle) -> Result<String, String> {
+    let mut cmd = Command::new("wsl");
+    cmd.args(wsl_setup::distro_args(app))
+        .args(["-e", "hostname", "-I"]);
+    #[cfg(windows)]
+    cmd.creation_flags(0x0800_0000);
+
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to get the WSL address: {}", e))?;
+
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .find(|address| address.parse::<std::net::Ipv4Addr>().is_ok())
+        .map(String::from)
+        .ok_or_else(|| "WSL has no IPv4 address".to_string())
+}
+
+// netsh needs admin rights, so every command goes through a single elevated cmd.exe to only
+// prompt once. Values are numbers and addresses the app produced itself.
+async fn run_elevated(commands: &[String]) -> Result<(), String> {
+    let script = commands.join(" & ").replace('\'', "''");
+    let mut cmd = Command::new("powershell");
+    cmd.args([
+        "-NoProfile",
+        "-Command",
+        &format!(
+            "$p = Start-Process -FilePath cmd.exe -ArgumentList '/c {script}' -Verb RunAs -WindowStyle Hidden -Wait -PassThru; exit $p.ExitCode"
+        ),
+    ]);
+    #[cfg(windows)]
+    cmd.creation_flags(0x0800_0000);
+
+    let status = cmd
+        .status()
+        .await
+        .map_err(|e| format!("Failed to run netsh: {}", e))?;
+
+    if !status.success() {
+        return Err("Changing port forwarding was cancelled or failed".to_string());
+    }
+
+    Ok(())
+}
+
+// The headers are localized, so rows are recognised by their shape: address, port, address, port
+fn parse_rules(output: &str) -> Vec<PortProxyRule> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let [listen_address, listen_port, connect_address, connect_port] = fields[..] else {
+                return None;
+            };
+
+            Some(PortProxyRule {
+                listen_address: listen_address.to_string(),
+                listen_port: listen_port.parse().ok()?,
+                connect_address: connect_address.to_string(),
+                connect_port: connect_port.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_portproxy_rules() {
+        let output = "\r\nListen on ipv4:             Connect to ipv4:\r\n\r\n\
+                      Address         Port        Address         Port\r\n\
+                      --------------- ----------  --------------- ----------\r\n\
+                      0.0.0.0         4096        172.20.1.2      4096\r\n\r\n";
+
+        assert_eq!(
+            parse_rules(output),
+            [PortProxyRule {
+                listen_address: "0.0.0.0".to_string(),
+                listen_port: 4096,
+                connect_address: "172.20.1.2".to_string(),
+                connect_port: 4096,
+            }]
+        );
+    }
+
+    #[test]
+    fn binds_wsl_sidecar_to_all_interfaces_for_lan_access() {
+        assert_eq!(bind_address("127.0.0.1", true, true), "0.0.0.0");
+        assert_eq!(bind_address("127.0.0.1", true, false), "127.0.0.1");
+        assert_eq!(bind_address("127.0.0.1", false, true), "127.0.0.1");
+    }
+
+    #[test]
+    fn removes_firewall_rule_with_proxy() {
+        let rule = PortProxyRule {
+            listen_address: "0.0.0.0".to_string(),
+            listen_port: 4096,
+            connect_address: "172.20.1.2".to_string(),
+            connect_port: 4096,
+        };
+
+        assert_eq!(
+            rule.remove_commands(),
+            [
+                "netsh interface portproxy delete v4tov4 listenaddress=0.0.0.0 listenport=4096",
+                "netsh advfirewall firewall delete rule name=\"opencode 4096\"",
+            ]
+        );
+    }
+}
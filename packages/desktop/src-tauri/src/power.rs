@@ -0,0 +1,216 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+const LOW_BATTERY_PERCENT: u8 = 20;
+// Probing spawns `pmset` or PowerShell, so results are reused for this long
+const CACHE_TTL: Duration = Duration::from_secs(30);
+
+static CACHE: Mutex<Option<(Instant, PowerState)>> = Mutex::new(None);
+
+#[derive(Clone, Copy, serde::Serialize, specta::Type, Debug, Default, PartialEq, Eq)]
+pub struct PowerState {
+    pub on_battery: bool,
+    pub battery_percent: Option<u8>,
+    pub power_saver: bool,
+}
+
+impl PowerState {
+    /// Whether background work should be throttled to save power.
+    pub fn is_low_power(&self) -> bool {
+        self.power_saver
+            || (self.on_battery
+                && self
+                    .battery_percent
+                    .is_some_and(|percent| percent <= LOW_BATTERY_PERCENT))
+    }
+
+    /// Stretches a polling interval while in low power mode.
+    pub fn interval(&self, normal: Duration) -> Duration {
+        if self.is_low_power() {
+            normal * 4
+        } else {
+            normal
+        }
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_power_state() -> PowerState {
+    power_state().await
+}
+
+/// The current power state, probed at most once per `CACHE_TTL`.
+pub async fn power_state() -> PowerState {
+    if let Some((probed_at, state)) = *CACHE.lock().unwrap()
+        && probed_at.elapsed() < CACHE_TTL
+    {
+        return state;
+    }
+
+    let state = probe().await;
+    *CACHE.lock().unwrap() = Some((Instant::now(), state));
+    state
+}
+
+/// The last probed power state, for callers that can't wait for a probe. Starts one in the
+/// background when it's stale, and assumes normal power until the first one finishes.
+pub fn cached_power_state() -> PowerState {
+    let cached = *CACHE.lock().unwrap();
+    match cached {
+        Some((probed_at, state)) if probed_at.elapsed() < CACHE_TTL => state,
+        stale => {
+            tauri::async_runtime::spawn(power_state());
+            stale.map(|(_, state)| state).unwrap_or_default()
+        }
+    }
+}
+
+async fn probe() -> PowerState {
+    #[cfg(target_os = "linux")]
+    {
+        tokio::task::spawn_blocking(linux_power_state)
+            .await
+            .unwrap_or_default()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        macos_power_state().await
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows_power_state().await
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn linux_power_state() -> PowerState {
+    let read = |path: std::path::PathBuf| {
+        std::fs::read_to_string(path)
+            .ok()
+            .map(|v| v.trim().to_string())
+    };
+
+    let battery = std::fs::read_dir("/sys/class/power_supply")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| read(path.join("type")).as_deref() == Some("Battery"));
+
+    let on_battery = battery
+        .as_ref()
+        .and_then(|path| read(path.join("status")))
+        .is_some_and(|status| status.eq_ignore_ascii_case("discharging"));
+    let battery_percent = battery
+        .as_ref()
+        .and_then(|path| read(path.join("capacity")))
+        .and_then(|v| v.parse().ok());
+
+    let power_saver = read("/sys/firmware/acpi/platform_profile".into())
+        .is_some_and(|profile| matches!(profile.as_str(), "low-power" | "quiet"));
+
+    PowerState {
+        on_battery,
+        battery_percent,
+        power_saver,
+    }
+}
+
+#[cfg(target_os = "macos")]
+async fn macos_power_state() -> PowerState {
+    async fn pmset(args: &[&str]) -> String {
+        tokio::process::Command::new("pmset")
+            .args(args)
+            .output()
+            .await
+            .ok()
+            .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+            .unwrap_or_default()
+    }
+
+    let (on_battery, battery_percent) = parse_pmset_batt(&pmset(&["-g", "batt"]).await);
+    let power_saver = pmset(&["-g"]).await.lines().any(|line| {
+        let mut parts = line.split_whitespace();
+        parts.next() == Some("lowpowermode") && parts.next() == Some("1")
+    });
+
+    PowerState {
+        on_battery,
+        battery_percent,
+        power_saver,
+    }
+}
+
+#[cfg(any(target_os = "macos", test))]
+fn parse_pmset_batt(output: &str) -> (bool, Option<u8>) {
+    let on_battery = output.contains("'Battery Power'");
+    let battery_percent = output
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .find_map(|token| token.strip_suffix('%')?.parse().ok());
+
+    (on_battery, battery_percent)
+}
+
+#[cfg(target_os = "windows")]
+async fn windows_power_state() -> PowerState {
+    // BatteryStatus 1 means the battery is discharging.
+    let output = tokio::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "Get-CimInstance Win32_Battery | ForEach-Object { \"$($_.BatteryStatus) $($_.EstimatedChargeRemaining)\" }",
+        ])
+        .creation_flags(0x0800_0000)
+        .output()
+        .await
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+        .unwrap_or_default();
+
+    let mut parts = output.split_whitespace();
+    let on_battery = parts.next() == Some("1");
+    let battery_percent = parts.next().and_then(|v| v.parse().ok());
+
+    PowerState {
+        on_battery,
+        battery_percent,
+        // Battery saver isn't exposed through WMI; low charge is still caught by `is_low_power`.
+        power_saver: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pmset_on_battery() {
+        let output = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1234)\t54%; discharging; 3:12 remaining present: true";
+        assert_eq!(parse_pmset_batt(output), (true, Some(54)));
+    }
+
+    #[test]
+    fn parses_pmset_on_ac() {
+        let output = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=1234)\t100%; charged; 0:00 remaining present: true";
+        assert_eq!(parse_pmset_batt(output), (false, Some(100)));
+    }
+
+    #[test]
+    fn low_battery_counts_as_low_power() {
+        let state = PowerState {
+            on_battery: true,
+            battery_percent: Some(15),
+            power_saver: false,
+        };
+        assert!(state.is_low_power());
+        assert_eq!(
+            state.interval(Duration::from_secs(1)),
+            Duration::from_secs(4)
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Printing a window's page, e.g. a conversation transcript, through the system's print dialog
+//! or straight to a PDF file, with the webview's own pagination rather than a screenshot.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use tauri::{WebviewWindow, webview::PlatformWebview};
+use tokio::sync::oneshot;
+
+use crate::validate;
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize, specta::Type, Debug)]
+#[serde(default)]
+pub struct PdfOptions {
+    /// Lays the pages out in landscape rather than portrait.
+    pub landscape: bool,
+}
+
+/// Opens the system's print dialog for the calling window.
+#[tauri::command]
+#[specta::specta]
+pub fn print_window(window: WebviewWindow) -> Result<(), String> {
+    window
+        .print()
+        .map_err(|e| format!("Failed to print: {}", e))
+}
+
+/// Saves the calling window's page to `path` as a PDF, without a dialog, replacing the file if it
+/// exists. Resolves once the file has been written.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_page_pdf(
+    window: WebviewWindow,
+    path: String,
+    options: PdfOptions,
+) -> Result<(), String> {
+    validate::path("path", &path)?;
+    let path = PathBuf::from(path);
+    if !path.is_absolute() {
+        return Err(format!("PDF path must be absolute, got {}", path.display()));
+    }
+
+    let (tx, rx) = oneshot::channel();
+    let done = Completion(Arc::new(Mutex::new(Some(tx))));
+    window
+        .with_webview(move |webview| {
+            // Safety: the webview is live and this runs on the main thread
+            if let Err(e) = unsafe { export(&webview, &path, &options, done.clone()) } {
+                done.finish(Err(e));
+            }
+        })
+        .map_err(|e| format!("Failed to reach the webview: {}", e))?;
+
+    rx.await
+        .map_err(|_| "The webview went away before exporting".to_string())?
+        .map_err(|e| format!("Failed to export PDF: {}", e))?;
+
+    tracing::info!(label = %window.label(), "Exported page to PDF");
+    Ok(())
+}
+
+/// Resolves an export once, from whichever of its completion handlers runs first.
+#[derive(Clone)]
+struct Completion(Arc<Mutex<Option<oneshot::Sender<Result<(), String>>>>>);
+
+impl Completion {
+    fn finish(&self, result: Result<(), String>) {
+        if let Some(tx) = self.0.lock().unwrap().take() {
+            let _ = tx.send(result);
+        }
+    }
+}
+
+// Told by AppKit when a print operation has run, with the operation's `Completion` as context.
+// The class itself is the delegate, so there's no instance to keep alive meanwhile.
+#[cfg(target_os = "macos")]
+objc2::define_class!(
+    #[unsafe(super(objc2::runtime::NSObject))]
+    #[name = "OpencodePrintDelegate"]
+    struct PrintDelegate;
+
+    impl PrintDelegate {
+        #[unsafe(method(printOperationDidRun:success:contextInfo:))]
+        fn did_run(
+            _operation: *mut objc2::runtime::AnyObject,
+            success: objc2::runtime::Bool,
+            context: *mut std::ffi::c_void,
+        ) {
+            // Safety: the context is the box leaked when the operation was started
+            let done = unsafe { Box::from_raw(context.cast::<Completion>()) };
+            done.finish(if success.as_bool() {
+                Ok(())
+            } else {
+                Err("The print operation didn't finish".to_string())
+            });
+        }
+    }
+);
+
+// AppKit's print operation of the WKWebView, saving to the file instead of printing
+#[cfg(target_os = "macos")]
+unsafe fn export(
+    webview: &PlatformWebview,
+    path: &Path,
+    options: &PdfOptions,
+    done: Completion,
+) -> Result<(), String> {
+    use objc2::{ClassType, class, msg_send, rc::Retained, runtime::AnyObject, sel};
+    use objc2_foundation::{NSRect, NSString, NSURL};
+
+    // `NSPrintingPaginationModeFit`, so wide content shrinks onto the page instead of being cut
+    const PAGINATION_FIT: usize = 1;
+
+    unsafe {
+        let wk_webview = (webview.inner() as *mut AnyObject)
+            .as_ref()
+            .ok_or("No webview")?;
+        let ns_window = webview.ns_window() as *mut AnyObject;
+
+        let shared: *mut AnyObject = msg_send![class!(NSPrintInfo), sharedPrintInfo];
+        let info: Retained<AnyObject> = msg_send![shared.as_ref().ok_or("No print info")?, copy];
+        let url = NSURL::fileURLWithPath(&NSString::from_str(&path.to_string_lossy()));
+        let settings: *mut AnyObject = msg_send![&*info, dictionary];
+        let _: () =
+            msg_send![settings, setObject: &*url, forKey: &*NSString::from_str("NSJobSavingURL")];
+        let _: () = msg_send![&*info, setJobDisposition: &*NSString::from_str("NSPrintSaveJob")];
+        let _: () = msg_send![&*info, setOrientation: isize::from(options.landscape)];
+        let _: () = msg_send![&*info, setHorizontalPagination: PAGINATION_FIT];
+
+        let operation: *mut AnyObject = msg_send![wk_webview, printOperationWithPrintInfo: &*info];
+        let operation = operation.as_ref().ok_or("The webview can't print")?;
+        let _: () = msg_send![operation, setShowsPrintPanel: false];
+        let _: () = msg_send![operation, setShowsProgressPanel: false];
+        // The operation's view starts out without a size, which prints blank pages
+        let view: *mut AnyObject = msg_send![operation, view];
+        let bounds: NSRect = msg_send![wk_webview, bounds];
+        let _: () = msg_send![view, setFrame: bounds];
+
+        let _: () = msg_send![
+            operation,
+            runOperationModalForWindow: ns_window,
+            delegate: PrintDelegate::class(),
+            didRunSelector: sel!(printOperationDidRun:success:contextInfo:),
+            contextInfo: Box::into_raw(Box::new(done)).cast::<std::ffi::c_void>()
+        ];
+    }
+    Ok(())
+}
+
+// WebView2's print-to-PDF, available since its runtime 1.0.1020
+#[cfg(windows)]
+unsafe fn export(
+    webview: &PlatformWebview,
+    path: &Path,
+    options: &PdfOptions,
+    done: Completion,
+) -> Result<(), String> {
+    use webview2_com::{Microsoft::Web::WebView2::Win32::*, PrintToPdfCompletedHandler};
+    use windows_core::{HSTRING, Interface};
+
+    let export = || -> windows_core::Result<()> {
+        unsafe {
+            let core = webview
+                .controller()
+                .CoreWebView2()?
+                .cast::<ICoreWebView2_7>()?;
+            let settings = webview
+                .environment()
+                .cast::<ICoreWebView2Environment6>()?
+                .CreatePrintSettings()?;
+            settings.SetOrientation(if options.landscape {
+                COREWEBVIEW2_PRINT_ORIENTATION_LANDSCAPE
+            } else {
+                COREWEBVIEW2_PRINT_ORIENTATION_PORTRAIT
+            })?;
+            settings.SetShouldPrintBackgrounds(true)?;
+
+            let handler = PrintToPdfCompletedHandler::create(Box::new(move |result, success| {
+                done.finish(match result {
+                    Ok(()) if success => Ok(()),
+                    Ok(()) => Err("WebView2 couldn't write the PDF".to_string()),
+                    Err(e) => Err(e.to_string()),
+                });
+                Ok(())
+            }));
+            core.PrintToPdf(&HSTRING::from(path), &settings, &handler)
+        }
+    };
+    export().map_err(|e| e.to_string())
+}
+
+// WebKitGTK's print operation, sent to GTK's "Print to File" printer
+#[cfg(target_os = "linux")]
+unsafe fn export(
+    webview: &PlatformWebview,
+    path: &Path,
+    options: &PdfOptions,
+    done: Completion,
+) -> Result<(), String> {
+    use gtk::{PageOrientation, PageSetup, PrintSettings};
+    use webkit2gtk::{PrintOperation, PrintOperationExt};
+
+    let uri = tauri::Url::from_file_path(path)
+        .map_err(|_| format!("Invalid PDF path {}", path.display()))?;
+
+    let settings = PrintSettings::new();
+    settings.set_printer("Print to File");
+    settings.set("output-file-format", Some("pdf"));
+    settings.set("output-uri", Some(uri.as_str()));
+    let page = PageSetup::new();
+    page.set_orientation(if options.landscape {
+        PageOrientation::Landscape
+    } else {
+        PageOrientation::Portrait
+    });
+
+    let operation = PrintOperation::new(&webview.inner());
+    operation.set_print_settings(&settings);
+    operation.set_page_setup(&page);
+    // `failed` is followed by `finished`, which then has nothing left to resolve
+    let failed = done.clone();
+    operation.connect_failed(move |_, e| failed.finish(Err(e.to_string())));
+    operation.connect_finished(move |_| done.finish(Ok(())));
+    operation.print();
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Instant,
+};
+
+use tauri::{AppHandle, Manager};
+
+use crate::{ServerState, cli::CommandChild};
+
+/// A CLI process spawned by the app that hasn't exited yet.
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+pub struct ChildInfo {
+    pub id: u32,
+    pub pid: Option<u32>,
+    /// Arguments the CLI was started with, e.g. `debug config`.
+    pub args: String,
+    /// Whether the process accepts `write_stdin`.
+    pub interactive: bool,
+    pub running_ms: u32,
+}
+
+struct Entry {
+    child: CommandChild,
+    args: String,
+    interactive: bool,
+    started: Instant,
+}
+
+/// Every CLI process the app spawns, whether it's the server, a config read or a command run
+/// from the UI, so none of them outlive the app.
+#[derive(Default)]
+pub struct ProcessRegistry {
+    next_id: AtomicU32,
+    children: Mutex<HashMap<u32, Entry>>,
+}
+
+impl ProcessRegistry {
+    pub fn next_id(&self) -> u32 {
+        self.next_id.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    pub fn insert(&self, id: u32, child: CommandChild, args: &str, interactive: bool) {
+        self.children.lock().unwrap().insert(
+            id,
+            Entry {
+                child,
+                args: args.to_string(),
+                interactive,
+                started: Instant::now(),
+            },
+        );
+    }
+
+    pub fn remove(&self, id: u32) {
+        self.children.lock().unwrap().remove(&id);
+    }
+
+    pub fn get(&self, id: u32) -> Option<CommandChild> {
+        self.children
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map(|entry| entry.child.clone())
+    }
+
+    pub fn list(&self) -> Vec<ChildInfo> {
+        let mut children = self
+            .children
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, entry)| ChildInfo {
+                id: *id,
+                pid: entry.child.pid(),
+                args: entry.args.clone(),
+                interactive: entry.interactive,
+                running_ms: entry.started.elapsed().as_millis() as u32,
+            })
+            .collect::<Vec<_>>();
+        children.sort_by_key(|child| child.id);
+        children
+    }
+
+    /// Kills every registered process, e.g. when the app exits.
+    pub fn kill_all(&self) {
+        let children = std::mem::take(&mut *self.children.lock().unwrap());
+        for (id, entry) in children {
+            tracing::info!(id, args = %entry.args, "Killing CLI process");
+            let _ = entry.child.kill();
+        }
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn list_children(app: AppHandle) -> Vec<ChildInfo> {
+    app.try_state::<ProcessRegistry>()
+        .map(|registry| registry.list())
+        .unwrap_or_default()
+}
+
+/// Kills a CLI process other than the server, which is stopped through `kill_sidecar` so the
+/// app knows it's gone.
+#[tauri::command]
+#[specta::specta]
+pub fn kill_child(app: AppHandle, id: u32) -> Result<(), String> {
+    let is_server = app.try_state::<ServerState>().is_some_and(|state| {
+        state
+            .child
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|child| child.id() == Some(id))
+    });
+    if is_server {
+        return Err("The server can't be killed as a CLI process, use kill_sidecar".to_string());
+    }
+
+    let child = app
+        .try_state::<ProcessRegistry>()
+        .and_then(|registry| registry.get(id))
+        .ok_or_else(|| format!("No running CLI process with id {}", id))?;
+
+    tracing::info!(id, "Killing CLI process");
+    child
+        .kill()
+        .map_err(|e| format!("Failed to kill CLI process: {}", e))
+}
@@ -0,0 +1,224 @@
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::{
+    ServerState, cli,
+    constants::{ACTIVE_PROJECT_KEY, BACKEND_RULES_KEY, SETTINGS_STORE},
+    server,
+};
+
+/// Where the CLI runs for a project on Windows.
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectBackend {
+    Native,
+    Wsl,
+}
+
+/// Runs projects under `prefix` natively or in WSL, regardless of the global WSL setting.
+#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type, Debug)]
+pub struct BackendRule {
+    /// A directory such as `C:\work` or `\\wsl$\Ubuntu\home`, matched case-insensitively.
+    pub prefix: String,
+    pub backend: ProjectBackend,
+}
+
+/// The last project the sidecar was started in, if it still exists.
+pub fn active_project(app: &AppHandle) -> Option<PathBuf> {
+    app.store(SETTINGS_STORE)
+        .ok()?
+        .get(ACTIVE_PROJECT_KEY)
+        .and_then(|v| v.as_str().map(PathBuf::from))
+        .filter(|path| path.is_dir())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_active_project(app: AppHandle) -> Option<String> {
+    active_project(&app).map(|path| path.to_string_lossy().to_string())
+}
+
+/// Remembers `path` as the active project and restarts the sidecar in it. Servers the app
+/// didn't spawn keep their own working directory.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_active_project(app: AppHandle, path: Option<String>) -> Result<(), String> {
+    let path = path.map(PathBuf::from);
+    if let Some(path) = &path
+        && !path.is_dir()
+    {
+        return Err(format!("Directory not found: {}", path.display()));
+    }
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    match &path {
+        Some(path) => store.set(
+            ACTIVE_PROJECT_KEY,
+            serde_json::Value::String(path.to_string_lossy().to_string()),
+        ),
+        None => {
+            store.delete(ACTIVE_PROJECT_KEY);
+        }
+    }
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    let Some(spawn) = app.state::<ServerState>().spawn() else {
+        tracing::info!(
+            ?path,
+            "Saved active project, server isn't managed by the app"
+        );
+        return Ok(());
+    };
+
+    tracing::info!(?path, "Switching sidecar to project");
+    server::restart_local_server(app, server::ServerSpawn { cwd: path, ..spawn }).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_backend_rules(app: AppHandle) -> Result<Vec<BackendRule>, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(BACKEND_RULES_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+/// The most specific rule wins. Takes effect the next time the sidecar starts.
+#[tauri::command]
+#[specta::specta]
+pub fn set_backend_rules(app: AppHandle, rules: Vec<BackendRule>) -> Result<(), String> {
+    if rules.iter().any(|rule| normalize(&rule.prefix).is_empty()) {
+        return Err("Backend rules need a directory".to_string());
+    }
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(BACKEND_RULES_KEY, serde_json::json!(rules));
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+/// Where the CLI runs for `path`, or for the active project when unset.
+#[tauri::command]
+#[specta::specta]
+pub fn get_effective_backend(app: AppHandle, path: Option<String>) -> ProjectBackend {
+    if !cfg!(windows) {
+        return ProjectBackend::Native;
+    }
+
+    effective_backend(&app, path.as_deref().map(Path::new))
+}
+
+/// Whether the CLI for `path`, or the active project when unset, runs inside WSL.
+pub fn uses_wsl(app: &AppHandle, path: Option<&Path>) -> bool {
+    cfg!(windows) && effective_backend(app, path) == ProjectBackend::Wsl
+}
+
+fn effective_backend(app: &AppHandle, path: Option<&Path>) -> ProjectBackend {
+    let default = if cli::is_wsl_enabled(app) {
+        ProjectBackend::Wsl
+    } else {
+        ProjectBackend::Native
+    };
+
+    let Some(path) = path.map(Path::to_path_buf).or_else(|| active_project(app)) else {
+        return default;
+    };
+
+    let rules = get_backend_rules(app.clone()).unwrap_or_default();
+    resolve(&rules, default, &path.to_string_lossy())
+}
+
+/// The backend of the longest rule prefix containing `path`, or `default` when none does.
+fn resolve(rules: &[BackendRule], default: ProjectBackend, path: &str) -> ProjectBackend {
+    let path = normalize(path);
+
+    rules
+        .iter()
+        .map(|rule| (normalize(&rule.prefix), rule.backend))
+        .filter(|(prefix, _)| {
+            path == *prefix
+                || path
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map_or(default, |(_, backend)| backend)
+}
+
+// Windows paths are case-insensitive and may use either separator
+fn normalize(path: &str) -> String {
+    path.trim()
+        .replace('\\', "/")
+        .trim_end_matches('/')
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(prefix: &str, backend: ProjectBackend) -> BackendRule {
+        BackendRule {
+            prefix: prefix.to_string(),
+            backend,
+        }
+    }
+
+    #[test]
+    fn longest_prefix_wins() {
+        let rules = [
+            rule(r"C:\code", ProjectBackend::Wsl),
+            rule(r"c:/code/windows-app/", ProjectBackend::Native),
+        ];
+
+        assert_eq!(
+            resolve(&rules, ProjectBackend::Native, r"C:\code\api"),
+            ProjectBackend::Wsl
+        );
+        assert_eq!(
+            resolve(&rules, ProjectBackend::Wsl, r"C:\Code\Windows-App\src"),
+            ProjectBackend::Native
+        );
+    }
+
+    #[test]
+    fn prefixes_match_whole_directories() {
+        let rules = [rule(r"C:\code", ProjectBackend::Wsl)];
+
+        assert_eq!(
+            resolve(&rules, ProjectBackend::Native, r"C:\code"),
+            ProjectBackend::Wsl
+        );
+        assert_eq!(
+            resolve(&rules, ProjectBackend::Native, r"C:\codebase"),
+            ProjectBackend::Native
+        );
+    }
+
+    #[test]
+    fn matches_wsl_share_paths() {
+        let rules = [rule(r"\\wsl$\Ubuntu", ProjectBackend::Wsl)];
+
+        assert_eq!(
+            resolve(&rules, ProjectBackend::Native, r"\\wsl$\ubuntu\home\me\app"),
+            ProjectBackend::Wsl
+        );
+    }
+}
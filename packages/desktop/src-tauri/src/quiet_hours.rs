@@ -0,0 +1,153 @@
+use chrono::{DateTime, FixedOffset, Local, NaiveTime, Utc};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::constants::{QUIET_HOURS_KEY, SETTINGS_STORE};
+
+#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type, Debug)]
+pub struct QuietHours {
+    pub enabled: bool,
+    /// Start of the quiet period as 24h `HH:MM`.
+    pub start: String,
+    /// End of the quiet period as 24h `HH:MM`. May be earlier than `start` to wrap midnight.
+    pub end: String,
+    /// Fixed offset from UTC in minutes. The system timezone is used when unset.
+    pub utc_offset_minutes: Option<i32>,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: "22:00".to_string(),
+            end: "07:00".to_string(),
+            utc_offset_minutes: None,
+        }
+    }
+}
+
+impl QuietHours {
+    fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let (Some(start), Some(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return false;
+        };
+
+        let time = match self.utc_offset_minutes {
+            Some(minutes) => match FixedOffset::east_opt(minutes * 60) {
+                Some(offset) => now.with_timezone(&offset).time(),
+                None => return false,
+            },
+            None => now.with_timezone(&Local).time(),
+        };
+
+        if start <= end {
+            start <= time && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+pub fn is_active(app: &AppHandle) -> bool {
+    get_quiet_hours(app.clone())
+        .map(|config| config.is_active_at(Utc::now()))
+        .unwrap_or(false)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_quiet_hours(app: AppHandle) -> Result<QuietHours, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(QUIET_HOURS_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_quiet_hours(app: AppHandle, config: QuietHours) -> Result<(), String> {
+    for value in [&config.start, &config.end] {
+        if parse_time(value).is_none() {
+            return Err(format!("Invalid time '{}', expected HH:MM", value));
+        }
+    }
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(QUIET_HOURS_KEY, serde_json::json!(config));
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn is_quiet_hours(app: AppHandle) -> bool {
+    is_active(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        format!("2026-01-15T{time}:00Z").parse().unwrap()
+    }
+
+    fn config(start: &str, end: &str, offset: i32) -> QuietHours {
+        QuietHours {
+            enabled: true,
+            start: start.into(),
+            end: end.into(),
+            utc_offset_minutes: Some(offset),
+        }
+    }
+
+    #[test]
+    fn same_day_window() {
+        let quiet = config("12:00", "14:00", 0);
+        assert!(quiet.is_active_at(utc("13:00")));
+        assert!(!quiet.is_active_at(utc("14:00")));
+        assert!(!quiet.is_active_at(utc("11:59")));
+    }
+
+    #[test]
+    fn window_wrapping_midnight() {
+        let quiet = config("22:00", "07:00", 0);
+        assert!(quiet.is_active_at(utc("23:30")));
+        assert!(quiet.is_active_at(utc("03:00")));
+        assert!(!quiet.is_active_at(utc("12:00")));
+    }
+
+    #[test]
+    fn applies_utc_offset() {
+        // 21:30 UTC is 23:30 at UTC+2
+        let quiet = config("22:00", "07:00", 120);
+        assert!(quiet.is_active_at(utc("21:30")));
+        assert!(!quiet.is_active_at(utc("19:30")));
+    }
+
+    #[test]
+    fn disabled_is_never_active() {
+        let quiet = QuietHours {
+            enabled: false,
+            ..config("00:00", "23:59", 0)
+        };
+        assert!(!quiet.is_active_at(utc("12:00")));
+    }
+}
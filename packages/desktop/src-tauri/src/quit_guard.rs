@@ -0,0 +1,146 @@
+//! Asks before quitting while an agent is working, since quitting stops the sidecar and the run
+//! with it. Each window tells the app whether it has work in progress.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use tauri::{AppHandle, Manager, WebviewWindow};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+// Set once the user chose to quit anyway, so the exit that follows goes through
+static CONFIRMED: AtomicBool = AtomicBool::new(false);
+
+/// What each window is busy with, by label.
+#[derive(Default)]
+pub struct BusyWindows(Mutex<HashMap<String, Option<String>>>);
+
+/// Marks the calling window as having work in progress, e.g. an agent run, with what it's doing
+/// to show when asking before quitting.
+#[tauri::command]
+#[specta::specta]
+pub fn set_busy_state(
+    app: AppHandle,
+    window: WebviewWindow,
+    busy: bool,
+    description: Option<String>,
+) {
+    let state = app.state::<BusyWindows>();
+    let mut windows = state.0.lock().unwrap();
+    if busy {
+        windows.insert(window.label().to_string(), description);
+    } else {
+        windows.remove(window.label());
+    }
+}
+
+/// Call on `ExitRequested`. Returns whether to prevent the exit, in which case the user is asked
+/// and the app exits with `code` if they agree.
+pub fn hold_exit(app: &AppHandle, code: Option<i32>) -> bool {
+    // Restarts come from updates and recovery, which have already asked
+    if code == Some(tauri::RESTART_EXIT_CODE) || CONFIRMED.load(Ordering::Relaxed) {
+        return false;
+    }
+    let Some(message) = message(app) else {
+        return false;
+    };
+
+    let handle = app.clone();
+    confirm(app, message, move || handle.exit(code.unwrap_or(0)));
+    true
+}
+
+/// Call on `CloseRequested`. Returns whether to keep window `label` open, in which case the user
+/// is asked and the window closes if they agree. Only closing the last window quits the app.
+pub fn hold_close(app: &AppHandle, label: &str) -> bool {
+    if CONFIRMED.load(Ordering::Relaxed) || app.webview_windows().keys().any(|other| other != label)
+    {
+        return false;
+    }
+    let Some(message) = message(app) else {
+        return false;
+    };
+
+    let window = app.get_webview_window(label);
+    confirm(app, message, move || {
+        if let Some(window) = window {
+            let _ = window.close();
+        }
+    });
+    true
+}
+
+fn confirm(app: &AppHandle, message: String, quit: impl FnOnce() + Send + 'static) {
+    app.dialog()
+        .message(message)
+        .title("Quit OpenCode?")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Quit".to_string(),
+            "Keep Running".to_string(),
+        ))
+        .show(move |confirmed| {
+            if confirmed {
+                CONFIRMED.store(true, Ordering::Relaxed);
+                quit();
+            }
+        });
+}
+
+// `None` when no open window is busy
+fn message(app: &AppHandle) -> Option<String> {
+    let state = app.state::<BusyWindows>();
+    let windows = state.0.lock().unwrap();
+    let open = app.webview_windows();
+    let descriptions = windows
+        .iter()
+        .filter(|(label, _)| open.contains_key(*label))
+        .map(|(_, description)| description.as_deref())
+        .collect::<Vec<_>>();
+
+    format_message(&descriptions)
+}
+
+fn format_message(descriptions: &[Option<&str>]) -> Option<String> {
+    if descriptions.is_empty() {
+        return None;
+    }
+
+    let mut message = if descriptions.len() == 1 {
+        "An agent run is in progress — quit anyway?".to_string()
+    } else {
+        format!(
+            "{} agent runs are in progress — quit anyway?",
+            descriptions.len()
+        )
+    };
+    for description in descriptions.iter().flatten() {
+        message.push_str(&format!("\n\n• {description}"));
+    }
+    Some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_what_windows_are_doing() {
+        assert_eq!(format_message(&[]), None);
+
+        let message = format_message(&[Some("Refactoring the parser"), None]).unwrap();
+        assert!(message.starts_with("2 agent runs are in progress"));
+        assert!(message.contains("• Refactoring the parser"));
+        assert_eq!(message.matches('•').count(), 1);
+
+        assert!(
+            format_message(&[None])
+                .unwrap()
+                .starts_with("An agent run is in progress — quit anyway?")
+        );
+    }
+}
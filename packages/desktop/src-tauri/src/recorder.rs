@@ -0,0 +1,184 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use tauri::{AppHandle, Emitter, Listener};
+use tauri_specta::Event;
+use tokio::sync::watch;
+
+use crate::{
+    InitStep, cli, fs_boundary, idle, init_progress, menu, migration_recovery, second_instance,
+    server, shell, usage, watchdog,
+};
+
+/// Path to record shell events to, as JSON lines. Recording is off unless set.
+const RECORD_ENV: &str = "OPENCODE_RECORD_EVENTS";
+/// Path of a previous recording to re-emit to the frontend. Debug builds only.
+const REPLAY_ENV: &str = "OPENCODE_REPLAY_EVENTS";
+
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ShellEventKind {
+    InitStep {
+        step: InitStep,
+    },
+    Health {
+        url: String,
+        healthy: bool,
+    },
+    Command {
+        args: String,
+    },
+    CommandTerminated {
+        code: Option<i32>,
+        signal: Option<i32>,
+    },
+    /// An event sent to the frontend, under its own name and with its JSON payload.
+    Emitted {
+        name: String,
+        payload: serde_json::Value,
+    },
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
+pub struct ShellEvent {
+    elapsed_ms: u32,
+    event: ShellEventKind,
+}
+
+struct Recorder {
+    start: Instant,
+    file: Mutex<File>,
+}
+
+static RECORDER: OnceLock<Recorder> = OnceLock::new();
+
+// The events the shell sends to the frontend, which a replay sends again
+const FRONTEND_EVENTS: &[&str] = &[
+    cli::sqlite_migration::SqliteMigrationProgress::NAME,
+    server::ServerEndpointChanged::NAME,
+    watchdog::SidecarMemoryLimitHit::NAME,
+    shell::SlowLoginShell::NAME,
+    cli::CliSyncProgress::NAME,
+    cli::CliManagedExternally::NAME,
+    usage::UsageDigestReady::NAME,
+    idle::SidecarIdleChanged::NAME,
+    fs_boundary::FilesystemBoundaryWarning::NAME,
+    menu::MenuCommand::NAME,
+    migration_recovery::MigrationFailed::NAME,
+    init_progress::InitProgress::NAME,
+    second_instance::SecondInstance::NAME,
+];
+
+pub fn init(app: &AppHandle) {
+    let Some(path) = std::env::var_os(RECORD_ENV)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+    else {
+        return;
+    };
+
+    match File::create(&path) {
+        Ok(file) => {
+            tracing::info!(path = %path.display(), "Recording shell events");
+            let _ = RECORDER.set(Recorder {
+                start: Instant::now(),
+                file: Mutex::new(file),
+            });
+            for name in FRONTEND_EVENTS {
+                app.listen_any(*name, move |event| {
+                    record(ShellEventKind::Emitted {
+                        name: name.to_string(),
+                        payload: serde_json::from_str(event.payload()).unwrap_or_default(),
+                    });
+                });
+            }
+        }
+        Err(e) => tracing::warn!("Failed to create shell event recording: {e}"),
+    }
+}
+
+pub fn record(event: ShellEventKind) {
+    let Some(recorder) = RECORDER.get() else {
+        return;
+    };
+
+    let entry = ShellEvent {
+        elapsed_ms: recorder.start.elapsed().as_millis() as u32,
+        event,
+    };
+    let Ok(line) = serde_json::to_string(&entry) else {
+        return;
+    };
+
+    let mut file = recorder.file.lock().unwrap();
+    let _ = writeln!(file, "{line}");
+}
+
+pub fn watch_init_steps(mut rx: watch::Receiver<InitStep>) {
+    if RECORDER.get().is_none() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        loop {
+            let step = *rx.borrow_and_update();
+            record(ShellEventKind::InitStep { step });
+
+            if matches!(step, InitStep::Done) || rx.changed().await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+// Re-emits the frontend's events from a recording, under their original names and with their
+// original timing, so race conditions from bug reports can be reproduced against a dev frontend.
+// Window-scoped events go to every window.
+pub fn spawn_replay(app: &AppHandle) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    let Some(path) = std::env::var_os(REPLAY_ENV).filter(|v| !v.is_empty()) else {
+        return;
+    };
+
+    let app = app.clone();
+    tokio::spawn(async move {
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) => {
+                tracing::warn!("Failed to open shell event recording: {e}");
+                return;
+            }
+        };
+
+        tracing::info!("Replaying shell events");
+        let start = Instant::now();
+
+        let events = BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str::<ShellEvent>(&line).ok())
+            .collect::<Vec<_>>();
+
+        for event in events {
+            let ShellEventKind::Emitted { name, payload } = event.event else {
+                continue;
+            };
+            let at = Duration::from_millis(event.elapsed_ms.into());
+            tokio::time::sleep(at.saturating_sub(start.elapsed())).await;
+
+            if let Err(e) = app.emit(&name, payload) {
+                tracing::warn!(%name, "Failed to replay shell event: {e}");
+            }
+        }
+
+        tracing::info!("Shell event replay finished");
+    });
+}
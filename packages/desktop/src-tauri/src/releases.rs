@@ -0,0 +1,141 @@
+use std::time::Duration;
+
+use semver::Version;
+
+const RELEASES_URL: &str = "https://api.github.com/repos/anomalyco/opencode/releases?per_page=50";
+
+#[derive(
+    Clone, Copy, serde::Serialize, serde::Deserialize, specta::Type, Debug, Default, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CliChannel {
+    #[default]
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl CliChannel {
+    pub const ALL: [Self; 3] = [Self::Stable, Self::Beta, Self::Nightly];
+
+    // Each channel also accepts everything from the channels that are more stable than it,
+    // so beta users get a stable release when it's newer than the last beta.
+    fn accepts(self, version: &Version) -> bool {
+        let pre = version.pre.as_str();
+        match self {
+            Self::Stable => pre.is_empty(),
+            Self::Beta => pre.is_empty() || pre.starts_with("beta"),
+            Self::Nightly => true,
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct Release {
+    tag_name: String,
+    #[serde(default)]
+    draft: bool,
+}
+
+pub async fn fetch_releases() -> Result<Vec<Release>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .user_agent(concat!("opencode-desktop/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client
+        .get(RELEASES_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to fetch CLI releases: {}", e))?;
+
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read CLI releases: {}", e))?;
+
+    serde_json::from_str(&body).map_err(|e| format!("Failed to parse CLI releases: {}", e))
+}
+
+/// Newest version in `releases` that belongs to `channel`.
+pub fn latest_for_channel(releases: &[Release], channel: CliChannel) -> Option<Version> {
+    releases
+        .iter()
+        .filter(|release| !release.draft)
+        .filter_map(|release| Version::parse(release.tag_name.trim_start_matches('v')).ok())
+        .filter(|version| channel.accepts(version))
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str) -> Release {
+        Release {
+            tag_name: tag.to_string(),
+            draft: false,
+        }
+    }
+
+    fn releases() -> Vec<Release> {
+        vec![
+            release("v1.2.0"),
+            release("v1.3.0-beta.2"),
+            release("v1.3.0-nightly.20260101"),
+            release("v1.1.9"),
+            release("not-a-version"),
+        ]
+    }
+
+    #[test]
+    fn stable_ignores_prereleases() {
+        assert_eq!(
+            latest_for_channel(&releases(), CliChannel::Stable),
+            Some(Version::parse("1.2.0").unwrap())
+        );
+    }
+
+    #[test]
+    fn beta_ignores_nightlies() {
+        assert_eq!(
+            latest_for_channel(&releases(), CliChannel::Beta),
+            Some(Version::parse("1.3.0-beta.2").unwrap())
+        );
+    }
+
+    #[test]
+    fn nightly_takes_newest() {
+        assert_eq!(
+            latest_for_channel(&releases(), CliChannel::Nightly),
+            Some(Version::parse("1.3.0-nightly.20260101").unwrap())
+        );
+    }
+
+    #[test]
+    fn beta_prefers_newer_stable() {
+        let releases = vec![release("v1.3.0-beta.1"), release("v1.3.0")];
+        assert_eq!(
+            latest_for_channel(&releases, CliChannel::Beta),
+            Some(Version::parse("1.3.0").unwrap())
+        );
+    }
+
+    #[test]
+    fn skips_drafts() {
+        let releases = vec![
+            release("v1.0.0"),
+            Release {
+                tag_name: "v2.0.0".to_string(),
+                draft: true,
+            },
+        ];
+        assert_eq!(
+            latest_for_channel(&releases, CliChannel::Stable),
+            Some(Version::parse("1.0.0").unwrap())
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! Launches of the app while it's already running, e.g. from a deep link or the command line.
+//! The new process exits right away and its arguments are handed to this one, which passes them
+//! on to the window they're about instead of dropping them.
+
+use std::path::Path;
+
+use tauri::{AppHandle, Manager};
+use tauri_specta::Event;
+
+use crate::windows::{self, MainWindow};
+
+const DEEP_LINK_SCHEME: &str = "opencode";
+
+/// Sent to the window a second launch is for, with what it was launched with. Deep links opening
+/// a project go to that project's window when it has one of its own, everything else to the main
+/// window.
+#[derive(tauri_specta::Event, serde::Serialize, serde::Deserialize, Clone, Debug, specta::Type)]
+pub struct SecondInstance {
+    /// Arguments without the program name.
+    pub args: Vec<String>,
+    /// Working directory of the second launch, for relative paths in `args`.
+    pub cwd: String,
+}
+
+/// Focuses the window the launch is for and forwards `args` and `cwd` to it.
+pub fn handle(app: &AppHandle, args: Vec<String>, cwd: String) {
+    let args: Vec<String> = args.into_iter().skip(1).collect();
+
+    let window = project_directory(&args, &cwd)
+        .and_then(|directory| app.get_webview_window(&windows::project_window_label(&directory)))
+        .or_else(|| app.get_webview_window(MainWindow::LABEL));
+    let Some(window) = window else {
+        return;
+    };
+
+    let _ = window.show();
+    let _ = window.set_focus();
+    let _ = window.unminimize();
+
+    tracing::info!(label = %window.label(), ?args, "Forwarding second launch");
+    let _ = SecondInstance { args, cwd }.emit_to(app, window.label());
+}
+
+/// The project the launch asks for, from an `opencode://open-project` deep link or a directory
+/// given as a path, relative to `cwd` or not.
+fn project_directory(args: &[String], cwd: &str) -> Option<String> {
+    args.iter().find_map(|arg| {
+        match tauri::Url::parse(arg) {
+            Ok(url) if url.scheme() == DEEP_LINK_SCHEME => {
+                if url.host_str() != Some("open-project") {
+                    return None;
+                }
+                return url
+                    .query_pairs()
+                    .find(|(key, _)| key == "directory")
+                    .map(|(_, directory)| directory.into_owned());
+            }
+            _ => {}
+        }
+
+        if arg.starts_with('-') {
+            return None;
+        }
+        let path = Path::new(cwd).join(arg);
+        path.is_dir().then(|| {
+            let path = path.canonicalize().unwrap_or(path);
+            path.to_string_lossy().into_owned()
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn finds_the_project_of_a_deep_link() {
+        assert_eq!(
+            project_directory(
+                &args(&[
+                    "--kiosk",
+                    "opencode://open-project?directory=%2FUsers%2Fme%2Fmy+app&session=ses_123"
+                ]),
+                "/"
+            )
+            .as_deref(),
+            Some("/Users/me/my app")
+        );
+        assert_eq!(
+            project_directory(&args(&["opencode://open-session?id=ses_123"]), "/"),
+            None
+        );
+    }
+
+    #[test]
+    fn resolves_directories_against_the_working_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("app")).unwrap();
+        let cwd = dir.path().to_string_lossy().to_string();
+
+        assert_eq!(
+            project_directory(&args(&["app"]), &cwd),
+            Some(
+                dir.path()
+                    .join("app")
+                    .canonicalize()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string()
+            )
+        );
+        assert_eq!(project_directory(&args(&["missing", "-v"]), &cwd), None);
+    }
+}
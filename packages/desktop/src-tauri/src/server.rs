@@ -1,19 +1,49 @@
-use std::time::{Duration, Instant};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
-use tauri::AppHandle;
+use reqwest::StatusCode;
+use tauri::{AppHandle, Manager};
 use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogResult};
 use tauri_plugin_store::StoreExt;
-use tokio::task::JoinHandle;
+use tauri_specta::Event;
+use tokio::{
+    task::JoinHandle,
+    time::{sleep, timeout},
+};
 
 use crate::{
-    cli,
+    ServerReadyData, ServerState, cli,
     cli::CommandChild,
-    constants::{DEFAULT_SERVER_URL_KEY, SETTINGS_STORE, WSL_ENABLED_KEY},
+    constants::{
+        DEFAULT_SERVER_URL_KEY, SERVER_FALLBACK_URLS_KEY, SETTINGS_STORE, WSL_ENABLED_KEY,
+        WSL_LAN_ACCESS_KEY,
+    },
+    credentials, idle, port_proxy, power,
+    recorder::{self, ShellEventKind},
+    validate, watchdog, wol,
 };
 
+const ENDPOINT_MONITOR_INTERVAL: Duration = Duration::from_secs(10);
+const RESTART_DELAY: Duration = Duration::from_secs(1);
+const RESTART_HEALTH_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type, Debug, Default)]
 pub struct WslConfig {
     pub enabled: bool,
+    /// Has the sidecar inside WSL listen on every interface, so `add_sidecar_port_proxy` can
+    /// forward LAN traffic to it. Takes effect when the server next starts.
+    #[serde(default)]
+    pub lan_access: bool,
+}
+
+#[derive(tauri_specta::Event, serde::Serialize, serde::Deserialize, Clone, Debug, specta::Type)]
+pub struct ServerEndpointChanged {
+    /// The endpoint that stopped responding.
+    pub previous: String,
+    pub url: String,
+    pub password: Option<String>,
 }
 
 #[tauri::command]
@@ -33,6 +63,10 @@ pub fn get_default_server_url(app: AppHandle) -> Result<Option<String>, String>
 #[tauri::command]
 #[specta::specta]
 pub async fn set_default_server_url(app: AppHandle, url: Option<String>) -> Result<(), String> {
+    if let Some(url) = &url {
+        validate::url("server URL", url)?;
+    }
+
     let store = app
         .store(SETTINGS_STORE)
         .map_err(|e| format!("Failed to open settings store: {}", e))?;
@@ -66,7 +100,16 @@ pub fn get_wsl_config(app: AppHandle) -> Result<WslConfig, String> {
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
 
-    Ok(WslConfig { enabled })
+    let lan_access = store
+        .get(WSL_LAN_ACCESS_KEY)
+        .as_ref()
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    Ok(WslConfig {
+        enabled,
+        lan_access,
+    })
 }
 
 #[tauri::command]
@@ -77,6 +120,10 @@ pub fn set_wsl_config(app: AppHandle, config: WslConfig) -> Result<(), String> {
         .map_err(|e| format!("Failed to open settings store: {}", e))?;
 
     store.set(WSL_ENABLED_KEY, serde_json::Value::Bool(config.enabled));
+    store.set(
+        WSL_LAN_ACCESS_KEY,
+        serde_json::Value::Bool(config.lan_access),
+    );
 
     store
         .save()
@@ -85,6 +132,72 @@ pub fn set_wsl_config(app: AppHandle, config: WslConfig) -> Result<(), String> {
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn get_server_fallback_urls(app: AppHandle) -> Result<Vec<String>, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(SERVER_FALLBACK_URLS_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_server_fallback_urls(app: AppHandle, urls: Vec<String>) -> Result<(), String> {
+    validate::urls("fallback server URL", &urls)?;
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(SERVER_FALLBACK_URLS_KEY, serde_json::json!(urls));
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    Ok(())
+}
+
+/// The configured server URL followed by its fallbacks, in the order they should be tried.
+fn server_candidates(app: &AppHandle, url: &str) -> Vec<String> {
+    let fallbacks = get_server_fallback_urls(app.clone()).unwrap_or_default();
+
+    std::iter::once(url.to_string())
+        .chain(fallbacks.into_iter().filter(|v| v != url))
+        .collect()
+}
+
+async fn first_healthy(candidates: &[String]) -> Option<ServerReadyData> {
+    for url in candidates {
+        if let Some(password) = endpoint_password(url).await {
+            return Some(ServerReadyData {
+                url: url.clone(),
+                password,
+            });
+        }
+    }
+
+    None
+}
+
+// The password a healthy endpoint takes, `Some(None)` when it needs none. Only passwords already
+// in the keychain are tried, so this never prompts.
+async fn endpoint_password(url: &str) -> Option<Option<String>> {
+    if check_health(url, None).await {
+        return Some(None);
+    }
+
+    let password = credentials::stored(url)?;
+    check_health(url, Some(&password))
+        .await
+        .then_some(Some(password))
+}
+
 pub async fn get_saved_server_url(app: &tauri::AppHandle) -> Option<String> {
     if let Some(url) = get_default_server_url(app.clone()).ok().flatten() {
         tracing::info!(%url, "Using desktop-specific custom URL");
@@ -101,21 +214,48 @@ pub async fn get_saved_server_url(app: &tauri::AppHandle) -> Option<String> {
     None
 }
 
+/// What's needed to start an identical sidecar, so the frontend can keep using the same
+/// URL and credentials after a restart.
+#[derive(Clone, Debug)]
+pub struct ServerSpawn {
+    pub hostname: String,
+    pub port: u32,
+    pub password: String,
+    pub cwd: Option<PathBuf>,
+}
+
 pub fn spawn_local_server(
     app: AppHandle,
     hostname: String,
     port: u32,
     password: String,
-) -> (CommandChild, HealthCheck) {
-    let (child, exit) = cli::serve(&app, &hostname, port, &password);
+    cwd: Option<PathBuf>,
+) -> Result<(CommandChild, HealthCheck), String> {
+    if let Some(state) = app.try_state::<ServerState>() {
+        state.set_failure(None);
+    }
+
+    // The app keeps connecting through `hostname`, which WSL forwards to the distro
+    let bind = port_proxy::bind_hostname(&app, &hostname, cwd.as_deref());
+    let (child, exit) =
+        cli::serve(&app, &bind, port, &password, cwd.as_deref()).map_err(|failure| {
+            let message = failure.message();
+            if let Some(state) = app.try_state::<ServerState>() {
+                state.set_failure(Some(failure));
+            }
+            message
+        })?;
 
     let health_check = HealthCheck(tokio::spawn(async move {
         let url = format!("http://{hostname}:{port}");
         let timestamp = Instant::now();
+        let interval = power::power_state()
+            .await
+            .interval(Duration::from_millis(100));
 
         let ready = async {
             loop {
-                tokio::time::sleep(Duration::from_millis(100)).await;
+                tokio::time::sleep(interval).await;
 
                 if check_health(&url, Some(&password)).await {
                     tracing::info!(elapsed = ?timestamp.elapsed(), "Server ready");
@@ -126,10 +266,22 @@ pub fn spawn_local_server(
 
         let terminated = async {
             match exit.await {
-                Ok(payload) => Err(format!(
-                    "Sidecar terminated before becoming healthy (code={:?} signal={:?})",
-                    payload.code, payload.signal
-                )),
+                Ok(exit) => {
+                    let failure = cli::SidecarFailure::classify(&exit);
+                    tracing::warn!(?failure, "Sidecar terminated before becoming healthy");
+
+                    let message = format!(
+                        "Sidecar terminated before becoming healthy: {} (code={:?} signal={:?})",
+                        failure.message(),
+                        exit.payload.code,
+                        exit.payload.signal
+                    );
+                    if let Some(state) = app.try_state::<ServerState>() {
+                        state.set_failure(Some(failure));
+                    }
+
+                    Err(message)
+                }
                 Err(_) => Err("Sidecar terminated before becoming healthy".to_string()),
             }
         };
@@ -140,15 +292,76 @@ pub fn spawn_local_server(
         }
     }));
 
-    (child, health_check)
+    Ok((child, health_check))
 }
 
 pub struct HealthCheck(pub JoinHandle<Result<(), String>>);
 
-pub async fn check_health(url: &str, password: Option<&str>) -> bool {
-    let Ok(url) = reqwest::Url::parse(url) else {
-        return false;
+/// Replaces the running sidecar with a fresh one spawned from `spawn`, then watches it like
+/// the original.
+pub async fn restart_local_server(app: AppHandle, spawn: ServerSpawn) -> Result<(), String> {
+    let state = app.state::<ServerState>();
+    if let Some(child) = state
+        .child
+        .lock()
+        .expect("Failed to acquire mutex lock")
+        .take()
+    {
+        let _ = child.kill();
+    }
+
+    // Give the old process a moment to release its port
+    sleep(RESTART_DELAY).await;
+
+    tracing::info!(port = spawn.port, cwd = ?spawn.cwd, "Restarting sidecar");
+    let (child, health_check) = spawn_local_server(
+        app.clone(),
+        spawn.hostname.clone(),
+        spawn.port,
+        spawn.password.clone(),
+        spawn.cwd.clone(),
+    )?;
+
+    let err = match timeout(RESTART_HEALTH_TIMEOUT, health_check.0).await {
+        Ok(Ok(Ok(()))) => None,
+        Ok(Ok(Err(e))) => Some(e),
+        Ok(Err(e)) => Some(format!("Health check task failed: {e}")),
+        Err(_) => Some("Health check timed out".to_string()),
     };
+    if let Some(err) = err {
+        let _ = child.kill();
+        return Err(err);
+    }
+
+    tracing::info!("Sidecar restarted");
+    state.set_child(Some(child.clone()));
+    state.set_spawn(Some(spawn.clone()));
+    idle::forget(&app);
+    watchdog::spawn(app.clone(), child, spawn);
+
+    Ok(())
+}
+
+/// Whether the server at `url` is up but turns away requests without a password.
+pub async fn requires_password(url: &str) -> bool {
+    health_status(url, None).await == Some(StatusCode::UNAUTHORIZED)
+}
+
+pub async fn check_health(url: &str, password: Option<&str>) -> bool {
+    let healthy = health_status(url, password)
+        .await
+        .is_some_and(|status| status.is_success());
+
+    recorder::record(ShellEventKind::Health {
+        url: url.to_string(),
+        healthy,
+    });
+
+    healthy
+}
+
+async fn health_status(url: &str, password: Option<&str>) -> Option<StatusCode> {
+    let url = reqwest::Url::parse(url).ok()?;
 
     let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(3));
 
@@ -159,12 +372,8 @@ pub async fn check_health(url: &str, password: Option<&str>) -> bool {
         builder = builder.no_proxy();
     };
 
-    let Ok(client) = builder.build() else {
-        return false;
-    };
-    let Ok(health_url) = url.join("/global/health") else {
-        return false;
-    };
+    let client = builder.build().ok()?;
+    let health_url = url.join("/global/health").ok()?;
 
     let mut req = client.get(health_url);
 
@@ -172,13 +381,10 @@ pub async fn check_health(url: &str, password: Option<&str>) -> bool {
         req = req.basic_auth("opencode", Some(password));
     }
 
-    req.send()
-        .await
-        .map(|r| r.status().is_success())
-        .unwrap_or(false)
+    req.send().await.ok().map(|r| r.status())
 }
 
-fn url_is_localhost(url: &reqwest::Url) -> bool {
+pub(crate) fn url_is_localhost(url: &reqwest::Url) -> bool {
     url.host_str().is_some_and(|host| {
         host.eq_ignore_ascii_case("localhost")
             || host
@@ -220,11 +426,33 @@ fn get_server_url_from_config(config: &cli::Config) -> Option<String> {
     Some(format!("http://{}:{}", hostname, port))
 }
 
-pub async fn check_health_or_ask_retry(app: &AppHandle, url: &str) -> bool {
+/// Returns the first healthy endpoint out of `url` and its fallbacks, asking the user
+/// whether to retry when none of them respond. Asks for the password of `url` when it
+/// requires one that isn't stored yet.
+pub async fn check_health_or_ask_retry(app: &AppHandle, url: &str) -> Option<ServerReadyData> {
     tracing::debug!(%url, "Checking health");
+    let candidates = server_candidates(app, url);
+    let mut offered_wake = false;
+
     loop {
-        if check_health(url, None).await {
-            return true;
+        if let Some(active) = first_healthy(&candidates).await {
+            return Some(active);
+        }
+
+        if requires_password(url).await
+            && let Some(password) = credentials::password_for(url).await
+        {
+            return Some(ServerReadyData {
+                url: url.to_string(),
+                password: Some(password),
+            });
+        }
+
+        if !offered_wake {
+            offered_wake = true;
+            if wol::offer_wake(app, url).await {
+                continue;
+            }
         }
 
         const RETRY: &str = "Retry";
@@ -245,5 +473,55 @@ pub async fn check_health_or_ask_retry(app: &AppHandle, url: &str) -> bool {
         }
     }
 
-    false
+    None
+}
+
+/// Watches the active endpoint of a configured server and fails over to the next
+/// healthy candidate when it stops responding, pointing the app and its windows at it.
+pub fn spawn_endpoint_monitor(app: AppHandle, url: String, active: ServerReadyData) {
+    let candidates = server_candidates(&app, &url);
+    if candidates.len() < 2 {
+        return;
+    }
+
+    let monitor_app = app.clone();
+    let monitor = tokio::spawn(async move {
+        let mut active = active;
+
+        loop {
+            tokio::time::sleep(
+                power::power_state()
+                    .await
+                    .interval(ENDPOINT_MONITOR_INTERVAL),
+            )
+            .await;
+
+            if check_health(&active.url, active.password.as_deref()).await {
+                continue;
+            }
+
+            tracing::warn!(url = %active.url, "Active server endpoint unhealthy, trying fallbacks");
+
+            let Some(next) = first_healthy(&candidates).await else {
+                continue;
+            };
+
+            if next.url != active.url {
+                tracing::info!(from = %active.url, to = %next.url, "Failed over to server endpoint");
+                if let Some(state) = monitor_app.try_state::<ServerState>() {
+                    state.set_ready(next.clone());
+                }
+                let _ = ServerEndpointChanged {
+                    previous: std::mem::replace(&mut active, next).url,
+                    url: active.url.clone(),
+                    password: active.password.clone(),
+                }
+                .emit(&monitor_app);
+            }
+        }
+    });
+
+    if let Some(state) = app.try_state::<ServerState>() {
+        state.set_endpoint_monitor(Some(monitor));
+    }
 }
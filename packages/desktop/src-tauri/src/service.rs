@@ -0,0 +1,630 @@
+use std::path::Path;
+
+use tauri::{AppHandle, Manager, path::BaseDirectory};
+use tauri_plugin_store::StoreExt;
+
+use crate::{
+    cli::get_cli_install_path,
+    constants::{SERVER_SERVICE_KEY, SETTINGS_STORE},
+    dev_profile, server,
+};
+
+const SERVICE_HOSTNAME: &str = "127.0.0.1";
+#[cfg(target_os = "linux")]
+const SYSTEMD_UNIT: &str = "opencode-server.service";
+#[cfg(any(target_os = "macos", test))]
+const LAUNCHD_LABEL: &str = "ai.opencode.server";
+#[cfg(windows)]
+const SCHEDULED_TASK: &str = "OpenCode Server";
+
+/// Connection details for the always-on server, saved so the app can adopt it at launch.
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
+pub struct ServerService {
+    pub port: u32,
+    pub password: String,
+}
+
+impl ServerService {
+    pub fn url(&self) -> String {
+        format!("http://{SERVICE_HOSTNAME}:{}", self.port)
+    }
+}
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+pub struct ServerServiceStatus {
+    pub installed: bool,
+    pub running: bool,
+    pub url: Option<String>,
+}
+
+// A dev build's settings may have been carried over from a release install, whose service
+// shares the release's data
+pub fn get(app: &AppHandle) -> Option<ServerService> {
+    if dev_profile::isolated() {
+        return None;
+    }
+
+    app.store(SETTINGS_STORE)
+        .ok()?
+        .get(SERVER_SERVICE_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_server_service_status(app: AppHandle) -> ServerServiceStatus {
+    let Some(service) = get(&app) else {
+        return ServerServiceStatus {
+            installed: false,
+            running: false,
+            url: None,
+        };
+    };
+
+    let url = service.url();
+    ServerServiceStatus {
+        installed: true,
+        running: server::check_health(&url, Some(&service.password)).await,
+        url: Some(url),
+    }
+}
+
+/// Installs a per-user service that keeps `opencode serve` running in the background, which
+/// the app then connects to instead of spawning its own sidecar.
+#[tauri::command]
+#[specta::specta]
+pub async fn install_server_service(app: AppHandle) -> Result<ServerServiceStatus, String> {
+    // systemctl, launchctl and schtasks block until they're done
+    tauri::async_runtime::spawn_blocking(move || install(&app))
+        .await
+        .map_err(|e| format!("Server service install task failed: {}", e))?
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn uninstall_server_service(app: AppHandle) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || uninstall(&app))
+        .await
+        .map_err(|e| format!("Server service uninstall task failed: {}", e))?
+}
+
+fn install(app: &AppHandle) -> Result<ServerServiceStatus, String> {
+    if dev_profile::isolated() {
+        return Err(
+            "Dev builds can't install the server service, it would replace the release's"
+                .to_string(),
+        );
+    }
+
+    let cli = get_cli_install_path(app)
+        .filter(|path| path.exists())
+        .ok_or_else(|| {
+            "Install the opencode CLI before installing the server service".to_string()
+        })?;
+
+    // Reinstalling keeps the existing port & password so connected clients keep working
+    let service = get(app).unwrap_or_else(|| ServerService {
+        port: crate::get_sidecar_port(),
+        password: uuid::Uuid::new_v4().to_string(),
+    });
+
+    let state_dir = app
+        .path()
+        .resolve("", BaseDirectory::AppLocalData)
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    std::fs::create_dir_all(&state_dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+
+    #[cfg(target_os = "linux")]
+    install_systemd(&cli, &service, &state_dir)?;
+
+    #[cfg(target_os = "macos")]
+    install_launchd(&cli, &service, &state_dir)?;
+
+    #[cfg(windows)]
+    install_scheduled_task(&cli, &service, &state_dir)?;
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    store.set(SERVER_SERVICE_KEY, serde_json::json!(service));
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    tracing::info!(port = service.port, cli = %cli.display(), "Installed server service");
+
+    Ok(ServerServiceStatus {
+        installed: true,
+        running: false,
+        url: Some(service.url()),
+    })
+}
+
+fn uninstall(app: &AppHandle) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    uninstall_systemd()?;
+
+    #[cfg(target_os = "macos")]
+    uninstall_launchd()?;
+
+    #[cfg(windows)]
+    uninstall_scheduled_task(app)?;
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    store.delete(SERVER_SERVICE_KEY);
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    tracing::info!("Uninstalled server service");
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    std::fs::write(path, contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("Failed to set permissions on {}: {}", path.display(), e))
+}
+
+pub(crate) fn run(program: &str, args: &[&str]) -> Result<(), String> {
+    let mut cmd = std::process::Command::new(program);
+    cmd.args(args);
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x0800_0000);
+    }
+
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            stderr.trim()
+        ));
+    }
+
+    Ok(())
+}
+
+fn serve_args(service: &ServerService) -> Vec<String> {
+    vec![
+        "serve".to_string(),
+        "--hostname".to_string(),
+        SERVICE_HOSTNAME.to_string(),
+        "--port".to_string(),
+        service.port.to_string(),
+    ]
+}
+
+#[cfg(target_os = "linux")]
+fn systemd_unit_path() -> Result<std::path::PathBuf, String> {
+    let config_dir =
+        dirs::config_dir().ok_or_else(|| "Could not determine config directory".to_string())?;
+    Ok(config_dir.join("systemd").join("user").join(SYSTEMD_UNIT))
+}
+
+#[cfg(target_os = "linux")]
+fn install_systemd(cli: &Path, service: &ServerService, state_dir: &Path) -> Result<(), String> {
+    // The password lives in a private env file rather than the world-readable unit
+    let env_file = state_dir.join("server-service.env");
+    write_private(
+        &env_file,
+        &format!(
+            "OPENCODE_SERVER_USERNAME=opencode\nOPENCODE_SERVER_PASSWORD={}\n",
+            service.password
+        ),
+    )?;
+
+    let unit_path = systemd_unit_path()?;
+    if let Some(dir) = unit_path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    std::fs::write(&unit_path, systemd_unit(cli, service, &env_file))
+        .map_err(|e| format!("Failed to write {}: {}", unit_path.display(), e))?;
+
+    run("systemctl", &["--user", "daemon-reload"])?;
+    run("systemctl", &["--user", "enable", "--now", SYSTEMD_UNIT])?;
+    // Pick up a new binary or port when reinstalling over a running service
+    run("systemctl", &["--user", "restart", SYSTEMD_UNIT])
+}
+
+#[cfg(target_os = "linux")]
+fn uninstall_systemd() -> Result<(), String> {
+    let unit_path = systemd_unit_path()?;
+    if !unit_path.exists() {
+        return Ok(());
+    }
+
+    let _ = run("systemctl", &["--user", "disable", "--now", SYSTEMD_UNIT]);
+    std::fs::remove_file(&unit_path)
+        .map_err(|e| format!("Failed to remove {}: {}", unit_path.display(), e))?;
+    run("systemctl", &["--user", "daemon-reload"])
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn systemd_unit(cli: &Path, service: &ServerService, env_file: &Path) -> String {
+    let exec = std::iter::once(cli.to_string_lossy().to_string())
+        .chain(serve_args(service))
+        .map(|arg| systemd_quote(&arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    format!(
+        "[Unit]
+Description=OpenCode server
+After=network.target
+
+[Service]
+ExecStart={exec}
+EnvironmentFile={env_file}
+Environment=OPENCODE_CLIENT=desktop
+Restart=on-failure
+RestartSec=5
+
+[Install]
+WantedBy=default.target
+",
+        env_file = systemd_quote(&env_file.to_string_lossy()),
+    )
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn systemd_quote(value: &str) -> String {
+    if value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_./:@".contains(c))
+    {
+        return value.to_string();
+    }
+
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('%', "%%")
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn launchd_plist_path() -> Result<std::path::PathBuf, String> {
+    let home = dirs::home_dir().ok_or_else(|| "Could not determine home directory".to_string())?;
+    Ok(home
+        .join("Library")
+        .join("LaunchAgents")
+        .join(format!("{LAUNCHD_LABEL}.plist")))
+}
+
+#[cfg(target_os = "macos")]
+fn launchd_domain() -> Result<String, String> {
+    let output = std::process::Command::new("id")
+        .arg("-u")
+        .output()
+        .map_err(|e| format!("Failed to determine user id: {}", e))?;
+    Ok(format!(
+        "gui/{}",
+        String::from_utf8_lossy(&output.stdout).trim()
+    ))
+}
+
+#[cfg(target_os = "macos")]
+fn install_launchd(cli: &Path, service: &ServerService, state_dir: &Path) -> Result<(), String> {
+    let plist_path = launchd_plist_path()?;
+    let log_path = state_dir.join("server-service.log");
+
+    // The plist carries the password, so it's only readable by the user
+    write_private(&plist_path, &launchd_plist(cli, service, &log_path))?;
+
+    let domain = launchd_domain()?;
+    let _ = run(
+        "launchctl",
+        &["bootout", &format!("{domain}/{LAUNCHD_LABEL}")],
+    );
+    run(
+        "launchctl",
+        &["bootstrap", &domain, &plist_path.to_string_lossy()],
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn uninstall_launchd() -> Result<(), String> {
+    let plist_path = launchd_plist_path()?;
+    if !plist_path.exists() {
+        return Ok(());
+    }
+
+    let domain = launchd_domain()?;
+    let _ = run(
+        "launchctl",
+        &["bootout", &format!("{domain}/{LAUNCHD_LABEL}")],
+    );
+    std::fs::remove_file(&plist_path)
+        .map_err(|e| format!("Failed to remove {}: {}", plist_path.display(), e))
+}
+
+#[cfg(any(target_os = "macos", test))]
+fn launchd_plist(cli: &Path, service: &ServerService, log_path: &Path) -> String {
+    let args = std::iter::once(cli.to_string_lossy().to_string())
+        .chain(serve_args(service))
+        .map(|arg| format!("\t\t<string>{}</string>", xml_escape(&arg)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let log = xml_escape(&log_path.to_string_lossy());
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>Label</key>
+	<string>{label}</string>
+	<key>ProgramArguments</key>
+	<array>
+{args}
+	</array>
+	<key>EnvironmentVariables</key>
+	<dict>
+		<key>OPENCODE_CLIENT</key>
+		<string>desktop</string>
+		<key>OPENCODE_SERVER_USERNAME</key>
+		<string>opencode</string>
+		<key>OPENCODE_SERVER_PASSWORD</key>
+		<string>{password}</string>
+	</dict>
+	<key>RunAtLoad</key>
+	<true/>
+	<key>KeepAlive</key>
+	<true/>
+	<key>StandardOutPath</key>
+	<string>{log}</string>
+	<key>StandardErrorPath</key>
+	<string>{log}</string>
+</dict>
+</plist>
+"#,
+        label = LAUNCHD_LABEL,
+        password = xml_escape(&service.password),
+    )
+}
+
+#[cfg(any(target_os = "macos", windows, test))]
+pub(crate) fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(windows)]
+fn install_scheduled_task(
+    cli: &Path,
+    service: &ServerService,
+    state_dir: &Path,
+) -> Result<(), String> {
+    // Task actions can't carry environment variables, so a launcher script in the user's
+    // local app data sets the password and starts the server without a console window
+    let launcher = state_dir.join("server-service.vbs");
+    std::fs::write(&launcher, hidden_launcher(cli, service))
+        .map_err(|e| format!("Failed to write {}: {}", launcher.display(), e))?;
+
+    // schtasks only reliably reads task definitions as UTF-16 with a BOM
+    let user = format!(
+        "{}\\{}",
+        std::env::var("USERDOMAIN").unwrap_or_default(),
+        std::env::var("USERNAME").unwrap_or_default()
+    );
+    let task_path = state_dir.join("server-service.xml");
+    let task = std::iter::once(0xfeff)
+        .chain(scheduled_task_xml(&launcher, &user).encode_utf16())
+        .flat_map(u16::to_le_bytes)
+        .collect::<Vec<_>>();
+    std::fs::write(&task_path, task)
+        .map_err(|e| format!("Failed to write {}: {}", task_path.display(), e))?;
+
+    // Stop a server left over from an earlier install so the new one can take the port
+    let _ = run("schtasks", &["/End", "/TN", SCHEDULED_TASK]);
+    stop_listener(service.port);
+
+    let result = run(
+        "schtasks",
+        &[
+            "/Create",
+            "/F",
+            "/TN",
+            SCHEDULED_TASK,
+            "/XML",
+            &task_path.to_string_lossy(),
+        ],
+    );
+    let _ = std::fs::remove_file(&task_path);
+    result?;
+
+    run("schtasks", &["/Run", "/TN", SCHEDULED_TASK])
+}
+
+#[cfg(windows)]
+fn uninstall_scheduled_task(app: &AppHandle) -> Result<(), String> {
+    let Some(service) = get(app) else {
+        return Ok(());
+    };
+
+    let _ = run("schtasks", &["/End", "/TN", SCHEDULED_TASK]);
+    let _ = run("schtasks", &["/Delete", "/F", "/TN", SCHEDULED_TASK]);
+    // Ending the task only stops the launcher, not the server it started
+    stop_listener(service.port);
+
+    if let Ok(state_dir) = app.path().resolve("", BaseDirectory::AppLocalData) {
+        let _ = std::fs::remove_file(state_dir.join("server-service.vbs"));
+    }
+
+    Ok(())
+}
+
+#[cfg(windows)]
+fn stop_listener(port: u32) {
+    let _ = run(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            &format!(
+                "Get-NetTCPConnection -LocalPort {port} -State Listen -ErrorAction SilentlyContinue | ForEach-Object {{ Stop-Process -Id $_.OwningProcess -Force }}"
+            ),
+        ],
+    );
+}
+
+#[cfg(any(windows, test))]
+fn hidden_launcher(cli: &Path, service: &ServerService) -> String {
+    let command = std::iter::once(format!("\"{}\"", cli.display()))
+        .chain(serve_args(service))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    format!(
+        "Set shell = CreateObject(\"WScript.Shell\")
+Set env = shell.Environment(\"Process\")
+env(\"OPENCODE_CLIENT\") = \"desktop\"
+env(\"OPENCODE_SERVER_USERNAME\") = \"opencode\"
+env(\"OPENCODE_SERVER_PASSWORD\") = {password}
+WScript.Quit shell.Run({command}, 0, True)
+",
+        password = vbs_string(&service.password),
+        command = vbs_string(&command),
+    )
+}
+
+#[cfg(any(windows, test))]
+fn vbs_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+// Runs at the user's own logon without elevation, and restarts the server if it exits
+#[cfg(any(windows, test))]
+fn scheduled_task_xml(launcher: &Path, user: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-16"?>
+<Task version="1.2" xmlns="http://schemas.microsoft.com/windows/2004/02/mit/task">
+  <Triggers>
+    <LogonTrigger>
+      <Enabled>true</Enabled>
+      <UserId>{user}</UserId>
+    </LogonTrigger>
+  </Triggers>
+  <Principals>
+    <Principal id="Author">
+      <UserId>{user}</UserId>
+      <LogonType>InteractiveToken</LogonType>
+      <RunLevel>LeastPrivilege</RunLevel>
+    </Principal>
+  </Principals>
+  <Settings>
+    <MultipleInstancesPolicy>IgnoreNew</MultipleInstancesPolicy>
+    <DisallowStartIfOnBatteries>false</DisallowStartIfOnBatteries>
+    <StopIfGoingOnBatteries>false</StopIfGoingOnBatteries>
+    <ExecutionTimeLimit>PT0S</ExecutionTimeLimit>
+    <RestartOnFailure>
+      <Interval>PT1M</Interval>
+      <Count>999</Count>
+    </RestartOnFailure>
+    <Hidden>true</Hidden>
+  </Settings>
+  <Actions Context="Author">
+    <Exec>
+      <Command>wscript.exe</Command>
+      <Arguments>//B //Nologo "{launcher}"</Arguments>
+    </Exec>
+  </Actions>
+</Task>
+"#,
+        user = xml_escape(user),
+        launcher = xml_escape(&launcher.to_string_lossy()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> ServerService {
+        ServerService {
+            port: 4096,
+            password: "p<&>".to_string(),
+        }
+    }
+
+    #[test]
+    fn systemd_unit_quotes_paths() {
+        let unit = systemd_unit(
+            Path::new("/home/me/My Apps/opencode"),
+            &service(),
+            Path::new("/home/me/.local/share/app/server-service.env"),
+        );
+
+        assert!(unit.contains(
+            "ExecStart=\"/home/me/My Apps/opencode\" serve --hostname 127.0.0.1 --port 4096\n"
+        ));
+        assert!(unit.contains("EnvironmentFile=/home/me/.local/share/app/server-service.env\n"));
+        assert!(!unit.contains("p<&>"));
+    }
+
+    #[test]
+    fn launchd_plist_escapes_values() {
+        let plist = launchd_plist(
+            Path::new("/Users/me/.opencode/bin/opencode"),
+            &service(),
+            Path::new("/tmp/server.log"),
+        );
+
+        assert!(plist.contains("<string>/Users/me/.opencode/bin/opencode</string>"));
+        assert!(plist.contains("<string>4096</string>"));
+        assert!(plist.contains("<string>p&lt;&amp;&gt;</string>"));
+    }
+
+    #[test]
+    fn hidden_launcher_quotes_command() {
+        let launcher = hidden_launcher(
+            Path::new(r"C:\Users\me\AppData\Local\opencode\bin\opencode.exe"),
+            &ServerService {
+                port: 4096,
+                password: "a\"b".to_string(),
+            },
+        );
+
+        assert!(launcher.contains("env(\"OPENCODE_SERVER_PASSWORD\") = \"a\"\"b\"\n"));
+        assert!(launcher.contains(
+            r#"shell.Run("""C:\Users\me\AppData\Local\opencode\bin\opencode.exe"" serve --hostname 127.0.0.1 --port 4096", 0, True)"#
+        ));
+    }
+
+    #[test]
+    fn scheduled_task_escapes_values() {
+        let task = scheduled_task_xml(Path::new(r"C:\Users\A&B\server-service.vbs"), r"PC\A&B");
+
+        assert!(task.contains(r"<UserId>PC\A&amp;B</UserId>"));
+        assert!(task.contains(
+            r#"<Arguments>//B //Nologo "C:\Users\A&amp;B\server-service.vbs"</Arguments>"#
+        ));
+    }
+}
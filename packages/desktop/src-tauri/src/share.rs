@@ -0,0 +1,157 @@
+use std::path::Path;
+
+use tauri::State;
+
+use crate::{ServerState, server::url_is_localhost};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerKind {
+    Codespaces,
+    Gitpod,
+    Container,
+}
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+pub struct ShareInfo {
+    url: String,
+    forwarded_url: Option<String>,
+    container: Option<ContainerKind>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ContainerEnv {
+    pub codespaces: Option<String>,
+    pub codespace_name: Option<String>,
+    pub codespaces_domain: Option<String>,
+    pub gitpod_workspace_url: Option<String>,
+    pub container_marker: bool,
+}
+
+impl ContainerEnv {
+    pub fn capture() -> Self {
+        Self {
+            codespaces: std::env::var("CODESPACES").ok(),
+            codespace_name: std::env::var("CODESPACE_NAME").ok(),
+            codespaces_domain: std::env::var("GITHUB_CODESPACES_PORT_FORWARDING_DOMAIN").ok(),
+            gitpod_workspace_url: std::env::var("GITPOD_WORKSPACE_URL").ok(),
+            container_marker: Path::new("/.dockerenv").exists()
+                || Path::new("/run/.containerenv").exists(),
+        }
+    }
+}
+
+pub fn detect_container(env: &ContainerEnv) -> Option<ContainerKind> {
+    if env
+        .codespaces
+        .as_deref()
+        .is_some_and(|v| v.eq_ignore_ascii_case("true"))
+        && env.codespace_name.as_deref().is_some_and(|v| !v.is_empty())
+    {
+        return Some(ContainerKind::Codespaces);
+    }
+
+    if env
+        .gitpod_workspace_url
+        .as_deref()
+        .is_some_and(|v| !v.is_empty())
+    {
+        return Some(ContainerKind::Gitpod);
+    }
+
+    env.container_marker.then_some(ContainerKind::Container)
+}
+
+/// Builds the externally reachable URL for a loopback port, for environments
+/// that forward ports through a known public hostname scheme.
+pub fn forwarded_url(env: &ContainerEnv, port: u16) -> Option<String> {
+    match detect_container(env)? {
+        ContainerKind::Codespaces => {
+            let name = env.codespace_name.as_deref()?;
+            let domain = env
+                .codespaces_domain
+                .as_deref()
+                .filter(|v| !v.is_empty())
+                .unwrap_or("app.github.dev");
+            Some(format!("https://{name}-{port}.{domain}"))
+        }
+        ContainerKind::Gitpod => {
+            let workspace = reqwest::Url::parse(env.gitpod_workspace_url.as_deref()?).ok()?;
+            Some(format!("https://{port}-{}", workspace.host_str()?))
+        }
+        // Plain containers don't expose a predictable host mapping, so loopback
+        // may not be reachable from wherever the webview runs.
+        ContainerKind::Container => None,
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_share_info(state: State<'_, ServerState>) -> Result<ShareInfo, String> {
+    let server = state
+        .status
+        .clone()
+        .await
+        .map_err(|_| "Failed to get server status".to_string())??;
+
+    let env = ContainerEnv::capture();
+    let forwarded_url = reqwest::Url::parse(&server.url)
+        .ok()
+        .filter(url_is_localhost)
+        .and_then(|url| url.port_or_known_default())
+        .and_then(|port| forwarded_url(&env, port));
+
+    Ok(ShareInfo {
+        url: server.url,
+        forwarded_url,
+        container: detect_container(&env),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codespaces_url_uses_default_domain() {
+        let env = ContainerEnv {
+            codespaces: Some("true".into()),
+            codespace_name: Some("fluffy-space".into()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            forwarded_url(&env, 4096).as_deref(),
+            Some("https://fluffy-space-4096.app.github.dev")
+        );
+    }
+
+    #[test]
+    fn gitpod_url_prefixes_port() {
+        let env = ContainerEnv {
+            gitpod_workspace_url: Some("https://abc-123.ws-eu.gitpod.io".into()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            forwarded_url(&env, 4096).as_deref(),
+            Some("https://4096-abc-123.ws-eu.gitpod.io")
+        );
+    }
+
+    #[test]
+    fn plain_container_has_no_forwarded_url() {
+        let env = ContainerEnv {
+            container_marker: true,
+            ..Default::default()
+        };
+
+        assert_eq!(detect_container(&env), Some(ContainerKind::Container));
+        assert_eq!(forwarded_url(&env, 4096), None);
+    }
+
+    #[test]
+    fn host_has_no_container() {
+        assert_eq!(detect_container(&ContainerEnv::default()), None);
+    }
+}
@@ -0,0 +1,348 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+use tauri_specta::Event;
+use tokio::process::Command;
+
+use crate::{
+    cli::get_user_shell,
+    constants::{SETTINGS_STORE, SHELL_MODE_KEY},
+    limits::{self, SidecarLimits},
+    wsl_script::quote,
+};
+
+const SLOW_LOGIN_SHELL: Duration = Duration::from_secs(2);
+const WHICH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How the sidecar is launched on macOS & Linux.
+#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type, Debug, Default, PartialEq)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum ShellMode {
+    /// Through a login `$SHELL`, so PATH and other variables from shell profiles apply.
+    #[default]
+    Login,
+    /// Execute the sidecar directly with the app's own environment.
+    Direct,
+    /// Through `shell`, with `args` placed before the sidecar command line, e.g. `["-c"]`.
+    Custom { shell: String, args: Vec<String> },
+}
+
+#[derive(tauri_specta::Event, serde::Serialize, serde::Deserialize, Clone, Debug, specta::Type)]
+pub struct SlowLoginShell {
+    pub shell: String,
+    pub startup_ms: u32,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_shell_mode(app: AppHandle) -> Result<ShellMode, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(SHELL_MODE_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_shell_mode(app: AppHandle, mode: ShellMode) -> Result<(), String> {
+    if let ShellMode::Custom { shell, .. } = &mode
+        && shell.trim().is_empty()
+    {
+        return Err("Custom shell can't be empty".to_string());
+    }
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(SHELL_MODE_KEY, serde_json::json!(mode));
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+pub fn get(app: &AppHandle) -> ShellMode {
+    get_shell_mode(app.clone()).unwrap_or_default()
+}
+
+/// Builds the command that runs the sidecar with `args` according to `mode`.
+pub fn command(mode: &ShellMode, sidecar: &Path, args: &str, limits: &SidecarLimits) -> Command {
+    match mode {
+        ShellMode::Login => {
+            let shell = get_user_shell();
+            let mut cmd = limits::command(&shell, limits);
+            cmd.args(ShellKind::detect(&shell).login_args())
+                .arg(command_line(&shell, sidecar, args));
+            cmd
+        }
+        ShellMode::Direct => {
+            let mut cmd = limits::command(sidecar, limits);
+            cmd.args(args.split_whitespace());
+            cmd
+        }
+        ShellMode::Custom {
+            shell,
+            args: shell_args,
+        } => {
+            let mut cmd = limits::command(shell, limits);
+            cmd.args(shell_args).arg(command_line(shell, sidecar, args));
+            cmd
+        }
+    }
+}
+
+/// Shell families that differ in how a login shell is started and how a path is quoted.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ShellKind {
+    Posix,
+    Fish,
+    Nushell,
+    PowerShell,
+    Csh,
+}
+
+impl ShellKind {
+    fn detect(shell: &str) -> Self {
+        let name = Path::new(shell)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(shell);
+        let name = name.strip_suffix(".exe").unwrap_or(name);
+
+        match name {
+            "fish" => Self::Fish,
+            "nu" => Self::Nushell,
+            "pwsh" | "powershell" => Self::PowerShell,
+            "csh" | "tcsh" => Self::Csh,
+            _ => Self::Posix,
+        }
+    }
+
+    fn login_args(self) -> &'static [&'static str] {
+        match self {
+            Self::Posix | Self::Fish | Self::Nushell => &["-il", "-c"],
+            // `-Login` is only honoured as the very first argument
+            Self::PowerShell => &["-Login", "-NoLogo", "-Command"],
+            // csh only acts as a login shell when `-l` is its sole argument, but still reads
+            // `.cshrc`/`.tcshrc` for `-c`
+            Self::Csh => &["-c"],
+        }
+    }
+
+    // `program` must be a plain command name, it isn't quoted
+    #[cfg(not(windows))]
+    fn which(self, program: &str) -> String {
+        match self {
+            Self::Posix | Self::Fish => format!("command -v {program}"),
+            Self::Nushell => format!("which {program} | get path.0"),
+            Self::PowerShell => format!("(Get-Command {program}).Source"),
+            Self::Csh => format!("which {program}"),
+        }
+    }
+
+    fn quote(self, value: &str) -> String {
+        match self {
+            Self::Posix => quote(value),
+            Self::Fish => format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'")),
+            Self::Nushell => format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"")),
+            Self::PowerShell => format!("'{}'", value.replace('\'', "''")),
+            // `!` triggers history substitution even inside single quotes
+            Self::Csh => format!("'{}'", value.replace('\'', "'\\''").replace('!', "\\!")),
+        }
+    }
+}
+
+fn command_line(shell: &str, sidecar: &Path, args: &str) -> String {
+    let kind = ShellKind::detect(shell);
+    let sidecar = kind.quote(&sidecar.to_string_lossy());
+
+    match kind {
+        // nushell needs `^` to run an external command from a quoted path
+        ShellKind::Nushell => format!("^{} {}", sidecar, args),
+        // PowerShell treats a quoted path as a string unless it's invoked with `&`
+        ShellKind::PowerShell => format!("& {} {}", sidecar, args),
+        _ => format!("{} {}", sidecar, args),
+    }
+}
+
+/// Resolves `program` the way the user's login shell would, so PATH changes made in shell
+/// profiles are taken into account. `None` when the shell can't find it.
+pub async fn which(program: &str) -> Option<PathBuf> {
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut cmd = Command::new("where.exe");
+        cmd.arg(program).creation_flags(0x0800_0000);
+        cmd
+    };
+
+    #[cfg(not(windows))]
+    let mut cmd = {
+        let shell = get_user_shell();
+        let kind = ShellKind::detect(&shell);
+        let mut cmd = Command::new(&shell);
+        cmd.args(kind.login_args()).arg(kind.which(program));
+        cmd
+    };
+
+    let output = tokio::time::timeout(
+        WHICH_TIMEOUT,
+        cmd.stdin(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    // `where` lists every match in PATH order, while profiles may print their own output
+    // before the shell's answer
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    let line = if cfg!(windows) {
+        lines.next()
+    } else {
+        lines.next_back()
+    };
+
+    line.map(PathBuf::from)
+}
+
+/// Times an empty login shell in the background and tells the frontend when heavy profiles
+/// are slowing down every sidecar start.
+pub fn spawn_startup_check(app: AppHandle) {
+    if cfg!(windows) || get(&app) != ShellMode::Login {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let shell = get_user_shell();
+        let started = Instant::now();
+
+        let status = Command::new(&shell)
+            .args(ShellKind::detect(&shell).login_args())
+            .arg("exit")
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .await;
+
+        let elapsed = started.elapsed();
+        if status.is_err() || elapsed < SLOW_LOGIN_SHELL {
+            return;
+        }
+
+        tracing::warn!(%shell, ?elapsed, "Login shell is slow to start");
+        let _ = SlowLoginShell {
+            shell,
+            startup_ms: elapsed.as_millis() as u32,
+        }
+        .emit(&app);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(cmd: &Command) -> Vec<String> {
+        cmd.as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn direct_mode_runs_sidecar() {
+        let cmd = command(
+            &ShellMode::Direct,
+            Path::new("/opt/opencode-cli"),
+            "serve --port 4096",
+            &SidecarLimits::default(),
+        );
+
+        assert_eq!(cmd.as_std().get_program(), "/opt/opencode-cli");
+        assert_eq!(args(&cmd), ["serve", "--port", "4096"]);
+    }
+
+    #[test]
+    fn custom_mode_uses_shell_and_flags() {
+        let mode = ShellMode::Custom {
+            shell: "/usr/bin/nu".to_string(),
+            args: vec!["--login".to_string(), "-c".to_string()],
+        };
+        let cmd = command(
+            &mode,
+            Path::new("/opt/opencode-cli"),
+            "serve",
+            &SidecarLimits::default(),
+        );
+
+        assert_eq!(cmd.as_std().get_program(), "/usr/bin/nu");
+        assert_eq!(
+            args(&cmd),
+            ["--login", "-c", "^\"/opt/opencode-cli\" serve"]
+        );
+    }
+
+    #[test]
+    fn detects_shell_kind() {
+        assert_eq!(ShellKind::detect("/bin/zsh"), ShellKind::Posix);
+        assert_eq!(ShellKind::detect("/opt/homebrew/bin/fish"), ShellKind::Fish);
+        assert_eq!(
+            ShellKind::detect("/usr/local/bin/pwsh"),
+            ShellKind::PowerShell
+        );
+        assert_eq!(ShellKind::detect("pwsh.exe"), ShellKind::PowerShell);
+        assert_eq!(ShellKind::detect("/bin/tcsh"), ShellKind::Csh);
+        assert_eq!(ShellKind::detect("nu"), ShellKind::Nushell);
+    }
+
+    #[test]
+    fn quotes_sidecar_path_per_shell() {
+        let sidecar = Path::new("/Users/me/My Apps/it's!/opencode-cli");
+
+        assert_eq!(
+            command_line("/bin/bash", sidecar, "serve"),
+            "'/Users/me/My Apps/it'\"'\"'s!/opencode-cli' serve"
+        );
+        assert_eq!(
+            command_line("/usr/bin/fish", sidecar, "serve"),
+            r"'/Users/me/My Apps/it\'s!/opencode-cli' serve"
+        );
+        assert_eq!(
+            command_line("/usr/bin/pwsh", sidecar, "serve"),
+            "& '/Users/me/My Apps/it''s!/opencode-cli' serve"
+        );
+        assert_eq!(
+            command_line("/bin/tcsh", sidecar, "serve"),
+            r"'/Users/me/My Apps/it'\''s\!/opencode-cli' serve"
+        );
+    }
+
+    #[test]
+    fn login_mode_args_match_shell() {
+        assert_eq!(
+            ShellKind::PowerShell.login_args(),
+            ["-Login", "-NoLogo", "-Command"]
+        );
+        assert_eq!(ShellKind::Csh.login_args(), ["-c"]);
+    }
+}
@@ -0,0 +1,183 @@
+use tauri::{AppHandle, Wry, plugin::TauriPlugin};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
+use tauri_plugin_store::StoreExt;
+
+use crate::{
+    constants::{GLOBAL_SHORTCUT_KEY, QUICK_PANEL_SHORTCUT_KEY, SETTINGS_STORE},
+    tray, validate,
+    windows::QuickPanel,
+};
+
+/// Toggles the quick panel when its shortcut is pressed, and summons the main window for the
+/// other registered shortcut.
+pub fn plugin() -> TauriPlugin<Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, shortcut, event| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+
+            if saved(app, QUICK_PANEL_SHORTCUT_KEY) == Some(*shortcut) {
+                QuickPanel::toggle(app);
+            } else {
+                tray::show_main_window(app);
+            }
+        })
+        .build()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_global_shortcut(app: AppHandle) -> Result<Option<String>, String> {
+    get_shortcut(&app, GLOBAL_SHORTCUT_KEY)
+}
+
+/// Registers a system-wide shortcut like `CommandOrControl+Shift+O` that focuses the main
+/// window, or creates it when it's closed. `None` removes it. Fails without changing anything
+/// when another application already holds the shortcut.
+#[tauri::command]
+#[specta::specta]
+pub fn set_global_shortcut(app: AppHandle, accelerator: Option<String>) -> Result<(), String> {
+    set_shortcut(&app, GLOBAL_SHORTCUT_KEY, accelerator)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_quick_panel_shortcut(app: AppHandle) -> Result<Option<String>, String> {
+    get_shortcut(&app, QUICK_PANEL_SHORTCUT_KEY)
+}
+
+/// Registers a system-wide shortcut that slides the quick panel down from the top of the screen,
+/// or back up when it's showing. `None` removes it.
+#[tauri::command]
+#[specta::specta]
+pub fn set_quick_panel_shortcut(app: AppHandle, accelerator: Option<String>) -> Result<(), String> {
+    set_shortcut(&app, QUICK_PANEL_SHORTCUT_KEY, accelerator)
+}
+
+/// Registers the saved shortcuts at startup. Another application may have taken one since, in
+/// which case it stays saved and is tried again on the next launch.
+pub fn register_saved(app: &AppHandle) {
+    for key in [GLOBAL_SHORTCUT_KEY, QUICK_PANEL_SHORTCUT_KEY] {
+        let Some(accelerator) = get_shortcut(app, key).ok().flatten() else {
+            continue;
+        };
+
+        let result = parse(&accelerator).and_then(|shortcut| {
+            app.global_shortcut()
+                .register(shortcut)
+                .map_err(|e| e.to_string())
+        });
+        if let Err(e) = result {
+            tracing::warn!(%accelerator, key, "Failed to register global shortcut: {e}");
+        }
+    }
+}
+
+fn get_shortcut(app: &AppHandle, key: &str) -> Result<Option<String>, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(key)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .flatten())
+}
+
+fn saved(app: &AppHandle, key: &str) -> Option<Shortcut> {
+    get_shortcut(app, key)
+        .ok()
+        .flatten()
+        .and_then(|saved| parse(&saved).ok())
+}
+
+fn set_shortcut(app: &AppHandle, key: &str, accelerator: Option<String>) -> Result<(), String> {
+    let shortcut = accelerator
+        .as_deref()
+        .map(|accelerator| {
+            validate::name("shortcut", accelerator)?;
+            parse(accelerator)
+        })
+        .transpose()?;
+    let previous = saved(app, key);
+
+    // Both shortcuts share one registry, so one can't be registered twice
+    let other = if key == GLOBAL_SHORTCUT_KEY {
+        QUICK_PANEL_SHORTCUT_KEY
+    } else {
+        GLOBAL_SHORTCUT_KEY
+    };
+    if let (Some(shortcut), Some(accelerator)) = (shortcut, &accelerator)
+        && saved(app, other) == Some(shortcut)
+    {
+        return Err(format!(
+            "Shortcut '{}' is already used by another OpenCode shortcut",
+            accelerator
+        ));
+    }
+
+    let global_shortcut = app.global_shortcut();
+    if let Some(previous) = previous {
+        let _ = global_shortcut.unregister(previous);
+    }
+
+    if let (Some(shortcut), Some(accelerator)) = (shortcut, &accelerator)
+        && let Err(e) = global_shortcut.register(shortcut)
+    {
+        if let Some(previous) = previous {
+            let _ = global_shortcut.register(previous);
+        }
+        return Err(format!(
+            "Shortcut '{}' is already in use by another application: {}",
+            accelerator, e
+        ));
+    }
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(key, serde_json::json!(accelerator));
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    tracing::info!(?accelerator, key, "Set global shortcut");
+    Ok(())
+}
+
+fn parse(accelerator: &str) -> Result<Shortcut, String> {
+    let shortcut = accelerator
+        .parse::<Shortcut>()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", accelerator, e))?;
+
+    // A bare key, or one with only Shift, would swallow normal typing in every application
+    if (shortcut.mods - Modifiers::SHIFT).is_empty() {
+        return Err(format!(
+            "Shortcut '{}' needs a modifier other than Shift",
+            accelerator
+        ));
+    }
+
+    Ok(shortcut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_shortcuts_with_modifiers() {
+        assert!(parse("CommandOrControl+Shift+O").is_ok());
+        assert!(parse("Alt+Space").is_ok());
+    }
+
+    #[test]
+    fn rejects_shortcuts_that_block_typing() {
+        assert!(parse("O").is_err());
+        assert!(parse("Shift+O").is_err());
+        assert!(parse("Control+NotAKey").is_err());
+    }
+}
@@ -0,0 +1,431 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use tauri::{AppHandle, Manager, path::BaseDirectory};
+
+use crate::{
+    app_dirs,
+    cli::get_sidecar_path,
+    db_guard::{self, DbQueryError},
+    integrity,
+};
+
+/// Size & sha256 of the sidecar this build was bundled with, embedded by `build.rs`.
+const MANIFEST_SIZE: Option<&str> = option_env!("OPENCODE_SIDECAR_SIZE");
+const MANIFEST_SHA256: Option<&str> = option_env!("OPENCODE_SIDECAR_SHA256");
+const BACKUP_DIR: &str = "sidecar-backup";
+const DB_QUERY_TIMEOUT: Duration = Duration::from_secs(30);
+#[cfg(target_os = "macos")]
+const QUARANTINE_XATTR: &str = "com.apple.quarantine";
+
+/// Something that keeps the bundled sidecar from starting.
+#[derive(Clone, serde::Serialize, specta::Type, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SidecarIssue {
+    Missing,
+    /// The file doesn't match the size the app was bundled with, e.g. after a broken update.
+    SizeMismatch,
+    ChecksumMismatch,
+    NotExecutable,
+    /// macOS refuses to run the binary until the quarantine attribute is removed.
+    Quarantined,
+}
+
+impl SidecarIssue {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::Missing => "it's missing",
+            Self::SizeMismatch | Self::ChecksumMismatch => "it's been modified",
+            Self::NotExecutable => "it isn't executable",
+            Self::Quarantined => "macOS has quarantined it",
+        }
+    }
+}
+
+/// Joins what's wrong with the sidecar into one line, e.g. for a dialog.
+pub fn describe(issues: &[SidecarIssue]) -> String {
+    issues
+        .iter()
+        .map(SidecarIssue::describe)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Lists everything wrong with the bundled sidecar. Empty when it should start fine.
+#[tauri::command]
+#[specta::specta]
+pub async fn check_sidecar(app: AppHandle) -> Result<Vec<SidecarIssue>, String> {
+    let path = get_sidecar_path(&app);
+    tokio::task::spawn_blocking(move || check(&path))
+        .await
+        .map_err(|e| format!("Sidecar check failed: {}", e))
+}
+
+/// Restores the bundled sidecar from the copy saved at an earlier launch and fixes its
+/// permissions, returning whatever issues remain.
+#[tauri::command]
+#[specta::specta]
+pub async fn repair_sidecar(app: AppHandle) -> Result<Vec<SidecarIssue>, String> {
+    let path = get_sidecar_path(&app);
+    let backup = backup_path(&app)?;
+
+    tokio::task::spawn_blocking(move || {
+        if let Some(issue) = content_issue(&path) {
+            tracing::warn!(?issue, path = %path.display(), "Restoring sidecar from backup");
+            if content_issue(&backup).is_some() {
+                return Err(
+                    "No intact copy of the sidecar is available, please reinstall the app"
+                        .to_string(),
+                );
+            }
+            copy(&backup, &path)?;
+        }
+
+        fix_permissions(&path)?;
+
+        let issues = check(&path);
+        tracing::info!(?issues, "Sidecar repair finished");
+        Ok(issues)
+    })
+    .await
+    .map_err(|e| format!("Sidecar repair failed: {}", e))?
+}
+
+pub fn check(path: &Path) -> Vec<SidecarIssue> {
+    check_with(path, Manifest::bundled(), signature(path))
+}
+
+fn check_with(path: &Path, manifest: Manifest, signature: Signature) -> Vec<SidecarIssue> {
+    if let Some(issue) = content_issue_with(path, manifest, signature) {
+        return vec![issue];
+    }
+
+    let mut issues = vec![];
+    if !is_executable(path) {
+        issues.push(SidecarIssue::NotExecutable);
+    }
+    if is_quarantined(path) {
+        issues.push(SidecarIssue::Quarantined);
+    }
+    issues
+}
+
+/// Makes the sidecar executable and clears the macOS quarantine attribute. Cheap enough to
+/// run before every spawn.
+pub fn fix_permissions(path: &Path) -> Result<(), String> {
+    if !path.exists() {
+        return Err(format!("Sidecar binary not found at {}", path.display()));
+    }
+
+    #[cfg(unix)]
+    if !is_executable(path) {
+        use std::os::unix::fs::PermissionsExt;
+
+        tracing::warn!(path = %path.display(), "Sidecar isn't executable, fixing permissions");
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make {} executable: {}", path.display(), e))?;
+    }
+
+    #[cfg(target_os = "macos")]
+    if is_quarantined(path) {
+        tracing::warn!(path = %path.display(), "Removing quarantine attribute from sidecar");
+        let status = std::process::Command::new("xattr")
+            .args(["-d", QUARANTINE_XATTR])
+            .arg(path)
+            .status()
+            .map_err(|e| format!("Failed to run xattr: {}", e))?;
+        if !status.success() {
+            return Err(format!(
+                "Failed to remove quarantine attribute from {}",
+                path.display()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Keeps a verified copy of the sidecar in app data, so `repair_sidecar` has something to
+/// restore from when the bundled one is damaged later.
+pub fn spawn_backup(app: AppHandle) {
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = get_sidecar_path(&app);
+        let Ok(backup) = backup_path(&app) else {
+            return;
+        };
+
+        // Only copies the sidecar when its manifest or code signature vouches for it
+        let signature = signature(&path);
+        if (MANIFEST_SHA256.is_none() && signature != Signature::Valid)
+            || content_issue_with(&path, Manifest::bundled(), signature).is_some()
+        {
+            return;
+        }
+        if content_issue(&backup).is_none() {
+            return;
+        }
+
+        if let Err(e) = copy(&path, &backup) {
+            tracing::warn!("Failed to back up sidecar: {e}");
+        }
+    });
+}
+
+/// Runs a read-only SQL query against the local opencode database through the bundled CLI.
+/// Returns no rows while the database doesn't exist yet, and is turned away while it's being
+/// migrated.
+pub async fn query_db<T: serde::de::DeserializeOwned>(
+    app: &AppHandle,
+    query: &str,
+) -> Result<Vec<T>, DbQueryError> {
+    if !app_dirs::opencode_db().is_ok_and(|path| path.exists()) {
+        return Ok(Vec::new());
+    }
+    db_guard::check()?;
+
+    let mut cmd = tokio::process::Command::new(get_sidecar_path(app));
+    cmd.args(["db", query, "--format", "json"])
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+    #[cfg(windows)]
+    cmd.creation_flags(0x0800_0000);
+
+    let output = tokio::time::timeout(DB_QUERY_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| "Timed out querying the database".to_string())?
+        .map_err(|e| format!("Failed to query the database: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to query the database: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    Ok(serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse query results: {}", e))?)
+}
+
+fn backup_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let file_name = get_sidecar_path(app)
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_else(|| "opencode-cli".into());
+
+    app.path()
+        .resolve(BACKUP_DIR, BaseDirectory::AppLocalData)
+        .map(|dir| dir.join(file_name))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+/// What the bundled sidecar looked like before the app was bundled, from `build.rs`.
+#[derive(Clone, Copy, Debug)]
+struct Manifest {
+    size: Option<u64>,
+    sha256: Option<&'static str>,
+}
+
+impl Manifest {
+    fn bundled() -> Self {
+        Self {
+            size: MANIFEST_SIZE.and_then(|size| size.parse().ok()),
+            sha256: MANIFEST_SHA256,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Signature {
+    /// Not code-signed, e.g. in dev builds or on Linux, so only the manifest can vouch for it.
+    Unsigned,
+    /// Signed and unchanged since. Signing rewrites the binary after `build.rs` recorded the
+    /// manifest, so the manifest no longer applies.
+    Valid,
+    /// Signed, but modified since.
+    Invalid,
+}
+
+fn content_issue(path: &Path) -> Option<SidecarIssue> {
+    content_issue_with(path, Manifest::bundled(), signature(path))
+}
+
+fn content_issue_with(
+    path: &Path,
+    manifest: Manifest,
+    signature: Signature,
+) -> Option<SidecarIssue> {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return Some(SidecarIssue::Missing);
+    };
+
+    match signature {
+        Signature::Valid => return None,
+        Signature::Invalid => return Some(SidecarIssue::ChecksumMismatch),
+        Signature::Unsigned => {}
+    }
+
+    if let Some(size) = manifest.size
+        && metadata.len() != size
+    {
+        return Some(SidecarIssue::SizeMismatch);
+    }
+
+    if let Some(sha256) = manifest.sha256
+        && integrity::verify_file(path, sha256).is_err()
+    {
+        return Some(SidecarIssue::ChecksumMismatch);
+    }
+
+    None
+}
+
+// macOS release builds sign the sidecar while bundling. `codesign --display` only succeeds
+// for signed binaries, `--verify` then checks nothing changed since.
+fn signature(path: &Path) -> Signature {
+    #[cfg(target_os = "macos")]
+    {
+        let codesign = |args: &[&str]| {
+            std::process::Command::new("codesign")
+                .args(args)
+                .arg(path)
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success())
+        };
+
+        if !codesign(&["--display"]) {
+            Signature::Unsigned
+        } else if codesign(&["--verify", "--strict"]) {
+            Signature::Valid
+        } else {
+            Signature::Invalid
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = path;
+        Signature::Unsigned
+    }
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::metadata(path).is_ok_and(|metadata| metadata.permissions().mode() & 0o111 != 0)
+    }
+
+    #[cfg(not(unix))]
+    {
+        path.exists()
+    }
+}
+
+fn is_quarantined(path: &Path) -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("xattr")
+            .args(["-p", QUARANTINE_XATTR])
+            .arg(path)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+// Written next to the target and renamed over it, so a failure never leaves a half-written
+// sidecar behind
+fn copy(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(dir) = to.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+
+    let partial = to.with_extension("partial");
+    std::fs::copy(from, &partial)
+        .map_err(|e| format!("Failed to copy {}: {}", from.display(), e))?;
+    std::fs::rename(&partial, to).map_err(|e| {
+        let _ = std::fs::remove_file(&partial);
+        format!("Failed to replace {}: {}", to.display(), e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sidecar(contents: &[u8]) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("opencode-cli");
+        std::fs::write(&path, contents).unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        (dir, path)
+    }
+
+    // The bytes a manifest recorded before signing
+    fn unsigned_manifest() -> Manifest {
+        Manifest {
+            size: Some(8),
+            sha256: Some("0000000000000000000000000000000000000000000000000000000000000000"),
+        }
+    }
+
+    #[test]
+    fn size_mismatch_alone_doesnt_break_a_signed_sidecar() {
+        let (_dir, path) = sidecar(b"signed and longer");
+
+        assert!(check_with(&path, unsigned_manifest(), Signature::Valid).is_empty());
+    }
+
+    #[test]
+    fn uses_manifest_for_unsigned_sidecars() {
+        let (_dir, path) = sidecar(b"truncated and longer");
+
+        assert_eq!(
+            check_with(&path, unsigned_manifest(), Signature::Unsigned),
+            [SidecarIssue::SizeMismatch]
+        );
+    }
+
+    #[test]
+    fn reports_modified_signed_sidecars() {
+        let (_dir, path) = sidecar(b"tampered");
+
+        assert_eq!(
+            check_with(&path, unsigned_manifest(), Signature::Invalid),
+            [SidecarIssue::ChecksumMismatch]
+        );
+    }
+
+    #[test]
+    fn reports_missing_sidecar() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert_eq!(
+            check_with(
+                &dir.path().join("opencode-cli"),
+                unsigned_manifest(),
+                Signature::Valid
+            ),
+            [SidecarIssue::Missing]
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! Makes app instances that share a database share one sidecar. Two instances can run at once
+//! with a dev build next to a release using the shared profile, or with several profiles pointed
+//! at the same data, and two sidecars migrating and writing one sqlite file would contend for it.
+//!
+//! The first instance to need a sidecar holds a lock next to the database for as long as it runs,
+//! and records the sidecar's PID and port. Later instances find the lock taken and connect to that
+//! sidecar instead of spawning their own. The owner stops its sidecar when it exits or the sidecar
+//! goes idle, releasing the lock, so they keep trying it, and the first to get it spawns a sidecar
+//! on the same port with the same password, which the others and its own windows keep using.
+
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use tauri::{AppHandle, Manager};
+use tokio::task::JoinHandle;
+
+use crate::{ServerState, app_dirs, project, server};
+
+const LOCK_FILE: &str = "desktop-sidecar.lock";
+// Kept apart from the lock, which Windows won't let other processes read while it's held
+const OWNER_FILE: &str = "desktop-sidecar.json";
+// Covers the owner spawning its sidecar and running migrations
+const OWNER_TIMEOUT: Duration = Duration::from_secs(30);
+const OWNER_POLL_INTERVAL: Duration = Duration::from_millis(250);
+const TAKEOVER_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// Held until the process exits or its sidecar is stopped for being idle, through restarts
+static HELD: Mutex<Option<File>> = Mutex::new(None);
+// Waits for the owner of the sidecar this instance uses to exit
+static TAKEOVER: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+
+/// The sidecar of the instance holding the lock.
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+pub struct SidecarOwner {
+    /// The app process that spawned the sidecar.
+    pub pid: u32,
+    pub port: u32,
+    pub password: String,
+}
+
+impl SidecarOwner {
+    pub fn url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+}
+
+/// Takes the lock for this instance, or waits for the instance holding it to have its sidecar
+/// running and returns that sidecar. `None` means this instance should spawn its own, which it
+/// also does when the other instance's sidecar never responds.
+pub async fn claim() -> Option<SidecarOwner> {
+    let dir = dir()?;
+    let started = Instant::now();
+
+    loop {
+        match try_acquire(&dir) {
+            Ok(true) => return None,
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!("Failed to lock sidecar: {e}");
+                return None;
+            }
+        }
+
+        // The owner records its sidecar just before spawning it, so it can be missing for a moment
+        if let Some(owner) = read_owner(&dir)
+            && owner.pid != std::process::id()
+            && server::check_health(&owner.url(), Some(&owner.password)).await
+        {
+            return Some(owner);
+        }
+
+        if started.elapsed() > OWNER_TIMEOUT {
+            tracing::warn!(
+                "Another instance holds the sidecar lock but its sidecar isn't responding"
+            );
+            return None;
+        }
+        tokio::time::sleep(OWNER_POLL_INTERVAL).await;
+    }
+}
+
+/// Records the sidecar this instance is about to spawn, for other instances to find. Does
+/// nothing when another instance holds the lock.
+pub fn record(port: u32, password: &str) {
+    if HELD.lock().unwrap().is_none() {
+        return;
+    }
+    let Some(dir) = dir() else {
+        return;
+    };
+
+    let owner = SidecarOwner {
+        pid: std::process::id(),
+        port,
+        password: password.to_string(),
+    };
+    if let Err(e) = write_owner(&dir, &owner) {
+        tracing::warn!("Failed to record sidecar: {e}");
+    }
+}
+
+/// Gives up the lock and forgets the recorded sidecar, e.g. when it's stopped for being idle,
+/// so instances still using it take it over instead of losing their server. Does nothing when
+/// another instance holds the lock.
+pub fn release() {
+    let mut held = HELD.lock().unwrap();
+    if held.is_none() {
+        return;
+    }
+
+    // Cleared while still locked, so it can't remove what the next owner records
+    if let Some(dir) = dir()
+        && read_owner(&dir).is_some_and(|owner| owner.pid == std::process::id())
+        && let Err(e) = std::fs::remove_file(dir.join(OWNER_FILE))
+    {
+        tracing::warn!("Failed to clear sidecar owner: {e}");
+    }
+
+    *held = None;
+    tracing::info!("Released sidecar lock");
+}
+
+/// Takes over the lock and the sidecar of `owner` once it exits, which stops its sidecar.
+pub fn watch_owner(app: AppHandle, owner: SidecarOwner) {
+    let Some(dir) = dir() else {
+        return;
+    };
+
+    let task = tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(TAKEOVER_POLL_INTERVAL).await;
+            match try_acquire(&dir) {
+                Ok(true) => break,
+                Ok(false) => continue,
+                Err(e) => {
+                    tracing::warn!("Failed to lock sidecar: {e}");
+                    return;
+                }
+            }
+        }
+
+        tracing::info!(
+            pid = owner.pid,
+            "Sidecar owner exited, taking over its sidecar"
+        );
+        record(owner.port, &owner.password);
+        if let Some(state) = app.try_state::<ServerState>() {
+            state.set_adopted(None);
+        }
+        let spawn = server::ServerSpawn {
+            hostname: "127.0.0.1".to_string(),
+            port: owner.port,
+            password: owner.password,
+            cwd: project::active_project(&app),
+        };
+        if let Err(e) = server::restart_local_server(app, spawn).await {
+            tracing::warn!("Failed to take over sidecar: {e}");
+        }
+    });
+
+    if let Some(previous) = TAKEOVER.lock().unwrap().replace(task) {
+        previous.abort();
+    }
+}
+
+/// Stops waiting to take over another instance's sidecar, e.g. when the connection is reset.
+pub fn stop_watching_owner() {
+    if let Some(task) = TAKEOVER.lock().unwrap().take() {
+        task.abort();
+    }
+}
+
+fn dir() -> Option<PathBuf> {
+    let db = app_dirs::opencode_db().ok()?;
+    Some(db.parent()?.to_path_buf())
+}
+
+// `true` once this process holds the lock, including from an earlier call
+fn try_acquire(dir: &Path) -> Result<bool, String> {
+    let mut held = HELD.lock().unwrap();
+    if held.is_some() {
+        return Ok(true);
+    }
+
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
+
+    let path = dir.join(LOCK_FILE);
+    let file = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+
+    match file.try_lock() {
+        Ok(()) => {
+            *held = Some(file);
+            Ok(true)
+        }
+        Err(std::fs::TryLockError::WouldBlock) => Ok(false),
+        Err(std::fs::TryLockError::Error(e)) => {
+            Err(format!("Failed to lock {}: {}", path.display(), e))
+        }
+    }
+}
+
+fn read_owner(dir: &Path) -> Option<SidecarOwner> {
+    let raw = std::fs::read_to_string(dir.join(OWNER_FILE)).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn write_owner(dir: &Path, owner: &SidecarOwner) -> Result<(), String> {
+    let raw = serde_json::to_string(owner).map_err(|e| e.to_string())?;
+    let path = dir.join(OWNER_FILE);
+    let staged = path.with_extension("partial");
+
+    std::fs::write(&staged, raw)
+        .and_then(|_| restrict(&staged))
+        .and_then(|_| std::fs::rename(&staged, &path))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+// The file holds the sidecar's password
+#[cfg(unix)]
+fn restrict(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+}
+
+#[cfg(not(unix))]
+fn restrict(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_owner() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(read_owner(dir.path()), None);
+
+        let owner = SidecarOwner {
+            pid: 42,
+            port: 4096,
+            password: "secret".to_string(),
+        };
+        write_owner(dir.path(), &owner).unwrap();
+        assert_eq!(read_owner(dir.path()), Some(owner));
+    }
+
+    #[test]
+    fn ignores_corrupt_owner() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(OWNER_FILE), "{\"pid\":").unwrap();
+        assert_eq!(read_owner(dir.path()), None);
+    }
+}
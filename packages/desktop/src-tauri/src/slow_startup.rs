@@ -0,0 +1,153 @@
+//! Artificial delays for each startup stage, to work on the loading window, progress UI and
+//! timeouts on a machine that starts too fast to see them. Set e.g.
+//! `OPENCODE_DESKTOP_SLOW_STARTUP=health_check=35,migration=5` for the stages to delay, in
+//! seconds, or a single number to delay all of them.
+
+use std::{sync::OnceLock, time::Duration};
+
+use tauri::AppHandle;
+use tauri_specta::Event;
+
+use crate::cli::sqlite_migration::SqliteMigrationProgress;
+
+const SLOW_STARTUP_ENV: &str = "OPENCODE_DESKTOP_SLOW_STARTUP";
+const MIGRATION_STEPS: u8 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stage {
+    CliSync,
+    /// Counted against the health check's timeout, so a long delay makes it time out.
+    HealthCheck,
+    /// Reports a fake migration with progress, even when the database already exists.
+    Migration,
+}
+
+#[derive(Default, Debug, PartialEq)]
+struct Delays {
+    cli_sync: Option<Duration>,
+    health_check: Option<Duration>,
+    migration: Option<Duration>,
+}
+
+impl Delays {
+    fn get(&self, stage: Stage) -> Option<Duration> {
+        match stage {
+            Stage::CliSync => self.cli_sync,
+            Stage::HealthCheck => self.health_check,
+            Stage::Migration => self.migration,
+        }
+    }
+}
+
+fn delays() -> &'static Delays {
+    static DELAYS: OnceLock<Delays> = OnceLock::new();
+    DELAYS.get_or_init(|| {
+        let Ok(value) = std::env::var(SLOW_STARTUP_ENV) else {
+            return Delays::default();
+        };
+
+        match parse(&value) {
+            Ok(delays) => {
+                tracing::warn!(?delays, "Simulating slow startup");
+                delays
+            }
+            Err(e) => {
+                tracing::warn!("Ignoring {SLOW_STARTUP_ENV}: {e}");
+                Delays::default()
+            }
+        }
+    })
+}
+
+/// Whether `stage` is delayed.
+pub fn simulates(stage: Stage) -> bool {
+    delays().get(stage).is_some()
+}
+
+/// Waits out the delay for `stage`, if there is one.
+pub async fn delay(stage: Stage) {
+    if let Some(delay) = delays().get(stage) {
+        tracing::info!(?stage, ?delay, "Delaying startup stage");
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Emits migration progress over the migration delay, ending with `Done`.
+pub fn spawn_migration(app: AppHandle) {
+    let Some(delay) = delays().get(Stage::Migration) else {
+        return;
+    };
+
+    tauri::async_runtime::spawn(async move {
+        for step in 0..MIGRATION_STEPS {
+            let percent = step * (100 / MIGRATION_STEPS);
+            let _ = SqliteMigrationProgress::InProgress(percent).emit(&app);
+            tokio::time::sleep(delay / u32::from(MIGRATION_STEPS)).await;
+        }
+        let _ = SqliteMigrationProgress::Done.emit(&app);
+    });
+}
+
+fn parse(value: &str) -> Result<Delays, String> {
+    let seconds = |value: &str| {
+        value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+            .ok_or_else(|| format!("'{}' isn't a number of seconds", value))
+    };
+
+    if !value.contains('=') {
+        let delay = Some(seconds(value)?);
+        return Ok(Delays {
+            cli_sync: delay,
+            health_check: delay,
+            migration: delay,
+        });
+    }
+
+    let mut delays = Delays::default();
+    for entry in value.split(',').filter(|entry| !entry.trim().is_empty()) {
+        let (stage, secs) = entry
+            .split_once('=')
+            .ok_or_else(|| format!("'{}' should be stage=seconds", entry))?;
+        let slot = match stage.trim() {
+            "cli_sync" => &mut delays.cli_sync,
+            "health_check" => &mut delays.health_check,
+            "migration" => &mut delays.migration,
+            other => return Err(format!("unknown stage '{}'", other)),
+        };
+        *slot = Some(seconds(secs)?);
+    }
+
+    Ok(delays)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_per_stage_delays() {
+        let delays = parse("health_check=35, migration=1.5").unwrap();
+        assert_eq!(delays.cli_sync, None);
+        assert_eq!(delays.health_check, Some(Duration::from_secs(35)));
+        assert_eq!(delays.migration, Some(Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn applies_single_delay_to_every_stage() {
+        let delays = parse("5").unwrap();
+        assert_eq!(delays.get(Stage::CliSync), Some(Duration::from_secs(5)));
+        assert_eq!(delays.get(Stage::Migration), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn rejects_invalid_delays() {
+        assert!(parse("soon").is_err());
+        assert!(parse("-1").is_err());
+        assert!(parse("startup=5").is_err());
+        assert!(parse("migration").is_err());
+    }
+}
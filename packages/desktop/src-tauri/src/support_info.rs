@@ -0,0 +1,163 @@
+//! A short plain-text summary of the app's setup for pasting into a support thread, with what's
+//! asked for first in every one of them.
+
+use std::fmt;
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::{ServerState, adoption, fallback, migration_recovery, project, server, service};
+
+#[derive(Debug, Default, PartialEq)]
+struct SupportInfo {
+    app_version: String,
+    server_version: Option<String>,
+    os: String,
+    backend_note: Option<String>,
+    server_mode: String,
+    last_error: Option<String>,
+}
+
+impl fmt::Display for SupportInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "OpenCode Desktop {}", self.app_version)?;
+        writeln!(
+            f,
+            "Server: {} ({})",
+            self.server_mode,
+            self.server_version.as_deref().unwrap_or("unknown")
+        )?;
+        writeln!(f, "OS: {}", self.os)?;
+        if let Some(note) = &self.backend_note {
+            writeln!(f, "Display: {note}")?;
+        }
+        write!(
+            f,
+            "Last error: {}",
+            self.last_error.as_deref().unwrap_or("none")
+        )
+    }
+}
+
+/// Copies the support info to the clipboard, and returns it.
+#[tauri::command]
+#[specta::specta]
+pub async fn copy_support_info(app: AppHandle) -> Result<String, String> {
+    let info = collect(&app).await.to_string();
+    app.clipboard()
+        .write_text(info.clone())
+        .map_err(|e| format!("Failed to copy support info: {}", e))?;
+
+    Ok(info)
+}
+
+/// For the tray and menu items, which have no caller to report to.
+pub fn spawn_copy(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = copy_support_info(app).await {
+            tracing::error!("{e}");
+        }
+    });
+}
+
+async fn collect(app: &AppHandle) -> SupportInfo {
+    let state = app.try_state::<ServerState>();
+    let ready = state.as_ref().and_then(|state| state.ready());
+
+    let server_version = match &ready {
+        Some(ready) => adoption::server_version(app, &ready.url, ready.password.as_deref()).await,
+        None => None,
+    };
+
+    SupportInfo {
+        app_version: app.package_info().version.to_string(),
+        server_version,
+        os: format!(
+            "{} {} ({})",
+            tauri_plugin_os::platform(),
+            tauri_plugin_os::version(),
+            tauri_plugin_os::arch()
+        ),
+        backend_note: fallback::backend_note(),
+        server_mode: server_mode(app, ready.is_some()).await,
+        last_error: state.and_then(|state| last_error(&state)),
+    }
+}
+
+async fn server_mode(app: &AppHandle, ready: bool) -> String {
+    if !ready {
+        return "not connected".to_string();
+    }
+
+    let state = app.state::<ServerState>();
+    if let Some(spawn) = state.spawn() {
+        return if project::uses_wsl(app, spawn.cwd.as_deref()) {
+            "local (WSL)".to_string()
+        } else {
+            "local".to_string()
+        };
+    }
+
+    if server::get_saved_server_url(app).await.is_some() {
+        "custom".to_string()
+    } else if service::get(app).is_some() {
+        "background service".to_string()
+    } else {
+        "existing local server".to_string()
+    }
+}
+
+fn last_error(state: &ServerState) -> Option<String> {
+    if let Some(failure) = state.failure.lock().unwrap().as_ref() {
+        return Some(failure.message());
+    }
+
+    if let Some(Ok(Err(e))) = state.status().peek() {
+        // Startup errors end with the logs, which don't fit here
+        return e.lines().next().map(String::from);
+    }
+
+    migration_recovery::get_migration_failure()
+        .map(|failure| format!("{} ({})", failure.message, failure.time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_compact_block() {
+        let info = SupportInfo {
+            app_version: "1.2.3".to_string(),
+            server_version: Some("1.2.3".to_string()),
+            os: "linux 6.8.0 (x86_64)".to_string(),
+            backend_note: Some("Forcing X11 due to OC_FORCE_X11=1".to_string()),
+            server_mode: "local".to_string(),
+            last_error: None,
+        };
+
+        assert_eq!(
+            info.to_string(),
+            "OpenCode Desktop 1.2.3\n\
+             Server: local (1.2.3)\n\
+             OS: linux 6.8.0 (x86_64)\n\
+             Display: Forcing X11 due to OC_FORCE_X11=1\n\
+             Last error: none"
+        );
+    }
+
+    #[test]
+    fn skips_missing_details() {
+        let info = SupportInfo {
+            server_mode: "not connected".to_string(),
+            last_error: Some("Port 4096 is already in use".to_string()),
+            ..Default::default()
+        };
+
+        let text = info.to_string();
+        assert!(text.contains("Server: not connected (unknown)"));
+        assert!(!text.contains("Display:"));
+        assert!(text.ends_with("Last error: Port 4096 is already in use"));
+    }
+}
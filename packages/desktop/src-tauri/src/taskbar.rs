@@ -0,0 +1,78 @@
+use tauri::{
+    AppHandle, Manager,
+    window::{ProgressBarState, ProgressBarStatus},
+};
+
+use crate::windows::MainWindow;
+
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskbarProgress {
+    /// Removes the progress bar.
+    None,
+    Normal,
+    /// Shows activity without a value, e.g. while an agent runs.
+    Indeterminate,
+    /// Yellow on Windows.
+    Paused,
+    /// Red on Windows.
+    Error,
+}
+
+impl From<TaskbarProgress> for ProgressBarStatus {
+    fn from(state: TaskbarProgress) -> Self {
+        match state {
+            TaskbarProgress::None => Self::None,
+            TaskbarProgress::Normal => Self::Normal,
+            TaskbarProgress::Indeterminate => Self::Indeterminate,
+            TaskbarProgress::Paused => Self::Paused,
+            TaskbarProgress::Error => Self::Error,
+        }
+    }
+}
+
+/// Shows `count` on the dock icon on macOS, or the launcher icon on Linux desktops with the
+/// Unity launcher API, e.g. for unread results. `0` clears it. Windows has no count badge, see
+/// `set_window_icon_overlay` instead.
+#[tauri::command]
+#[specta::specta]
+pub fn set_badge_count(app: AppHandle, count: u32) -> Result<(), String> {
+    // The badge belongs to the app, but is set through any of its windows
+    let Some(window) = app
+        .get_webview_window(MainWindow::LABEL)
+        .or_else(|| app.webview_windows().into_values().next())
+    else {
+        return Ok(());
+    };
+
+    window
+        .set_badge_count((count > 0).then_some(count.into()))
+        .map_err(|e| format!("Failed to set badge count: {}", e))
+}
+
+/// Shows progress on the taskbar button of window `label` on Windows, or on the dock icon on
+/// macOS and the launcher icon on Linux, which are shared by every window. `value` is a
+/// percentage, ignored for `none` and `indeterminate`.
+#[tauri::command]
+#[specta::specta]
+pub fn set_taskbar_progress(
+    app: AppHandle,
+    label: String,
+    state: TaskbarProgress,
+    value: Option<u8>,
+) -> Result<(), String> {
+    if let Some(value) = value.filter(|value| *value > 100) {
+        return Err(format!("Progress must be between 0 and 100, got {}", value));
+    }
+
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("No window with label '{}'", label))?;
+
+    window
+        .set_progress_bar(ProgressBarState {
+            status: Some(state.into()),
+            progress: value.map(u64::from),
+        })
+        .map_err(|e| format!("Failed to set taskbar progress: {}", e))
+}
@@ -0,0 +1,259 @@
+//! What the titlebar and window gestures do. The frontend draws the titlebar on every platform,
+//! so double-clicks land in the webview and are handled here instead of by the OS.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use tauri::{AppHandle, Manager, WebviewWindow, WindowEvent};
+use tauri_plugin_store::StoreExt;
+
+use crate::constants::{SETTINGS_STORE, TITLEBAR_KEY};
+
+// Swings shorter than this are the window settling, not shaking
+const SHAKE_MIN_SWING: i32 = 40;
+const SHAKE_REVERSALS: usize = 4;
+const SHAKE_WINDOW: Duration = Duration::from_millis(800);
+
+#[derive(
+    Clone, Copy, Default, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DoubleClickAction {
+    /// What the OS is set to do on macOS, maximizing elsewhere.
+    #[default]
+    System,
+    /// Maximizes the window, or restores it when it's maximized.
+    Maximize,
+    Minimize,
+    None,
+}
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize, specta::Type, Debug)]
+#[serde(default)]
+pub struct TitlebarSettings {
+    pub double_click: DoubleClickAction,
+    /// Swipe between pages with the trackpad, with the fingers set in System Settings. macOS only.
+    pub swipe_navigation: bool,
+    /// Shaking a window by its titlebar minimizes the app's other windows. Windows does this
+    /// itself for every window, following the system setting.
+    pub shake_to_minimize: bool,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_titlebar_settings(app: AppHandle) -> Result<TitlebarSettings, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(TITLEBAR_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_titlebar_settings(app: AppHandle, settings: TitlebarSettings) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(TITLEBAR_KEY, serde_json::json!(settings));
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    for window in app.webview_windows().into_values() {
+        set_swipe_navigation(&window, settings.swipe_navigation);
+    }
+    Ok(())
+}
+
+/// Acts on the calling window for a double-click on its titlebar.
+#[tauri::command]
+#[specta::specta]
+pub async fn titlebar_double_click(app: AppHandle, window: WebviewWindow) -> Result<(), String> {
+    let action = match get_titlebar_settings(app)?.double_click {
+        DoubleClickAction::System => system_action().await,
+        action => action,
+    };
+
+    let result = match action {
+        DoubleClickAction::Minimize => window.minimize(),
+        DoubleClickAction::None => Ok(()),
+        _ if window.is_maximized().unwrap_or(false) => window.unmaximize(),
+        _ => window.maximize(),
+    };
+    result.map_err(|e| format!("Failed to handle titlebar double-click: {}", e))
+}
+
+/// Applies the gesture settings to `window`, and watches it for shakes.
+pub fn attach(app: &AppHandle, window: &WebviewWindow) {
+    let settings = get_titlebar_settings(app.clone()).unwrap_or_default();
+    set_swipe_navigation(window, settings.swipe_navigation);
+
+    if cfg!(windows) {
+        return;
+    }
+
+    let app = app.clone();
+    let label = window.label().to_string();
+    let shake = Mutex::new(ShakeDetector::default());
+    window.on_window_event(move |event| {
+        let WindowEvent::Moved(position) = event else {
+            return;
+        };
+        if !shake.lock().unwrap().moved(position.x, Instant::now()) {
+            return;
+        }
+        if !get_titlebar_settings(app.clone()).is_ok_and(|settings| settings.shake_to_minimize) {
+            return;
+        }
+
+        tracing::info!(
+            label = label.as_str(),
+            "Window shaken, minimizing the others"
+        );
+        for (other, window) in app.webview_windows() {
+            if other != label && window.is_visible().unwrap_or(false) {
+                let _ = window.minimize();
+            }
+        }
+    });
+}
+
+// Read on every double-click so changes in System Settings apply right away
+#[cfg(target_os = "macos")]
+async fn system_action() -> DoubleClickAction {
+    tokio::process::Command::new("defaults")
+        .args(["read", "-g", "AppleActionOnDoubleClick"])
+        .output()
+        .await
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse_system_action(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or(DoubleClickAction::Maximize)
+}
+
+#[cfg(not(target_os = "macos"))]
+async fn system_action() -> DoubleClickAction {
+    DoubleClickAction::Maximize
+}
+
+// Values of `AppleActionOnDoubleClick`, where zooming and filling both mean maximizing here
+#[cfg_attr(not(any(target_os = "macos", test)), allow(dead_code))]
+fn parse_system_action(value: &str) -> DoubleClickAction {
+    match value.trim() {
+        "Minimize" => DoubleClickAction::Minimize,
+        "None" => DoubleClickAction::None,
+        _ => DoubleClickAction::Maximize,
+    }
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+fn set_swipe_navigation(window: &WebviewWindow, enabled: bool) {
+    #[cfg(target_os = "macos")]
+    let _ = window.with_webview(move |webview| unsafe {
+        use objc2::rc::Retained;
+        use objc2_web_kit::WKWebView;
+
+        let wk_webview: Retained<WKWebView> = Retained::retain(webview.inner().cast()).unwrap();
+        wk_webview.setAllowsBackForwardNavigationGestures(enabled);
+    });
+}
+
+/// Notices a window being dragged quickly back and forth.
+#[derive(Default)]
+struct ShakeDetector {
+    last_x: Option<i32>,
+    /// Where the current swing started and which way it's going.
+    swing: Option<(i32, i32)>,
+    reversals: Vec<Instant>,
+}
+
+impl ShakeDetector {
+    /// Feeds the window's new position. Returns true once it's been shaken.
+    fn moved(&mut self, x: i32, now: Instant) -> bool {
+        let Some(last_x) = self.last_x.replace(x) else {
+            return false;
+        };
+        let direction = (x - last_x).signum();
+        if direction == 0 {
+            return false;
+        }
+
+        match self.swing {
+            Some((start, current)) if current != direction => {
+                if (last_x - start).abs() >= SHAKE_MIN_SWING {
+                    self.reversals.push(now);
+                }
+                self.swing = Some((last_x, direction));
+            }
+            None => self.swing = Some((last_x, direction)),
+            _ => {}
+        }
+
+        self.reversals
+            .retain(|reversal| now.duration_since(*reversal) <= SHAKE_WINDOW);
+        if self.reversals.len() < SHAKE_REVERSALS {
+            return false;
+        }
+        self.reversals.clear();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_macos_preference() {
+        assert_eq!(
+            parse_system_action("Minimize\n"),
+            DoubleClickAction::Minimize
+        );
+        assert_eq!(parse_system_action("None\n"), DoubleClickAction::None);
+        assert_eq!(parse_system_action("Fill\n"), DoubleClickAction::Maximize);
+        assert_eq!(
+            parse_system_action("Maximize\n"),
+            DoubleClickAction::Maximize
+        );
+    }
+
+    #[test]
+    fn detects_shakes_but_not_drags() {
+        let start = Instant::now();
+        let mut shake = ShakeDetector::default();
+        let swings = [0, 100, 0, 100, 0, 100];
+        let shaken = swings
+            .iter()
+            .enumerate()
+            .map(|(i, x)| shake.moved(*x, start + Duration::from_millis(i as u64 * 100)))
+            .collect::<Vec<_>>();
+        assert_eq!(shaken, [false, false, false, false, false, true]);
+
+        let mut drag = ShakeDetector::default();
+        assert!((0..50).all(|i| !drag.moved(i * 10, start + Duration::from_millis(i as u64 * 20))));
+
+        // Too slow to be a shake
+        let mut slow = ShakeDetector::default();
+        assert!(
+            swings
+                .iter()
+                .enumerate()
+                .all(|(i, x)| !slow.moved(*x, start + Duration::from_secs(i as u64)))
+        );
+
+        // Jitter while holding the window still
+        let mut jitter = ShakeDetector::default();
+        assert!(
+            [0, 5, 0, 5, 0, 5, 0]
+                .iter()
+                .all(|x| !jitter.moved(*x, start))
+        );
+    }
+}
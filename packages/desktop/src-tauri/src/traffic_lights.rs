@@ -0,0 +1,178 @@
+//! Where the window buttons sit in macOS windows, which draw their titlebar in the frontend.
+//! AppKit moves the buttons back on its own when a window leaves fullscreen, and puts them on
+//! the right in right-to-left locales, so they're placed again after each of those and lined up
+//! with the frontend's header. Other platforms have no traffic lights and ignore all of this.
+
+use std::sync::Mutex;
+
+use tauri::{AppHandle, LogicalPosition, Manager, WebviewWindow};
+
+// Matches the frontend's header at its default height
+static INSET: Mutex<LogicalPosition<f64>> = Mutex::new(LogicalPosition::new(12.0, 18.0));
+const MAX_INSET: f64 = 100.0;
+
+/// Moves the window buttons of every window to `x` from the side of the titlebar they're on and
+/// `y` from the top, e.g. to center them in the header after it changed height.
+#[tauri::command]
+#[specta::specta]
+pub fn set_traffic_light_inset(app: AppHandle, x: f64, y: f64) -> Result<(), String> {
+    if !(0.0..=MAX_INSET).contains(&x) || !(0.0..=MAX_INSET).contains(&y) {
+        return Err(format!(
+            "Traffic light inset must be between 0 and {}, got ({}, {})",
+            MAX_INSET, x, y
+        ));
+    }
+
+    *INSET.lock().unwrap() = LogicalPosition::new(x, y);
+    for window in app.webview_windows().into_values() {
+        if !window.is_fullscreen().unwrap_or(false) {
+            position(&window);
+        }
+    }
+    Ok(())
+}
+
+/// Places the buttons of `window` now and whenever AppKit might have moved them, and tells the
+/// page when it goes fullscreen, where the buttons leave the titlebar.
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+pub fn attach(window: &WebviewWindow) {
+    #[cfg(target_os = "macos")]
+    {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use tauri::WindowEvent;
+
+        position(window);
+
+        let fullscreen = AtomicBool::new(window.is_fullscreen().unwrap_or(false));
+        let handle = window.clone();
+        window.on_window_event(move |event| {
+            if !matches!(event, WindowEvent::Resized(_) | WindowEvent::Focused(_)) {
+                return;
+            }
+
+            let now = handle.is_fullscreen().unwrap_or(false);
+            if fullscreen.swap(now, Ordering::Relaxed) != now {
+                let script = if now {
+                    "document.documentElement.dataset.fullscreen = ''"
+                } else {
+                    "delete document.documentElement.dataset.fullscreen"
+                };
+                let _ = handle.eval(script);
+            }
+            if !now {
+                position(&handle);
+            }
+        });
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn position(window: &WebviewWindow) {
+    let Ok(ns_window) = window.ns_window() else {
+        return;
+    };
+    let ns_window = ns_window as usize;
+    let inset = *INSET.lock().unwrap();
+    let handle = window.clone();
+
+    let _ = window.run_on_main_thread(move || {
+        // Safety: the pointer is the window's live NSWindow, used on the main thread
+        let Some(rtl) =
+            (unsafe { place(&*(ns_window as *const objc2::runtime::AnyObject), inset) })
+        else {
+            return;
+        };
+
+        let _ = handle.eval(format!(
+            "document.documentElement.dataset.trafficLights = {}",
+            serde_json::json!(if rtl { "right" } else { "left" })
+        ));
+    });
+}
+
+#[cfg(not(target_os = "macos"))]
+fn position(_window: &WebviewWindow) {}
+
+/// Moves the buttons of `ns_window` to `inset`, returning whether they're on the right. Call on
+/// the main thread with a live window.
+#[cfg(target_os = "macos")]
+unsafe fn place(
+    ns_window: &objc2::runtime::AnyObject,
+    inset: LogicalPosition<f64>,
+) -> Option<bool> {
+    use objc2::{msg_send, runtime::AnyObject};
+    use objc2_foundation::{NSPoint, NSRect};
+
+    // `NSWindowButton` values for close, minimize and zoom
+    const BUTTONS: [usize; 3] = [0, 1, 2];
+    // `NSUserInterfaceLayoutDirectionRightToLeft`
+    const RIGHT_TO_LEFT: isize = 1;
+
+    unsafe {
+        let mut buttons = Vec::with_capacity(BUTTONS.len());
+        for kind in BUTTONS {
+            let button: *mut AnyObject = msg_send![ns_window, standardWindowButton: kind];
+            buttons.push(button.as_ref()?);
+        }
+        let (close, minimize) = (buttons[0], buttons[1]);
+
+        // The buttons sit in the titlebar container, which is sized to push them down to `y`
+        let superview: *mut AnyObject = msg_send![close, superview];
+        let container: *mut AnyObject = msg_send![superview.as_ref()?, superview];
+        let container = container.as_ref()?;
+
+        let close_frame: NSRect = msg_send![close, frame];
+        let minimize_frame: NSRect = msg_send![minimize, frame];
+        let window_frame: NSRect = msg_send![ns_window, frame];
+        let mut container_frame: NSRect = msg_send![container, frame];
+        container_frame.size.height = close_frame.size.height + inset.y;
+        container_frame.origin.y = window_frame.size.height - container_frame.size.height;
+        let _: () = msg_send![container, setFrame: container_frame];
+
+        let direction: isize = msg_send![ns_window, windowTitlebarLayoutDirection];
+        let rtl = direction == RIGHT_TO_LEFT;
+        let xs = button_xs(
+            inset.x,
+            (minimize_frame.origin.x - close_frame.origin.x).abs(),
+            close_frame.size.width,
+            container_frame.size.width,
+            rtl,
+        );
+        for (button, x) in buttons.into_iter().zip(xs) {
+            let frame: NSRect = msg_send![button, frame];
+            let _: () = msg_send![button, setFrameOrigin: NSPoint::new(x, frame.origin.y)];
+        }
+
+        Some(rtl)
+    }
+}
+
+/// Where each of close, minimize and zoom starts, laid out from the left, or from the right with
+/// close outermost when the titlebar is right-to-left.
+#[cfg_attr(not(any(target_os = "macos", test)), allow(dead_code))]
+fn button_xs(inset: f64, spacing: f64, width: f64, container_width: f64, rtl: bool) -> [f64; 3] {
+    [0.0, 1.0, 2.0].map(|i| {
+        if rtl {
+            container_width - inset - width - i * spacing
+        } else {
+            inset + i * spacing
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mirrors_buttons_right_to_left() {
+        assert_eq!(
+            button_xs(12.0, 20.0, 14.0, 800.0, false),
+            [12.0, 32.0, 52.0]
+        );
+        assert_eq!(
+            button_xs(12.0, 20.0, 14.0, 800.0, true),
+            [774.0, 754.0, 734.0]
+        );
+    }
+}
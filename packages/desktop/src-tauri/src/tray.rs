@@ -0,0 +1,249 @@
+use std::time::Duration;
+
+use tauri::{
+    AppHandle, Manager, Wry,
+    menu::{Menu, MenuItem, PredefinedMenuItem},
+    tray::TrayIconBuilder,
+};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_opener::OpenerExt;
+use tauri_plugin_store::StoreExt;
+use tokio::time::sleep;
+
+use crate::{
+    ServerReadyData, ServerState,
+    app_dirs::AppDirs,
+    constants::{CLOSE_TO_TRAY_KEY, SETTINGS_STORE},
+    server, support_info,
+    windows::MainWindow,
+};
+
+const TRAY_ID: &str = "main";
+const STATUS_INTERVAL: Duration = Duration::from_secs(10);
+// Checked more often while starting so the tray catches up soon after the server is ready
+const STARTING_STATUS_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TrayStatus {
+    Starting,
+    Running,
+    /// The sidecar the app spawned isn't running, e.g. after being stopped for being idle.
+    Stopped,
+    Error,
+}
+
+impl TrayStatus {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Starting => "Server starting…",
+            Self::Running => "Server running",
+            Self::Stopped => "Server stopped",
+            Self::Error => "Server error",
+        }
+    }
+}
+
+/// The tray's status line, updated as the server comes and goes.
+struct TrayStatusItem(MenuItem<Wry>);
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_close_to_tray(app: AppHandle) -> Result<bool, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(CLOSE_TO_TRAY_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+/// Hides the main window on close instead of quitting, so the sidecar keeps running in the
+/// background until Quit is picked from the tray.
+#[tauri::command]
+#[specta::specta]
+pub fn set_close_to_tray(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(CLOSE_TO_TRAY_KEY, serde_json::json!(enabled));
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+/// Whether closing the window labelled `label` should hide it instead.
+pub fn closes_to_tray(app: &AppHandle, label: &str) -> bool {
+    label == MainWindow::LABEL && get_close_to_tray(app.clone()).unwrap_or(false)
+}
+
+/// Shows the tray icon and keeps its status in sync with the server.
+pub fn create(app: &AppHandle) -> Result<(), tauri::Error> {
+    let status = MenuItem::with_id(
+        app,
+        "tray-status",
+        TrayStatus::Starting.label(),
+        false,
+        None::<&str>,
+    )?;
+    let show_item = MenuItem::with_id(app, "tray-show", "Show Window", true, None::<&str>)?;
+    let restart_item =
+        MenuItem::with_id(app, "tray-restart", "Restart Server", true, None::<&str>)?;
+    let copy_item = MenuItem::with_id(app, "tray-copy-url", "Copy Server URL", true, None::<&str>)?;
+    let logs_item = MenuItem::with_id(app, "tray-logs", "Open Logs", true, None::<&str>)?;
+    let support_item = MenuItem::with_id(
+        app,
+        "tray-copy-support-info",
+        "Copy Support Info",
+        true,
+        None::<&str>,
+    )?;
+    let quit_item = MenuItem::with_id(app, "tray-quit", "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &status,
+            &PredefinedMenuItem::separator(app)?,
+            &show_item,
+            &restart_item,
+            &copy_item,
+            &logs_item,
+            &support_item,
+            &PredefinedMenuItem::separator(app)?,
+            &quit_item,
+        ],
+    )?;
+
+    let mut tray = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(TrayStatus::Starting.label())
+        .menu(&menu)
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            "tray-show" => show_main_window(app),
+            "tray-restart" => restart_server(app),
+            "tray-copy-url" => copy_server_url(app),
+            "tray-logs" => open_logs(app),
+            "tray-copy-support-info" => support_info::spawn_copy(app),
+            "tray-quit" => app.exit(0),
+            _ => {}
+        });
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+
+    app.manage(TrayStatusItem(status));
+    spawn_status(app.clone());
+    Ok(())
+}
+
+/// Brings back the main window, creating it for a launch that hasn't opened one yet.
+pub fn show_main_window(app: &AppHandle) {
+    #[cfg(target_os = "macos")]
+    let _ = app.set_activation_policy(tauri::ActivationPolicy::Regular);
+
+    let existed = app.get_webview_window(MainWindow::LABEL).is_some();
+    match MainWindow::create(app) {
+        Ok(window) if existed => {
+            let _ = window.show();
+        }
+        // A new window shows itself once it has painted
+        Ok(_) => {}
+        Err(e) => tracing::error!("Failed to create main window: {e}"),
+    }
+}
+
+fn spawn_status(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last = None;
+        loop {
+            let status = current_status(&app).await;
+            if last != Some(status) {
+                tracing::info!(?status, "Tray status changed");
+                if let Some(item) = app.try_state::<TrayStatusItem>() {
+                    let _ = item.0.set_text(status.label());
+                }
+                if let Some(tray) = app.tray_by_id(TRAY_ID) {
+                    let _ = tray.set_tooltip(Some(status.label()));
+                }
+                last = Some(status);
+            }
+
+            sleep(if status == TrayStatus::Starting {
+                STARTING_STATUS_INTERVAL
+            } else {
+                STATUS_INTERVAL
+            })
+            .await;
+        }
+    });
+}
+
+async fn current_status(app: &AppHandle) -> TrayStatus {
+    let Some(ready) = ready_server(app) else {
+        return match app.try_state::<ServerState>().map(|state| state.status()) {
+            Some(status) if status.peek().is_some() => TrayStatus::Error,
+            _ => TrayStatus::Starting,
+        };
+    };
+
+    let state = app.state::<ServerState>();
+    if *state.spawned_by_us.lock().unwrap() && state.child.lock().unwrap().is_none() {
+        return TrayStatus::Stopped;
+    }
+
+    if server::check_health(&ready.url, ready.password.as_deref()).await {
+        TrayStatus::Running
+    } else {
+        TrayStatus::Error
+    }
+}
+
+fn ready_server(app: &AppHandle) -> Option<ServerReadyData> {
+    app.try_state::<ServerState>()?.ready()
+}
+
+// Only a sidecar the app spawned can be restarted, adopted and remote servers are left alone
+fn restart_server(app: &AppHandle) {
+    let Some(spawn) = app
+        .try_state::<ServerState>()
+        .and_then(|state| state.spawn())
+    else {
+        tracing::info!("No sidecar spawned by the app to restart");
+        return;
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = server::restart_local_server(app, spawn).await {
+            tracing::error!("Failed to restart sidecar: {e}");
+        }
+    });
+}
+
+fn copy_server_url(app: &AppHandle) {
+    let Some(ready) = ready_server(app) else {
+        tracing::info!("Server isn't ready, nothing to copy");
+        return;
+    };
+
+    if let Err(e) = app.clipboard().write_text(ready.url) {
+        tracing::error!("Failed to copy server URL: {e}");
+    }
+}
+
+pub fn open_logs(app: &AppHandle) {
+    let result = AppDirs::get()
+        .ok_or_else(|| "Could not determine log directory".to_string())
+        .and_then(|dirs| {
+            app.opener()
+                .open_path(dirs.logs.to_string_lossy(), None::<&str>)
+                .map_err(|e| e.to_string())
+        });
+
+    if let Err(e) = result {
+        tracing::error!("Failed to open logs: {e}");
+    }
+}
@@ -0,0 +1,323 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, TimeDelta, Utc};
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_store::StoreExt;
+use tauri_specta::Event;
+
+use crate::{
+    constants::{SETTINGS_STORE, USAGE_DIGEST_KEY, USAGE_DIGEST_SENT_KEY},
+    db_guard::{self, DbQueryError},
+    quiet_hours, sidecar,
+};
+
+const DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(30 * 60);
+const DIGEST_DAYS: u32 = 7;
+
+/// Totals over assistant messages, with subagent sessions counted towards their parent.
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq)]
+pub struct UsageSummary {
+    pub days: u32,
+    pub sessions: u32,
+    pub input_tokens: f64,
+    pub output_tokens: f64,
+    pub reasoning_tokens: f64,
+    pub cache_read_tokens: f64,
+    pub cache_write_tokens: f64,
+    /// In US dollars, as reported by the providers.
+    pub cost: f64,
+}
+
+impl UsageSummary {
+    fn total_tokens(&self) -> f64 {
+        self.input_tokens
+            + self.output_tokens
+            + self.reasoning_tokens
+            + self.cache_read_tokens
+            + self.cache_write_tokens
+    }
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type, Debug)]
+pub struct UsageDigest {
+    pub enabled: bool,
+    /// Day to send the digest on, from 0 for Monday to 6 for Sunday.
+    pub weekday: u32,
+    /// Local hour to send the digest at, from 0 to 23.
+    pub hour: u32,
+}
+
+impl Default for UsageDigest {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            weekday: 0,
+            hour: 9,
+        }
+    }
+}
+
+impl UsageDigest {
+    // The latest scheduled time at or before `now`
+    fn last_scheduled(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        let days_back = (now.weekday().num_days_from_monday() + 7 - self.weekday) % 7;
+        let scheduled =
+            (now.date() - TimeDelta::days(days_back.into())).and_hms_opt(self.hour, 0, 0)?;
+
+        Some(if scheduled > now {
+            scheduled - TimeDelta::days(7)
+        } else {
+            scheduled
+        })
+    }
+
+    /// Whether a digest is owed, i.e. a scheduled time has passed since the last one was sent.
+    /// A digest missed while the app wasn't running is sent late rather than skipped.
+    fn is_due(&self, now: NaiveDateTime, last_sent: Option<NaiveDateTime>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let Some(scheduled) = self.last_scheduled(now) else {
+            return false;
+        };
+
+        last_sent.is_none_or(|sent| sent < scheduled)
+    }
+}
+
+/// Sent along with the digest notification so the app can show the full summary.
+#[derive(tauri_specta::Event, serde::Serialize, serde::Deserialize, Clone, Debug, specta::Type)]
+pub struct UsageDigestReady {
+    pub summary: UsageSummary,
+}
+
+/// Sessions, tokens and costs over the last `days` days, read from the local database.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_usage_summary(app: AppHandle, days: u32) -> Result<UsageSummary, DbQueryError> {
+    let since = (Utc::now() - TimeDelta::days(days.into())).timestamp_millis();
+
+    let summary = sidecar::query_db::<UsageSummary>(
+        &app,
+        &format!(
+            "SELECT {days} AS days, \
+             COUNT(DISTINCT COALESCE(s.parent_id, m.session_id)) AS sessions, \
+             COALESCE(SUM(json_extract(m.data, '$.tokens.input')), 0) AS input_tokens, \
+             COALESCE(SUM(json_extract(m.data, '$.tokens.output')), 0) AS output_tokens, \
+             COALESCE(SUM(json_extract(m.data, '$.tokens.reasoning')), 0) AS reasoning_tokens, \
+             COALESCE(SUM(json_extract(m.data, '$.tokens.cache.read')), 0) AS cache_read_tokens, \
+             COALESCE(SUM(json_extract(m.data, '$.tokens.cache.write')), 0) AS cache_write_tokens, \
+             COALESCE(SUM(json_extract(m.data, '$.cost')), 0) AS cost \
+             FROM message m LEFT JOIN session s ON s.id = m.session_id \
+             WHERE json_extract(m.data, '$.role') = 'assistant' AND m.time_created >= {since}"
+        ),
+    )
+    .await?
+    .into_iter()
+    .next()
+    .unwrap_or_default();
+
+    Ok(UsageSummary { days, ..summary })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_usage_digest(app: AppHandle) -> Result<UsageDigest, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(USAGE_DIGEST_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_usage_digest(app: AppHandle, config: UsageDigest) -> Result<(), String> {
+    if config.weekday > 6 {
+        return Err(format!(
+            "Invalid weekday {}, expected 0 to 6",
+            config.weekday
+        ));
+    }
+    if config.hour > 23 {
+        return Err(format!("Invalid hour {}, expected 0 to 23", config.hour));
+    }
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    // Start counting from now, so enabling the digest doesn't immediately send one
+    if config.enabled && !get_usage_digest(app.clone()).is_ok_and(|current| current.enabled) {
+        store.set(
+            USAGE_DIGEST_SENT_KEY,
+            serde_json::json!(Utc::now().timestamp_millis()),
+        );
+    }
+    store.set(USAGE_DIGEST_KEY, serde_json::json!(config));
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+/// Periodically checks whether the weekly digest is due and sends it, holding it back during
+/// quiet hours.
+pub fn spawn_digest(app: AppHandle) {
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = send_digest_if_due(&app).await {
+                tracing::warn!("Failed to send usage digest: {e}");
+            }
+
+            tokio::time::sleep(DIGEST_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+async fn send_digest_if_due(app: &AppHandle) -> Result<(), String> {
+    let config = get_usage_digest(app.clone())?;
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    let last_sent = store
+        .get(USAGE_DIGEST_SENT_KEY)
+        .and_then(|v| v.as_i64())
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|sent| sent.with_timezone(&Local).naive_local());
+
+    let now = Local::now();
+    if !config.is_due(now.naive_local(), last_sent) || quiet_hours::is_active(app) {
+        return Ok(());
+    }
+
+    let summary = db_guard::retry(|| get_usage_summary(app.clone(), DIGEST_DAYS)).await?;
+
+    app.notification()
+        .builder()
+        .title("Your week in OpenCode")
+        .body(digest_body(&summary))
+        .show()
+        .map_err(|e| format!("Failed to show notification: {}", e))?;
+    let _ = UsageDigestReady {
+        summary: summary.clone(),
+    }
+    .emit(app);
+
+    store.set(
+        USAGE_DIGEST_SENT_KEY,
+        serde_json::json!(now.timestamp_millis()),
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    tracing::info!(?summary, "Sent usage digest");
+    Ok(())
+}
+
+fn digest_body(summary: &UsageSummary) -> String {
+    if summary.sessions == 0 {
+        return "No sessions this week".to_string();
+    }
+
+    format!(
+        "{} {}, {} tokens, ${:.2}",
+        summary.sessions,
+        if summary.sessions == 1 {
+            "session"
+        } else {
+            "sessions"
+        },
+        compact(summary.total_tokens()),
+        summary.cost
+    )
+}
+
+fn compact(value: f64) -> String {
+    match value {
+        v if v >= 1e9 => format!("{:.1}B", v / 1e9),
+        v if v >= 1e6 => format!("{:.1}M", v / 1e6),
+        v if v >= 1e3 => format!("{:.1}K", v / 1e3),
+        v => format!("{v:.0}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> NaiveDateTime {
+        value.parse().unwrap()
+    }
+
+    // 2026-01-12 is a Monday
+    fn monday_nine() -> UsageDigest {
+        UsageDigest {
+            enabled: true,
+            weekday: 0,
+            hour: 9,
+        }
+    }
+
+    #[test]
+    fn due_once_schedule_passes() {
+        let config = monday_nine();
+        let sent = Some(at("2026-01-05T09:10:00"));
+
+        assert!(!config.is_due(at("2026-01-12T08:59:00"), sent));
+        assert!(config.is_due(at("2026-01-12T09:00:00"), sent));
+        assert!(!config.is_due(at("2026-01-12T09:30:00"), Some(at("2026-01-12T09:00:00"))));
+    }
+
+    #[test]
+    fn missed_digest_is_sent_late() {
+        let config = monday_nine();
+        assert!(config.is_due(at("2026-01-15T18:00:00"), Some(at("2026-01-05T09:00:00"))));
+        assert!(config.is_due(at("2026-01-15T18:00:00"), None));
+    }
+
+    #[test]
+    fn disabled_digest_is_never_due() {
+        let config = UsageDigest {
+            enabled: false,
+            ..monday_nine()
+        };
+        assert!(!config.is_due(at("2026-01-12T10:00:00"), None));
+    }
+
+    #[test]
+    fn schedule_wraps_to_previous_week() {
+        let config = UsageDigest {
+            weekday: 6,
+            hour: 20,
+            ..monday_nine()
+        };
+        assert_eq!(
+            config.last_scheduled(at("2026-01-14T12:00:00")),
+            Some(at("2026-01-11T20:00:00"))
+        );
+    }
+
+    #[test]
+    fn formats_digest_body() {
+        let summary = UsageSummary {
+            days: 7,
+            sessions: 12,
+            input_tokens: 1_000_000.0,
+            output_tokens: 200_000.0,
+            cost: 3.456,
+            ..Default::default()
+        };
+        assert_eq!(digest_body(&summary), "12 sessions, 1.2M tokens, $3.46");
+        assert_eq!(
+            digest_body(&UsageSummary::default()),
+            "No sessions this week"
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Checks for command inputs that are passed on to processes, URLs or settings as-is. The
+//! webview is the only caller, but it renders remote content, so a compromised page could send
+//! anything these commands accept.
+
+use std::fmt;
+
+// PATH_MAX on Linux, and well beyond what Windows accepts with long path support
+const MAX_PATH_LEN: usize = 4096;
+const MAX_URL_LEN: usize = 2048;
+const MAX_NAME_LEN: usize = 256;
+const MAX_URLS: usize = 32;
+
+/// Why a command rejected its input.
+#[derive(Clone, serde::Serialize, specta::Type, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InvalidInput {
+    Empty {
+        field: String,
+    },
+    TooLong {
+        field: String,
+        max: u32,
+    },
+    /// Contains a control character such as a newline or NUL.
+    ControlCharacter {
+        field: String,
+    },
+    InvalidUrl {
+        field: String,
+        reason: String,
+    },
+    TooMany {
+        field: String,
+        max: u32,
+    },
+}
+
+impl fmt::Display for InvalidInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty { field } => write!(f, "Invalid {field}: must not be empty"),
+            Self::TooLong { field, max } => {
+                write!(f, "Invalid {field}: longer than {max} characters")
+            }
+            Self::ControlCharacter { field } => {
+                write!(f, "Invalid {field}: contains a control character")
+            }
+            Self::InvalidUrl { field, reason } => write!(f, "Invalid {field}: {reason}"),
+            Self::TooMany { field, max } => write!(f, "Invalid {field}: more than {max} entries"),
+        }
+    }
+}
+
+impl From<InvalidInput> for String {
+    fn from(error: InvalidInput) -> Self {
+        error.to_string()
+    }
+}
+
+pub fn path(field: &str, value: &str) -> Result<(), InvalidInput> {
+    text(field, value, MAX_PATH_LEN)
+}
+
+/// An app name or executable to look up, e.g. `code` or `Visual Studio Code`.
+pub fn name(field: &str, value: &str) -> Result<(), InvalidInput> {
+    text(field, value, MAX_NAME_LEN)
+}
+
+/// An absolute `http` or `https` URL with a host.
+pub fn url(field: &str, value: &str) -> Result<(), InvalidInput> {
+    text(field, value, MAX_URL_LEN)?;
+
+    let invalid = |reason: &str| InvalidInput::InvalidUrl {
+        field: field.to_string(),
+        reason: reason.to_string(),
+    };
+    let parsed = reqwest::Url::parse(value).map_err(|e| invalid(&e.to_string()))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(invalid("must use http or https"));
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err(invalid("must have a host"));
+    }
+
+    Ok(())
+}
+
+/// A route of the app's own frontend, e.g. `/L2hvbWUvbWUvYXBw/session/abc`.
+pub fn route(field: &str, value: &str) -> Result<(), InvalidInput> {
+    text(field, value, MAX_URL_LEN)?;
+
+    // `//host/path` and `/\host/path` are taken for another origin
+    if !value.starts_with('/') || value.starts_with("//") || value.contains('\\') {
+        return Err(InvalidInput::InvalidUrl {
+            field: field.to_string(),
+            reason: "must be a path in the app, starting with /".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+pub fn urls(field: &str, values: &[String]) -> Result<(), InvalidInput> {
+    if values.len() > MAX_URLS {
+        return Err(InvalidInput::TooMany {
+            field: field.to_string(),
+            max: MAX_URLS as u32,
+        });
+    }
+
+    values.iter().try_for_each(|value| url(field, value))
+}
+
+fn text(field: &str, value: &str, max: usize) -> Result<(), InvalidInput> {
+    if value.is_empty() {
+        return Err(InvalidInput::Empty {
+            field: field.to_string(),
+        });
+    }
+    if value.chars().count() > max {
+        return Err(InvalidInput::TooLong {
+            field: field.to_string(),
+            max: max as u32,
+        });
+    }
+    if value.chars().any(char::is_control) {
+        return Err(InvalidInput::ControlCharacter {
+            field: field.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_control_characters() {
+        for value in ["a\nb", "a\0b", "a\u{1b}[2J", "a\rb"] {
+            assert_eq!(
+                path("path", value),
+                Err(InvalidInput::ControlCharacter {
+                    field: "path".to_string()
+                }),
+                "{value:?}"
+            );
+        }
+        assert_eq!(path("path", r"C:\Users\me\Mes Documents"), Ok(()));
+        assert_eq!(path("path", "~/code/naïve"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_empty_and_long_values() {
+        assert!(matches!(name("app", ""), Err(InvalidInput::Empty { .. })));
+        assert!(matches!(
+            name("app", &"a".repeat(MAX_NAME_LEN + 1)),
+            Err(InvalidInput::TooLong { max: 256, .. })
+        ));
+        assert_eq!(name("app", &"é".repeat(MAX_NAME_LEN)), Ok(()));
+    }
+
+    #[test]
+    fn accepts_only_http_urls() {
+        assert_eq!(url("url", "http://127.0.0.1:4096"), Ok(()));
+        assert_eq!(url("url", "https://opencode.example.com/base"), Ok(()));
+
+        for value in [
+            "file:///etc/passwd",
+            "javascript:alert(1)",
+            "localhost:4096",
+            "http://",
+        ] {
+            assert!(
+                matches!(url("url", value), Err(InvalidInput::InvalidUrl { .. })),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_only_app_routes() {
+        assert_eq!(route("route", "/L2hvbWUvbWUvYXBw/session/abc"), Ok(()));
+        assert_eq!(route("route", "/?pip=1"), Ok(()));
+
+        for value in [
+            "https://example.com",
+            "//example.com/x",
+            "/\\example.com/x",
+            "session/abc",
+        ] {
+            assert!(
+                matches!(route("route", value), Err(InvalidInput::InvalidUrl { .. })),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn limits_url_count() {
+        let values = vec!["http://localhost".to_string(); MAX_URLS + 1];
+        assert!(matches!(
+            urls("urls", &values),
+            Err(InvalidInput::TooMany { .. })
+        ));
+        assert_eq!(urls("urls", &values[..MAX_URLS]), Ok(()));
+    }
+
+    #[test]
+    fn formats_errors() {
+        assert_eq!(
+            String::from(InvalidInput::TooLong {
+                field: "path".to_string(),
+                max: 4096
+            }),
+            "Invalid path: longer than 4096 characters"
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use tauri::AppHandle;
+
+use crate::autostart;
+
+/// Passed by the login item so the app starts the sidecar without opening a window.
+pub const WARM_UP_ARG: &str = "--warm-up";
+
+/// Whether this process was started hidden by the login item.
+pub fn is_warm_up_launch() -> bool {
+    std::env::args().any(|arg| arg == WARM_UP_ARG)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_warm_up_enabled(app: AppHandle) -> Result<bool, String> {
+    let state = autostart::get_autostart_state(app)?;
+    Ok(state.enabled && state.hidden)
+}
+
+/// Starts the app in the tray at login and spawns the sidecar before any window opens, so the
+/// first launch of the day skips waiting for the shell and the server. The same as a hidden
+/// autostart.
+#[tauri::command]
+#[specta::specta]
+pub fn set_warm_up_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
+    if enabled {
+        autostart::enable_autostart(app, true)
+    } else {
+        autostart::disable_autostart(app)
+    }
+}
+
+/// Keeps a warm-up launch out of the dock, leaving only the tray icon until a window is opened.
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+pub fn hide_from_dock(app: &AppHandle) -> Result<(), tauri::Error> {
+    #[cfg(target_os = "macos")]
+    app.set_activation_policy(tauri::ActivationPolicy::Accessory)?;
+
+    Ok(())
+}
@@ -0,0 +1,301 @@
+use std::time::Duration;
+
+use tauri::AppHandle;
+use tauri_specta::Event;
+use tokio::time::sleep;
+
+use crate::{
+    cli::CommandChild,
+    limits,
+    server::{self, ServerSpawn},
+};
+
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(tauri_specta::Event, serde::Serialize, serde::Deserialize, Clone, Debug, specta::Type)]
+pub struct SidecarMemoryLimitHit {
+    pub limit_mb: u32,
+    pub usage_mb: u32,
+    pub restarting: bool,
+}
+
+/// Samples the sidecar's memory and kills it when it exceeds the configured limit,
+/// restarting it when the restart policy is enabled.
+pub fn spawn(app: AppHandle, child: CommandChild, spawn: ServerSpawn) {
+    let limits = limits::get(&app);
+    let (Some(limit_mb), Some(pid)) = (limits.memory_limit_mb, child.pid()) else {
+        return;
+    };
+    let Some(sampler) = Sampler::new(&app, pid, &spawn, limit_mb) else {
+        tracing::warn!(
+            pid,
+            "Can't measure the sidecar's memory, not enforcing its limit"
+        );
+        return;
+    };
+
+    tokio::spawn(async move {
+        loop {
+            sleep(WATCHDOG_INTERVAL).await;
+
+            // The processes are gone, so the sidecar exited on its own
+            let Some(usage_mb) = sampler.usage_mb().await else {
+                return;
+            };
+
+            if usage_mb <= limit_mb {
+                continue;
+            }
+
+            tracing::warn!(pid, usage_mb, limit_mb, "Sidecar exceeded memory limit");
+            let _ = child.kill();
+
+            let restarting = limits.restart_on_memory_limit;
+            let _ = SidecarMemoryLimitHit {
+                limit_mb,
+                usage_mb,
+                restarting,
+            }
+            .emit(&app);
+
+            if restarting && let Err(e) = server::restart_local_server(app, spawn).await {
+                tracing::error!("Failed to restart sidecar: {e}");
+            }
+
+            return;
+        }
+    });
+}
+
+/// Where the sidecar's memory is read from.
+enum Sampler {
+    /// The process group the sidecar leads.
+    #[cfg(unix)]
+    Group(u32),
+    /// A job holding the sidecar and whatever it starts, which also caps their memory.
+    #[cfg(windows)]
+    Job(MemoryJob),
+    /// The sidecar's process group inside WSL, found by its port, since the process the app
+    /// started is only `wsl.exe`.
+    #[cfg(windows)]
+    Wsl { distro_args: Vec<String>, port: u32 },
+}
+
+impl Sampler {
+    fn new(app: &AppHandle, pid: u32, spawn: &ServerSpawn, limit_mb: u32) -> Option<Self> {
+        #[cfg(unix)]
+        {
+            let _ = (app, spawn, limit_mb);
+            Some(Self::Group(pid))
+        }
+
+        #[cfg(windows)]
+        {
+            if crate::project::uses_wsl(app, spawn.cwd.as_deref()) {
+                return Some(Self::Wsl {
+                    distro_args: crate::wsl_setup::distro_args(app),
+                    port: spawn.port,
+                });
+            }
+            MemoryJob::attach(pid, limits::memory_ceiling_mb(limit_mb)).map(Self::Job)
+        }
+    }
+
+    /// The sidecar's memory in MB, or `None` once it has exited.
+    async fn usage_mb(&self) -> Option<u32> {
+        match self {
+            #[cfg(unix)]
+            Self::Group(pgid) => {
+                let output = tokio::process::Command::new("ps")
+                    .args(["-A", "-o", "pgid=", "-o", "rss="])
+                    .output()
+                    .await
+                    .ok()?;
+
+                parse_ps_group_rss(&String::from_utf8_lossy(&output.stdout), *pgid)
+                    .map(|kb| kb / 1024)
+            }
+            #[cfg(windows)]
+            Self::Job(job) => job.peak_usage_mb(),
+            #[cfg(windows)]
+            Self::Wsl { distro_args, port } => {
+                let output = crate::wsl_setup::wsl()
+                    .args(distro_args)
+                    .args(["-e", "ps", "-A", "-o", "pgid=", "-o", "rss=", "-o", "args="])
+                    .output()
+                    .await
+                    .ok()?;
+
+                let output = String::from_utf8_lossy(&output.stdout);
+                let pgid = find_serve_group(&output, *port)?;
+                parse_ps_group_rss(&output, pgid).map(|kb| kb / 1024)
+            }
+        }
+    }
+}
+
+/// A job object the sidecar is nested into, alongside the one that ends it with the app.
+#[cfg(windows)]
+struct MemoryJob(windows_sys::Win32::Foundation::HANDLE);
+
+// Safety: the handle is only used through the job object APIs, which may be called from any
+// thread
+#[cfg(windows)]
+unsafe impl Send for MemoryJob {}
+#[cfg(windows)]
+unsafe impl Sync for MemoryJob {}
+
+#[cfg(windows)]
+impl MemoryJob {
+    /// Puts process `pid`, and whatever it starts from then on, in a job that can't commit more
+    /// than `ceiling_mb` between them.
+    fn attach(pid: u32, ceiling_mb: u32) -> Option<Self> {
+        use windows_sys::Win32::{
+            Foundation::CloseHandle,
+            System::{
+                JobObjects::{
+                    AssignProcessToJobObject, CreateJobObjectW, JOB_OBJECT_LIMIT_JOB_MEMORY,
+                    JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JobObjectExtendedLimitInformation,
+                    SetInformationJobObject,
+                },
+                Threading::{OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE},
+            },
+        };
+
+        // Safety: the structs are plain data sized for the calls, and handles are checked
+        // before use
+        unsafe {
+            let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if handle.is_null() {
+                return None;
+            }
+            let job = Self(handle);
+
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_JOB_MEMORY;
+            info.JobMemoryLimit = ceiling_mb as usize * 1024 * 1024;
+            if SetInformationJobObject(
+                job.0,
+                JobObjectExtendedLimitInformation,
+                (&raw const info).cast(),
+                size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            ) == 0
+            {
+                return None;
+            }
+
+            let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid);
+            if process.is_null() {
+                return None;
+            }
+            let assigned = AssignProcessToJobObject(job.0, process);
+            CloseHandle(process);
+
+            (assigned != 0).then_some(job)
+        }
+    }
+
+    /// The most memory the job's processes have had committed at once, in MB, so spikes
+    /// between samples count too. `None` once they've all exited.
+    fn peak_usage_mb(&self) -> Option<u32> {
+        use windows_sys::Win32::System::JobObjects::{
+            JOBOBJECT_BASIC_ACCOUNTING_INFORMATION, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+            JobObjectBasicAccountingInformation, JobObjectExtendedLimitInformation,
+            QueryInformationJobObject,
+        };
+
+        // Safety: the structs are plain data sized for the calls
+        unsafe {
+            let mut accounting: JOBOBJECT_BASIC_ACCOUNTING_INFORMATION = std::mem::zeroed();
+            if QueryInformationJobObject(
+                self.0,
+                JobObjectBasicAccountingInformation,
+                (&raw mut accounting).cast(),
+                size_of::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>() as u32,
+                std::ptr::null_mut(),
+            ) == 0
+                || accounting.ActiveProcesses == 0
+            {
+                return None;
+            }
+
+            let mut limits: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+            if QueryInformationJobObject(
+                self.0,
+                JobObjectExtendedLimitInformation,
+                (&raw mut limits).cast(),
+                size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                std::ptr::null_mut(),
+            ) == 0
+            {
+                return None;
+            }
+
+            Some((limits.PeakJobMemoryUsed / (1024 * 1024)) as u32)
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for MemoryJob {
+    fn drop(&mut self) {
+        // Safety: the handle came from `CreateJobObjectW` and is closed only here. The job
+        // doesn't kill its processes when closed.
+        unsafe {
+            windows_sys::Win32::Foundation::CloseHandle(self.0);
+        }
+    }
+}
+
+// Finds the process group of `opencode serve` on `port` in `ps -o pgid= -o rss= -o args=`
+#[cfg(any(windows, test))]
+fn find_serve_group(output: &str, port: u32) -> Option<u32> {
+    let port = port.to_string();
+    output.lines().find_map(|line| {
+        let parts = line.split_whitespace().collect::<Vec<_>>();
+        let args = parts.get(2..)?;
+        let serves = args.contains(&"serve")
+            && args
+                .windows(2)
+                .any(|pair| pair[0] == "--port" && pair[1] == port);
+        serves.then(|| parts[0].parse().ok()).flatten()
+    })
+}
+
+fn parse_ps_group_rss(output: &str, pgid: u32) -> Option<u32> {
+    let rss = output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let group = parts.next()?.parse::<u32>().ok()?;
+            let rss = parts.next()?.parse::<u32>().ok()?;
+            (group == pgid).then_some(rss)
+        })
+        .collect::<Vec<_>>();
+
+    (!rss.is_empty()).then(|| rss.iter().sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_rss_for_process_group() {
+        let output = "  1 2048\n 42 1024\n 42 3072\n 7 512\n";
+        assert_eq!(parse_ps_group_rss(output, 42), Some(4096));
+        assert_eq!(parse_ps_group_rss(output, 99), None);
+    }
+
+    #[test]
+    fn finds_serve_group_by_port() {
+        let output = "  1  2048 /sbin/init\n\
+                      42  1024 /home/me/.opencode/bin/opencode --print-logs serve --hostname 127.0.0.1 --port 4096\n\
+                      42  3072 node lsp-server\n\
+                      50   512 /home/me/.opencode/bin/opencode serve --port 4097\n";
+        assert_eq!(find_serve_group(output, 4096), Some(42));
+        assert_eq!(find_serve_group(output, 4097), Some(50));
+        assert_eq!(find_serve_group(output, 4098), None);
+        assert_eq!(parse_ps_group_rss(output, 42), Some(4096));
+    }
+}
@@ -0,0 +1,195 @@
+use std::{os::windows::process::CommandExt, path::Path, process::Command};
+
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    IDYES, MB_ICONERROR, MB_ICONWARNING, MB_OK, MB_YESNO, MessageBoxW,
+};
+
+// The Evergreen bootstrapper, which downloads and installs the runtime for this machine
+const BOOTSTRAPPER_URL: &str = "https://go.microsoft.com/fwlink/p/?LinkId=2124703";
+// Product code of the Evergreen runtime in EdgeUpdate's client registrations
+const CLIENT_ID: &str = "{F3017226-FE2A-4295-8BDF-00C3A9A7E4C5}";
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// Makes sure the WebView2 runtime is usable before any window is created, offering to install
+/// it when it's missing or broken. Returns `false` when the app can't start.
+pub fn ensure_runtime() -> bool {
+    if runtime_version().is_some() {
+        return true;
+    }
+
+    if !ask(
+        "OpenCode needs the Microsoft Edge WebView2 Runtime, which is missing or damaged on \
+         this computer.\n\nDownload and install it now?",
+    ) {
+        return false;
+    }
+
+    if let Err(e) = install() {
+        error(&format!(
+            "The WebView2 Runtime couldn't be installed: {e}\n\nYou can install it yourself \
+             from https://developer.microsoft.com/microsoft-edge/webview2/"
+        ));
+        return false;
+    }
+
+    if runtime_version().is_none() {
+        error("The WebView2 Runtime still can't be found. Restart your computer and try again.");
+        return false;
+    }
+
+    true
+}
+
+// Installs register under HKLM for all users, in the 32-bit view on 64-bit Windows, or under
+// HKCU for the current user only
+fn runtime_version() -> Option<String> {
+    [
+        format!(r"HKLM\SOFTWARE\WOW6432Node\Microsoft\EdgeUpdate\Clients\{CLIENT_ID}"),
+        format!(r"HKLM\SOFTWARE\Microsoft\EdgeUpdate\Clients\{CLIENT_ID}"),
+        format!(r"HKCU\Software\Microsoft\EdgeUpdate\Clients\{CLIENT_ID}"),
+    ]
+    .iter()
+    .find_map(|key| {
+        let output = Command::new("reg")
+            .args(["query", key, "/v", "pv"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .ok()?;
+
+        output
+            .status
+            .success()
+            .then(|| parse_version(&String::from_utf8_lossy(&output.stdout)))
+            .flatten()
+    })
+}
+
+fn install() -> Result<(), String> {
+    let dir = tempfile::tempdir().map_err(|e| format!("Failed to create temp dir: {}", e))?;
+    let setup = dir.path().join("MicrosoftEdgeWebview2Setup.exe");
+
+    tauri::async_runtime::block_on(download(&setup))?;
+    verify_signature(&setup)?;
+
+    // Not silent, so the installer's own progress window shows up
+    let status = Command::new(&setup)
+        .arg("/install")
+        .status()
+        .map_err(|e| format!("Failed to run the installer: {}", e))?;
+    if !status.success() {
+        return Err(format!("the installer failed (code={:?})", status.code()));
+    }
+
+    Ok(())
+}
+
+async fn download(path: &Path) -> Result<(), String> {
+    let res = reqwest::get(BOOTSTRAPPER_URL)
+        .await
+        .and_then(|res| res.error_for_status())
+        .map_err(|e| format!("Failed to download the installer: {}", e))?;
+    let bytes = res
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to download the installer: {}", e))?;
+
+    std::fs::write(path, bytes).map_err(|e| format!("Failed to save the installer: {}", e))
+}
+
+// Refuses to run a bootstrapper that isn't Authenticode-signed by Microsoft, e.g. one swapped
+// out by a proxy. The path goes through the environment so it needs no quoting.
+fn verify_signature(path: &Path) -> Result<(), String> {
+    let output = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "$s = Get-AuthenticodeSignature -LiteralPath $env:OPENCODE_WEBVIEW2_SETUP; \
+             '{0}|{1}' -f $s.Status, $s.SignerCertificate.Subject",
+        ])
+        .env("OPENCODE_WEBVIEW2_SETUP", path)
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Failed to check the installer's signature: {}", e))?;
+
+    if !signed_by_microsoft(&String::from_utf8_lossy(&output.stdout)) {
+        return Err("the downloaded installer isn't signed by Microsoft".to_string());
+    }
+
+    Ok(())
+}
+
+// `Get-AuthenticodeSignature` reports e.g. `Valid|CN=Microsoft Corporation, O=Microsoft
+// Corporation, L=Redmond, S=Washington, C=US`
+fn signed_by_microsoft(output: &str) -> bool {
+    let Some((status, subject)) = output.trim().split_once('|') else {
+        return false;
+    };
+
+    status == "Valid"
+        && subject
+            .split(',')
+            .any(|part| part.trim() == "O=Microsoft Corporation")
+}
+
+fn ask(message: &str) -> bool {
+    message_box(message, MB_YESNO | MB_ICONWARNING) == IDYES
+}
+
+fn error(message: &str) {
+    message_box(message, MB_OK | MB_ICONERROR);
+}
+
+fn message_box(message: &str, style: u32) -> i32 {
+    let wide = |value: &str| value.encode_utf16().chain([0]).collect::<Vec<u16>>();
+    let (text, caption) = (wide(message), wide("OpenCode"));
+
+    // Safety: both strings are NUL-terminated and outlive the call, and no owner window is
+    // passed
+    unsafe { MessageBoxW(std::ptr::null_mut(), text.as_ptr(), caption.as_ptr(), style) }
+}
+
+// `reg query` prints e.g. `    pv    REG_SZ    120.0.2210.91`. A broken or half-removed
+// install leaves an empty or zero version behind.
+fn parse_version(output: &str) -> Option<String> {
+    let version = output.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        (fields.next()? == "pv" && fields.next()? == "REG_SZ").then(|| fields.next())?
+    })?;
+
+    version
+        .split('.')
+        .any(|part| part.parse::<u32>().is_ok_and(|n| n > 0))
+        .then(|| version.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_registry_version() {
+        let output = "\r\nHKEY_LOCAL_MACHINE\\SOFTWARE\\WOW6432Node\\Microsoft\\EdgeUpdate\\Clients\\{F3017226-FE2A-4295-8BDF-00C3A9A7E4C5}\r\n    pv    REG_SZ    120.0.2210.91\r\n\r\n";
+        assert_eq!(parse_version(output), Some("120.0.2210.91".to_string()));
+    }
+
+    #[test]
+    fn accepts_only_valid_microsoft_signatures() {
+        let subject =
+            "CN=Microsoft Corporation, O=Microsoft Corporation, L=Redmond, S=Washington, C=US";
+        assert!(signed_by_microsoft(&format!("Valid|{subject}\r\n")));
+        assert!(!signed_by_microsoft(&format!("HashMismatch|{subject}")));
+        assert!(!signed_by_microsoft(
+            "Valid|CN=Microsoft Corporation, O=Not Microsoft Corporation"
+        ));
+        assert!(!signed_by_microsoft("NotSigned|"));
+        assert!(!signed_by_microsoft(""));
+    }
+
+    #[test]
+    fn treats_empty_version_as_missing() {
+        assert_eq!(parse_version("    pv    REG_SZ    0.0.0.0\r\n"), None);
+        assert_eq!(parse_version("    pv    REG_SZ\r\n"), None);
+        assert_eq!(parse_version(""), None);
+    }
+}
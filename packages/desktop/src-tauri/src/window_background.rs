@@ -0,0 +1,90 @@
+//! The color windows are filled with behind the page. It follows the frontend's theme, so a
+//! window opens on the theme's background instead of flashing white or black before the page
+//! has loaded, and resizing doesn't show the wrong color at the edges.
+
+use tauri::{AppHandle, Manager, Runtime, WebviewWindowBuilder, window::Color};
+use tauri_plugin_store::StoreExt;
+
+use crate::{
+    constants::{SETTINGS_STORE, WINDOW_BACKGROUND_KEY},
+    window_effects::{self, WindowEffect},
+};
+
+/// Fills window `label` with `color`, a `#rrggbb` or `#rrggbbaa` hex color, and keeps it for
+/// windows opened later. Does nothing while a window effect is on, which shows instead.
+#[tauri::command]
+#[specta::specta]
+pub fn set_window_background(app: AppHandle, label: String, color: String) -> Result<(), String> {
+    let parsed = parse_color(&color)
+        .ok_or_else(|| format!("Invalid color '{}', expected #rrggbb or #rrggbbaa", color))?;
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("No window with label '{}'", label))?;
+
+    // The page lets a window effect show through, so its background isn't the theme's
+    if window_effects::get_window_effect(app.clone()) != WindowEffect::None {
+        return Ok(());
+    }
+
+    window
+        .set_background_color(Some(parsed))
+        .map_err(|e| format!("Failed to set window background: {}", e))?;
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    if store.get(WINDOW_BACKGROUND_KEY) == Some(serde_json::json!(color)) {
+        return Ok(());
+    }
+    store.set(WINDOW_BACKGROUND_KEY, serde_json::json!(color));
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+/// Fills windows created with `builder` with the last theme's background, unless they're
+/// transparent for a window effect.
+pub fn configure<'a, R: Runtime, M: Manager<R>>(
+    builder: WebviewWindowBuilder<'a, R, M>,
+    app: &AppHandle,
+) -> WebviewWindowBuilder<'a, R, M> {
+    if window_effects::get_window_effect(app.clone()) != WindowEffect::None {
+        return builder;
+    }
+
+    let saved = app
+        .store(SETTINGS_STORE)
+        .ok()
+        .and_then(|store| store.get(WINDOW_BACKGROUND_KEY))
+        .and_then(|value| value.as_str().and_then(parse_color));
+    match saved {
+        Some(color) => builder.background_color(color),
+        None => builder,
+    }
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#')?;
+    if !matches!(hex.len(), 6 | 8) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(Color(channel(0)?, channel(2)?, channel(4)?, alpha))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hex_colors() {
+        assert_eq!(parse_color("#0a0a0a"), Some(Color(10, 10, 10, 255)));
+        assert_eq!(parse_color("#FFFFFF80"), Some(Color(255, 255, 255, 128)));
+        assert_eq!(parse_color("0a0a0a"), None);
+        assert_eq!(parse_color("#fff"), None);
+        assert_eq!(parse_color("#gggggg"), None);
+        assert_eq!(parse_color("#+f+f+f"), None);
+    }
+}
@@ -0,0 +1,189 @@
+//! Translucent window backgrounds drawn by the OS: vibrancy on macOS, Mica and Acrylic on
+//! Windows, and blur behind the window where the compositor offers it. The frontend lets the
+//! effect show through its background while one is on.
+
+use tauri::{
+    AppHandle, Manager, Runtime, WebviewWindow, WebviewWindowBuilder,
+    window::{Effect, EffectsBuilder},
+};
+use tauri_plugin_store::StoreExt;
+
+use crate::constants::{SETTINGS_STORE, WINDOW_EFFECT_KEY};
+
+#[derive(
+    Clone, Copy, Default, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowEffect {
+    #[default]
+    None,
+    /// macOS only.
+    Vibrancy,
+    /// Windows 11 only.
+    Mica,
+    /// Windows only.
+    Acrylic,
+    /// Windows, and KDE Plasma on X11. GNOME has no way for apps to ask for blur.
+    Blur,
+}
+
+impl WindowEffect {
+    fn supported(self) -> bool {
+        match self {
+            Self::None => true,
+            Self::Vibrancy => cfg!(target_os = "macos"),
+            Self::Mica | Self::Acrylic => cfg!(windows),
+            Self::Blur => cfg!(any(windows, target_os = "linux")),
+        }
+    }
+
+    fn native(self) -> Option<Effect> {
+        match self {
+            Self::None => None,
+            Self::Vibrancy => Some(Effect::UnderWindowBackground),
+            Self::Mica => Some(Effect::Mica),
+            Self::Acrylic => Some(Effect::Acrylic),
+            Self::Blur => Some(Effect::Blur),
+        }
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_window_effect(app: AppHandle) -> WindowEffect {
+    app.store(SETTINGS_STORE)
+        .ok()
+        .and_then(|store| store.get(WINDOW_EFFECT_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .filter(|effect: &WindowEffect| effect.supported())
+        .unwrap_or_default()
+}
+
+/// Applies `effect` to every window and keeps it for new ones. Returns whether windows need to be
+/// reopened to show it, which is the case when turning an effect on in windows that were opened
+/// without one.
+#[tauri::command]
+#[specta::specta]
+pub fn set_window_effect(app: AppHandle, effect: WindowEffect) -> Result<bool, String> {
+    if !effect.supported() {
+        return Err(format!(
+            "The {:?} window effect isn't available on this platform",
+            effect
+        ));
+    }
+
+    let previous = get_window_effect(app.clone());
+    for window in app.webview_windows().into_values() {
+        apply(&window, effect)?;
+    }
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    store.set(WINDOW_EFFECT_KEY, serde_json::json!(effect));
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    // Transparency can only be set when a window is created
+    Ok(previous == WindowEffect::None && effect != WindowEffect::None)
+}
+
+/// Makes windows created with `builder` transparent with the saved effect behind them.
+pub fn configure<'a, R: Runtime, M: Manager<R>>(
+    builder: WebviewWindowBuilder<'a, R, M>,
+    app: &AppHandle,
+) -> WebviewWindowBuilder<'a, R, M> {
+    let effect = get_window_effect(app.clone());
+    let Some(native) = effect.native() else {
+        return builder;
+    };
+
+    let builder = builder.transparent(true);
+    if cfg!(target_os = "linux") {
+        // Blur on Linux is a hint set once the window exists, see `attach`
+        return builder;
+    }
+    builder.effects(EffectsBuilder::new().effect(native).build())
+}
+
+/// Sets up what can only be done once `window` exists.
+pub fn attach(_app: &AppHandle, _window: &WebviewWindow) {
+    #[cfg(target_os = "linux")]
+    if get_window_effect(_app.clone()) == WindowEffect::Blur
+        && let Err(e) = set_blur_hint(_window, true)
+    {
+        tracing::warn!(label = _window.label(), "Failed to set blur hint: {e}");
+    }
+}
+
+/// Script for new windows to let the effect show through the page.
+pub fn initialization_script(app: &AppHandle) -> String {
+    format!(
+        r#"
+            document.addEventListener("DOMContentLoaded", () => {{
+              {}
+            }});
+          "#,
+        page_script(get_window_effect(app.clone()))
+    )
+}
+
+fn apply(window: &WebviewWindow, effect: WindowEffect) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    set_blur_hint(window, effect == WindowEffect::Blur)?;
+    #[cfg(not(target_os = "linux"))]
+    window
+        .set_effects(
+            effect
+                .native()
+                .map(|native| EffectsBuilder::new().effect(native).build()),
+        )
+        .map_err(|e| format!("Failed to set window effect: {}", e))?;
+
+    let _ = window.eval(page_script(effect));
+    Ok(())
+}
+
+fn page_script(effect: WindowEffect) -> String {
+    match effect {
+        WindowEffect::None => "delete document.documentElement.dataset.windowEffect".to_string(),
+        effect => format!(
+            "document.documentElement.dataset.windowEffect = {}",
+            serde_json::json!(effect)
+        ),
+    }
+}
+
+// KWin blurs behind windows that set this property, an empty region meaning the whole window
+#[cfg(target_os = "linux")]
+fn set_blur_hint(window: &WebviewWindow, enabled: bool) -> Result<(), String> {
+    use gtk::{
+        gdk::{self, Atom, ChangeData, PropMode},
+        prelude::WidgetExt,
+    };
+
+    let gtk_window = window
+        .gtk_window()
+        .map_err(|e| format!("Failed to set blur: {}", e))?;
+    window
+        .run_on_main_thread(move || {
+            let Some(gdk_window) = gtk_window.window() else {
+                return;
+            };
+            let property = Atom::intern("_KDE_NET_WM_BLUR_BEHIND_REGION");
+            if enabled {
+                gdk::property_change(
+                    &gdk_window,
+                    &property,
+                    &Atom::intern("CARDINAL"),
+                    32,
+                    PropMode::Replace,
+                    ChangeData::ULongs(&[]),
+                );
+            } else {
+                gdk::property_delete(&gdk_window, &property);
+            }
+        })
+        .map_err(|e| format!("Failed to set blur: {}", e))
+}
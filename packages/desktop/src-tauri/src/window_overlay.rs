@@ -0,0 +1,133 @@
+use tauri::{AppHandle, Manager};
+
+/// Status shown over a window's icon so it can be told apart from other opencode windows.
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IconOverlay {
+    Running,
+    NeedsAttention,
+    Error,
+}
+
+impl IconOverlay {
+    #[cfg_attr(target_os = "macos", allow(dead_code))]
+    fn color(self) -> [u8; 3] {
+        match self {
+            Self::Running => [0x22, 0xa0, 0x6b],
+            Self::NeedsAttention => [0xf5, 0x9e, 0x0b],
+            Self::Error => [0xdc, 0x26, 0x26],
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    fn badge(self) -> &'static str {
+        match self {
+            Self::Running => "●",
+            Self::NeedsAttention => "!",
+            Self::Error => "×",
+        }
+    }
+}
+
+/// Draws `kind` over the app icon of window `label`, or restores the plain icon for `None`.
+/// The dock icon on macOS is shared by every window, so there the glyph is shown as a badge.
+#[tauri::command]
+#[specta::specta]
+pub fn set_window_icon_overlay(
+    app: AppHandle,
+    label: String,
+    kind: Option<IconOverlay>,
+) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("No window with label '{}'", label))?;
+
+    #[cfg(target_os = "macos")]
+    {
+        window
+            .set_badge_label(kind.map(|kind| kind.badge().to_string()))
+            .map_err(|e| format!("Failed to set dock badge: {}", e))
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let icon = app
+            .default_window_icon()
+            .ok_or_else(|| "App has no window icon".to_string())?;
+
+        let mut rgba = icon.rgba().to_vec();
+        if let Some(kind) = kind {
+            composite(&mut rgba, icon.width(), icon.height(), kind.color());
+        }
+
+        window
+            .set_icon(tauri::image::Image::new_owned(
+                rgba,
+                icon.width(),
+                icon.height(),
+            ))
+            .map_err(|e| format!("Failed to set window icon: {}", e))
+    }
+}
+
+// A filled dot with a white ring in the bottom-right corner, anti-aliased over one pixel
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+fn composite(rgba: &mut [u8], width: u32, height: u32, color: [u8; 3]) {
+    let size = width.min(height) as f32;
+    let radius = size * 0.22;
+    let ring = (radius * 0.2).max(1.0);
+    let cx = width as f32 - radius - size * 0.03;
+    let cy = height as f32 - radius - size * 0.03;
+
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f32 + 0.5 - cx;
+            let dy = y as f32 + 0.5 - cy;
+            let distance = (dx * dx + dy * dy).sqrt();
+
+            let coverage = (radius + 0.5 - distance).clamp(0.0, 1.0);
+            if coverage == 0.0 {
+                continue;
+            }
+
+            let fill = (radius - ring + 0.5 - distance).clamp(0.0, 1.0);
+            let src = [0, 1, 2].map(|i| color[i] as f32 * fill + 255.0 * (1.0 - fill));
+
+            let offset = ((y * width + x) * 4) as usize;
+            let Some(pixel) = rgba.get_mut(offset..offset + 4) else {
+                continue;
+            };
+            for (channel, src) in pixel.iter_mut().zip(src.into_iter().chain([255.0])) {
+                *channel = (src * coverage + *channel as f32 * (1.0 - coverage)).round() as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(rgba: &[u8], width: u32, x: u32, y: u32) -> [u8; 4] {
+        let offset = ((y * width + x) * 4) as usize;
+        rgba[offset..offset + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn draws_dot_in_bottom_right_corner() {
+        let mut rgba = vec![0; 100 * 100 * 4];
+        composite(&mut rgba, 100, 100, IconOverlay::Error.color());
+
+        // centre of the dot, its ring, and untouched icon
+        assert_eq!(pixel(&rgba, 100, 75, 75), [0xdc, 0x26, 0x26, 255]);
+        assert_eq!(pixel(&rgba, 100, 75, 55), [255, 255, 255, 255]);
+        assert_eq!(pixel(&rgba, 100, 10, 10), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn ignores_short_buffers() {
+        let mut rgba = vec![0; 16];
+        composite(&mut rgba, 100, 100, IconOverlay::Running.color());
+        assert_eq!(rgba, vec![0; 16]);
+    }
+}
@@ -0,0 +1,299 @@
+//! Per-window display preferences, saved by window label so each window comes back the way it
+//! was left, e.g. floating half-transparent over an editor.
+
+use std::collections::HashMap;
+
+use tauri::{AppHandle, LogicalSize, Manager, WebviewWindow};
+use tauri_plugin_store::StoreExt;
+
+use crate::constants::{SETTINGS_STORE, WINDOW_PREFERENCES_KEY};
+
+// Below this the window is too faint to find again
+const MIN_OPACITY: f64 = 0.2;
+const COMPACT_SIZE: LogicalSize<f64> = LogicalSize::new(440.0, 720.0);
+const DEFAULT_SIZE: LogicalSize<f64> = LogicalSize::new(1280.0, 800.0);
+// Matches the range the frontend's zoom shortcuts step through
+const MIN_ZOOM: f64 = 0.2;
+const MAX_ZOOM: f64 = 10.0;
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize, Debug)]
+struct WindowPreferences {
+    always_on_top: bool,
+    opacity: Option<f64>,
+    compact: bool,
+    /// The window's size before it went compact, to return to afterwards.
+    expanded: Option<ExpandedBounds>,
+    zoom: Option<f64>,
+    /// Name of the monitor the window was last on, to go back to when it's restored off-screen.
+    monitor: Option<String>,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
+struct ExpandedBounds {
+    width: f64,
+    height: f64,
+    maximized: bool,
+}
+
+/// Keeps the focused window above all others.
+#[tauri::command]
+#[specta::specta]
+pub fn set_window_always_on_top(
+    app: AppHandle,
+    window: WebviewWindow,
+    enabled: bool,
+) -> Result<(), String> {
+    let window = focused_window(&app, window);
+    window
+        .set_always_on_top(enabled)
+        .map_err(|e| format!("Failed to set always on top: {}", e))?;
+
+    update(&app, window.label(), |prefs| prefs.always_on_top = enabled)
+}
+
+/// Sets how opaque the focused window is, from 0.2 to 1. Needs a compositor on Linux.
+#[tauri::command]
+#[specta::specta]
+pub fn set_window_opacity(
+    app: AppHandle,
+    window: WebviewWindow,
+    opacity: f64,
+) -> Result<(), String> {
+    if !(MIN_OPACITY..=1.0).contains(&opacity) {
+        return Err(format!(
+            "Opacity must be between {} and 1, got {}",
+            MIN_OPACITY, opacity
+        ));
+    }
+
+    let window = focused_window(&app, window);
+    set_opacity(&window, opacity)?;
+
+    update(&app, window.label(), |prefs| {
+        prefs.opacity = (opacity < 1.0).then_some(opacity)
+    })
+}
+
+/// Shrinks the focused window to a narrow pane, or brings back its earlier size.
+#[tauri::command]
+#[specta::specta]
+pub fn set_compact_mode(
+    app: AppHandle,
+    window: WebviewWindow,
+    enabled: bool,
+) -> Result<(), String> {
+    let window = focused_window(&app, window);
+    let prefs = load(&app).remove(window.label()).unwrap_or_default();
+    if prefs.compact == enabled {
+        return Ok(());
+    }
+
+    let expanded = if enabled {
+        let scale = window.scale_factor().map_err(|e| e.to_string())?;
+        let size = window
+            .inner_size()
+            .map_err(|e| format!("Failed to read window size: {}", e))?
+            .to_logical::<f64>(scale);
+        let expanded = ExpandedBounds {
+            width: size.width,
+            height: size.height,
+            maximized: window.is_maximized().unwrap_or(false),
+        };
+        enter_compact(&window).map_err(|e| format!("Failed to enter compact mode: {}", e))?;
+        Some(expanded)
+    } else {
+        leave_compact(&window, prefs.expanded.as_ref())
+            .map_err(|e| format!("Failed to leave compact mode: {}", e))?;
+        None
+    };
+
+    update(&app, window.label(), |prefs| {
+        prefs.compact = enabled;
+        prefs.expanded = expanded;
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_zoom_level(app: AppHandle, window: WebviewWindow) -> f64 {
+    load(&app)
+        .remove(window.label())
+        .and_then(|prefs| prefs.zoom)
+        .unwrap_or(1.0)
+}
+
+/// Scales the calling window's content, where 1 is the default size. Kept for the window's label,
+/// since pinch and the webview's own zoom shortcuts are disabled.
+#[tauri::command]
+#[specta::specta]
+pub fn set_zoom_level(app: AppHandle, window: WebviewWindow, level: f64) -> Result<(), String> {
+    if !(MIN_ZOOM..=MAX_ZOOM).contains(&level) {
+        return Err(format!(
+            "Zoom level must be between {} and {}, got {}",
+            MIN_ZOOM, MAX_ZOOM, level
+        ));
+    }
+
+    window
+        .set_zoom(level)
+        .map_err(|e| format!("Failed to set zoom level: {}", e))?;
+
+    update(&app, window.label(), |prefs| {
+        prefs.zoom = (level != 1.0).then_some(level)
+    })
+}
+
+/// Reapplies the preferences saved for `window`'s label after it's created.
+pub fn restore(app: &AppHandle, window: &WebviewWindow) {
+    let Some(prefs) = load(app).remove(window.label()) else {
+        return;
+    };
+
+    if prefs.always_on_top {
+        let _ = window.set_always_on_top(true);
+    }
+    if let Some(opacity) = prefs.opacity
+        && let Err(e) = set_opacity(window, opacity)
+    {
+        tracing::warn!(label = window.label(), "Failed to restore opacity: {e}");
+    }
+    if prefs.compact {
+        let _ = enter_compact(window);
+    }
+    if let Some(zoom) = prefs.zoom {
+        let _ = window.set_zoom(zoom);
+    }
+}
+
+/// The monitor `label` was last on.
+pub fn saved_monitor(app: &AppHandle, label: &str) -> Option<String> {
+    load(app).remove(label).and_then(|prefs| prefs.monitor)
+}
+
+/// Records the monitor `window` is on now, after it's been moved.
+pub fn remember_monitor(app: &AppHandle, window: &WebviewWindow) {
+    let Some(name) = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|monitor| monitor.name().cloned())
+    else {
+        return;
+    };
+    if saved_monitor(app, window.label()).as_ref() == Some(&name) {
+        return;
+    }
+
+    if let Err(e) = update(app, window.label(), |prefs| prefs.monitor = Some(name)) {
+        tracing::warn!(label = window.label(), "Failed to remember monitor: {e}");
+    }
+}
+
+// Commands run for the window that invoked them, which is normally but not always the one in
+// front
+fn focused_window(app: &AppHandle, caller: WebviewWindow) -> WebviewWindow {
+    app.webview_windows()
+        .into_values()
+        .find(|window| window.is_focused().unwrap_or(false))
+        .unwrap_or(caller)
+}
+
+fn enter_compact(window: &WebviewWindow) -> Result<(), tauri::Error> {
+    window.unmaximize()?;
+    window.set_size(COMPACT_SIZE)
+}
+
+fn leave_compact(
+    window: &WebviewWindow,
+    expanded: Option<&ExpandedBounds>,
+) -> Result<(), tauri::Error> {
+    match expanded {
+        Some(bounds) if bounds.maximized => window.maximize(),
+        Some(bounds) => window.set_size(LogicalSize::new(bounds.width, bounds.height)),
+        None => window.set_size(DEFAULT_SIZE),
+    }
+}
+
+fn load(app: &AppHandle) -> HashMap<String, WindowPreferences> {
+    app.store(SETTINGS_STORE)
+        .ok()
+        .and_then(|store| store.get(WINDOW_PREFERENCES_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+fn update(
+    app: &AppHandle,
+    label: &str,
+    change: impl FnOnce(&mut WindowPreferences),
+) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    let mut all = load(app);
+    change(all.entry(label.to_string()).or_default());
+    store.set(WINDOW_PREFERENCES_KEY, serde_json::json!(all));
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+#[cfg(target_os = "linux")]
+fn set_opacity(window: &WebviewWindow, opacity: f64) -> Result<(), String> {
+    let gtk_window = window
+        .gtk_window()
+        .map_err(|e| format!("Failed to set opacity: {}", e))?;
+    window
+        .run_on_main_thread(move || {
+            use gtk::prelude::WidgetExt;
+            gtk_window.set_opacity(opacity);
+        })
+        .map_err(|e| format!("Failed to set opacity: {}", e))
+}
+
+#[cfg(target_os = "macos")]
+fn set_opacity(window: &WebviewWindow, opacity: f64) -> Result<(), String> {
+    let ns_window = window
+        .ns_window()
+        .map_err(|e| format!("Failed to set opacity: {}", e))? as usize;
+    window
+        .run_on_main_thread(move || {
+            // Safety: the pointer is the window's live NSWindow, used on the main thread
+            unsafe {
+                let ns_window = &*(ns_window as *const objc2::runtime::AnyObject);
+                let _: () = objc2::msg_send![ns_window, setAlphaValue: opacity];
+            }
+        })
+        .map_err(|e| format!("Failed to set opacity: {}", e))
+}
+
+#[cfg(windows)]
+fn set_opacity(window: &WebviewWindow, opacity: f64) -> Result<(), String> {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GWL_EXSTYLE, GetWindowLongPtrW, LWA_ALPHA, SetLayeredWindowAttributes, SetWindowLongPtrW,
+        WS_EX_LAYERED,
+    };
+
+    let hwnd = window
+        .hwnd()
+        .map_err(|e| format!("Failed to set opacity: {}", e))?
+        .0;
+    let alpha = (opacity * 255.0).round() as u8;
+
+    // Safety: the handle belongs to a live window owned by this process
+    let ok = unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED as isize);
+        SetLayeredWindowAttributes(hwnd, 0, alpha, LWA_ALPHA)
+    };
+    if ok == 0 {
+        return Err(format!(
+            "Failed to set opacity: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+
+    Ok(())
+}
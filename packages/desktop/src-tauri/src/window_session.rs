@@ -0,0 +1,157 @@
+//! Which windows were open when the app was last left, and where each one was, so the next
+//! launch can reopen them. Sizes and positions are restored separately by the window-state
+//! plugin.
+
+use tauri::{AppHandle, Manager, WebviewWindow};
+use tauri_plugin_store::StoreExt;
+
+use crate::{
+    constants::WINDOW_SESSION_STORE,
+    launch_args::LaunchArgs,
+    validate,
+    windows::{self, MainWindow, ProjectWindows},
+};
+
+const WINDOWS_KEY: &str = "windows";
+
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+struct SessionWindow {
+    label: String,
+    /// The project a project window was opened on, `None` for the main window.
+    project_path: Option<String>,
+    /// Path, query and fragment of the page the window was showing.
+    route: Option<String>,
+}
+
+/// Records the windows that are open now, e.g. as the app exits. A kiosk launch leaves the
+/// session alone, so the next normal launch still gets it back.
+pub fn save(app: &AppHandle) {
+    if LaunchArgs::get().kiosk {
+        return;
+    }
+
+    let projects = app.state::<ProjectWindows>();
+    let windows = app
+        .webview_windows()
+        .into_values()
+        .filter_map(|window| {
+            let label = window.label().to_string();
+            let project_path = projects
+                .path(&label)
+                .map(|path| path.to_string_lossy().to_string());
+            if label != MainWindow::LABEL && project_path.is_none() {
+                return None;
+            }
+
+            Some(SessionWindow {
+                route: window.url().ok().as_ref().and_then(route),
+                label,
+                project_path,
+            })
+        })
+        .collect::<Vec<_>>();
+    // Exiting after the last window closed, which already saved it
+    if windows.is_empty() {
+        return;
+    }
+
+    let result = app
+        .store(WINDOW_SESSION_STORE)
+        .map_err(|e| e.to_string())
+        .and_then(|store| {
+            store.set(WINDOWS_KEY, serde_json::json!(windows));
+            store.save().map_err(|e| e.to_string())
+        });
+    match result {
+        Ok(()) => tracing::info!(count = windows.len(), "Saved window session"),
+        Err(e) => tracing::warn!("Failed to save window session: {e}"),
+    }
+}
+
+/// Whether closing window `label` leaves no windows to save afterwards, so the session has to
+/// be saved before it goes.
+pub fn is_last_window(app: &AppHandle, label: &str) -> bool {
+    let projects = app.state::<ProjectWindows>();
+    app.webview_windows()
+        .keys()
+        .filter(|other| *other != label)
+        .all(|other| other != MainWindow::LABEL && projects.path(other).is_none())
+}
+
+/// Reopens the project windows of the last session and returns every window to the page it
+/// was on. The main window must already exist.
+pub fn restore(app: &AppHandle) {
+    if LaunchArgs::get().kiosk {
+        return;
+    }
+
+    let windows = app
+        .store(WINDOW_SESSION_STORE)
+        .ok()
+        .and_then(|store| store.get(WINDOWS_KEY))
+        .and_then(|v| serde_json::from_value::<Vec<SessionWindow>>(v).ok())
+        .unwrap_or_default();
+
+    for saved in windows {
+        let window = match &saved.project_path {
+            None => app.get_webview_window(MainWindow::LABEL),
+            Some(path) => match windows::create_project_window(app.clone(), path.clone()) {
+                Ok(label) => app.get_webview_window(&label),
+                Err(e) => {
+                    tracing::warn!(%path, "Failed to reopen project window: {e}");
+                    continue;
+                }
+            },
+        };
+
+        if let (Some(window), Some(route)) = (window, &saved.route)
+            && let Err(e) = navigate(&window, route)
+        {
+            tracing::warn!(label = saved.label, "Failed to restore route: {e}");
+        }
+    }
+}
+
+fn navigate(window: &WebviewWindow, route: &str) -> Result<(), String> {
+    // A saved route is joined onto the window's own URL, so it must not point anywhere else
+    validate::route("route", route)?;
+
+    let url = window
+        .url()
+        .map_err(|e| e.to_string())?
+        .join(route)
+        .map_err(|e| e.to_string())?;
+    window.navigate(url).map_err(|e| e.to_string())
+}
+
+// The start page isn't worth saving
+fn route(url: &tauri::Url) -> Option<String> {
+    let mut route = url.path().to_string();
+    if let Some(query) = url.query() {
+        route.push('?');
+        route.push_str(query);
+    }
+    if let Some(fragment) = url.fragment() {
+        route.push('#');
+        route.push_str(fragment);
+    }
+    (route != "/").then_some(route)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_path_query_and_fragment() {
+        let url = tauri::Url::parse("tauri://localhost/L2hvbWU/session/abc?tab=1#end").unwrap();
+        assert_eq!(
+            route(&url),
+            Some("/L2hvbWU/session/abc?tab=1#end".to_string())
+        );
+        assert_eq!(
+            route(&tauri::Url::parse("http://localhost:1420/").unwrap()),
+            None
+        );
+    }
+}
@@ -0,0 +1,330 @@
+//! Saves windows' size and position for the window-state plugin as they change, along with the
+//! monitor each is on. One task debounces saves for every window, coalescing a burst of moves
+//! such as a drag into a single write at most once a second, and saves happen right away when a
+//! window loses focus, closes or the app is about to exit, so the last geometry isn't lost to an
+//! abrupt shutdown. Both the debounce and whether geometry is kept at all are settings, as the
+//! writes stutter on slow disks such as network home directories.
+//!
+//! There's no portable event before the system sleeps. Waking up is noticed instead, from the
+//! wall clock jumping ahead of the monotonic one, which doesn't count time asleep on Linux
+//! and macOS. Monitors are often unplugged in the meantime, so windows are checked then too.
+
+use std::{
+    collections::HashSet,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicU32, Ordering},
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use tauri::{AppHandle, Manager, WebviewWindow, WindowEvent};
+use tauri_plugin_store::StoreExt;
+use tauri_plugin_window_state::AppHandleExt;
+use tokio::{
+    sync::Notify,
+    time::{sleep, timeout},
+};
+
+use crate::{
+    app_dirs::AppDirs,
+    constants::{SETTINGS_STORE, WINDOW_STATE_KEY, window_state_flags},
+    monitors, window_prefs,
+};
+
+const MAX_DEBOUNCE_MS: u32 = 10_000;
+// Writes are spaced at least this far apart, however short the debounce
+const MIN_SAVE_INTERVAL: Duration = Duration::from_secs(1);
+const WAKE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+// More wall-clock time than this beyond the check interval means the system slept
+const MIN_SLEEP: Duration = Duration::from_secs(30);
+
+#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type, Debug, PartialEq)]
+#[serde(default)]
+pub struct WindowStateSettings {
+    /// Keep windows' size and position between launches. Turning it back on needs a restart.
+    pub enabled: bool,
+    /// How long windows must stay still before their geometry is written, up to 10 seconds.
+    pub debounce_ms: u32,
+}
+
+impl Default for WindowStateSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            debounce_ms: 200,
+        }
+    }
+}
+
+pub struct WindowStateSaver {
+    /// Windows that changed since the last save.
+    pending: Mutex<HashSet<String>>,
+    changed: Notify,
+    /// Windows with a listener, so one recreated under the same label gets a new one.
+    tracked: Mutex<HashSet<String>>,
+    /// Whether the window-state plugin was registered at launch.
+    registered: bool,
+    enabled: AtomicBool,
+    debounce_ms: AtomicU32,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_window_state_settings(app: AppHandle) -> Result<WindowStateSettings, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(WINDOW_STATE_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+/// Saves the settings and applies what can be applied now. Returns whether the app needs to be
+/// restarted, which is the case when turning persistence back on.
+#[tauri::command]
+#[specta::specta]
+pub fn set_window_state_settings(
+    app: AppHandle,
+    settings: WindowStateSettings,
+) -> Result<bool, String> {
+    if settings.debounce_ms > MAX_DEBOUNCE_MS {
+        return Err(format!(
+            "Debounce must be at most {} ms, got {}",
+            MAX_DEBOUNCE_MS, settings.debounce_ms
+        ));
+    }
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    store.set(WINDOW_STATE_KEY, serde_json::json!(settings));
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    let Some(saver) = app.try_state::<WindowStateSaver>() else {
+        return Ok(false);
+    };
+    saver
+        .debounce_ms
+        .store(settings.debounce_ms, Ordering::Relaxed);
+    saver
+        .enabled
+        .store(settings.enabled && saver.registered, Ordering::Relaxed);
+
+    Ok(settings.enabled && !saver.registered)
+}
+
+/// Whether to register the window-state plugin, read from the settings file before the store
+/// plugin is available.
+pub fn enabled_at_launch() -> bool {
+    AppDirs::get()
+        .and_then(|dirs| std::fs::read_to_string(dirs.data.join(SETTINGS_STORE)).ok())
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .and_then(|root| root.get(WINDOW_STATE_KEY).cloned())
+        .and_then(|v| serde_json::from_value::<WindowStateSettings>(v).ok())
+        .unwrap_or_default()
+        .enabled
+}
+
+/// Starts the task saving window state. Call once, before any window is tracked, with whether
+/// the window-state plugin was registered.
+pub fn spawn(app: &AppHandle, registered: bool) {
+    let settings = get_window_state_settings(app.clone()).unwrap_or_default();
+    app.manage(WindowStateSaver {
+        pending: Mutex::new(HashSet::new()),
+        changed: Notify::new(),
+        tracked: Mutex::new(HashSet::new()),
+        registered,
+        enabled: AtomicBool::new(registered),
+        debounce_ms: AtomicU32::new(settings.debounce_ms.min(MAX_DEBOUNCE_MS)),
+    });
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let saver = handle.state::<WindowStateSaver>();
+        let mut last_save = None::<Instant>;
+        loop {
+            saver.changed.notified().await;
+
+            // Wait for the windows to settle, then for the last write to be far enough behind.
+            // Every window that changes meanwhile is saved in the same write
+            let debounce = Duration::from_millis(saver.debounce_ms.load(Ordering::Relaxed).into());
+            while timeout(debounce, saver.changed.notified()).await.is_ok() {}
+            sleep(save_delay(last_save.map(|at| at.elapsed()))).await;
+
+            let labels = std::mem::take(&mut *saver.pending.lock().unwrap());
+            if labels.is_empty() {
+                // Saved meanwhile on blur or close
+                continue;
+            }
+            last_save = Some(Instant::now());
+            let app = handle.clone();
+            let _ = handle.run_on_main_thread(move || save(&app, labels));
+        }
+    });
+
+    spawn_wake_watch(app.clone());
+}
+
+/// Saves `window`'s state as it changes, and moves it back on-screen when it was restored onto a
+/// monitor that's gone.
+pub fn track(app: &AppHandle, window: &WebviewWindow) {
+    monitors::ensure_visible(app, window);
+
+    let Some(saver) = app.try_state::<WindowStateSaver>() else {
+        return;
+    };
+    if !saver
+        .tracked
+        .lock()
+        .unwrap()
+        .insert(window.label().to_string())
+    {
+        return;
+    }
+
+    let app = app.clone();
+    let label = window.label().to_string();
+    window.on_window_event(move |event| match event {
+        WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+            let saver = app.state::<WindowStateSaver>();
+            if saver.enabled.load(Ordering::Relaxed) {
+                saver.pending.lock().unwrap().insert(label.clone());
+                saver.changed.notify_one();
+            }
+        }
+        WindowEvent::Focused(false) | WindowEvent::CloseRequested { .. } => save_now(&app, &label),
+        WindowEvent::Destroyed => {
+            app.state::<WindowStateSaver>()
+                .tracked
+                .lock()
+                .unwrap()
+                .remove(&label);
+        }
+        _ => {}
+    });
+}
+
+/// Saves every tracked window now, e.g. when the app is about to exit. Call on the main thread.
+pub fn save_all(app: &AppHandle) {
+    let Some(saver) = app.try_state::<WindowStateSaver>() else {
+        return;
+    };
+    saver.pending.lock().unwrap().clear();
+    let labels = saver.tracked.lock().unwrap().clone();
+    save(app, labels);
+}
+
+// Saves `label` without waiting for the debounce, along with any other window waiting for it
+fn save_now(app: &AppHandle, label: &str) {
+    let mut labels = app
+        .try_state::<WindowStateSaver>()
+        .map(|saver| std::mem::take(&mut *saver.pending.lock().unwrap()))
+        .unwrap_or_default();
+    labels.insert(label.to_string());
+    save(app, labels);
+}
+
+fn save(app: &AppHandle, labels: HashSet<String>) {
+    if !app
+        .try_state::<WindowStateSaver>()
+        .is_some_and(|saver| saver.enabled.load(Ordering::Relaxed))
+    {
+        return;
+    }
+
+    if let Err(e) = app.save_window_state(window_state_flags()) {
+        tracing::warn!("Failed to save window state: {e}");
+    }
+    for window in labels
+        .iter()
+        .filter_map(|label| app.get_webview_window(label))
+    {
+        window_prefs::remember_monitor(app, &window);
+    }
+}
+
+fn spawn_wake_watch(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let (instant, wall) = (Instant::now(), SystemTime::now());
+            tokio::time::sleep(WAKE_CHECK_INTERVAL).await;
+
+            let Ok(wall_elapsed) = wall.elapsed() else {
+                continue;
+            };
+            if !slept(instant.elapsed(), wall_elapsed) {
+                continue;
+            }
+
+            tracing::info!(?wall_elapsed, "Woke up from sleep, checking windows");
+            let handle = app.clone();
+            let _ = app.run_on_main_thread(move || {
+                for window in handle.webview_windows().into_values() {
+                    if handle
+                        .state::<WindowStateSaver>()
+                        .tracked
+                        .lock()
+                        .unwrap()
+                        .contains(window.label())
+                    {
+                        monitors::ensure_visible(&handle, &window);
+                    }
+                }
+                save_all(&handle);
+            });
+        }
+    });
+}
+
+// How long to hold a save so writes stay `MIN_SAVE_INTERVAL` apart
+fn save_delay(since_last_save: Option<Duration>) -> Duration {
+    since_last_save.map_or(Duration::ZERO, |elapsed| {
+        MIN_SAVE_INTERVAL.saturating_sub(elapsed)
+    })
+}
+
+fn slept(monotonic: Duration, wall: Duration) -> bool {
+    wall.saturating_sub(monotonic) >= MIN_SLEEP
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notices_wall_clock_jumps() {
+        let interval = Duration::from_secs(10);
+        assert!(!slept(interval, Duration::from_secs(11)));
+        assert!(slept(interval, Duration::from_secs(3600)));
+        // The wall clock set back, e.g. by NTP
+        assert!(!slept(interval, Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn spaces_saves_a_second_apart() {
+        assert_eq!(save_delay(None), Duration::ZERO);
+        assert_eq!(
+            save_delay(Some(Duration::from_millis(200))),
+            Duration::from_millis(800)
+        );
+        assert_eq!(save_delay(Some(Duration::from_secs(5))), Duration::ZERO);
+    }
+
+    #[test]
+    fn missing_settings_keep_persistence_on() {
+        let settings: WindowStateSettings =
+            serde_json::from_value(serde_json::json!({ "debounce_ms": 1000 })).unwrap();
+        assert_eq!(
+            settings,
+            WindowStateSettings {
+                enabled: true,
+                debounce_ms: 1000,
+            }
+        );
+    }
+}
@@ -1,11 +1,22 @@
 use crate::{
-    constants::{UPDATER_ENABLED, window_state_flags},
-    server::get_wsl_config,
+    ServerState, constants::UPDATER_ENABLED, csd, first_paint, integrity, launch_args::LaunchArgs,
+    server::get_wsl_config, titlebar, traffic_lights, validate, window_background, window_effects,
+    window_prefs, window_state,
+};
+use std::{
+    collections::HashMap,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+use tauri::{
+    AppHandle, Manager, PhysicalPosition, PhysicalSize, Runtime, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder,
 };
-use std::{ops::Deref, time::Duration};
-use tauri::{AppHandle, Manager, Runtime, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
-use tauri_plugin_window_state::AppHandleExt;
-use tokio::sync::mpsc;
 
 #[cfg(target_os = "linux")]
 use std::sync::OnceLock;
@@ -43,11 +54,8 @@ impl MainWindow {
             return Ok(Self(window));
         }
 
-        let wsl_enabled = get_wsl_config(app.clone())
-            .ok()
-            .map(|v| v.enabled)
-            .unwrap_or(false);
-        let decorations = use_decorations();
+        let kiosk = LaunchArgs::get().kiosk;
+        let decorations = use_decorations() && !kiosk;
         let window_builder = base_window_config(
             WebviewWindowBuilder::new(app, Self::LABEL, WebviewUrl::App("/".into())),
             app,
@@ -56,22 +64,30 @@ impl MainWindow {
         .title("OpenCode")
         .disable_drag_drop_handler()
         .zoom_hotkeys_enabled(false)
-        .visible(true)
-        .maximized(true)
-        .initialization_script(format!(
-            r#"
-            window.__OPENCODE__ ??= {{}};
-            window.__OPENCODE__.updaterEnabled = {UPDATER_ENABLED};
-            window.__OPENCODE__.wsl = {wsl_enabled};
-          "#
-        ));
+        .visible(false)
+        .initialization_script(initialization_script(app));
+        let window_builder = if kiosk {
+            window_builder.decorations(false).fullscreen(true)
+        } else {
+            window_builder.maximized(true)
+        };
 
         let window = window_builder.build()?;
+        window_effects::attach(app, &window);
+        traffic_lights::attach(&window);
 
-        // Ensure window is focused after creation (e.g., after update/relaunch)
-        let _ = window.set_focus();
+        // Shown and focused once the frontend has painted, which also brings it forward after an
+        // update or relaunch
+        first_paint::show_when_ready(app, &window);
+
+        // The window state plugin skips the main window in kiosk mode, see `run`
+        if kiosk {
+            return Ok(Self(window));
+        }
 
-        setup_window_state_listener(app, &window);
+        window_state::track(app, &window);
+        titlebar::attach(app, &window);
+        window_prefs::restore(app, &window);
 
         #[cfg(windows)]
         {
@@ -79,40 +95,394 @@ impl MainWindow {
             let _ = window.create_overlay_titlebar();
         }
 
+        #[cfg(target_os = "linux")]
+        if !decorations {
+            csd::attach(&window);
+        }
+
         Ok(Self(window))
     }
 }
 
-fn setup_window_state_listener(app: &AppHandle, window: &WebviewWindow) {
-    let (tx, mut rx) = mpsc::channel::<()>(1);
+/// Switches the calling window in or out of fullscreen, returning whether it's now fullscreen.
+#[tauri::command]
+#[specta::specta]
+pub fn toggle_fullscreen(window: WebviewWindow) -> Result<bool, String> {
+    let fullscreen = !window
+        .is_fullscreen()
+        .map_err(|e| format!("Failed to read fullscreen state: {}", e))?;
+    window
+        .set_fullscreen(fullscreen)
+        .map_err(|e| format!("Failed to set fullscreen: {}", e))?;
 
-    window.on_window_event(move |event| {
-        use tauri::WindowEvent;
-        if !matches!(event, WindowEvent::Moved(_) | WindowEvent::Resized(_)) {
-            return;
+    Ok(fullscreen)
+}
+
+/// Windows opened on a single project next to the main window, keyed by label.
+#[derive(Default)]
+pub struct ProjectWindows(Mutex<HashMap<String, PathBuf>>);
+
+impl ProjectWindows {
+    /// The project shown in window `label`, if it's a project window.
+    pub fn path(&self, label: &str) -> Option<PathBuf> {
+        self.0.lock().unwrap().get(label).cloned()
+    }
+}
+
+/// Opens the project at `path` in a window of its own, or focuses the one already showing it,
+/// and returns the window's label. The label is the same every time a project is opened, so its
+/// size and position are restored.
+#[tauri::command]
+#[specta::specta]
+pub fn create_project_window(app: AppHandle, path: String) -> Result<String, String> {
+    validate::path("path", &path)?;
+
+    let label = project_window_label(&path);
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.show();
+        let _ = window.set_focus();
+        let _ = window.unminimize();
+        return Ok(label);
+    }
+
+    let server_url = app
+        .try_state::<ServerState>()
+        .and_then(|state| state.ready())
+        .map(|ready| ready.url);
+    let name = Path::new(&path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.clone());
+
+    let window = base_window_config(
+        WebviewWindowBuilder::new(
+            &app,
+            &label,
+            WebviewUrl::App(format!("/{}", route_segment(&path)).into()),
+        ),
+        &app,
+        use_decorations(),
+    )
+    .title(format!("{name} — OpenCode"))
+    .disable_drag_drop_handler()
+    .zoom_hotkeys_enabled(false)
+    .inner_size(1280.0, 800.0)
+    .visible(false)
+    .initialization_script(format!(
+        r#"{}
+            window.__OPENCODE__.projectPath = {};
+            window.__OPENCODE__.serverUrl = {};
+          "#,
+        initialization_script(&app),
+        serde_json::json!(path),
+        serde_json::json!(server_url),
+    ))
+    .build()
+    .map_err(|e| format!("Failed to create project window: {}", e))?;
+    window_effects::attach(&app, &window);
+    traffic_lights::attach(&window);
+
+    first_paint::show_when_ready(&app, &window);
+    window_state::track(&app, &window);
+    titlebar::attach(&app, &window);
+    window_prefs::restore(&app, &window);
+
+    #[cfg(windows)]
+    {
+        use tauri_plugin_decorum::WebviewWindowExt;
+        let _ = window.create_overlay_titlebar();
+    }
+
+    #[cfg(target_os = "linux")]
+    if !use_decorations() {
+        csd::attach(&window);
+    }
+
+    let windows = app.state::<ProjectWindows>();
+    windows
+        .0
+        .lock()
+        .unwrap()
+        .insert(label.clone(), PathBuf::from(&path));
+    window.on_window_event({
+        let app = app.clone();
+        let label = label.clone();
+        move |event| {
+            if matches!(event, tauri::WindowEvent::Destroyed) {
+                app.state::<ProjectWindows>()
+                    .0
+                    .lock()
+                    .unwrap()
+                    .remove(&label);
+            }
         }
-        let _ = tx.try_send(());
     });
 
-    tokio::spawn({
-        let app = app.clone();
+    tracing::info!(%label, %path, "Created project window");
+    Ok(label)
+}
 
-        async move {
-            let save = || {
-                let handle = app.clone();
-                let app = app.clone();
-                let _ = handle.run_on_main_thread(move || {
-                    let _ = app.save_window_state(window_state_flags());
-                });
-            };
+const PIP_SIZE: (f64, f64) = (420.0, 280.0);
+const PIP_MIN_SIZE: (f64, f64) = (240.0, 160.0);
+
+/// A small frameless window kept above other apps, to watch e.g. a session's output while working
+/// in another app. There's one at most, opening it again switches what it shows. Its size and
+/// position are kept like any other window's.
+pub struct PipWindow;
+
+impl PipWindow {
+    pub const LABEL: &str = "pip";
+}
 
-            while rx.recv().await.is_some() {
-                tokio::time::sleep(Duration::from_millis(200)).await;
+/// Shows the frontend's `route` in the picture-in-picture window, opening it if needed.
+#[tauri::command]
+#[specta::specta]
+pub fn create_pip_window(app: AppHandle, route: String) -> Result<(), String> {
+    validate::route("route", &route)?;
+
+    if let Some(window) = app.get_webview_window(PipWindow::LABEL) {
+        let url = window
+            .url()
+            .map_err(|e| format!("Failed to read window URL: {}", e))?
+            .join(&route)
+            .map_err(|e| format!("Invalid route: {}", e))?;
+        window
+            .navigate(url)
+            .map_err(|e| format!("Failed to show route: {}", e))?;
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
 
-                save();
+    let window = base_window_config(
+        WebviewWindowBuilder::new(
+            &app,
+            PipWindow::LABEL,
+            WebviewUrl::App(route.clone().into()),
+        ),
+        &app,
+        false,
+    )
+    .title("OpenCode")
+    .disable_drag_drop_handler()
+    .zoom_hotkeys_enabled(false)
+    .always_on_top(true)
+    .visible_on_all_workspaces(true)
+    .skip_taskbar(true)
+    .inner_size(PIP_SIZE.0, PIP_SIZE.1)
+    .min_inner_size(PIP_MIN_SIZE.0, PIP_MIN_SIZE.1)
+    .visible(false)
+    .initialization_script(format!(
+        r#"{}
+            window.__OPENCODE__.pip = true;
+          "#,
+        initialization_script(&app),
+    ))
+    .build()
+    .map_err(|e| format!("Failed to create picture-in-picture window: {}", e))?;
+    window_effects::attach(&app, &window);
+    traffic_lights::attach(&window);
+
+    first_paint::show_when_ready(&app, &window);
+
+    window_state::track(&app, &window);
+    titlebar::attach(&app, &window);
+    window_prefs::restore(&app, &window);
+
+    tracing::info!(%route, "Created picture-in-picture window");
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn close_pip_window(app: AppHandle) -> Result<(), String> {
+    let Some(window) = app.get_webview_window(PipWindow::LABEL) else {
+        return Ok(());
+    };
+
+    window
+        .close()
+        .map_err(|e| format!("Failed to close picture-in-picture window: {}", e))
+}
+
+// Fraction of the monitor's height the quick panel covers
+const QUICK_PANEL_HEIGHT: f64 = 0.4;
+const QUICK_PANEL_SLIDE: Duration = Duration::from_millis(150);
+const QUICK_PANEL_FRAMES: u32 = 10;
+
+/// Whether the quick panel is sliding in or out, so presses during the animation are ignored.
+#[derive(Default)]
+pub struct QuickPanelState {
+    animating: AtomicBool,
+}
+
+/// A frameless, always-on-top window that slides down from the top of the monitor under the
+/// cursor, like a Quake console. It's created on first use and hidden rather than closed.
+pub struct QuickPanel;
+
+impl QuickPanel {
+    pub const LABEL: &str = "quick-panel";
+
+    /// Slides the panel in, or out when it's showing.
+    pub fn toggle(app: &AppHandle) {
+        if app
+            .state::<QuickPanelState>()
+            .animating
+            .swap(true, Ordering::SeqCst)
+        {
+            return;
+        }
+
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let result = match app.get_webview_window(Self::LABEL) {
+                Some(window) if window.is_visible().unwrap_or(false) => {
+                    Self::slide_out(&window).await
+                }
+                Some(window) => Self::slide_in(&app, &window).await,
+                None => match Self::create(&app) {
+                    Ok(window) => {
+                        first_paint::ready(&app, &window).await;
+                        Self::slide_in(&app, &window).await
+                    }
+                    Err(e) => Err(e),
+                },
+            };
+            if let Err(e) = result {
+                tracing::error!("Failed to toggle quick panel: {e}");
             }
+
+            app.state::<QuickPanelState>()
+                .animating
+                .store(false, Ordering::SeqCst);
+        });
+    }
+
+    fn create(app: &AppHandle) -> Result<WebviewWindow, tauri::Error> {
+        let window = base_window_config(
+            WebviewWindowBuilder::new(app, Self::LABEL, WebviewUrl::App("/".into())),
+            app,
+            false,
+        )
+        .title("OpenCode")
+        .disable_drag_drop_handler()
+        .zoom_hotkeys_enabled(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .resizable(false)
+        .visible(false)
+        .initialization_script(format!(
+            r#"{}
+            window.__OPENCODE__.quickPanel = true;
+          "#,
+            initialization_script(app),
+        ))
+        .build()?;
+        window_effects::attach(app, &window);
+        traffic_lights::attach(&window);
+
+        tracing::info!("Created quick panel");
+        Ok(window)
+    }
+
+    // Window positions are ignored on Wayland, where the panel just appears in place
+    async fn slide_in(app: &AppHandle, window: &WebviewWindow) -> Result<(), tauri::Error> {
+        let cursor = app.cursor_position()?;
+        let monitor = match app.monitor_from_point(cursor.x, cursor.y)? {
+            Some(monitor) => Some(monitor),
+            None => app.primary_monitor()?,
+        };
+        let Some(monitor) = monitor else {
+            tracing::warn!("No monitor to show the quick panel on");
+            return Ok(());
+        };
+
+        let area = monitor.work_area();
+        let height = (f64::from(area.size.height) * QUICK_PANEL_HEIGHT) as u32;
+        window.set_size(PhysicalSize::new(area.size.width, height))?;
+        window.set_position(PhysicalPosition::new(
+            area.position.x,
+            slide_y(area.position.y, height, 0.0),
+        ))?;
+        window.show()?;
+        window.set_focus()?;
+
+        Self::animate(window, area.position.x, area.position.y, height, false).await
+    }
+
+    async fn slide_out(window: &WebviewWindow) -> Result<(), tauri::Error> {
+        let position = window.outer_position()?;
+        let height = window.outer_size()?.height;
+
+        Self::animate(window, position.x, position.y, height, true).await?;
+        window.hide()
+    }
+
+    async fn animate(
+        window: &WebviewWindow,
+        x: i32,
+        top: i32,
+        height: u32,
+        reverse: bool,
+    ) -> Result<(), tauri::Error> {
+        for frame in 1..=QUICK_PANEL_FRAMES {
+            tokio::time::sleep(QUICK_PANEL_SLIDE / QUICK_PANEL_FRAMES).await;
+
+            let progress = f64::from(frame) / f64::from(QUICK_PANEL_FRAMES);
+            let progress = if reverse { 1.0 - progress } else { progress };
+            window.set_position(PhysicalPosition::new(x, slide_y(top, height, progress)))?;
         }
-    });
+        Ok(())
+    }
+}
+
+// Where the panel's top edge is partway through sliding in, easing out so it settles gently
+fn slide_y(top: i32, height: u32, progress: f64) -> i32 {
+    let eased = 1.0 - (1.0 - progress.clamp(0.0, 1.0)).powi(3);
+    top - (f64::from(height) * (1.0 - eased)).round() as i32
+}
+
+fn initialization_script(app: &AppHandle) -> String {
+    let wsl_enabled = get_wsl_config(app.clone())
+        .ok()
+        .map(|v| v.enabled)
+        .unwrap_or(false);
+
+    let csd = cfg!(target_os = "linux") && !use_decorations();
+
+    let mut script = format!(
+        r#"
+            window.__OPENCODE__ ??= {{}};
+            window.__OPENCODE__.updaterEnabled = {UPDATER_ENABLED};
+            window.__OPENCODE__.wsl = {wsl_enabled};
+            window.__OPENCODE__.csd = {csd};
+          "#
+    );
+    if csd {
+        script.push_str(csd::SCRIPT);
+    }
+    script.push_str(&window_effects::initialization_script(app));
+    script
+}
+
+pub(crate) fn project_window_label(path: &str) -> String {
+    format!("project-{}", &integrity::sha256_hex(path.as_bytes())[..16])
+}
+
+// The frontend's route for a project directory, matching `base64Encode` in `@opencode-ai/util`
+fn route_segment(path: &str) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+    let mut encoded = String::new();
+    for chunk in path.as_bytes().chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, byte)| {
+            bits | (u32::from(*byte) << (16 - 8 * i))
+        });
+        for i in 0..=chunk.len() {
+            encoded.push(ALPHABET[((bits >> (18 - 6 * i)) & 0x3f) as usize] as char);
+        }
+    }
+    encoded
 }
 
 pub struct LoadingWindow(WebviewWindow);
@@ -141,7 +511,10 @@ impl LoadingWindow {
         .inner_size(640.0, 480.0)
         .visible(true);
 
-        Ok(Self(window_builder.build()?))
+        let window = window_builder.build()?;
+        window_effects::attach(app, &window);
+        traffic_lights::attach(&window);
+        Ok(Self(window))
     }
 }
 
@@ -150,7 +523,8 @@ fn base_window_config<'a, R: Runtime, M: Manager<R>>(
     _app: &AppHandle,
     decorations: bool,
 ) -> WebviewWindowBuilder<'a, R, M> {
-    let window_builder = window_builder.decorations(decorations);
+    let window_builder = window_effects::configure(window_builder.decorations(decorations), _app);
+    let window_builder = window_background::configure(window_builder, _app);
 
     #[cfg(windows)]
     let window_builder = window_builder
@@ -167,8 +541,38 @@ fn base_window_config<'a, R: Runtime, M: Manager<R>>(
     #[cfg(target_os = "macos")]
     let window_builder = window_builder
         .title_bar_style(tauri::TitleBarStyle::Overlay)
-        .hidden_title(true)
-        .traffic_light_position(tauri::LogicalPosition::new(12.0, 18.0));
+        .hidden_title(true);
 
     window_builder
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_route_like_frontend() {
+        assert_eq!(route_segment("/home/me/app"), "L2hvbWUvbWUvYXBw");
+        assert_eq!(route_segment("/a"), "L2E");
+        assert_eq!(route_segment("/ab"), "L2Fi");
+        assert_eq!(route_segment("C:\\code\\naïve"), "QzpcY29kZVxuYcOvdmU");
+        assert_eq!(route_segment("??>"), "Pz8-");
+    }
+
+    #[test]
+    fn quick_panel_slides_from_above_monitor() {
+        assert_eq!(slide_y(0, 400, 0.0), -400);
+        assert_eq!(slide_y(0, 400, 1.0), 0);
+        assert_eq!(slide_y(1080, 400, 1.0), 1080);
+        assert_eq!(slide_y(0, 400, 0.5), -50);
+        assert_eq!(slide_y(0, 400, 2.0), 0);
+    }
+
+    #[test]
+    fn labels_are_stable_per_project() {
+        let label = project_window_label("/home/me/app");
+        assert_eq!(label, project_window_label("/home/me/app"));
+        assert_ne!(label, project_window_label("/home/me/other"));
+        assert!(label.starts_with("project-") && label.len() == 24);
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    collections::HashMap,
+    net::{Ipv4Addr, UdpSocket},
+    time::Duration,
+};
+
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogResult};
+use tauri_plugin_store::StoreExt;
+
+use crate::{
+    constants::{SETTINGS_STORE, WAKE_ON_LAN_KEY},
+    validate,
+};
+
+const WOL_PORT: u16 = 9;
+const DEFAULT_WAKE_DELAY_SECS: u32 = 20;
+
+#[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type, Debug)]
+pub struct WakeOnLanConfig {
+    pub mac: String,
+    pub broadcast: Option<String>,
+    pub delay_secs: Option<u32>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn wake_host(mac: String, broadcast: Option<String>) -> Result<(), String> {
+    let mac = parse_mac(&mac).ok_or_else(|| format!("Invalid MAC address '{}'", mac))?;
+    let broadcast = match broadcast {
+        Some(v) => v
+            .parse::<Ipv4Addr>()
+            .map_err(|e| format!("Invalid broadcast address '{}': {}", v, e))?,
+        None => Ipv4Addr::BROADCAST,
+    };
+
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .map_err(|e| format!("Failed to open socket: {}", e))?;
+    socket
+        .set_broadcast(true)
+        .map_err(|e| format!("Failed to enable broadcast: {}", e))?;
+    socket
+        .send_to(&magic_packet(mac), (broadcast, WOL_PORT))
+        .map_err(|e| format!("Failed to send magic packet: {}", e))?;
+
+    tracing::info!(%broadcast, "Sent Wake-on-LAN packet");
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_wake_on_lan_config(
+    app: AppHandle,
+    url: String,
+) -> Result<Option<WakeOnLanConfig>, String> {
+    Ok(read_configs(&app)?.remove(&url))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_wake_on_lan_config(
+    app: AppHandle,
+    url: String,
+    config: Option<WakeOnLanConfig>,
+) -> Result<(), String> {
+    validate::url("server URL", &url)?;
+
+    if let Some(config) = &config
+        && parse_mac(&config.mac).is_none()
+    {
+        return Err(format!("Invalid MAC address '{}'", config.mac));
+    }
+
+    let mut configs = read_configs(&app)?;
+    match config {
+        Some(config) => configs.insert(url, config),
+        None => configs.remove(&url),
+    };
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    store.set(WAKE_ON_LAN_KEY, serde_json::json!(configs));
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+fn read_configs(app: &AppHandle) -> Result<HashMap<String, WakeOnLanConfig>, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(WAKE_ON_LAN_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+/// Offers to wake the host behind `url` when it has a Wake-on-LAN config. Returns true
+/// once a packet was sent and the configured delay has passed, so the caller can retry.
+pub async fn offer_wake(app: &AppHandle, url: &str) -> bool {
+    let Some(config) = get_wake_on_lan_config(app.clone(), url.to_string())
+        .ok()
+        .flatten()
+    else {
+        return false;
+    };
+
+    const WAKE: &str = "Wake";
+
+    let res = app
+        .dialog()
+        .message(format!(
+            "Could not connect to configured server:\n{}\n\nWould you like to send a Wake-on-LAN packet to {} and try again?",
+            url, config.mac
+        ))
+        .title("Server Unreachable")
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            WAKE.to_string(),
+            "Skip".to_string(),
+        ))
+        .blocking_show_with_result();
+
+    if !matches!(res, MessageDialogResult::Custom(name) if name == WAKE) {
+        return false;
+    }
+
+    if let Err(e) = wake_host(config.mac, config.broadcast) {
+        tracing::warn!("Failed to wake server host: {e}");
+        return false;
+    }
+
+    let delay = config.delay_secs.unwrap_or(DEFAULT_WAKE_DELAY_SECS);
+    tokio::time::sleep(Duration::from_secs(delay.into())).await;
+
+    true
+}
+
+fn parse_mac(mac: &str) -> Option<[u8; 6]> {
+    let parts = mac
+        .trim()
+        .split([':', '-'])
+        .map(|part| {
+            u8::from_str_radix(part, 16)
+                .ok()
+                .filter(|_| part.len() == 2)
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    parts.try_into().ok()
+}
+
+fn magic_packet(mac: [u8; 6]) -> Vec<u8> {
+    std::iter::repeat_n(0xff, 6)
+        .chain(std::iter::repeat_n(mac, 16).flatten())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_colon_and_dash_macs() {
+        let expected = Some([0xaa, 0xbb, 0xcc, 0x01, 0x02, 0x03]);
+        assert_eq!(parse_mac("aa:bb:cc:01:02:03"), expected);
+        assert_eq!(parse_mac("AA-BB-CC-01-02-03"), expected);
+    }
+
+    #[test]
+    fn rejects_malformed_macs() {
+        assert_eq!(parse_mac("aa:bb:cc:01:02"), None);
+        assert_eq!(parse_mac("aa:bb:cc:01:02:03:04"), None);
+        assert_eq!(parse_mac("aa:bb:cc:01:02:3"), None);
+        assert_eq!(parse_mac("zz:bb:cc:01:02:03"), None);
+    }
+
+    #[test]
+    fn magic_packet_repeats_mac() {
+        let mac = [1, 2, 3, 4, 5, 6];
+        let packet = magic_packet(mac);
+
+        assert_eq!(packet.len(), 102);
+        assert_eq!(&packet[..6], &[0xff; 6]);
+        assert!(packet[6..].chunks(6).all(|chunk| chunk == mac));
+    }
+}
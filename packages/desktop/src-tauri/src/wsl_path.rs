@@ -0,0 +1,253 @@
+use std::{
+    collections::HashMap,
+    process::Command,
+    sync::{LazyLock, Mutex},
+};
+
+use tauri::AppHandle;
+
+use crate::{validate, wsl_script::quote, wsl_setup};
+
+const CACHE_CAPACITY: usize = 1024;
+
+// Expands a leading `~` to the distro's home and converts every path given as an argument,
+// printing each result NUL-terminated so an empty result marks a failed conversion
+const BATCH_SCRIPT: &str = r#"flag="$1"; shift
+for p in "$@"; do
+  case "$p" in "~"*) p="$HOME${p#\~}" ;; esac
+  out=$(wslpath "$flag" "$p" 2>/dev/null) || out=""
+  printf '%s\0' "$out"
+done"#;
+
+static CACHE: LazyLock<Mutex<PathCache>> =
+    LazyLock::new(|| Mutex::new(PathCache::new(CACHE_CAPACITY)));
+
+#[derive(Clone, Copy, serde::Deserialize, specta::Type, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WslPathMode {
+    Windows,
+    Linux,
+}
+
+impl WslPathMode {
+    fn flag(self) -> &'static str {
+        match self {
+            Self::Windows => "-w",
+            Self::Linux => "-u",
+        }
+    }
+}
+
+type CacheKey = (Vec<String>, WslPathMode, String);
+
+/// Least recently used conversions, keyed by the distro arguments, mode and path.
+struct PathCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<CacheKey, (String, u64)>,
+}
+
+impl PathCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    fn get(&mut self, key: &CacheKey) -> Option<String> {
+        self.tick += 1;
+        let (value, used) = self.entries.get_mut(key)?;
+        *used = self.tick;
+        Some(value.clone())
+    }
+
+    fn insert(&mut self, key: CacheKey, value: String) {
+        self.tick += 1;
+        if self.entries.len() >= self.capacity
+            && !self.entries.contains_key(&key)
+            && let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(key, _)| key.clone())
+        {
+            self.entries.remove(&oldest);
+        }
+        self.entries.insert(key, (value, self.tick));
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn wsl_path(app: AppHandle, path: String, mode: Option<WslPathMode>) -> Result<String, String> {
+    validate::path("path", &path)?;
+
+    if !cfg!(windows) {
+        return Ok(path);
+    }
+
+    let mode = mode.unwrap_or(WslPathMode::Linux);
+    let flag = mode.flag();
+    let distro_args = wsl_setup::distro_args(&app);
+    let key = (distro_args.clone(), mode, path.clone());
+    if let Some(converted) = CACHE.lock().unwrap().get(&key) {
+        return Ok(converted);
+    }
+
+    let output = if path.starts_with('~') {
+        let suffix = path.strip_prefix('~').unwrap_or("");
+        let cmd = format!("wslpath {flag} \"$HOME\"{}", quote(suffix));
+        Command::new("wsl")
+            .args(distro_args)
+            .args(["-e", "sh", "-lc", &cmd])
+            .output()
+            .map_err(|e| format!("Failed to run wslpath: {e}"))?
+    } else {
+        Command::new("wsl")
+            .args(distro_args)
+            .args(["-e", "wslpath", flag, &path])
+            .output()
+            .map_err(|e| format!("Failed to run wslpath: {e}"))?
+    };
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if stderr.is_empty() {
+            return Err("wslpath failed".to_string());
+        }
+        return Err(stderr);
+    }
+
+    let converted = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    CACHE.lock().unwrap().insert(key, converted.clone());
+    Ok(converted)
+}
+
+/// Converts many paths with a single WSL process. Entries are `None` where the conversion
+/// failed, in the same order as `paths`.
+#[tauri::command]
+#[specta::specta]
+pub fn wsl_paths(
+    app: AppHandle,
+    paths: Vec<String>,
+    mode: Option<WslPathMode>,
+) -> Result<Vec<Option<String>>, String> {
+    for path in &paths {
+        validate::path("path", path)?;
+    }
+
+    if !cfg!(windows) {
+        return Ok(paths.into_iter().map(Some).collect());
+    }
+
+    let mode = mode.unwrap_or(WslPathMode::Linux);
+    let distro_args = wsl_setup::distro_args(&app);
+    let key = |path: &str| (distro_args.clone(), mode, path.to_string());
+
+    let mut results = {
+        let mut cache = CACHE.lock().unwrap();
+        paths
+            .iter()
+            .map(|path| cache.get(&key(path)))
+            .collect::<Vec<_>>()
+    };
+
+    let missing = paths
+        .iter()
+        .zip(&results)
+        .filter(|(_, result)| result.is_none())
+        .map(|(path, _)| path.as_str())
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return Ok(results);
+    }
+
+    let output = Command::new("wsl")
+        .args(&distro_args)
+        .args(["-e", "sh", "-c", BATCH_SCRIPT, "sh", mode.flag()])
+        .args(&missing)
+        .output()
+        .map_err(|e| format!("Failed to run wslpath: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if stderr.is_empty() {
+            return Err("wslpath failed".to_string());
+        }
+        return Err(stderr);
+    }
+
+    let mut converted = parse_batch_output(&output.stdout).into_iter();
+    let mut cache = CACHE.lock().unwrap();
+    for (path, result) in paths.iter().zip(results.iter_mut()) {
+        if result.is_some() {
+            continue;
+        }
+
+        *result = converted.next().flatten();
+        if let Some(value) = result {
+            cache.insert(key(path), value.clone());
+        }
+    }
+
+    Ok(results)
+}
+
+fn parse_batch_output(stdout: &[u8]) -> Vec<Option<String>> {
+    let stdout = String::from_utf8_lossy(stdout);
+    let mut entries = stdout.split('\0').collect::<Vec<_>>();
+    // Every entry is terminated, so the last split is what follows the final NUL
+    entries.pop();
+
+    entries
+        .into_iter()
+        .map(|entry| Some(entry.trim().to_string()).filter(|entry| !entry.is_empty()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(path: &str) -> CacheKey {
+        (vec![], WslPathMode::Linux, path.to_string())
+    }
+
+    #[test]
+    fn cache_evicts_least_recently_used() {
+        let mut cache = PathCache::new(2);
+        cache.insert(key("a"), "/a".to_string());
+        cache.insert(key("b"), "/b".to_string());
+        assert_eq!(cache.get(&key("a")), Some("/a".to_string()));
+
+        cache.insert(key("c"), "/c".to_string());
+        assert_eq!(cache.get(&key("b")), None);
+        assert_eq!(cache.get(&key("a")), Some("/a".to_string()));
+        assert_eq!(cache.get(&key("c")), Some("/c".to_string()));
+    }
+
+    #[test]
+    fn cache_keys_include_mode() {
+        let mut cache = PathCache::new(2);
+        cache.insert(key("C:\\a"), "/mnt/c/a".to_string());
+        assert_eq!(
+            cache.get(&(vec![], WslPathMode::Windows, "C:\\a".to_string())),
+            None
+        );
+    }
+
+    #[test]
+    fn parses_batch_output() {
+        assert_eq!(
+            parse_batch_output(b"/mnt/c/a\0\0/home/me\n\0"),
+            [
+                Some("/mnt/c/a".to_string()),
+                None,
+                Some("/home/me".to_string())
+            ]
+        );
+        assert_eq!(parse_batch_output(b""), []);
+    }
+}
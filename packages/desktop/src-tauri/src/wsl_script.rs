@@ -0,0 +1,305 @@
+use crate::cli::{INSTALL_SCRIPT, WSL_CLI_MISSING_EXIT_CODE};
+
+const WSL_BIN: &str = "$HOME/.opencode/bin/opencode";
+// Ends the bundled install script on the script's stdin
+const INSTALL_SCRIPT_EOF: &str = "OPENCODE_INSTALL_SCRIPT_EOF";
+
+/// Builds the bash script run through `wsl -e bash -lc` to invoke the CLI inside WSL.
+///
+/// Every value coming from the host is single-quoted, so versions, env values and args can't
+/// break out of the script no matter what they contain.
+#[derive(Clone, Debug, Default)]
+pub struct WslScript {
+    install_version: Option<String>,
+    reinstall: bool,
+    fail_if_missing: bool,
+    env: Vec<(String, EnvValue)>,
+    args: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+enum EnvValue {
+    Literal(String),
+    Expr(&'static str),
+}
+
+impl WslScript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Installs this CLI version first when no CLI is present in the distro yet.
+    pub fn install_if_missing(mut self, version: impl Into<String>) -> Self {
+        self.install_version = Some(version.into());
+        self
+    }
+
+    /// Installs this CLI version even when a CLI is present, replacing the existing one.
+    pub fn reinstall(mut self, version: impl Into<String>) -> Self {
+        self.install_version = Some(version.into());
+        self.reinstall = true;
+        self
+    }
+
+    /// Exits with `WSL_CLI_MISSING_EXIT_CODE` when no CLI is present in the distro, for when the
+    /// user hasn't agreed to installing it yet. `install_if_missing` takes precedence.
+    pub fn fail_if_missing(mut self) -> Self {
+        self.fail_if_missing = true;
+        self
+    }
+
+    /// Sets an environment variable for the CLI, replacing an earlier value for the same key.
+    /// Keys that aren't valid shell identifiers are dropped.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.set_env(key.into(), EnvValue::Literal(value.into()));
+        self
+    }
+
+    pub fn envs<K: Into<String>, V: Into<String>>(
+        mut self,
+        envs: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        for (key, value) in envs {
+            self.set_env(key.into(), EnvValue::Literal(value.into()));
+        }
+        self
+    }
+
+    /// Sets an environment variable to a shell expression that's expanded inside WSL,
+    /// e.g. `"$HOME/.local/state"`. Only accepts static strings so host input can't end up here.
+    pub fn env_expr(mut self, key: impl Into<String>, expr: &'static str) -> Self {
+        self.set_env(key.into(), EnvValue::Expr(expr));
+        self
+    }
+
+    pub fn args<S: Into<String>>(mut self, args: impl IntoIterator<Item = S>) -> Self {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    fn set_env(&mut self, key: String, value: EnvValue) {
+        if !is_identifier(&key) {
+            tracing::warn!(%key, "Skipping invalid environment variable for WSL");
+            return;
+        }
+
+        self.env.retain(|(existing, _)| *existing != key);
+        self.env.push((key, value));
+    }
+
+    /// What has to be written to the script's stdin before anything meant for the CLI, when
+    /// the script may install it.
+    pub fn stdin(&self) -> Option<String> {
+        self.install_version.as_ref()?;
+
+        let mut stdin = INSTALL_SCRIPT.to_string();
+        if !stdin.ends_with('\n') {
+            stdin.push('\n');
+        }
+        stdin.push_str(INSTALL_SCRIPT_EOF);
+        stdin.push('\n');
+        Some(stdin)
+    }
+
+    pub fn build(&self) -> String {
+        let mut script = vec!["set -e".to_string(), format!("BIN=\"{WSL_BIN}\"")];
+
+        if let Some(version) = &self.install_version {
+            let condition = if self.reinstall {
+                "if true; then"
+            } else {
+                "if [ ! -x \"$BIN\" ]; then"
+            };
+            script.extend([
+                // The install script bundled with this build comes in on stdin rather than in
+                // the command line, which Windows limits in length. It's read line by line up
+                // to the marker so the CLI still gets the rest of stdin.
+                "INSTALL=\"\"".to_string(),
+                "INSTALL_READ=\"\"".to_string(),
+                "while IFS= read -r LINE; do".to_string(),
+                format!(
+                    "  if [ \"$LINE\" = \"{INSTALL_SCRIPT_EOF}\" ]; then INSTALL_READ=1; break; fi"
+                ),
+                "  INSTALL=\"$INSTALL$LINE\"$'\\n'".to_string(),
+                "done".to_string(),
+                "if [ -z \"$INSTALL_READ\" ]; then".to_string(),
+                "  echo \"Didn't receive the opencode install script\" >&2".to_string(),
+                "  exit 1".to_string(),
+                "fi".to_string(),
+                condition.to_string(),
+                // Exits with `INSTALL_VERIFY_EXIT_CODE` when the downloaded archive doesn't match
+                // the release's published sha256
+                format!(
+                    "  printf '%s' \"$INSTALL\" | bash -s -- --version {} --no-modify-path --verify-checksum",
+                    quote(version)
+                ),
+                "  echo \"Verifying opencode installation\" >&2".to_string(),
+                "fi".to_string(),
+                "unset INSTALL LINE INSTALL_READ".to_string(),
+            ]);
+        } else if self.fail_if_missing {
+            script.extend([
+                "if [ ! -x \"$BIN\" ]; then".to_string(),
+                "  echo \"opencode is not installed in WSL\" >&2".to_string(),
+                format!("  exit {WSL_CLI_MISSING_EXIT_CODE}"),
+                "fi".to_string(),
+            ]);
+        }
+
+        let mut exec = self
+            .env
+            .iter()
+            .map(|(key, value)| match value {
+                EnvValue::Literal(value) => format!("{}={}", key, quote(value)),
+                EnvValue::Expr(expr) => format!("{}=\"{}\"", key, expr),
+            })
+            .collect::<Vec<_>>();
+        exec.push("exec \"$BIN\"".to_string());
+        exec.extend(self.args.iter().map(|arg| quote(arg)));
+
+        script.push(exec.join(" "));
+        script.join("\n")
+    }
+}
+
+pub fn quote(input: &str) -> String {
+    if input.is_empty() {
+        return "''".to_string();
+    }
+
+    let mut escaped = String::from("'");
+    escaped.push_str(&input.replace("'", "'\"'\"'"));
+    escaped.push('\'');
+    escaped
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::INSTALL_VERIFY_EXIT_CODE;
+
+    fn exec_line(script: &str) -> &str {
+        script.lines().last().unwrap()
+    }
+
+    #[test]
+    fn quotes_args_and_env() {
+        let script = WslScript::new()
+            .env("TOKEN", "a b'c")
+            .args(["serve", "--hostname", "$(reboot)"])
+            .build();
+
+        assert_eq!(
+            exec_line(&script),
+            "TOKEN='a b'\"'\"'c' exec \"$BIN\" 'serve' '--hostname' '$(reboot)'"
+        );
+    }
+
+    #[test]
+    fn quotes_empty_values() {
+        let script = WslScript::new().env("EMPTY", "").args([""]).build();
+        assert_eq!(exec_line(&script), "EMPTY='' exec \"$BIN\" ''");
+    }
+
+    #[test]
+    fn expands_env_expressions() {
+        let script = WslScript::new()
+            .env_expr("XDG_STATE_HOME", "$HOME/.local/state")
+            .build();
+        assert_eq!(
+            exec_line(&script),
+            "XDG_STATE_HOME=\"$HOME/.local/state\" exec \"$BIN\""
+        );
+    }
+
+    #[test]
+    fn later_env_replaces_earlier() {
+        let script = WslScript::new()
+            .env("OPENCODE_CLIENT", "desktop")
+            .envs([("OPENCODE_CLIENT", "other")])
+            .build();
+        assert_eq!(exec_line(&script), "OPENCODE_CLIENT='other' exec \"$BIN\"");
+    }
+
+    #[test]
+    fn skips_invalid_env_keys() {
+        let script = WslScript::new()
+            .env("BAD KEY", "x")
+            .env("1BAD", "x")
+            .env("GOOD_1", "x")
+            .build();
+        assert_eq!(exec_line(&script), "GOOD_1='x' exec \"$BIN\"");
+    }
+
+    #[test]
+    fn install_block_is_optional() {
+        let script = WslScript::new().build();
+        assert!(!script.contains("install"));
+        assert!(WslScript::new().stdin().is_none());
+
+        let script = WslScript::new().install_if_missing("1.2.3").build();
+        assert!(script.contains("--version '1.2.3' --no-modify-path --verify-checksum"));
+        assert!(!script.contains(INSTALL_SCRIPT.trim_end_matches('\n')));
+    }
+
+    #[test]
+    fn sends_install_script_on_stdin() {
+        let stdin = WslScript::new()
+            .install_if_missing("1.2.3")
+            .stdin()
+            .unwrap();
+        let (install, rest) = stdin
+            .split_once(&format!("\n{INSTALL_SCRIPT_EOF}\n"))
+            .unwrap();
+
+        assert_eq!(install, INSTALL_SCRIPT.trim_end_matches('\n'));
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn install_script_doesnt_end_early() {
+        assert!(
+            !INSTALL_SCRIPT
+                .lines()
+                .any(|line| line == INSTALL_SCRIPT_EOF)
+        );
+    }
+
+    #[test]
+    fn install_script_verifies_checksums() {
+        assert!(INSTALL_SCRIPT.contains("--verify-checksum)"));
+        assert!(INSTALL_SCRIPT.contains(&format!("exit {INSTALL_VERIFY_EXIT_CODE}")));
+    }
+
+    #[test]
+    fn reinstalls_over_existing_cli() {
+        let script = WslScript::new().reinstall("1.2.3").build();
+        assert!(script.contains("if true; then"));
+        assert!(script.contains("--version '1.2.3' --no-modify-path"));
+
+        let script = WslScript::new().install_if_missing("1.2.3").build();
+        assert!(!script.contains("if true; then"));
+    }
+
+    #[test]
+    fn fails_without_installing() {
+        let script = WslScript::new().fail_if_missing().build();
+        assert!(script.contains(&format!("exit {WSL_CLI_MISSING_EXIT_CODE}")));
+        assert!(!script.contains("bash -s"));
+        assert!(WslScript::new().fail_if_missing().stdin().is_none());
+
+        let script = WslScript::new()
+            .fail_if_missing()
+            .install_if_missing("1.2.3")
+            .build();
+        assert!(!script.contains(&format!("exit {WSL_CLI_MISSING_EXIT_CODE}")));
+    }
+}
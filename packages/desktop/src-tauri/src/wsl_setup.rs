@@ -0,0 +1,872 @@
+use std::{fmt, process::Stdio, time::Duration};
+
+use tauri::{AppHandle, ipc::Channel};
+use tauri_plugin_store::StoreExt;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader},
+    process::Command,
+    time::{Instant, sleep},
+};
+
+use crate::{
+    cli::{INSTALL_VERIFY_EXIT_CODE, WSL_CLI_MISSING_EXIT_CODE, strip_ansi},
+    constants::{SETTINGS_STORE, WSL_CLI_INSTALL_KEY, WSL_DISTRO_KEY, WSL_ENABLED_KEY},
+    wsl_script::WslScript,
+};
+
+const DISTRO_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const DISTRO_INSTALL_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+// A stopped distro can take a while to start, especially right after boot
+const DISTRO_BOOT_TIMEOUT: Duration = Duration::from_secs(60);
+const ENVIRONMENT_CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+const CLI_VERSION_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Prints one line per problem so the result doesn't depend on the distro's shell or locale
+const ENVIRONMENT_SCRIPT: &str = r#"
+for tool in bash curl; do
+  command -v "$tool" >/dev/null 2>&1 || echo "missing $tool"
+done
+if command -v curl >/dev/null 2>&1; then
+  curl -fsS --max-time 10 -o /dev/null https://opencode.ai/install >/dev/null 2>&1 || echo "no-network"
+fi
+mkdir -p "$HOME/.opencode/bin" 2>/dev/null && [ -w "$HOME/.opencode/bin" ] || echo "bin-not-writable"
+"#;
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+pub struct WslStatus {
+    pub feature_enabled: bool,
+    pub distros: Vec<String>,
+    pub cli_version: Option<String>,
+}
+
+/// A distro as listed by `wsl -l -v`.
+#[derive(Clone, serde::Serialize, specta::Type, Debug, PartialEq)]
+pub struct WslDistro {
+    pub name: String,
+    /// e.g. `Running` or `Stopped`, in the language Windows is set to.
+    pub state: String,
+    /// 1 or 2. WSL 1 distros can still run the CLI, just more slowly.
+    pub version: u32,
+    pub default: bool,
+}
+
+/// Progress of installing the CLI inside WSL, parsed from the install script's output.
+#[derive(Clone, serde::Serialize, specta::Type, Debug, PartialEq)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum WslInstallProgress {
+    /// `percent` is only known once the download has started reporting progress.
+    Downloading {
+        percent: Option<u32>,
+    },
+    Extracting,
+    Verifying,
+    Output {
+        line: String,
+    },
+}
+
+/// Something in WSL that would stop the sidecar from running there.
+#[derive(Clone, serde::Serialize, specta::Type, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WslIssue {
+    NotInstalled,
+    NoDistro,
+    /// The distro runs on WSL 1, which can't reach servers on the Windows host reliably.
+    NotWsl2 {
+        distro: String,
+    },
+    DistroDoesNotBoot {
+        error: String,
+    },
+    MissingTool {
+        tool: String,
+    },
+    /// The install script can't be downloaded from inside the distro.
+    NoNetwork,
+    /// `$HOME/.opencode/bin` can't be created or written to.
+    BinNotWritable,
+}
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+pub struct WslEnvironment {
+    /// The distro that was checked, `None` when none could be found.
+    pub distro: Option<String>,
+    /// Empty when WSL mode should work.
+    pub issues: Vec<WslIssue>,
+}
+
+#[derive(Clone, serde::Serialize, specta::Type, Debug)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum WslSetupProgress {
+    CheckingFeature,
+    /// Waiting for the user to approve the elevated `wsl --install`.
+    InstallingWsl,
+    /// WSL was enabled but Windows has to restart before a distro can be installed.
+    RebootRequired,
+    WaitingForDistro {
+        elapsed_secs: u32,
+    },
+    DistroReady {
+        distro: String,
+    },
+    BootstrappingCli,
+    CliOutput {
+        line: String,
+    },
+    Done {
+        version: String,
+    },
+}
+
+/// Why installing the CLI in WSL failed.
+#[derive(Debug, PartialEq)]
+pub enum BootstrapError {
+    /// The downloaded release didn't match its published sha256, so nothing was installed.
+    ChecksumMismatch,
+    Failed(String),
+}
+
+impl fmt::Display for BootstrapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ChecksumMismatch => write!(
+                f,
+                "The downloaded opencode release failed checksum verification"
+            ),
+            Self::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<BootstrapError> for String {
+    fn from(error: BootstrapError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Reports how far along WSL is, so the setup flow can skip finished steps.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_wsl_status() -> Result<WslStatus, String> {
+    if !cfg!(windows) {
+        return Err("WSL is only available on Windows".to_string());
+    }
+
+    let feature_enabled = feature_enabled().await;
+    let distros = if feature_enabled {
+        list_distros().await
+    } else {
+        vec![]
+    };
+    let cli_version = if distros.is_empty() {
+        None
+    } else {
+        cli_version().await
+    };
+
+    Ok(WslStatus {
+        feature_enabled,
+        distros,
+        cli_version,
+    })
+}
+
+/// Walks through enabling WSL, installing a distro and bootstrapping the CLI inside it,
+/// enabling the WSL sidecar once everything succeeded.
+#[tauri::command]
+#[specta::specta]
+pub async fn run_wsl_setup(
+    app: AppHandle,
+    events: Channel<WslSetupProgress>,
+) -> Result<(), String> {
+    if !cfg!(windows) {
+        return Err("WSL is only available on Windows".to_string());
+    }
+
+    let _ = events.send(WslSetupProgress::CheckingFeature);
+    let mut distros = if feature_enabled().await {
+        list_distros().await
+    } else {
+        vec![]
+    };
+
+    if distros.is_empty() {
+        tracing::info!("No WSL distro found, installing WSL");
+        let _ = events.send(WslSetupProgress::InstallingWsl);
+        install_wsl().await?;
+
+        if !feature_enabled().await {
+            tracing::info!("WSL installed, reboot required");
+            let _ = events.send(WslSetupProgress::RebootRequired);
+            return Ok(());
+        }
+
+        distros = wait_for_distro(&events).await?;
+    }
+
+    let distro = distros.into_iter().next().unwrap_or_default();
+    tracing::info!(%distro, "WSL distro ready");
+    let _ = events.send(WslSetupProgress::DistroReady { distro });
+
+    let _ = events.send(WslSetupProgress::BootstrappingCli);
+    let version = bootstrap_cli(&app, false, &|progress| {
+        if let WslInstallProgress::Output { line } = progress {
+            let _ = events.send(WslSetupProgress::CliOutput { line });
+        }
+    })
+    .await?;
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    store.set(WSL_ENABLED_KEY, serde_json::Value::Bool(true));
+    store.set(WSL_CLI_INSTALL_KEY, serde_json::Value::Bool(true));
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    tracing::info!(%version, "WSL setup complete");
+    let _ = events.send(WslSetupProgress::Done { version });
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_wsl_distros() -> Result<Vec<WslDistro>, String> {
+    if !cfg!(windows) {
+        return Err("WSL is only available on Windows".to_string());
+    }
+
+    let output = wsl()
+        .args(["--list", "--verbose"])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to list WSL distros: {}", e))?;
+
+    if !output.status.success() {
+        return Err(decode_wsl_output(&output.stdout).trim().to_string());
+    }
+
+    Ok(parse_verbose_distros(&decode_wsl_output(&output.stdout)))
+}
+
+/// The distro hosting the WSL sidecar, `None` for the WSL default.
+#[tauri::command]
+#[specta::specta]
+pub fn get_wsl_distro(app: AppHandle) -> Result<Option<String>, String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    Ok(store
+        .get(WSL_DISTRO_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_wsl_distro(app: AppHandle, distro: Option<String>) -> Result<(), String> {
+    if let Some(distro) = &distro
+        && !list_distros().await.contains(distro)
+    {
+        return Err(format!("WSL distro '{}' is not installed", distro));
+    }
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    match distro {
+        Some(distro) => {
+            store.set(WSL_DISTRO_KEY, serde_json::Value::String(distro));
+        }
+        None => {
+            store.delete(WSL_DISTRO_KEY);
+        }
+    }
+
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+/// Checks everything WSL mode needs before the sidecar is started in the selected distro, so
+/// problems can be reported directly instead of as a health check timeout.
+#[tauri::command]
+#[specta::specta]
+pub async fn check_wsl_environment(app: AppHandle) -> Result<WslEnvironment, String> {
+    if !cfg!(windows) {
+        return Err("WSL is only available on Windows".to_string());
+    }
+
+    let result = |distro: Option<String>, issues: Vec<WslIssue>| {
+        tracing::info!(?distro, ?issues, "Checked WSL environment");
+        Ok::<_, String>(WslEnvironment { distro, issues })
+    };
+
+    if !feature_enabled().await {
+        return result(None, vec![WslIssue::NotInstalled]);
+    }
+
+    let distros = list_wsl_distros().await.unwrap_or_default();
+    let selected = get_wsl_distro(app.clone()).ok().flatten();
+    let Some(distro) = distros.into_iter().find(|distro| {
+        selected
+            .as_ref()
+            .map_or(distro.default, |name| *name == distro.name)
+    }) else {
+        return result(selected, vec![WslIssue::NoDistro]);
+    };
+
+    let mut issues = vec![];
+    if distro.version < 2 {
+        issues.push(WslIssue::NotWsl2 {
+            distro: distro.name.clone(),
+        });
+    }
+
+    let booted = tokio::time::timeout(
+        DISTRO_BOOT_TIMEOUT,
+        wsl().args(["-d", &distro.name, "-e", "true"]).output(),
+    )
+    .await;
+    let boot_error = match booted {
+        Err(_) => Some("Timed out starting the distro".to_string()),
+        Ok(Err(e)) => Some(e.to_string()),
+        // wsl.exe's own errors end up on stdout
+        Ok(Ok(output)) if !output.status.success() => Some(
+            [&output.stderr, &output.stdout]
+                .map(|bytes| decode_wsl_output(bytes).trim().to_string())
+                .into_iter()
+                .find(|message| !message.is_empty())
+                .unwrap_or_else(|| format!("Distro exited with {}", output.status)),
+        ),
+        Ok(Ok(_)) => None,
+    };
+    if let Some(error) = boot_error {
+        issues.push(WslIssue::DistroDoesNotBoot { error });
+        return result(Some(distro.name), issues);
+    }
+
+    let output = tokio::time::timeout(
+        ENVIRONMENT_CHECK_TIMEOUT,
+        wsl()
+            .args(["-d", &distro.name, "-e", "sh", "-c", ENVIRONMENT_SCRIPT])
+            .stdin(Stdio::null())
+            .output(),
+    )
+    .await
+    .map_err(|_| "Timed out checking the WSL environment".to_string())?
+    .map_err(|e| format!("Failed to check the WSL environment: {}", e))?;
+
+    issues.extend(parse_environment_report(&String::from_utf8_lossy(
+        &output.stdout,
+    )));
+    result(Some(distro.name), issues)
+}
+
+/// Installs the CLI in the selected distro, reporting progress, and returns its version. This
+/// also lets later launches install the CLI by themselves when it goes missing.
+#[tauri::command]
+#[specta::specta]
+pub async fn install_cli_wsl(
+    app: AppHandle,
+    events: Channel<WslInstallProgress>,
+) -> Result<String, String> {
+    if !cfg!(windows) {
+        return Err("WSL is only available on Windows".to_string());
+    }
+
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    store.set(WSL_CLI_INSTALL_KEY, serde_json::Value::Bool(true));
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    tracing::info!("Installing CLI in WSL");
+    let version = bootstrap_cli(&app, false, &|progress| {
+        let _ = events.send(progress);
+    })
+    .await?;
+
+    tracing::info!(%version, "Installed CLI in WSL");
+    Ok(version)
+}
+
+/// Whether the user agreed to installing the CLI in WSL, through `install_cli_wsl` or setup.
+pub fn cli_install_allowed(app: &AppHandle) -> bool {
+    app.store(SETTINGS_STORE)
+        .ok()
+        .and_then(|store| store.get(WSL_CLI_INSTALL_KEY))
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false)
+}
+
+/// Reinstalls the CLI in WSL when it's older than the app. The native sync never looks inside
+/// the distro, so this copy would otherwise stay on the version first installed.
+pub async fn sync_cli(app: &AppHandle) -> Result<(), String> {
+    if !cfg!(windows) {
+        return Ok(());
+    }
+
+    if cfg!(debug_assertions) {
+        tracing::debug!("Skipping WSL CLI sync for debug build");
+        return Ok(());
+    }
+
+    if !cli_install_allowed(app) {
+        tracing::info!("CLI install in WSL wasn't allowed, skipping WSL sync");
+        return Ok(());
+    }
+
+    let Some(cli_version) = installed_cli_version(app).await? else {
+        tracing::info!("No CLI installation found in WSL, skipping sync");
+        return Ok(());
+    };
+
+    let app_version = app.package_info().version.clone();
+    if cli_version >= app_version {
+        tracing::info!(
+            %cli_version, %app_version,
+            "CLI in WSL is up to date, skipping sync"
+        );
+        return Ok(());
+    }
+
+    tracing::info!(
+        %cli_version, %app_version,
+        "CLI in WSL is older than app version, syncing"
+    );
+
+    let version = bootstrap_cli(app, true, &|_| {}).await?;
+
+    tracing::info!(%version, "Synced CLI in WSL");
+    Ok(())
+}
+
+/// Arguments selecting the configured distro, to go before anything else passed to `wsl`.
+pub fn distro_args(app: &AppHandle) -> Vec<String> {
+    get_wsl_distro(app.clone())
+        .ok()
+        .flatten()
+        .map(|distro| vec!["-d".to_string(), distro])
+        .unwrap_or_default()
+}
+
+pub fn wsl() -> Command {
+    let mut cmd = Command::new("wsl");
+    cmd.kill_on_drop(true);
+
+    #[cfg(windows)]
+    cmd.creation_flags(0x0800_0000);
+
+    cmd
+}
+
+async fn feature_enabled() -> bool {
+    wsl()
+        .arg("--status")
+        .output()
+        .await
+        .is_ok_and(|output| output.status.success())
+}
+
+async fn list_distros() -> Vec<String> {
+    let Ok(output) = wsl().args(["--list", "--quiet"]).output().await else {
+        return vec![];
+    };
+
+    if !output.status.success() {
+        return vec![];
+    }
+
+    parse_distros(&decode_wsl_output(&output.stdout))
+}
+
+async fn cli_version() -> Option<String> {
+    let output = wsl()
+        .args([
+            "-e",
+            "bash",
+            "-lc",
+            "\"$HOME/.opencode/bin/opencode\" --version",
+        ])
+        .output()
+        .await
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// `None` when the distro has no CLI yet
+async fn installed_cli_version(app: &AppHandle) -> Result<Option<semver::Version>, String> {
+    let script = WslScript::new()
+        .fail_if_missing()
+        .args(["--version"])
+        .build();
+
+    let output = tokio::time::timeout(
+        CLI_VERSION_TIMEOUT,
+        wsl()
+            .args(distro_args(app))
+            .args(["-e", "bash", "-lc", &script])
+            .stdin(Stdio::null())
+            .output(),
+    )
+    .await
+    .map_err(|_| "Timed out getting CLI version in WSL".to_string())?
+    .map_err(|e| format!("Failed to get CLI version in WSL: {}", e))?;
+
+    match output.status.code() {
+        Some(0) => {}
+        Some(WSL_CLI_MISSING_EXIT_CODE) => return Ok(None),
+        code => {
+            return Err(format!(
+                "Failed to get CLI version in WSL (code={:?})",
+                code
+            ));
+        }
+    }
+
+    let cli_version_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    semver::Version::parse(&cli_version_str)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse CLI version '{}': {}", cli_version_str, e))
+}
+
+// `wsl --install` needs admin rights, so it's started through an elevated PowerShell. The
+// first distro launch then opens its own console to create the Linux user.
+async fn install_wsl() -> Result<(), String> {
+    let mut cmd = Command::new("powershell");
+    cmd.args([
+        "-NoProfile",
+        "-Command",
+        "Start-Process -FilePath wsl.exe -ArgumentList '--install' -Verb RunAs",
+    ]);
+
+    #[cfg(windows)]
+    cmd.creation_flags(0x0800_0000);
+
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to start WSL installation: {}", e))?;
+
+    if !output.status.success() {
+        return Err("WSL installation was cancelled".to_string());
+    }
+
+    Ok(())
+}
+
+async fn wait_for_distro(events: &Channel<WslSetupProgress>) -> Result<Vec<String>, String> {
+    let started = Instant::now();
+
+    loop {
+        let distros = list_distros().await;
+        // A distro is listed as soon as it's registered, but can't run commands until the
+        // user has finished creating their account.
+        if !distros.is_empty()
+            && wsl()
+                .args(["-e", "true"])
+                .output()
+                .await
+                .is_ok_and(|output| output.status.success())
+        {
+            return Ok(distros);
+        }
+
+        let elapsed = started.elapsed();
+        if elapsed > DISTRO_INSTALL_TIMEOUT {
+            return Err("Timed out waiting for the WSL distro to finish installing".to_string());
+        }
+
+        let _ = events.send(WslSetupProgress::WaitingForDistro {
+            elapsed_secs: elapsed.as_secs() as u32,
+        });
+        sleep(DISTRO_POLL_INTERVAL).await;
+    }
+}
+
+async fn bootstrap_cli(
+    app: &AppHandle,
+    reinstall: bool,
+    on_progress: &(dyn Fn(WslInstallProgress) + Sync),
+) -> Result<String, BootstrapError> {
+    let version = app.package_info().version.to_string();
+    let script = if reinstall {
+        WslScript::new().reinstall(version)
+    } else {
+        WslScript::new().install_if_missing(version)
+    }
+    .args(["--version"]);
+
+    let mut child = wsl()
+        .args(distro_args(app))
+        .args(["-e", "bash", "-lc", &script.build()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| BootstrapError::Failed(format!("Failed to run CLI bootstrap: {}", e)))?;
+
+    // Closing stdin afterwards leaves the CLI with nothing to read
+    let stdin = script.stdin().unwrap_or_default();
+    let pipe = child.stdin.take();
+    let write_stdin = async move {
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.write_all(stdin.as_bytes()).await;
+        }
+    };
+
+    // The install script prints its messages on stdout and curl's progress bar on stderr,
+    // while the last line on stdout is the installed version
+    let (version, _, _) = tokio::join!(
+        read_install_output(child.stdout.take(), on_progress),
+        read_install_output(child.stderr.take(), on_progress),
+        write_stdin,
+    );
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| BootstrapError::Failed(format!("Failed to run CLI bootstrap: {}", e)))?;
+
+    match status.code() {
+        Some(0) => version.ok_or_else(|| {
+            BootstrapError::Failed("CLI bootstrap didn't report a version".to_string())
+        }),
+        Some(INSTALL_VERIFY_EXIT_CODE) => Err(BootstrapError::ChecksumMismatch),
+        code => Err(BootstrapError::Failed(format!(
+            "CLI bootstrap failed (code={:?})",
+            code
+        ))),
+    }
+}
+
+// Splits on `\r` as well as newlines so each redraw of curl's progress bar is seen. Returns the
+// last plain output line.
+async fn read_install_output(
+    reader: Option<impl AsyncRead + Unpin>,
+    on_progress: &(dyn Fn(WslInstallProgress) + Sync),
+) -> Option<String> {
+    let mut segments = BufReader::new(reader?).split(b'\r');
+    let mut last = None;
+    let mut downloaded = false;
+
+    while let Ok(Some(segment)) = segments.next_segment().await {
+        for line in String::from_utf8_lossy(&segment).lines() {
+            let line = strip_ansi(line).trim().to_string();
+            let Some(progress) = install_progress(&line) else {
+                continue;
+            };
+
+            if progress == (WslInstallProgress::Downloading { percent: Some(100) }) {
+                // The archive is unpacked right after the download, without any output
+                if !downloaded {
+                    downloaded = true;
+                    on_progress(progress);
+                    on_progress(WslInstallProgress::Extracting);
+                }
+                continue;
+            }
+
+            if matches!(progress, WslInstallProgress::Output { .. }) {
+                last = Some(line);
+            }
+            on_progress(progress);
+        }
+    }
+
+    last
+}
+
+fn install_progress(line: &str) -> Option<WslInstallProgress> {
+    if line.is_empty() {
+        return None;
+    }
+
+    if line.starts_with("Installing opencode version") {
+        return Some(WslInstallProgress::Downloading { percent: None });
+    }
+
+    if line == "Verifying opencode installation" {
+        return Some(WslInstallProgress::Verifying);
+    }
+
+    // curl's `-#` progress bar, e.g. `######     45.2%`
+    if line.starts_with('#')
+        && let Some(percent) = line
+            .trim_start_matches('#')
+            .trim()
+            .strip_suffix('%')
+            .and_then(|percent| percent.parse::<f32>().ok())
+    {
+        return Some(WslInstallProgress::Downloading {
+            percent: Some(percent.clamp(0.0, 100.0) as u32),
+        });
+    }
+
+    Some(WslInstallProgress::Output {
+        line: line.to_string(),
+    })
+}
+
+// wsl.exe writes its own messages as UTF-16LE, while commands run inside a distro use UTF-8.
+fn decode_wsl_output(bytes: &[u8]) -> String {
+    if bytes.len() >= 2 && bytes.len() % 2 == 0 && bytes.iter().skip(1).step_by(2).all(|b| *b == 0)
+    {
+        let units = bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect::<Vec<_>>();
+        return String::from_utf16_lossy(&units);
+    }
+
+    String::from_utf8_lossy(bytes).to_string()
+}
+
+fn parse_distros(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(|line| line.trim_matches(|c: char| c.is_whitespace() || c == '\u{feff}'))
+        .filter(|line| !line.is_empty())
+        // Docker Desktop's internal distros can't run the CLI
+        .filter(|line| !line.starts_with("docker-desktop"))
+        .map(String::from)
+        .collect()
+}
+
+fn parse_environment_report(output: &str) -> Vec<WslIssue> {
+    output
+        .lines()
+        .filter_map(|line| match line.trim() {
+            "no-network" => Some(WslIssue::NoNetwork),
+            "bin-not-writable" => Some(WslIssue::BinNotWritable),
+            line => line
+                .strip_prefix("missing ")
+                .map(|tool| WslIssue::MissingTool {
+                    tool: tool.to_string(),
+                }),
+        })
+        .collect()
+}
+
+// Skips the header, whose column names are localized. The default distro is marked with `*`.
+fn parse_verbose_distros(output: &str) -> Vec<WslDistro> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let line = line.trim_matches(|c: char| c.is_whitespace() || c == '\u{feff}');
+            let (default, line) = match line.strip_prefix('*') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?.to_string();
+            let state = fields.next()?.to_string();
+            let version = fields.next()?.parse().ok()?;
+
+            Some(WslDistro {
+                name,
+                state,
+                version,
+                default,
+            })
+        })
+        .filter(|distro| !distro.name.starts_with("docker-desktop"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(value: &str) -> Vec<u8> {
+        value.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    #[test]
+    fn decodes_utf16_output() {
+        assert_eq!(decode_wsl_output(&utf16("Ubuntu\r\n")), "Ubuntu\r\n");
+        assert_eq!(decode_wsl_output(b"Ubuntu\n"), "Ubuntu\n");
+    }
+
+    #[test]
+    fn parses_distro_list() {
+        let output = "\u{feff}Ubuntu-24.04\r\ndocker-desktop\r\n\r\nDebian\r\n";
+        assert_eq!(parse_distros(output), ["Ubuntu-24.04", "Debian"]);
+    }
+
+    #[test]
+    fn parses_verbose_distro_list() {
+        let output = "  NAME              STATE           VERSION\r\n\
+                      * Ubuntu-24.04      Running         2\r\n\
+                        docker-desktop    Stopped         2\r\n\
+                        Debian            Stopped         1\r\n";
+
+        assert_eq!(
+            parse_verbose_distros(output),
+            [
+                WslDistro {
+                    name: "Ubuntu-24.04".to_string(),
+                    state: "Running".to_string(),
+                    version: 2,
+                    default: true,
+                },
+                WslDistro {
+                    name: "Debian".to_string(),
+                    state: "Stopped".to_string(),
+                    version: 1,
+                    default: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_environment_report() {
+        assert_eq!(parse_environment_report(""), []);
+        assert_eq!(
+            parse_environment_report("missing curl\nbin-not-writable\nhello from .profile\n"),
+            [
+                WslIssue::MissingTool {
+                    tool: "curl".to_string()
+                },
+                WslIssue::BinNotWritable,
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_install_progress() {
+        assert_eq!(
+            install_progress("Installing opencode version: 1.2.3"),
+            Some(WslInstallProgress::Downloading { percent: None })
+        );
+        assert_eq!(
+            install_progress("##########                  35.7%"),
+            Some(WslInstallProgress::Downloading { percent: Some(35) })
+        );
+        assert_eq!(
+            install_progress("Verifying opencode installation"),
+            Some(WslInstallProgress::Verifying)
+        );
+        assert_eq!(
+            install_progress("1.2.3"),
+            Some(WslInstallProgress::Output {
+                line: "1.2.3".to_string()
+            })
+        );
+        assert_eq!(install_progress(""), None);
+    }
+}
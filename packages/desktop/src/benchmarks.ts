@@ -0,0 +1,17 @@
+import { commands } from "./bindings"
+
+const INVOKE_SAMPLES = 200
+
+// Hidden, for performance bug triage: run `await __OPENCODE__.runBenchmarks()` from devtools
+export async function runBenchmarks() {
+  const payload = "x".repeat(1024)
+  const samples: number[] = []
+
+  for (let i = 0; i < INVOKE_SAMPLES; i++) {
+    const start = performance.now()
+    await commands.benchmarkEcho(payload)
+    samples.push(performance.now() - start)
+  }
+
+  return commands.runBenchmarks(samples)
+}
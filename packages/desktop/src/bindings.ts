@@ -5,47 +5,1339 @@ import * as __TAURI_EVENT from "@tauri-apps/api/event";
 
 /** Commands */
 export const commands = {
-	killSidecar: () => __TAURI_INVOKE<void>("kill_sidecar"),
-	installCli: () => __TAURI_INVOKE<string>("install_cli"),
+	/**
+	 * Kills the sidecar, refusing to touch servers the app didn't spawn unless `force` is set.
+	 */
+	killSidecar: (force: boolean) => __TAURI_INVOKE<null>("kill_sidecar", { force }),
+	/**
+	 * Why the last sidecar exited before becoming healthy, if it did.
+	 */
+	getSidecarFailure: () => __TAURI_INVOKE<SidecarFailure | null>("get_sidecar_failure"),
+	/**
+	 * Installs the bundled sidecar as the `opencode` CLI, streaming the installer's output.
+	 */
+	installCli: (events: Channel) => __TAURI_INVOKE<CliInstallResult>("install_cli", { events }),
+	pinCliVersion: (version: string) => __TAURI_INVOKE<null>("pin_cli_version", { version }),
+	unpinCliVersion: () => __TAURI_INVOKE<null>("unpin_cli_version"),
+	getCliInstallDir: () => __TAURI_INVOKE<string>("get_cli_install_dir"),
+	/**
+	 * Changes where the CLI is installed, moving an existing install to the new location.
+	 * Passing `None` restores the default location.
+	 */
+	setCliInstallDir: (dir: string | null) => __TAURI_INVOKE<null>("set_cli_install_dir", { dir }),
 	awaitInitialization: (events: Channel) => __TAURI_INVOKE<ServerReadyData>("await_initialization", { events }),
 	getDefaultServerUrl: () => __TAURI_INVOKE<string | null>("get_default_server_url"),
 	setDefaultServerUrl: (url: string | null) => __TAURI_INVOKE<null>("set_default_server_url", { url }),
 	getWslConfig: () => __TAURI_INVOKE<WslConfig>("get_wsl_config"),
 	setWslConfig: (config: WslConfig) => __TAURI_INVOKE<null>("set_wsl_config", { config }),
+	/**
+	 * Reports how far along WSL is, so the setup flow can skip finished steps.
+	 */
+	getWslStatus: () => __TAURI_INVOKE<WslStatus>("get_wsl_status"),
+	/**
+	 * Walks through enabling WSL, installing a distro and bootstrapping the CLI inside it,
+	 * enabling the WSL sidecar once everything succeeded.
+	 */
+	runWslSetup: (events: Channel) => __TAURI_INVOKE<null>("run_wsl_setup", { events }),
+	listWslDistros: () => __TAURI_INVOKE<WslDistro[]>("list_wsl_distros"),
+	/**
+	 * The distro hosting the WSL sidecar, `None` for the WSL default.
+	 */
+	getWslDistro: () => __TAURI_INVOKE<string | null>("get_wsl_distro"),
+	setWslDistro: (distro: string | null) => __TAURI_INVOKE<null>("set_wsl_distro", { distro }),
+	/**
+	 * Checks everything WSL mode needs before the sidecar is started in the selected distro, so
+	 * problems can be reported directly instead of as a health check timeout.
+	 */
+	checkWslEnvironment: () => __TAURI_INVOKE<WslEnvironment>("check_wsl_environment"),
+	/**
+	 * Installs the CLI in the selected distro, reporting progress, and returns its version. This
+	 * also lets later launches install the CLI by themselves when it goes missing.
+	 */
+	installCliWsl: (events: Channel) => __TAURI_INVOKE<string>("install_cli_wsl", { events }),
+	getServerFallbackUrls: () => __TAURI_INVOKE<string[]>("get_server_fallback_urls"),
+	setServerFallbackUrls: (urls: string[]) => __TAURI_INVOKE<null>("set_server_fallback_urls", { urls }),
 	getDisplayBackend: () => __TAURI_INVOKE<"wayland" | "auto" | null>("get_display_backend"),
 	setDisplayBackend: (backend: LinuxDisplayBackend) => __TAURI_INVOKE<null>("set_display_backend", { backend }),
 	parseMarkdownCommand: (markdown: string) => __TAURI_INVOKE<string>("parse_markdown_command", { markdown }),
 	checkAppExists: (appName: string) => __TAURI_INVOKE<boolean>("check_app_exists", { appName }),
 	wslPath: (path: string, mode: "windows" | "linux" | null) => __TAURI_INVOKE<string>("wsl_path", { path, mode }),
+	/**
+	 * Converts many paths with a single WSL process. Entries are `None` where the conversion
+	 * failed, in the same order as `paths`.
+	 */
+	wslPaths: (paths: string[], mode: "windows" | "linux" | null) => __TAURI_INVOKE<(string | null)[]>("wsl_paths", { paths, mode }),
 	resolveAppPath: (appName: string) => __TAURI_INVOKE<string | null>("resolve_app_path", { appName }),
+	getShareInfo: () => __TAURI_INVOKE<ShareInfo>("get_share_info"),
+	refreshCliConfig: () => __TAURI_INVOKE<null>("refresh_cli_config"),
+	getCliChannel: () => __TAURI_INVOKE<CliChannel>("get_cli_channel"),
+	/**
+	 * Sets the release channel the installed CLI follows and syncs to it.
+	 */
+	setCliChannel: (channel: CliChannel) => __TAURI_INVOKE<null>("set_cli_channel", { channel }),
+	/**
+	 * Reports the installed CLI version against the latest release on each channel,
+	 * without installing anything.
+	 */
+	checkCliUpdates: () => __TAURI_INVOKE<CliUpdateStatus>("check_cli_updates"),
+	/**
+	 * Runs an allowlisted CLI subcommand, streaming its output until it exits. With
+	 * `interactive`, its stdin stays open for `write_stdin` so prompts can be answered.
+	 */
+	runCliCommand: (args: string[], cwd: string | null, interactive: boolean, events: Channel) => __TAURI_INVOKE<null>("run_cli_command", { args, cwd, interactive, events }),
+	discoverServers: (port: number | null) => __TAURI_INVOKE<DiscoveredServer[]>("discover_servers", { port }),
+	wakeHost: (mac: string, broadcast: string | null) => __TAURI_INVOKE<null>("wake_host", { mac, broadcast }),
+	getWakeOnLanConfig: (url: string) => __TAURI_INVOKE<WakeOnLanConfig | null>("get_wake_on_lan_config", { url }),
+	setWakeOnLanConfig: (url: string, config: WakeOnLanConfig | null) => __TAURI_INVOKE<null>("set_wake_on_lan_config", { url, config }),
+	getQuietHours: () => __TAURI_INVOKE<QuietHours>("get_quiet_hours"),
+	setQuietHours: (config: QuietHours) => __TAURI_INVOKE<null>("set_quiet_hours", { config }),
+	isQuietHours: () => __TAURI_INVOKE<boolean>("is_quiet_hours"),
+	getNotificationRules: () => __TAURI_INVOKE<NotificationRule[]>("get_notification_rules"),
+	/**
+	 * Rules are checked in order, so more specific ones should come first.
+	 */
+	setNotificationRules: (rules: NotificationRule[]) => __TAURI_INVOKE<null>("set_notification_rules", { rules }),
+	/**
+	 * Decides how a notification about `kind` in project `directory` should be delivered.
+	 */
+	routeNotification: (kind: NotificationKind, directory: string | null) => __TAURI_INVOKE<NotificationAction>("route_notification", { kind, directory }),
+	getPowerState: () => __TAURI_INVOKE<PowerState>("get_power_state"),
+	/**
+	 * Writes completion scripts for the installed CLI and returns the path written.
+	 * Uses the user's login shell when `shell` isn't given.
+	 */
+	installShellCompletions: (shell: CompletionShell | null) => __TAURI_INVOKE<string>("install_shell_completions", { shell }),
+	getSidecarLimits: () => __TAURI_INVOKE<SidecarLimits>("get_sidecar_limits"),
+	setSidecarLimits: (limits: SidecarLimits) => __TAURI_INVOKE<null>("set_sidecar_limits", { limits }),
+	getServerServiceStatus: () => __TAURI_INVOKE<ServerServiceStatus>("get_server_service_status"),
+	/**
+	 * Installs a per-user service that keeps `opencode serve` running in the background, which
+	 * the app then connects to instead of spawning its own sidecar.
+	 */
+	installServerService: () => __TAURI_INVOKE<ServerServiceStatus>("install_server_service"),
+	uninstallServerService: () => __TAURI_INVOKE<null>("uninstall_server_service"),
+	getActiveProject: () => __TAURI_INVOKE<string | null>("get_active_project"),
+	/**
+	 * Remembers `path` as the active project and restarts the sidecar in it. Servers the app
+	 * didn't spawn keep their own working directory.
+	 */
+	setActiveProject: (path: string | null) => __TAURI_INVOKE<null>("set_active_project", { path }),
+	getBackendRules: () => __TAURI_INVOKE<BackendRule[]>("get_backend_rules"),
+	/**
+	 * The most specific rule wins. Takes effect the next time the sidecar starts.
+	 */
+	setBackendRules: (rules: BackendRule[]) => __TAURI_INVOKE<null>("set_backend_rules", { rules }),
+	/**
+	 * Where the CLI runs for `path`, or for the active project when unset.
+	 */
+	getEffectiveBackend: (path: string | null) => __TAURI_INVOKE<ProjectBackend>("get_effective_backend", { path }),
+	getShellMode: () => __TAURI_INVOKE<ShellMode>("get_shell_mode"),
+	setShellMode: (mode: ShellMode) => __TAURI_INVOKE<null>("set_shell_mode", { mode }),
+	getAdoptedServer: () => __TAURI_INVOKE<AdoptedServer | null>("get_adopted_server"),
+	getEnvPolicy: () => __TAURI_INVOKE<EnvPolicy>("get_env_policy"),
+	setEnvPolicy: (policy: EnvPolicy) => __TAURI_INVOKE<null>("set_env_policy", { policy }),
+	/**
+	 * Lists exactly which variables the sidecar would be started with under `policy`, or the
+	 * saved policy when none is given. Login shells may still add variables from profiles.
+	 */
+	previewSidecarEnv: (policy: EnvPolicy | null) => __TAURI_INVOKE<SidecarEnvVar[]>("preview_sidecar_env", { policy }),
+	/**
+	 * Lists everything wrong with the bundled sidecar. Empty when it should start fine.
+	 */
+	checkSidecar: () => __TAURI_INVOKE<SidecarIssue[]>("check_sidecar"),
+	/**
+	 * Restores the bundled sidecar from the copy saved at an earlier launch and fixes its
+	 * permissions, returning whatever issues remain.
+	 */
+	repairSidecar: () => __TAURI_INVOKE<SidecarIssue[]>("repair_sidecar"),
+	/**
+	 * Every init step so far with when it started and how long it took, to explain slow
+	 * startups.
+	 */
+	getInitTimeline: () => __TAURI_INVOKE<InitTimelineEntry[]>("get_init_timeline"),
+	/**
+	 * Gives up on starting the server, e.g. when a migration takes too long. Startup fails with an
+	 * error that can be retried from, like any other startup failure.
+	 */
+	cancelInitialization: () => __TAURI_INVOKE<null>("cancel_initialization"),
+	/**
+	 * The stage startup last reported, for a loading window opened after it started.
+	 */
+	getInitProgress: () => __TAURI_INVOKE<InitProgress | null>("get_init_progress"),
+	/**
+	 * Brings the installed CLI up to date right away, instead of waiting for the next launch.
+	 */
+	syncCliNow: () => __TAURI_INVOKE<CliSyncResult>("sync_cli_now"),
+	/**
+	 * Tears down the current server connection and runs the startup flow again without touching
+	 * windows, e.g. after switching servers or toggling WSL. Streams init steps like
+	 * `await_initialization`.
+	 */
+	reinitialize: (events: Channel) => __TAURI_INVOKE<ServerReadyData>("reinitialize", { events }),
+	/**
+	 * Stops a running `install_cli`. The install script is killed and anything it staged is
+	 * removed, so the previous CLI stays in place. On Windows the copy is quick and is left to
+	 * finish.
+	 */
+	cancelInstallCli: () => __TAURI_INVOKE<null>("cancel_install_cli"),
+	/**
+	 * Checks everything the desktop app needs to run opencode, with a suggested fix for each
+	 * problem found.
+	 */
+	runDoctor: () => __TAURI_INVOKE<DoctorReport>("run_doctor"),
+	/**
+	 * Checks that the installed CLI runs, matches the bundled sidecar when it claims the same
+	 * version, and is what the user's shell runs for `opencode`.
+	 */
+	verifyCliInstall: () => __TAURI_INVOKE<CliInstallHealth>("verify_cli_install"),
+	/**
+	 * Reinstalls a missing or broken CLI from the bundled sidecar and puts its directory first
+	 * on PATH in the user's shell config, then verifies again. Another `opencode` that comes
+	 * earlier in PATH is never touched.
+	 */
+	repairCliInstall: () => __TAURI_INVOKE<CliInstallHealth>("repair_cli_install"),
+	/**
+	 * Sends `data` to the stdin of an interactive `run_cli_command`. Include the trailing newline
+	 * to submit a prompt.
+	 */
+	writeStdin: (childId: number, data: string) => __TAURI_INVOKE<null>("write_stdin", { childId, data }),
+	/**
+	 * Installs the app's own CLI ahead of a package manager's on PATH and keeps syncing it from
+	 * then on.
+	 */
+	takeOverCli: () => __TAURI_INVOKE<CliInstallHealth>("take_over_cli"),
+	listChildren: () => __TAURI_INVOKE<ChildInfo[]>("list_children"),
+	/**
+	 * Kills a CLI process other than the server, which is stopped through `kill_sidecar` so the
+	 * app knows it's gone.
+	 */
+	killChild: (id: number) => __TAURI_INVOKE<null>("kill_child", { id }),
+	getOsSearchEnabled: () => __TAURI_INVOKE<boolean>("get_os_search_enabled"),
+	/**
+	 * Turning OS search off removes every entry the app added.
+	 */
+	setOsSearchEnabled: (enabled: boolean) => __TAURI_INVOKE<null>("set_os_search_enabled", { enabled }),
+	/**
+	 * Replaces the OS search entries with the most recently updated sessions, returning how many
+	 * were indexed. Spotlight picks up `.webloc` files on macOS, and Windows Search indexes `.url`
+	 * shortcuts in the Start menu; both open the session through the `opencode://` deep link.
+	 */
+	indexSessionsForSearch: () => __TAURI_INVOKE<number>("index_sessions_for_search"),
+	clearSearchIndex: () => __TAURI_INVOKE<null>("clear_search_index"),
+	/**
+	 * Draws `kind` over the app icon of window `label`, or restores the plain icon for `None`.
+	 * The dock icon on macOS is shared by every window, so there the glyph is shown as a badge.
+	 */
+	setWindowIconOverlay: (label: string, kind: IconOverlay | null) => __TAURI_INVOKE<null>("set_window_icon_overlay", { label, kind }),
+	/**
+	 * Asks the OS to draw attention to window `label`, e.g. when an agent finishes or needs
+	 * approval. Does nothing while the window is focused; `None` cancels an earlier request.
+	 */
+	requestAttention: (label: string, level: AttentionLevel | null) => __TAURI_INVOKE<null>("request_attention", { label, level }),
+	/**
+	 * Shows `count` on the dock icon on macOS, or the launcher icon on Linux desktops with the
+	 * Unity launcher API, e.g. for unread results. `0` clears it. Windows has no count badge, see
+	 * `set_window_icon_overlay` instead.
+	 */
+	setBadgeCount: (count: number) => __TAURI_INVOKE<null>("set_badge_count", { count }),
+	/**
+	 * Shows progress on the taskbar button of window `label` on Windows, or on the dock icon on
+	 * macOS and the launcher icon on Linux, which are shared by every window. `value` is a
+	 * percentage, ignored for `none` and `indeterminate`.
+	 */
+	setTaskbarProgress: (label: string, state: TaskbarProgress, value: number | null) => __TAURI_INVOKE<null>("set_taskbar_progress", { label, state, value }),
+	listPortProxies: () => __TAURI_INVOKE<PortProxyRule[]>("list_port_proxies"),
+	/**
+	 * Forwards the sidecar's port on every Windows interface to the server inside WSL and opens
+	 * it in the firewall, so other devices on the LAN can reach it. Asks for elevation.
+	 */
+	addSidecarPortProxy: () => __TAURI_INVOKE<PortProxyRule>("add_sidecar_port_proxy"),
+	removePortProxy: (listenPort: number) => __TAURI_INVOKE<null>("remove_port_proxy", { listenPort }),
+	/**
+	 * Sessions, tokens and costs over the last `days` days, read from the local database.
+	 */
+	getUsageSummary: (days: number) => __TAURI_INVOKE<UsageSummary>("get_usage_summary", { days }),
+	getUsageDigest: () => __TAURI_INVOKE<UsageDigest>("get_usage_digest"),
+	setUsageDigest: (config: UsageDigest) => __TAURI_INVOKE<null>("set_usage_digest", { config }),
+	/**
+	 * Starts the sidecar again if it was stopped for being idle, resolving once it's healthy.
+	 * The frontend calls this before talking to the server after a long time in the background.
+	 */
+	wakeSidecar: () => __TAURI_INVOKE<null>("wake_sidecar"),
+	/**
+	 * Whether the project at `path`, or the active project when unset, crosses the boundary.
+	 */
+	checkFilesystemBoundary: (path: string | null) => __TAURI_INVOKE<FilesystemBoundaryWarning | null>("check_filesystem_boundary", { path }),
+	getWarmUpEnabled: () => __TAURI_INVOKE<boolean>("get_warm_up_enabled"),
+	/**
+	 * Starts the app in the tray at login and spawns the sidecar before any window opens, so the
+	 * first launch of the day skips waiting for the shell and the server. The same as a hidden
+	 * autostart.
+	 */
+	setWarmUpEnabled: (enabled: boolean) => __TAURI_INVOKE<null>("set_warm_up_enabled", { enabled }),
+	getAutostartState: () => __TAURI_INVOKE<AutostartState>("get_autostart_state"),
+	/**
+	 * Starts the app when the user logs in, in the tray without a window when `hidden`.
+	 */
+	enableAutostart: (hidden: boolean) => __TAURI_INVOKE<null>("enable_autostart", { hidden }),
+	disableAutostart: () => __TAURI_INVOKE<null>("disable_autostart"),
+	/**
+	 * Returns `payload` unchanged, so the frontend can time invoke round trips.
+	 */
+	benchmarkEcho: (payload: string) => __TAURI_INVOKE<string>("benchmark_echo", { payload }),
+	/**
+	 * Measures event emission and markdown rendering throughput. The webview can't be timed from
+	 * here, so invoke latency is computed from `invoke_samples_ms` the caller collected.
+	 */
+	runBenchmarks: (invokeSamplesMs: number[]) => __TAURI_INVOKE<BenchmarkReport>("run_benchmarks", { invokeSamplesMs }),
+	getCloseToTray: () => __TAURI_INVOKE<boolean>("get_close_to_tray"),
+	/**
+	 * Hides the main window on close instead of quitting, so the sidecar keeps running in the
+	 * background until Quit is picked from the tray.
+	 */
+	setCloseToTray: (enabled: boolean) => __TAURI_INVOKE<null>("set_close_to_tray", { enabled }),
+	getGlobalShortcut: () => __TAURI_INVOKE<string | null>("get_global_shortcut"),
+	/**
+	 * Registers a system-wide shortcut like `CommandOrControl+Shift+O` that focuses the main
+	 * window, or creates it when it's closed. `None` removes it. Fails without changing anything
+	 * when another application already holds the shortcut.
+	 */
+	setGlobalShortcut: (accelerator: string | null) => __TAURI_INVOKE<null>("set_global_shortcut", { accelerator }),
+	getPlatformCapabilities: () => __TAURI_INVOKE<PlatformCapabilities>("get_platform_capabilities"),
+	/**
+	 * Opens the project at `path` in a window of its own, or focuses the one already showing it,
+	 * and returns the window's label. The label is the same every time a project is opened, so its
+	 * size and position are restored.
+	 */
+	createProjectWindow: (path: string) => __TAURI_INVOKE<string>("create_project_window", { path }),
+	/**
+	 * Shows the frontend's `route` in the picture-in-picture window, opening it if needed.
+	 */
+	createPipWindow: (route: string) => __TAURI_INVOKE<null>("create_pip_window", { route }),
+	closePipWindow: () => __TAURI_INVOKE<null>("close_pip_window"),
+	/**
+	 * Switches the calling window in or out of fullscreen, returning whether it's now fullscreen.
+	 */
+	toggleFullscreen: () => __TAURI_INVOKE<boolean>("toggle_fullscreen"),
+	/**
+	 * Starts moving the calling window with the mouse, for a mouse button that's held down.
+	 */
+	startDragging: () => __TAURI_INVOKE<null>("start_dragging"),
+	/**
+	 * Acts on the calling window like the button of a native titlebar.
+	 */
+	windowButton: (button: WindowButton) => __TAURI_INVOKE<null>("window_button", { button }),
+	/**
+	 * The last migration that failed, until it's recovered from.
+	 */
+	getMigrationFailure: () => __TAURI_INVOKE<MigrationFailure | null>("get_migration_failure"),
+	/**
+	 * Recovers from the failed migration. Call `reinitialize` afterwards to start the server again,
+	 * except after `Restore`, which quits the app.
+	 */
+	recoverMigration: (recovery: MigrationRecovery) => __TAURI_INVOKE<null>("recover_migration", { recovery }),
+	/**
+	 * Every connected monitor.
+	 */
+	listMonitors: () => __TAURI_INVOKE<MonitorInfo[]>("list_monitors"),
+	/**
+	 * Moves the calling window to the middle of the monitor named `name`, keeping it maximized
+	 * when it was.
+	 */
+	moveWindowToMonitor: (name: string) => __TAURI_INVOKE<null>("move_window_to_monitor", { name }),
+	/**
+	 * The configuration the app is running with, for support to see what a user's app is actually
+	 * doing.
+	 */
+	getEffectiveConfig: () => __TAURI_INVOKE<ConfigEntry[]>("get_effective_config"),
+	/**
+	 * Copies the support info to the clipboard, and returns it.
+	 */
+	copySupportInfo: () => __TAURI_INVOKE<string>("copy_support_info"),
+	getWindowEffect: () => __TAURI_INVOKE<WindowEffect>("get_window_effect"),
+	/**
+	 * Applies `effect` to every window and keeps it for new ones. Returns whether windows need to be
+	 * reopened to show it, which is the case when turning an effect on in windows that were opened
+	 * without one.
+	 */
+	setWindowEffect: (effect: WindowEffect) => __TAURI_INVOKE<boolean>("set_window_effect", { effect }),
+	/**
+	 * Fills window `label` with `color`, a `#rrggbb` or `#rrggbbaa` hex color, and keeps it for
+	 * windows opened later. Does nothing while a window effect is on, which shows instead.
+	 */
+	setWindowBackground: (label: string, color: string) => __TAURI_INVOKE<null>("set_window_background", { label, color }),
+	getDeveloperMode: () => __TAURI_INVOKE<boolean>("get_developer_mode"),
+	/**
+	 * Turning it off closes any open inspector. The menu item to toggle the inspector shows after a
+	 * restart.
+	 */
+	setDeveloperMode: (enabled: boolean) => __TAURI_INVOKE<null>("set_developer_mode", { enabled }),
+	/**
+	 * Opens the inspector for the calling window.
+	 */
+	openDevtools: () => __TAURI_INVOKE<null>("open_devtools"),
+	closeDevtools: () => __TAURI_INVOKE<null>("close_devtools"),
+	/**
+	 * Opens the system's print dialog for the calling window.
+	 */
+	printWindow: () => __TAURI_INVOKE<null>("print_window"),
+	/**
+	 * Saves the calling window's page to `path` as a PDF, without a dialog, replacing the file if it
+	 * exists. Resolves once the file has been written.
+	 */
+	exportPagePdf: (path: string, options: PdfOptions) => __TAURI_INVOKE<null>("export_page_pdf", { path, options }),
+	/**
+	 * Asks whether to apply the deep link `url`, over the calling window. Resolves to whether the
+	 * link may be applied. Links this app doesn't know are refused.
+	 */
+	confirmDeepLink: (url: string) => __TAURI_INVOKE<boolean>("confirm_deep_link", { url }),
+	getWindowStateSettings: () => __TAURI_INVOKE<WindowStateSettings>("get_window_state_settings"),
+	/**
+	 * Saves the settings and applies what can be applied now. Returns whether the app needs to be
+	 * restarted, which is the case when turning persistence back on.
+	 */
+	setWindowStateSettings: (settings: WindowStateSettings) => __TAURI_INVOKE<boolean>("set_window_state_settings", { settings }),
+	/**
+	 * Marks the calling window as having work in progress, e.g. an agent run, with what it's doing
+	 * to show when asking before quitting.
+	 */
+	setBusyState: (busy: boolean, description: string | null) => __TAURI_INVOKE<null>("set_busy_state", { busy, description }),
+	getTitlebarSettings: () => __TAURI_INVOKE<TitlebarSettings>("get_titlebar_settings"),
+	setTitlebarSettings: (settings: TitlebarSettings) => __TAURI_INVOKE<null>("set_titlebar_settings", { settings }),
+	/**
+	 * Acts on the calling window for a double-click on its titlebar.
+	 */
+	titlebarDoubleClick: () => __TAURI_INVOKE<null>("titlebar_double_click"),
+	/**
+	 * Moves the window buttons of every window to `x` from the side of the titlebar they're on and
+	 * `y` from the top, e.g. to center them in the header after it changed height.
+	 */
+	setTrafficLightInset: (x: number, y: number) => __TAURI_INVOKE<null>("set_traffic_light_inset", { x, y }),
+	getQuickPanelShortcut: () => __TAURI_INVOKE<string | null>("get_quick_panel_shortcut"),
+	/**
+	 * Registers a system-wide shortcut that slides the quick panel down from the top of the screen,
+	 * or back up when it's showing. `None` removes it.
+	 */
+	setQuickPanelShortcut: (accelerator: string | null) => __TAURI_INVOKE<null>("set_quick_panel_shortcut", { accelerator }),
+	/**
+	 * Keeps the focused window above all others.
+	 */
+	setWindowAlwaysOnTop: (enabled: boolean) => __TAURI_INVOKE<null>("set_window_always_on_top", { enabled }),
+	/**
+	 * Sets how opaque the focused window is, from 0.2 to 1. Needs a compositor on Linux.
+	 */
+	setWindowOpacity: (opacity: number) => __TAURI_INVOKE<null>("set_window_opacity", { opacity }),
+	/**
+	 * Shrinks the focused window to a narrow pane, or brings back its earlier size.
+	 */
+	setCompactMode: (enabled: boolean) => __TAURI_INVOKE<null>("set_compact_mode", { enabled }),
+	getZoomLevel: () => __TAURI_INVOKE<number>("get_zoom_level"),
+	/**
+	 * Scales the calling window's content, where 1 is the default size. Kept for the window's label,
+	 * since pinch and the webview's own zoom shortcuts are disabled.
+	 */
+	setZoomLevel: (level: number) => __TAURI_INVOKE<null>("set_zoom_level", { level }),
+	setMenuLabels: (labels: MenuLabels) => __TAURI_INVOKE<null>("set_menu_labels", { labels }),
 };
 
 /** Events */
 export const events = {
 	loadingWindowComplete: makeEvent<LoadingWindowComplete>("loading-window-complete"),
 	sqliteMigrationProgress: makeEvent<SqliteMigrationProgress>("sqlite-migration-progress"),
+	serverEndpointChanged: makeEvent<ServerEndpointChanged>("server-endpoint-changed"),
+	sidecarMemoryLimitHit: makeEvent<SidecarMemoryLimitHit>("sidecar-memory-limit-hit"),
+	slowLoginShell: makeEvent<SlowLoginShell>("slow-login-shell"),
+	cliSyncProgress: makeEvent<CliSyncProgress>("cli-sync-progress"),
+	cliManagedExternally: makeEvent<CliManagedExternally>("cli-managed-externally"),
+	usageDigestReady: makeEvent<UsageDigestReady>("usage-digest-ready"),
+	sidecarIdleChanged: makeEvent<SidecarIdleChanged>("sidecar-idle-changed"),
+	filesystemBoundaryWarning: makeEvent<FilesystemBoundaryWarning>("filesystem-boundary-warning"),
+	menuCommand: makeEvent<MenuCommand>("menu-command"),
+	migrationFailed: makeEvent<MigrationFailed>("migration-failed"),
+	initProgress: makeEvent<InitProgress>("init-progress"),
+	secondInstance: makeEvent<SecondInstance>("second-instance"),
+	frontendReady: makeEvent<FrontendReady>("frontend-ready"),
 };
 
 /* Types */
+/**
+ * A server the app connected to but didn't start, so it must never be killed by the app.
+ */
+export type AdoptedServer = {
+		url: string,
+		version: string | null,
+		requires_auth: boolean,
+		client_id: string,
+	};
+
+/**
+ * A CLI process spawned by the app that hasn't exited yet.
+ */
+export type AttentionLevel = 
+/**
+ * Bounces the dock icon once, or flashes the taskbar button until the window is focused.
+ */
+"informational" | 
+/**
+ * Keeps bouncing the dock icon until the app is activated.
+ */
+"critical";
+
+export type AutostartState = {
+		enabled: boolean,
+		/**
+		 * Start in the tray with the server running, and open no window until one is asked for.
+		 */
+		hidden: boolean,
+	};
+
+/**
+ * Runs projects under `prefix` natively or in WSL, regardless of the global WSL setting.
+ */
+export type BackendRule = {
+		/**
+		 * A directory such as `C:\work` or `\\wsl$\Ubuntu\home`, matched case-insensitively.
+		 */
+		prefix: string,
+		backend: ProjectBackend,
+	};
+
+/**
+ * Performance of the shell on this machine, attached to performance bug reports.
+ */
+export type BenchmarkReport = {
+		os: string,
+		arch: string,
+		cpus: number,
+		/**
+		 * Round trips of `benchmark_echo` as timed by the caller.
+		 */
+		invoke: LatencyStats | null,
+		events_per_sec: number,
+		markdown_mb_per_sec: number,
+	};
+
+export type BoundaryCrossing = 
+/**
+ * A sidecar inside WSL working on the Windows drive, e.g. `/mnt/c/...`.
+ */
+"wsl_to_windows" | 
+/**
+ * A Windows sidecar working inside WSL through `\\wsl$\...`.
+ */
+"windows_to_wsl";
+
+export type ChildInfo = {
+		id: number,
+		pid: number | null,
+		/**
+		 * Arguments the CLI was started with, e.g. `debug config`.
+		 */
+		args: string,
+		/**
+		 * Whether the process accepts `write_stdin`.
+		 */
+		interactive: boolean,
+		running_ms: number,
+	};
+
+export type CliChannel = "stable" | "beta" | "nightly";
+
+export type CliChannelVersion = {
+		channel: CliChannel,
+		latest: string | null,
+		update_available: boolean,
+	};
+
+export type CliCommandEvent = 
+/**
+ * Always sent first, with the id to pass to `write_stdin` or `kill_child`.
+ */
+{ type: "started"; child_id: number | null } | { type: "stdout"; line: string } | { type: "stderr"; line: string } | { type: "error"; message: string } | { type: "terminated"; code: number | null; signal: number | null };
+
+export type CliInstallHealth = {
+		path: string,
+		version: string | null,
+		issues: CliInstallIssue[],
+	};
+
+/**
+ * Something wrong with the installed CLI.
+ */
+export type CliInstallIssue = { kind: "not_installed" } | 
+/**
+ * The binary is there, but `opencode --version` fails.
+ */
+{ kind: "does_not_run"; error: string } | 
+/**
+ * The binary claims the bundled version but its contents differ from the bundled sidecar.
+ */
+{ kind: "checksum_mismatch" } | 
+/**
+ * The user's shell can't find `opencode` at all.
+ */
+{ kind: "not_on_path" } | 
+/**
+ * The user's shell finds another `opencode` first, e.g. one installed with Homebrew.
+ */
+{ kind: "shadowed"; path: string };
+
+export type CliInstallResult = {
+		path: string,
+		/**
+		 * Shell config files the installer added the CLI's directory to `PATH` in.
+		 */
+		modified_rc_files: string[],
+	};
+
+/**
+ * Sent when sync leaves the CLI alone because a package manager owns the `opencode` the user
+ * runs. `take_over_cli` makes the app manage it instead.
+ */
+export type CliManagedExternally = {
+		manager: PackageManager,
+		path: string,
+		upgrade_command: string,
+	};
+
+export type CliSyncProgress = 
+/**
+ * Comparing the installed CLI against the app and the release channel.
+ */
+{ status: "checking" } | 
+/**
+ * Downloading & installing a newer release through the install script.
+ */
+{ status: "downloading"; from: string; to: string } | 
+/**
+ * Installing the sidecar bundled with the app.
+ */
+{ status: "installing"; from: string; to: string } | { status: "done"; result: CliSyncResult } | { status: "failed"; message: string };
+
+export type CliSyncResult = {
+		/**
+		 * Installed version before the sync.
+		 */
+		before: string | null,
+		/**
+		 * Installed version after the sync, differing from `before` when the CLI was updated.
+		 */
+		after: string | null,
+		skipped: CliSyncSkip | null,
+	};
+
+/**
+ * Why a CLI sync didn't install anything.
+ */
+export type CliSyncSkip = { reason: "debug_build" } | 
+/**
+ * The `opencode` the user runs belongs to a package manager, which should update it.
+ */
+{ reason: "managed_externally"; manager: PackageManager } | { reason: "not_installed" } | { reason: "pinned"; version: string } | { reason: "up_to_date" };
+
+export type CliUpdateStatus = {
+		installed: string | null,
+		pinned: string | null,
+		channel: CliChannel,
+		channels: CliChannelVersion[],
+	};
+
+export type CompletionShell = "bash" | "zsh" | "fish" | "nushell";
+
+export type ConfigEntry = {
+		key: string,
+		/**
+		 * The value as JSON, with secrets redacted.
+		 */
+		value: string,
+		source: ConfigSource,
+		/**
+		 * Set when the value is ignored because another source takes precedence.
+		 */
+		overridden_by: ConfigSource | null,
+};
+
+export type ConfigSource = 
+/**
+ * Fixed when the app was built.
+ */
+"build" | 
+/**
+ * A flag the app was launched with.
+ */
+"launch_argument" | 
+/**
+ * An environment variable the app was started with.
+ */
+"environment" | 
+/**
+ * A policy in the settings that limits what else applies, e.g. the sidecar's environment.
+ */
+"policy" | 
+/**
+ * Saved in the app's settings.
+ */
+"settings" | 
+/**
+ * The `server` block of the CLI's config.
+ */
+"cli_config";
+
+export type ContainerKind = "codespaces" | "gitpod" | "container";
+
+/**
+ * Why a read from the local database failed.
+ */
+export type DbQueryError = 
+/**
+ * The server is migrating the database. Reads work again once `SqliteMigrationProgress`
+ * reports it's done.
+ */
+{ kind: "migration_in_progress" } | { kind: "failed"; message: string };
+
+export type DiscoveredServer = {
+		name: string,
+		url: string,
+		source: DiscoverySource,
+	};
+
+export type DiscoverySource = "tailscale";
+
+export type DoctorCheck = {
+		name: string,
+		status: DoctorStatus,
+		detail: string,
+		/**
+		 * What the user can do about a warning or failure.
+		 */
+		remediation: string | null,
+	};
+
+export type DoctorReport = {
+		checks: DoctorCheck[],
+	};
+
+export type DoctorStatus = "pass" | "warn" | "fail";
+
+export type DoubleClickAction = 
+/**
+ * What the OS is set to do on macOS, maximizing elsewhere.
+ */
+"system" | 
+/**
+ * Maximizes the window, or restores it when it's maximized.
+ */
+"maximize" | "minimize" | "none";
+
+/**
+ * Which of the app's environment variables are passed on to the sidecar. Variables the app
+ * sets itself, like `OPENCODE_CLIENT`, are always passed. Patterns may end in `*` to match a
+ * prefix, e.g. `AWS_*`.
+ */
+export type EnvPolicy = { policy: "inherit_all" } | 
+/**
+ * Only pass variables matching `vars`, plus the few the sidecar needs to run.
+ */
+{ policy: "allowlist"; vars: string[] } | 
+/**
+ * Pass everything except variables matching `vars`.
+ */
+{ policy: "denylist"; vars: string[] };
+
+/**
+ * Sent when the sidecar starts in a project on the other side of the WSL filesystem boundary.
+ * Every file access then goes over the 9p protocol, which makes watching and indexing slow.
+ */
+export type FilesystemBoundaryWarning = {
+		path: string,
+		crossing: BoundaryCrossing,
+		impact: string,
+		suggestion: string,
+	};
+
+/**
+ * Sent by a window's frontend once it has painted for the first time.
+ */
+export type FrontendReady = {
+		label: string,
+	};
+
+/**
+ * Status shown over a window's icon so it can be told apart from other opencode windows.
+ */
+export type IconOverlay = "running" | "needs_attention" | "error";
+
+/**
+ * What startup is doing. Stages can arrive out of order, e.g. the CLI syncs alongside the
+ * server starting.
+ */
+export type InitProgress = 
+/**
+ * Bringing the installed CLI up to date.
+ */
+{ stage: "cli_sync" } | 
+/**
+ * Reading the server settings and the CLI's config.
+ */
+{ stage: "config_read" } | 
+/**
+ * Starting the local server.
+ */
+{ stage: "server_spawn" } | 
+/**
+ * Waiting for the server to respond, with how much of the time it's given has passed.
+ */
+{ stage: "health_check"; percent: number } | 
+/**
+ * Migrating the database.
+ */
+{ stage: "migration"; percent: number };
+
 export type InitStep = { phase: "server_waiting" } | { phase: "sqlite_waiting" } | { phase: "done" };
 
+export type InitTimelineEntry = {
+		step: InitStep,
+		/**
+		 * Milliseconds since initialization started.
+		 */
+		started_ms: number,
+		/**
+		 * How long the step lasted, or has lasted so far if it's still the current one.
+		 */
+		duration_ms: number,
+	};
+
+export type InstallProgress = { type: "started"; path: string } | 
+/**
+ * A line printed by the install script, without terminal colors.
+ */
+{ type: "output"; line: string };
+
+/**
+ * Why a command rejected its input.
+ */
+export type InvalidInput = { kind: "empty"; field: string } | { kind: "too_long"; field: string; max: number } | 
+/**
+ * Contains a control character such as a newline or NUL.
+ */
+{ kind: "control_character"; field: string } | { kind: "invalid_url"; field: string; reason: string } | { kind: "too_many"; field: string; max: number };
+
+export type KillSidecarError = 
+/**
+ * The server wasn't started by this app. Pass `force` to kill it anyway.
+ */
+{ kind: "not_owned"; url: string | null };
+
+export type LatencyStats = {
+		samples: number,
+		min_ms: number,
+		median_ms: number,
+		p95_ms: number,
+		max_ms: number,
+	};
+
 export type LinuxDisplayBackend = "wayland" | "auto";
 
 export type LoadingWindowComplete = null;
 
+/**
+ * Sent to every window when a menu item is picked, for the focused one to run the command with
+ * this id.
+ */
+export type MenuCommand = {
+		id: string,
+	};
+
+/**
+ * Labels for the items the frontend translates, applied once its locale is known.
+ */
+export type MenuLabels = {
+		check_for_updates: string,
+		install_cli: string,
+		reload_webview: string,
+		restart: string,
+	};
+
+/**
+ * Sent when the sidecar exits mid-migration, before startup fails.
+ */
+export type MigrationFailed = {
+		failure: MigrationFailure,
+	};
+
+/**
+ * A migration the sidecar didn't finish.
+ */
+export type MigrationFailure = {
+		/**
+		 * Why the sidecar stopped, including its exit code or signal.
+		 */
+		message: string,
+		/**
+		 * When the migration failed, in RFC 3339.
+		 */
+		time: string,
+	};
+
+export type MigrationRecovery = 
+/**
+ * Puts the data back the way it was before the migration and quits, e.g. to go back to an
+ * earlier version of the app.
+ */
+"restore" | 
+/**
+ * Puts the data back and migrates it again.
+ */
+"retry" | 
+/**
+ * Sets the data aside and starts with an empty database.
+ */
+"start_fresh";
+
+export type MonitorInfo = {
+		/**
+		 * Pass to `move_window_to_monitor`. Not every platform names its monitors.
+		 */
+		name: string | null,
+		/**
+		 * Position and size in physical pixels, in the desktop's coordinates.
+		 */
+		x: number,
+		y: number,
+		width: number,
+		height: number,
+		scale_factor: number,
+		primary: boolean,
+	};
+
+export type NotificationAction = 
+/**
+ * Show a system notification.
+ */
+"toast" | 
+/**
+ * Only play the in-app sound, without a system notification.
+ */
+"sound" | 
+/**
+ * Only mark the window's icon.
+ */
+"badge_only" | "suppress";
+
+export type NotificationKind = "response_ready" | "permission" | "question" | "error";
+
+/**
+ * Unset fields match anything.
+ */
+export type NotificationRule = {
+		kind: NotificationKind | null,
+		/**
+		 * A project directory, or just its folder name.
+		 */
+		project: string | null,
+		severity: NotificationSeverity | null,
+		action: NotificationAction,
+	};
+
+export type NotificationSeverity = "info" | "warning" | "error";
+
+/**
+ * Where an `opencode` binary outside the app's own install directory came from.
+ */
+export type PackageManager = "homebrew" | 
+/**
+ * apt, or another distribution package manager installing into `/usr`.
+ */
+"apt" | "scoop" | "npm" | "nix";
+
+export type PdfOptions = {
+		/**
+		 * Lays the pages out in landscape rather than portrait.
+		 */
+		landscape: boolean,
+	};
+
+/**
+ * What the webview the app runs in supports, for features that depend on the platform.
+ */
+export type PlatformCapabilities = {
+		/**
+		 * WebKitGTK version the app is running with, on Linux.
+		 */
+		webkitgtk_version: string | null,
+		/**
+		 * Whether the webview is recent enough for the frontend to render correctly.
+		 */
+		webview_supported: boolean,
+		/**
+		 * How to update the webview when it's unsupported, specific to the distro when known.
+		 */
+		webview_guidance: string | null,
+	};
+
+/**
+ * A `netsh interface portproxy` rule forwarding a Windows port, e.g. to a server inside WSL.
+ */
+export type PortProxyRule = {
+		listen_address: string,
+		listen_port: number,
+		connect_address: string,
+		connect_port: number,
+	};
+
+export type PowerState = {
+		on_battery: boolean,
+		battery_percent: number | null,
+		power_saver: boolean,
+	};
+
+/**
+ * Where the CLI runs for a project on Windows.
+ */
+export type ProjectBackend = "native" | "wsl";
+
+export type QuietHours = {
+		enabled: boolean,
+		/**
+		 * Start of the quiet period as 24h `HH:MM`.
+		 */
+		start: string,
+		/**
+		 * End of the quiet period as 24h `HH:MM`. May be earlier than `start` to wrap midnight.
+		 */
+		end: string,
+		/**
+		 * Fixed offset from UTC in minutes. The system timezone is used when unset.
+		 */
+		utc_offset_minutes: number | null,
+	};
+
+/**
+ * Sent to the window a second launch is for, with what it was launched with. Deep links opening
+ * a project go to that project's window when it has one of its own, everything else to the main
+ * window.
+ */
+export type SecondInstance = {
+		/**
+		 * Arguments without the program name.
+		 */
+		args: string[],
+		/**
+		 * Working directory of the second launch, for relative paths in `args`.
+		 */
+		cwd: string,
+	};
+
+export type ServerEndpointChanged = {
+		/**
+		 * The endpoint that stopped responding.
+		 */
+		previous: string,
+		url: string,
+		password: string | null,
+	};
+
 export type ServerReadyData = {
 		url: string,
 		password: string | null,
 	};
 
+export type ServerServiceStatus = {
+		installed: boolean,
+		running: boolean,
+		url: string | null,
+	};
+
+export type ShareInfo = {
+		url: string,
+		forwarded_url: string | null,
+		container: ContainerKind | null,
+	};
+
+/**
+ * How the sidecar is launched on macOS & Linux.
+ */
+export type ShellMode = 
+/**
+ * Through a login `$SHELL`, so PATH and other variables from shell profiles apply.
+ */
+{ mode: "login" } | 
+/**
+ * Execute the sidecar directly with the app's own environment.
+ */
+{ mode: "direct" } | 
+/**
+ * Through `shell`, with `args` placed before the sidecar command line, e.g. `["-c"]`.
+ */
+{ mode: "custom"; shell: string; args: string[] };
+
+export type SidecarEnvVar = {
+		key: string,
+		value: string,
+	};
+
+/**
+ * Why the sidecar exited before becoming healthy, so the frontend can suggest a fix.
+ */
+export type SidecarFailure = { kind: "port_in_use"; port: number | null } | { kind: "missing_shared_library"; library: string | null } | { kind: "config_parse_error"; detail: string } | { kind: "crashed_with_signal"; signal: number } | { kind: "auth_misconfigured" } | 
+/**
+ * WSL mode is on but the CLI still has to be installed with `install_cli_wsl`.
+ */
+{ kind: "wsl_cli_missing" } | 
+/**
+ * The CLI release downloaded into WSL didn't match its published sha256.
+ */
+{ kind: "checksum_mismatch" } | 
+/**
+ * The sidecar couldn't be started at all. `issues` lists what's wrong with the bundled
+ * binary, which `repair_sidecar` can fix.
+ */
+{ kind: "spawn_failed"; error: string; issues: SidecarIssue[] } | { kind: "unknown"; code: number | null };
+
+/**
+ * Sent when the sidecar is stopped for being idle, and again once it's running after a wake.
+ */
+export type SidecarIdleChanged = {
+		stopped: boolean,
+	};
+
+/**
+ * Something that keeps the bundled sidecar from starting.
+ */
+export type SidecarIssue = { kind: "missing" } | 
+/**
+ * The file doesn't match the size the app was bundled with, e.g. after a broken update.
+ */
+{ kind: "size_mismatch" } | { kind: "checksum_mismatch" } | { kind: "not_executable" } | 
+/**
+ * macOS refuses to run the binary until the quarantine attribute is removed.
+ */
+{ kind: "quarantined" };
+
+export type SidecarLimits = {
+		priority: SidecarPriority,
+		/**
+		 * CPU cap as a percentage of one core. Linux only, applied through a systemd user scope.
+		 */
+		cpu_limit_percent: number | null,
+		/**
+		 * Resident memory ceiling for the sidecar and its children, in MB.
+		 */
+		memory_limit_mb: number | null,
+		/**
+		 * Start a fresh sidecar after it's killed for exceeding `memory_limit_mb`.
+		 */
+		restart_on_memory_limit: boolean,
+		/**
+		 * Stop the sidecar after this many minutes without a focused window or a busy session.
+		 * It starts again on the same port and password when a window is focused.
+		 */
+		idle_shutdown_minutes: number | null,
+	};
+
+export type SidecarMemoryLimitHit = {
+		limit_mb: number,
+		usage_mb: number,
+		restarting: boolean,
+	};
+
+export type SidecarPriority = "normal" | "below_normal" | "idle";
+
+export type SlowLoginShell = {
+		shell: string,
+		startup_ms: number,
+	};
+
 export type SqliteMigrationProgress = { type: "InProgress"; value: number } | { type: "Done" };
 
+export type TaskbarProgress = 
+/**
+ * Removes the progress bar.
+ */
+"none" | "normal" | 
+/**
+ * Shows activity without a value, e.g. while an agent runs.
+ */
+"indeterminate" | 
+/**
+ * Yellow on Windows.
+ */
+"paused" | 
+/**
+ * Red on Windows.
+ */
+"error";
+
+export type TitlebarSettings = {
+		double_click: DoubleClickAction,
+		/**
+		 * Swipe between pages with the trackpad, with the fingers set in System Settings. macOS only.
+		 */
+		swipe_navigation: boolean,
+		/**
+		 * Shaking a window by its titlebar minimizes the app's other windows. Windows does this
+		 * itself for every window, following the system setting.
+		 */
+		shake_to_minimize: boolean,
+	};
+
+export type UsageDigest = {
+		enabled: boolean,
+		/**
+		 * Day to send the digest on, from 0 for Monday to 6 for Sunday.
+		 */
+		weekday: number,
+		/**
+		 * Local hour to send the digest at, from 0 to 23.
+		 */
+		hour: number,
+	};
+
+/**
+ * Sent along with the digest notification so the app can show the full summary.
+ */
+export type UsageDigestReady = {
+		summary: UsageSummary,
+	};
+
+/**
+ * Totals over assistant messages, with subagent sessions counted towards their parent.
+ */
+export type UsageSummary = {
+		days: number,
+		sessions: number,
+		input_tokens: number,
+		output_tokens: number,
+		reasoning_tokens: number,
+		cache_read_tokens: number,
+		cache_write_tokens: number,
+		/**
+		 * In US dollars, as reported by the providers.
+		 */
+		cost: number,
+	};
+
+export type WakeOnLanConfig = {
+		mac: string,
+		broadcast: string | null,
+		delay_secs: number | null,
+	};
+
+export type WindowButton = "minimize" | 
+/**
+ * Maximizes the window, or restores it when it's maximized.
+ */
+"maximize" | "close";
+
+export type WindowEffect = "none" | 
+/**
+ * macOS only.
+ */
+"vibrancy" | 
+/**
+ * Windows 11 only.
+ */
+"mica" | 
+/**
+ * Windows only.
+ */
+"acrylic" | 
+/**
+ * Windows, and KDE Plasma on X11. GNOME has no way for apps to ask for blur.
+ */
+"blur";
+
+export type WindowStateSettings = {
+		/**
+		 * Keep windows' size and position between launches. Turning it back on needs a restart.
+		 */
+		enabled: boolean,
+		/**
+		 * How long windows must stay still before their geometry is written, up to 10 seconds.
+		 */
+		debounce_ms: number,
+	};
+
 export type WslConfig = {
 		enabled: boolean,
+		/**
+		 * Has the sidecar inside WSL listen on every interface, so `add_sidecar_port_proxy` can
+		 * forward LAN traffic to it. Takes effect when the server next starts.
+		 */
+		lan_access: boolean,
 	};
 
+/**
+ * A distro as listed by `wsl -l -v`.
+ */
+export type WslDistro = {
+		name: string,
+		/**
+		 * e.g. `Running` or `Stopped`, in the language Windows is set to.
+		 */
+		state: string,
+		/**
+		 * 1 or 2. WSL 1 distros can still run the CLI, just more slowly.
+		 */
+		version: number,
+		default: boolean,
+	};
+
+export type WslEnvironment = {
+		/**
+		 * The distro that was checked, `None` when none could be found.
+		 */
+		distro: string | null,
+		/**
+		 * Empty when WSL mode should work.
+		 */
+		issues: WslIssue[],
+	};
+
+/**
+ * Something in WSL that would stop the sidecar from running there.
+ */
+/**
+ * Progress of installing the CLI inside WSL, parsed from the install script's output.
+ */
+export type WslInstallProgress = 
+/**
+ * `percent` is only known once the download has started reporting progress.
+ */
+{ stage: "downloading"; percent: number | null } | { stage: "extracting" } | { stage: "verifying" } | { stage: "output"; line: string };
+
+export type WslIssue = { kind: "not_installed" } | { kind: "no_distro" } | 
+/**
+ * The distro runs on WSL 1, which can't reach servers on the Windows host reliably.
+ */
+{ kind: "not_wsl2"; distro: string } | { kind: "distro_does_not_boot"; error: string } | { kind: "missing_tool"; tool: string } | 
+/**
+ * The install script can't be downloaded from inside the distro.
+ */
+{ kind: "no_network" } | 
+/**
+ * `$HOME/.opencode/bin` can't be created or written to.
+ */
+{ kind: "bin_not_writable" };
+
 export type WslPathMode = "windows" | "linux";
 
+export type WslSetupProgress = { step: "checking_feature" } | 
+/**
+ * Waiting for the user to approve the elevated `wsl --install`.
+ */
+{ step: "installing_wsl" } | 
+/**
+ * WSL was enabled but Windows has to restart before a distro can be installed.
+ */
+{ step: "reboot_required" } | { step: "waiting_for_distro"; elapsed_secs: number } | { step: "distro_ready"; distro: string } | { step: "bootstrapping_cli" } | { step: "cli_output"; line: string } | { step: "done"; version: string };
+
+export type WslStatus = {
+		feature_enabled: boolean,
+		distros: string[],
+		cli_version: string | null,
+	};
+
 /* Tauri Specta runtime */
 function makeEvent<T>(name: string) {
     const base = {
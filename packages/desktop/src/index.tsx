@@ -7,6 +7,8 @@ import {
   PlatformProvider,
   Platform,
   useCommand,
+  useServer,
+  normalizeServerUrl,
   handleNotificationClick,
 } from "@opencode-ai/app"
 import { open, save } from "@tauri-apps/plugin-dialog"
@@ -29,9 +31,10 @@ import { UPDATER_ENABLED } from "./updater"
 import { initI18n, t } from "./i18n"
 import pkg from "../package.json"
 import "./styles.css"
-import { commands, InitStep } from "./bindings"
+import { commands, events, InitStep } from "./bindings"
 import { Channel } from "@tauri-apps/api/core"
 import { createMenu } from "./menu"
+import { runBenchmarks } from "./benchmarks"
 
 const root = document.getElementById("root")
 if (import.meta.env.DEV && !(root instanceof HTMLElement)) {
@@ -55,7 +58,16 @@ const emitDeepLinks = (urls: string[]) => {
 const listenForDeepLinks = async () => {
   const startUrls = await getCurrent().catch(() => null)
   if (startUrls?.length) emitDeepLinks(startUrls)
-  await onOpenUrl((urls) => emitDeepLinks(urls)).catch(() => undefined)
+  // Elsewhere a deep link opened while running starts a second instance, which forwards it to the
+  // one window it's for instead of every window
+  if (ostype() === "macos") {
+    await onOpenUrl((urls) => emitDeepLinks(urls)).catch(() => undefined)
+    return
+  }
+  await events
+    .secondInstance(getCurrentWindow())
+    .listen((e) => emitDeepLinks(e.payload.args.filter((arg) => arg.startsWith("opencode://"))))
+    .catch(() => undefined)
 }
 
 const createPlatform = (password: Accessor<string | null>): Platform => {
@@ -73,7 +85,8 @@ const createPlatform = (password: Accessor<string | null>): Platform => {
   const handleWslPicker = async <T extends string | string[]>(result: T | null): Promise<T | null> => {
     if (!result || !window.__OPENCODE__?.wsl) return result
     if (Array.isArray(result)) {
-      return Promise.all(result.map((path) => commands.wslPath(path, "linux").catch(() => path))) as any
+      const converted = await commands.wslPaths(result, "linux").catch(() => [])
+      return result.map((path, i) => converted[i] ?? path) as any
     }
     return commands.wslPath(result, "linux").catch(() => result) as any
   }
@@ -117,7 +130,7 @@ const createPlatform = (password: Accessor<string | null>): Platform => {
     async openPath(path: string, app?: string) {
       const os = ostype()
       if (os === "windows") {
-        const resolvedApp = (app && (await commands.resolveAppPath(app))) || app
+        const resolvedApp = (app && (await commands.resolveAppPath(app).catch(() => null))) || app
         const resolvedPath = await (async () => {
           if (window.__OPENCODE__?.wsl) {
             const converted = await commands.wslPath(path, "windows").catch(() => null)
@@ -294,6 +307,7 @@ const createPlatform = (password: Accessor<string | null>): Platform => {
 
     checkUpdate: async () => {
       if (!UPDATER_ENABLED) return { updateAvailable: false }
+      if (await commands.isQuietHours().catch(() => false)) return { updateAvailable: false }
       const next = await check().catch(() => null)
       if (!next) return { updateAvailable: false }
       const ok = await next
@@ -307,16 +321,16 @@ const createPlatform = (password: Accessor<string | null>): Platform => {
 
     update: async () => {
       if (!UPDATER_ENABLED || !update) return
-      if (ostype() === "windows") await commands.killSidecar().catch(() => undefined)
+      if (ostype() === "windows") await commands.killSidecar(false).catch(() => undefined)
       await update.install().catch(() => undefined)
     },
 
     restart: async () => {
-      await commands.killSidecar().catch(() => undefined)
+      await commands.killSidecar(false).catch(() => undefined)
       await relaunch()
     },
 
-    notify: async (title, description, href) => {
+    notify: async (title, description, href, meta) => {
       const granted = await isPermissionGranted().catch(() => false)
       const permission = granted ? "granted" : await requestPermission().catch(() => "denied")
       if (permission !== "granted") return
@@ -324,6 +338,20 @@ const createPlatform = (password: Accessor<string | null>): Platform => {
       const win = getCurrentWindow()
       const focused = await win.isFocused().catch(() => document.hasFocus())
       if (focused) return
+      if (await commands.isQuietHours().catch(() => false)) return
+
+      const action = meta
+        ? await commands.routeNotification(meta.kind, meta.directory ?? null).catch(() => "toast" as const)
+        : "toast"
+      // "sound" leaves it to the sound the app already played
+      if (action === "suppress" || action === "sound") return
+      if (action === "badge_only") {
+        await commands.setWindowIconOverlay(win.label, meta?.kind === "error" ? "error" : "needs_attention").catch(
+          () => undefined,
+        )
+        void win.once("tauri://focus", () => commands.setWindowIconOverlay(win.label, null).catch(() => undefined))
+        return
+      }
 
       await Promise.resolve()
         .then(() => {
@@ -370,7 +398,8 @@ const createPlatform = (password: Accessor<string | null>): Platform => {
     },
 
     setWslEnabled: async (enabled) => {
-      await commands.setWslConfig({ enabled })
+      const current = await commands.getWslConfig().catch(() => null)
+      await commands.setWslConfig({ enabled, lan_access: current?.lan_access ?? false })
     },
 
     getDefaultServerUrl: async () => {
@@ -421,6 +450,50 @@ const createPlatform = (password: Accessor<string | null>): Platform => {
         }, "image/png")
       })
     },
+
+    setBusyState: async (busy, description) => {
+      await commands.setBusyState(busy, description ?? null)
+    },
+
+    isQuietHours: () => commands.isQuietHours(),
+
+    titlebarDoubleClick: async () => {
+      await commands.titlebarDoubleClick()
+    },
+
+    setTrafficLightInset: async (x, y) => {
+      await commands.setTrafficLightInset(x, y)
+    },
+
+    setBadgeCount: async (count) => {
+      await commands.setBadgeCount(count)
+    },
+
+    setTaskbarProgress: async (state, value) => {
+      await commands.setTaskbarProgress(getCurrentWindow().label, state, value ?? null)
+    },
+
+    setWindowBackground: async (color) => {
+      // Any CSS color, e.g. a theme's `oklch()`, drawn once to read it back as hex
+      const canvas = document.createElement("canvas")
+      canvas.width = canvas.height = 1
+      const ctx = canvas.getContext("2d")
+      if (!ctx) return
+      ctx.fillStyle = color
+      ctx.fillRect(0, 0, 1, 1)
+      const hex = Array.from(ctx.getImageData(0, 0, 1, 1).data, (channel) => channel.toString(16).padStart(2, "0"))
+      await commands.setWindowBackground(getCurrentWindow().label, `#${hex.join("")}`)
+    },
+
+    printPage: async () => {
+      await commands.printWindow()
+    },
+
+    exportPagePdf: async (path, landscape) => {
+      await commands.exportPagePdf(path, { landscape: landscape ?? false })
+    },
+
+    confirmDeepLink: (url) => commands.confirmDeepLink(url),
   }
 }
 
@@ -429,6 +502,8 @@ createMenu((id) => {
   menuTrigger?.(id)
 })
 void listenForDeepLinks()
+window.__OPENCODE__ ??= {}
+window.__OPENCODE__.runBenchmarks = runBenchmarks
 
 render(() => {
   const [serverPassword, setServerPassword] = createSignal<string | null>(null)
@@ -444,8 +519,20 @@ render(() => {
   }
 
   onMount(() => {
+    // The window is hidden until this, so it opens on the splash instead of a blank page. Hidden
+    // webviews may not run animation frames, so a timer stands in for one
+    let painted = false
+    const ready = () => {
+      if (painted) return
+      painted = true
+      void events.frontendReady.emit({ label: getCurrentWindow().label }).catch(() => undefined)
+    }
+    requestAnimationFrame(ready)
+    const fallback = setTimeout(ready, 100)
+
     document.addEventListener("click", handleClick)
     onCleanup(() => {
+      clearTimeout(fallback)
       document.removeEventListener("click", handleClick)
     })
   })
@@ -461,9 +548,22 @@ render(() => {
 
             function Inner() {
               const cmd = useCommand()
+              const server = useServer()
 
               menuTrigger = (id) => cmd.trigger(id)
 
+              // Follow the shell when it fails over to another endpoint of the server in use
+              onMount(() => {
+                const listener = events.serverEndpointChanged.listen((e) => {
+                  if (server.url !== normalizeServerUrl(e.payload.previous)) return
+                  setServerPassword(e.payload.password)
+                  window.__OPENCODE__ ??= {}
+                  window.__OPENCODE__.serverPassword = e.payload.password ?? undefined
+                  server.setActive(e.payload.url)
+                })
+                onCleanup(() => listener.then((cb) => cb()))
+              })
+
               return null
             }
 
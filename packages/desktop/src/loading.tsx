@@ -4,24 +4,36 @@ import "@opencode-ai/app/index.css"
 import { Font } from "@opencode-ai/ui/font"
 import { Splash } from "@opencode-ai/ui/logo"
 import { Progress } from "@opencode-ai/ui/progress"
+import { Button } from "@opencode-ai/ui/button"
 import "./styles.css"
-import { createEffect, createMemo, createSignal, onCleanup, onMount } from "solid-js"
-import { commands, events, InitStep } from "./bindings"
+import { createEffect, createMemo, createSignal, onCleanup, onMount, Show } from "solid-js"
+import { commands, events, InitProgress, InitStep } from "./bindings"
 import { Channel } from "@tauri-apps/api/core"
 
 const root = document.getElementById("root")!
 const lines = ["Just a moment...", "Migrating your database", "This may take a couple of minutes"]
 const delays = [3000, 9000]
+const stages: Record<InitProgress["stage"], string> = {
+  cli_sync: "Updating the CLI",
+  config_read: "Reading your settings",
+  server_spawn: "Starting the server",
+  health_check: "Waiting for the server",
+  migration: "Migrating your database",
+}
 
 render(() => {
   const [step, setStep] = createSignal<InitStep | null>(null)
   const [line, setLine] = createSignal(0)
   const [percent, setPercent] = createSignal(0)
+  const [progress, setProgress] = createSignal<InitProgress | null>(null)
+  const [cancelling, setCancelling] = createSignal(false)
 
   const phase = createMemo(() => step()?.phase)
 
   const value = createMemo(() => {
     if (phase() === "done") return 100
+    const current = progress()
+    if (current?.stage === "health_check") return Math.max(25, Math.min(100, current.percent))
     return Math.max(25, Math.min(100, percent()))
   })
 
@@ -40,8 +52,15 @@ render(() => {
       if (e.payload.type === "Done") setPercent(100)
     })
 
+    // Startup is usually well underway by the time this window opens
+    commands.getInitProgress().then((last) => {
+      if (last) setProgress((current) => current ?? last)
+    })
+    const progressListener = events.initProgress.listen((e) => setProgress(e.payload))
+
     onCleanup(() => {
       listener.then((cb) => cb())
+      progressListener.then((cb) => cb())
       timers.forEach(clearTimeout)
     })
   })
@@ -55,7 +74,10 @@ render(() => {
 
   const status = createMemo(() => {
     if (phase() === "done") return "All done"
+    if (cancelling()) return "Cancelling..."
     if (phase() === "sqlite_waiting") return lines[line()]
+    const current = progress()
+    if (current) return stages[current.stage]
     return "Just a moment..."
   })
 
@@ -75,6 +97,19 @@ render(() => {
               aria-label="Database migration progress"
               getValueLabel={({ value }) => `${Math.round(value)}%`}
             />
+            <Show when={phase() !== "done"}>
+              <Button
+                variant="ghost"
+                size="small"
+                disabled={cancelling()}
+                onClick={() => {
+                  setCancelling(true)
+                  commands.cancelInitialization().catch(() => setCancelling(false))
+                }}
+              >
+                Cancel
+              </Button>
+            </Show>
           </div>
         </div>
       </div>
@@ -33,19 +33,22 @@ export async function runUpdater({ alertOnFail }: { alertOnFail: boolean }) {
     return
   }
 
+  // Automatic checks defer the prompt to the next check outside quiet hours
+  if (!alertOnFail && (await commands.isQuietHours().catch(() => false))) return
+
   const shouldUpdate = await ask(t("desktop.updater.downloaded.prompt", { version: update.version }), {
     title: t("desktop.updater.downloaded.title"),
   })
   if (!shouldUpdate) return
 
   try {
-    if (ostype() === "windows") await commands.killSidecar()
+    if (ostype() === "windows") await commands.killSidecar(false).catch(() => undefined)
     await update.install()
   } catch {
     await message(t("desktop.updater.installFailed.message"), { title: t("desktop.updater.installFailed.title") })
     return
   }
 
-  await commands.killSidecar()
+  await commands.killSidecar(false).catch(() => undefined)
   await relaunch()
 }
@@ -2,10 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-import { invoke } from "@tauri-apps/api/core"
 import { type as ostype } from "@tauri-apps/plugin-os"
 import { createSignal } from "solid-js"
 
+import { commands } from "./bindings"
+
 const OS_NAME = ostype()
 
 const [webviewZoom, setWebviewZoom] = createSignal(1)
@@ -17,21 +18,26 @@ const clamp = (value: number) => Math.min(Math.max(value, MIN_ZOOM_LEVEL), MAX_Z
 
 const applyZoom = (next: number) => {
   setWebviewZoom(next)
-  invoke("plugin:webview|set_webview_zoom", {
-    value: next,
-  })
+  void commands.setZoomLevel(next).catch(() => undefined)
 }
 
-window.addEventListener("keydown", (event) => {
-  if (!(OS_NAME === "macos" ? event.metaKey : event.ctrlKey)) return
+// The window restores its saved zoom itself, this only catches the signal up
+void commands
+  .getZoomLevel()
+  .then(setWebviewZoom)
+  .catch(() => undefined)
 
-  let newZoom = webviewZoom()
+const zoomIn = () => applyZoom(clamp(webviewZoom() + 0.2))
+const zoomOut = () => applyZoom(clamp(webviewZoom() - 0.2))
+const resetZoom = () => applyZoom(1)
 
-  if (event.key === "-") newZoom -= 0.2
-  if (event.key === "=" || event.key === "+") newZoom += 0.2
-  if (event.key === "0") newZoom = 1
+// On macOS the View menu's shortcuts handle zooming
+window.addEventListener("keydown", (event) => {
+  if (OS_NAME === "macos" || !event.ctrlKey) return
 
-  applyZoom(clamp(newZoom))
+  if (event.key === "-") zoomOut()
+  if (event.key === "=" || event.key === "+") zoomIn()
+  if (event.key === "0") resetZoom()
 })
 
-export { webviewZoom }
+export { webviewZoom, zoomIn, zoomOut, resetZoom }
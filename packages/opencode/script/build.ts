@@ -214,7 +214,9 @@ if (Script.release) {
       await $`zip -r ../../${key}.zip *`.cwd(`dist/${key}/bin`)
     }
   }
-  await $`gh release upload v${Script.version} ./dist/*.zip ./dist/*.tar.gz --clobber`
+  // Lets the install script verify downloads with --verify-checksum
+  await $`sha256sum *.zip *.tar.gz > SHA256SUMS`.cwd("dist")
+  await $`gh release upload v${Script.version} ./dist/*.zip ./dist/*.tar.gz ./dist/SHA256SUMS --clobber`
 }
 
 export { binaries }